@@ -0,0 +1,353 @@
+//! Applies the migrations of a project to a disposable database, so that a single `check` run
+//! verifies that the migrations actually apply and lints the schema they result in.
+//!
+//! All up migrations are applied in the order of their sequence number, each one in its own
+//! transaction. Statements that Postgres refuses to run inside a transaction block, such as
+//! `create index concurrently`, are run on their own between the transactions. A failing
+//! statement is reported at its range, and stops the remaining migrations from being applied.
+//! Optionally, the locks every statement acquires are read from `pg_locks` and reported, so
+//! that the lock-related rules of the linter can be verified against what Postgres actually
+//! does.
+
+use crate::cli_options::{ApplyOptions, RuleSelectionOptions};
+use crate::migrations::{MigrationFile, collect_migrations};
+use crate::{CliDiagnostic, CliSession, VcsIntegration};
+use pgls_analyse::RuleCategoriesBuilder;
+use pgls_configuration::database::PartialDatabaseConfiguration;
+use pgls_configuration::{Merge, PartialConfiguration};
+use pgls_console::fmt::Display;
+use pgls_diagnostics::{Advices, Diagnostic, DiagnosticExt, Error, Visit};
+use pgls_fs::FileSystem;
+use pgls_query::NodeEnum;
+use pgls_text_size::TextRange;
+use pgls_workspace::features::diagnostics::PullDatabaseDiagnosticsParams;
+use pgls_workspace::workspace::server::load_migration_layout;
+use sqlx::postgres::PgConnectOptions;
+use sqlx::{Connection, PgConnection};
+use std::collections::BTreeSet;
+use std::io;
+use std::path::Path;
+use tokio::runtime::Runtime;
+
+/// The relation locks held by the current transaction, except the one on `pg_locks` itself
+const HELD_LOCKS_QUERY: &str = "select l.relation::regclass::text, l.mode
+from pg_catalog.pg_locks l
+where l.pid = pg_catalog.pg_backend_pid()
+  and l.locktype = 'relation'
+  and l.granted
+  and l.relation <> 'pg_catalog.pg_locks'::regclass";
+
+/// Applies the migrations to the database of `--apply-to`, then lints the resulting schema.
+/// Returns the diagnostics of both steps.
+///
+/// The workspace is connected to the database of `--apply-to` afterwards.
+pub(crate) fn apply_migrations(
+    session: &mut CliSession,
+    configuration: &PartialConfiguration,
+    options: &ApplyOptions,
+    rule_selection: &RuleSelectionOptions,
+) -> Result<Vec<Error>, CliDiagnostic> {
+    let Some(connection_string) = options.apply_to.as_deref() else {
+        return Ok(vec![]);
+    };
+
+    // the database of `--apply-to` is expected to be empty, so every migration is replayed
+    // regardless of `after`
+    let working_directory = session.fs().working_directory().unwrap_or_default();
+    let mut diagnostics = replay_migrations(
+        &**session.fs(),
+        &working_directory,
+        configuration,
+        &connect_options(connection_string)?,
+        options.capture_locks,
+    )?;
+
+    diagnostics.extend(lint_schema(
+        session,
+        configuration,
+        connection_string,
+        rule_selection,
+    )?);
+
+    Ok(diagnostics)
+}
+
+/// Applies all up migrations to the database of `connect_options`, in order. Returns the
+/// diagnostic of the migration that failed to apply, if any, and the locks of every statement
+/// if `capture_locks` is set.
+pub(crate) fn replay_migrations(
+    fs: &dyn FileSystem,
+    working_directory: &Path,
+    configuration: &PartialConfiguration,
+    connect_options: &PgConnectOptions,
+    capture_locks: bool,
+) -> Result<Vec<Error>, CliDiagnostic> {
+    let migrations = configuration.migrations.as_ref();
+    let Some(migrations_dir) = migrations.and_then(|migrations| migrations.migrations_dir.as_ref())
+    else {
+        return Err(CliDiagnostic::incompatible_end_configuration(
+            "Applying migrations requires the `migrations.migrationsDir` option to be configured.",
+        ));
+    };
+    let tool = migrations
+        .and_then(|migrations| migrations.tool)
+        .unwrap_or_default();
+    let migrations_dir = working_directory.join(migrations_dir);
+    let layout = load_migration_layout(fs, &migrations_dir, tool);
+    let (migrations, _) = collect_migrations(fs, &migrations_dir, layout.as_ref())?;
+    let migration_files = migrations
+        .into_iter()
+        .filter(|migration| migration.direction.is_applied());
+
+    let runtime = Runtime::new().map_err(CliDiagnostic::io_error)?;
+    runtime.block_on(async {
+        let mut conn = PgConnection::connect_with(connect_options)
+            .await
+            .map_err(|err| {
+                CliDiagnostic::io_error(io::Error::other(format!(
+                    "Failed to connect to the database to apply the migrations to: {err}"
+                )))
+            })?;
+        let server_version_num = server_version_num(&mut conn).await?;
+
+        let mut diagnostics = vec![];
+        for migration in migration_files {
+            let name = migration
+                .path
+                .strip_prefix(working_directory)
+                .unwrap_or(&migration.path)
+                .display()
+                .to_string();
+
+            let applied = apply_migration(
+                &mut conn,
+                &migration,
+                capture_locks,
+                server_version_num,
+                &mut |diagnostic| {
+                    diagnostics.push(
+                        diagnostic
+                            .with_file_path(name.as_str())
+                            .with_file_source_code(migration.content.as_str()),
+                    )
+                },
+            )
+            .await?;
+
+            // Later migrations build upon the one that failed, so they aren't applied
+            if !applied {
+                break;
+            }
+        }
+
+        conn.close().await.ok();
+
+        Ok(diagnostics)
+    })
+}
+
+/// Applies the applied sections of a single migration in a transaction, except for the
+/// statements that can't run inside one. Returns whether it was applied.
+async fn apply_migration(
+    conn: &mut PgConnection,
+    migration: &MigrationFile,
+    capture_locks: bool,
+    server_version_num: i32,
+    report: &mut impl FnMut(Error),
+) -> Result<bool, CliDiagnostic> {
+    let mut in_transaction = false;
+    let mut held_locks = BTreeSet::new();
+    for range in migration.applied_statements() {
+        let statement = &migration.content[range];
+
+        let outside_transaction = runs_outside_transaction(statement, server_version_num);
+        if outside_transaction && in_transaction {
+            run(conn, "commit").await?;
+            in_transaction = false;
+            held_locks.clear();
+        } else if !outside_transaction && !in_transaction {
+            run(conn, "begin").await?;
+            in_transaction = true;
+        }
+
+        if let Err(err) = sqlx::raw_sql(statement).execute(&mut *conn).await {
+            let message = match err.as_database_error() {
+                Some(err) => err.message().to_string(),
+                None => err.to_string(),
+            };
+            report(Error::from(MigrationFailed {
+                span: range,
+                message,
+            }));
+            if in_transaction {
+                run(conn, "rollback").await?;
+            }
+            return Ok(false);
+        }
+
+        // Locks of statements outside a transaction are already released once they're done
+        if capture_locks && in_transaction {
+            let locks: BTreeSet<(String, String)> = sqlx::query_as(HELD_LOCKS_QUERY)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(database_error)?
+                .into_iter()
+                .collect();
+
+            // Locks are held until the end of the transaction, so the ones that weren't held
+            // before were acquired by this statement
+            let acquired: Vec<String> = locks
+                .difference(&held_locks)
+                .map(|(relation, mode)| format!("{mode} on {relation}"))
+                .collect();
+            if !acquired.is_empty() {
+                report(Error::from(LocksAcquired {
+                    span: range,
+                    locks: AcquiredLocks(acquired),
+                }));
+            }
+            held_locks = locks;
+        }
+    }
+
+    if in_transaction {
+        run(conn, "commit").await?;
+    }
+    Ok(true)
+}
+
+async fn run(conn: &mut PgConnection, statement: &str) -> Result<(), CliDiagnostic> {
+    sqlx::raw_sql(statement)
+        .execute(conn)
+        .await
+        .map_err(database_error)?;
+    Ok(())
+}
+
+async fn server_version_num(conn: &mut PgConnection) -> Result<i32, CliDiagnostic> {
+    sqlx::query_scalar("select current_setting('server_version_num')::int")
+        .fetch_one(conn)
+        .await
+        .map_err(database_error)
+}
+
+/// Whether Postgres refuses to run the statement inside a transaction block
+fn runs_outside_transaction(statement: &str, server_version_num: i32) -> bool {
+    let Some(stmt) = pgls_query::parse(statement)
+        .ok()
+        .and_then(|parsed| parsed.into_root())
+    else {
+        return false;
+    };
+
+    match stmt {
+        NodeEnum::IndexStmt(stmt) => stmt.concurrent,
+        NodeEnum::DropStmt(stmt) => stmt.concurrent,
+        NodeEnum::ReindexStmt(stmt) => stmt.params.iter().any(|param| {
+            matches!(
+                param.node.as_ref(),
+                Some(NodeEnum::DefElem(option)) if option.defname == "concurrently"
+            )
+        }),
+        NodeEnum::VacuumStmt(stmt) => stmt.is_vacuumcmd,
+        // adding enum values inside a transaction block is only supported since Postgres 12
+        NodeEnum::AlterEnumStmt(stmt) => stmt.old_val.is_empty() && server_version_num < 120000,
+        NodeEnum::CreatedbStmt(_)
+        | NodeEnum::DropdbStmt(_)
+        | NodeEnum::CreateTableSpaceStmt(_)
+        | NodeEnum::DropTableSpaceStmt(_)
+        | NodeEnum::AlterSystemStmt(_) => true,
+        _ => false,
+    }
+}
+
+/// Parses a connection string into the options to connect with
+pub(crate) fn connect_options(connection_string: &str) -> Result<PgConnectOptions, CliDiagnostic> {
+    connection_string.parse().map_err(|err| {
+        CliDiagnostic::io_error(io::Error::other(format!(
+            "Invalid connection string: {err}"
+        )))
+    })
+}
+
+/// Lints the schema that results from the migrations with the database linters
+fn lint_schema(
+    session: &mut CliSession,
+    configuration: &PartialConfiguration,
+    connection_string: &str,
+    rule_selection: &RuleSelectionOptions,
+) -> Result<Vec<Error>, CliDiagnostic> {
+    let mut configuration = configuration.clone();
+    configuration.merge_with(PartialConfiguration {
+        db: Some(PartialDatabaseConfiguration {
+            connection_string: Some(connection_string.to_string()),
+            disable_connection: Some(false),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    session.setup_workspace(configuration, VcsIntegration::Disabled)?;
+
+    let result = session
+        .workspace()
+        .pull_db_diagnostics(PullDatabaseDiagnosticsParams {
+            categories: RuleCategoriesBuilder::default().all().build(),
+            max_diagnostics: u32::MAX,
+            only: rule_selection.only.clone(),
+            skip: rule_selection.skip.clone(),
+        })?;
+
+    Ok(result
+        .diagnostics
+        .into_iter()
+        .map(Error::from)
+        .filter(|diagnostic| rule_selection.is_reported(diagnostic))
+        .collect())
+}
+
+fn database_error(err: sqlx::Error) -> CliDiagnostic {
+    CliDiagnostic::io_error(io::Error::other(format!(
+        "Failed to apply the migrations: {err}"
+    )))
+}
+
+#[derive(Debug, Diagnostic)]
+#[diagnostic(
+    category = "migrations/apply",
+    severity = Error,
+    message(
+        description = "The migration failed to apply: {message}",
+        message("The migration failed to apply: "{self.message})
+    )
+)]
+struct MigrationFailed {
+    #[location(span)]
+    span: TextRange,
+    message: String,
+}
+
+#[derive(Debug, Diagnostic)]
+#[diagnostic(
+    category = "migrations/locks",
+    severity = Information,
+    message(
+        description = "The statement acquired locks.",
+        message("The statement acquired the following locks.")
+    )
+)]
+struct LocksAcquired {
+    #[location(span)]
+    span: TextRange,
+    #[advice]
+    locks: AcquiredLocks,
+}
+
+#[derive(Debug)]
+struct AcquiredLocks(Vec<String>);
+
+impl Advices for AcquiredLocks {
+    fn record(&self, visitor: &mut dyn Visit) -> io::Result<()> {
+        let list: Vec<_> = self.0.iter().map(|lock| lock as &dyn Display).collect();
+        visitor.record_list(&list)
+    }
+}
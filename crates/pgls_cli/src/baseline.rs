@@ -0,0 +1,372 @@
+//! Baseline files record the diagnostics a project has accepted, so that new rules can be
+//! adopted on directories of historical migrations that can no longer be changed.
+//!
+//! Diagnostics in files are identified by their rule, the path of the file and the
+//! fingerprint of the statement they belong to, which makes the baseline robust against
+//! formatting changes and statements moving around in a file. Diagnostics of `dblint` are
+//! identified by their rule and the database object they are reported on.
+
+use crate::CliDiagnostic;
+use crate::cli_options::BaselineOptions;
+use crate::reporter::Report;
+use path_absolutize::Absolutize;
+use pgls_console::{Console, ConsoleExt, markup};
+use pgls_diagnostics::{
+    DatabaseObjectOwned, Diagnostic, DiagnosticExt, Error, LogAdvice, LogCategory, Resource,
+};
+use pgls_text_size::TextRange;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+const BASELINE_VERSION: u32 = 1;
+
+/// Applies the baseline options to the report of a command.
+///
+/// With `--write-baseline`, all diagnostics of the report are recorded in the baseline
+/// file and suppressed. With `--baseline`, the diagnostics recorded in the baseline file
+/// are suppressed, and the entries of the baseline that no longer occur are reported.
+///
+/// The report is expected to contain all diagnostics, and to be truncated afterwards.
+pub(crate) fn apply_baseline(
+    console: &mut dyn Console,
+    options: &BaselineOptions,
+    report: Report,
+) -> Result<Report, CliDiagnostic> {
+    let Report {
+        diagnostics,
+        duration,
+        skipped_diagnostics,
+        traversal,
+        ..
+    } = report;
+
+    let root = traversal
+        .as_ref()
+        .and_then(|traversal| traversal.workspace_root.clone());
+    // Paths are only known for commands that traverse files, `dblint` always checks the
+    // whole database.
+    let checked_paths: Option<HashSet<String>> = traversal.as_ref().map(|traversal| {
+        traversal
+            .evaluated_paths
+            .iter()
+            .map(|path| relative_path(&path.display().to_string(), root.as_deref()))
+            .collect()
+    });
+
+    let mut keys = KeyBuilder::new(root.as_deref());
+
+    let diagnostics = if let Some(path) = &options.write_baseline {
+        let mut baseline = Baseline::default();
+
+        // Entries of files that were not checked in this run are kept as they are
+        if let (Some(checked_paths), Some(previous)) = (&checked_paths, Baseline::read(path)?) {
+            baseline
+                .entries
+                .extend(previous.entries.into_iter().filter(|(key, _)| {
+                    key.path
+                        .as_ref()
+                        .is_some_and(|path| !checked_paths.contains(path))
+                }));
+        }
+
+        let mut recorded = 0;
+        let mut remaining = vec![];
+        for diagnostic in diagnostics {
+            match keys.key(&diagnostic) {
+                Some(key) => {
+                    *baseline.entries.entry(key).or_default() += 1;
+                    recorded += 1;
+                }
+                None => remaining.push(diagnostic),
+            }
+        }
+
+        baseline.write(path)?;
+
+        console.error(markup! {
+            <Info>"Recorded "{recorded}" diagnostics in the baseline "<Emphasis>{path.display().to_string()}</Emphasis>"."</Info>
+        });
+
+        remaining
+    } else if let Some(path) = &options.baseline {
+        let Some(mut baseline) = Baseline::read(path)? else {
+            return Err(CliDiagnostic::io_error(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("The baseline file {} does not exist.", path.display()),
+            )));
+        };
+
+        let mut suppressed = 0;
+        let mut remaining: Vec<Error> = diagnostics
+            .into_iter()
+            .filter(|diagnostic| {
+                let count = keys
+                    .key(diagnostic)
+                    .and_then(|key| baseline.entries.get_mut(&key))
+                    .filter(|count| **count > 0);
+                match count {
+                    Some(count) => {
+                        *count -= 1;
+                        suppressed += 1;
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .collect();
+
+        if suppressed > 0 {
+            console.error(markup! {
+                <Info>"Suppressed "{suppressed}" diagnostics recorded in the baseline "<Emphasis>{path.display().to_string()}</Emphasis>"."</Info>
+            });
+        }
+
+        remaining.extend(
+            baseline
+                .entries
+                .into_iter()
+                .filter(|(key, count)| {
+                    *count > 0
+                        && match (&key.path, &checked_paths) {
+                            (Some(path), Some(checked_paths)) => checked_paths.contains(path),
+                            _ => true,
+                        }
+                })
+                .map(|(key, _)| key.into_stale_diagnostic()),
+        );
+
+        remaining
+    } else {
+        diagnostics
+    };
+
+    Ok(Report::new(
+        diagnostics,
+        duration,
+        skipped_diagnostics,
+        traversal,
+    ))
+}
+
+#[derive(Debug, Default)]
+struct Baseline {
+    /// The number of diagnostics recorded for each key
+    entries: BTreeMap<BaselineKey, u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineEntry {
+    #[serde(flatten)]
+    key: BaselineKey,
+    count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BaselineKey {
+    /// The category of the diagnostic
+    rule: String,
+    /// The path of the file, relative to the workspace root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    /// The fingerprint of the statement the diagnostic belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fingerprint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schema: Option<String>,
+    /// The name of the database object the diagnostic is reported on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    object: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    object_type: Option<String>,
+}
+
+impl Baseline {
+    fn read(path: &Path) -> Result<Option<Self>, CliDiagnostic> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(CliDiagnostic::io_error(err)),
+        };
+
+        let file: BaselineFile = serde_json::from_str(&content)
+            .map_err(|e| CliDiagnostic::io_error(std::io::Error::other(e)))?;
+
+        let mut entries = BTreeMap::new();
+        for entry in file.entries {
+            *entries.entry(entry.key).or_default() += entry.count;
+        }
+
+        Ok(Some(Self { entries }))
+    }
+
+    fn write(self, path: &Path) -> Result<(), CliDiagnostic> {
+        let file = BaselineFile {
+            version: BASELINE_VERSION,
+            entries: self
+                .entries
+                .into_iter()
+                .map(|(key, count)| BaselineEntry { key, count })
+                .collect(),
+        };
+
+        let mut content = serde_json::to_string_pretty(&file)
+            .map_err(|e| CliDiagnostic::io_error(std::io::Error::other(e)))?;
+        content.push('\n');
+
+        std::fs::write(path, content).map_err(CliDiagnostic::io_error)
+    }
+}
+
+impl BaselineKey {
+    fn into_stale_diagnostic(self) -> Error {
+        let db_object = self.object.map(|name| DatabaseObjectOwned {
+            schema: self.schema,
+            name,
+            object_type: self.object_type,
+        });
+
+        // the database object isn't printed in the header, so it is named in the message
+        let rule = match &db_object {
+            Some(object) => {
+                let name = match &object.schema {
+                    Some(schema) => format!("{schema}.{}", object.name),
+                    None => object.name.clone(),
+                };
+                match &object.object_type {
+                    Some(object_type) => format!("{} on {object_type} {name}", self.rule),
+                    None => format!("{} on {name}", self.rule),
+                }
+            }
+            None => self.rule,
+        };
+
+        let diagnostic = Error::from(StaleBaselineEntry {
+            rule,
+            db_object,
+            advice: LogAdvice {
+                category: LogCategory::Info,
+                text: markup! {
+                    "Run the command with "<Emphasis>"--write-baseline"</Emphasis>" to remove it from the baseline."
+                }
+                .to_owned(),
+            },
+        });
+
+        match self.path {
+            Some(path) => diagnostic.with_file_path(path),
+            None => diagnostic,
+        }
+    }
+}
+
+/// Emitted for an entry of the baseline that no longer matches any diagnostic
+#[derive(Debug, Diagnostic)]
+#[diagnostic(
+    category = "baseline",
+    severity = Information,
+    message(
+        description = "The baseline entry for {rule} no longer occurs.",
+        message("The baseline entry for "<Emphasis>{self.rule}</Emphasis>" no longer occurs.")
+    )
+)]
+struct StaleBaselineEntry {
+    rule: String,
+    #[location(database_object)]
+    db_object: Option<DatabaseObjectOwned>,
+    #[advice]
+    advice: LogAdvice<pgls_console::MarkupBuf>,
+}
+
+/// Computes the baseline keys of diagnostics, caching the statements of each file.
+struct KeyBuilder<'a> {
+    root: Option<&'a Path>,
+    statements: HashMap<String, Vec<TextRange>>,
+}
+
+impl<'a> KeyBuilder<'a> {
+    fn new(root: Option<&'a Path>) -> Self {
+        Self {
+            root,
+            statements: HashMap::new(),
+        }
+    }
+
+    /// Diagnostics without a category can't be recorded in the baseline.
+    fn key(&mut self, diagnostic: &Error) -> Option<BaselineKey> {
+        let rule = diagnostic.category()?.name().to_string();
+        let location = diagnostic.location();
+
+        let mut key = BaselineKey {
+            rule,
+            path: None,
+            fingerprint: None,
+            schema: None,
+            object: None,
+            object_type: None,
+        };
+
+        if let Some(Resource::File(path)) = location.resource {
+            key.path = Some(relative_path(path, self.root));
+
+            if let (Some(span), Some(source_code)) = (location.span, location.source_code) {
+                let statements = self
+                    .statements
+                    .entry(path.to_string())
+                    .or_insert_with(|| pgls_statement_splitter::split(source_code.text).ranges);
+
+                key.fingerprint = statements
+                    .iter()
+                    .find(|range| range.contains_inclusive(span.start()))
+                    .map(|range| &source_code.text[*range])
+                    .map(statement_fingerprint);
+            }
+        } else if let Some(object) = location.database_object {
+            key.schema = object.schema.map(str::to_string);
+            key.object = Some(object.name.to_string());
+            key.object_type = object.object_type.map(str::to_string);
+        }
+
+        Some(key)
+    }
+}
+
+/// Statements that can't be parsed have no fingerprint, so a hash of their text is used
+/// instead, with whitespace normalized.
+fn statement_fingerprint(statement: &str) -> String {
+    match pgls_query::fingerprint(statement) {
+        Ok(fingerprint) => fingerprint.hex,
+        Err(_) => {
+            // FNV-1a, which is stable across platforms and releases
+            let mut hash: u64 = 0xcbf29ce484222325;
+            for word in statement.split_whitespace() {
+                for byte in word.bytes().chain(std::iter::once(b' ')) {
+                    hash ^= u64::from(byte);
+                    hash = hash.wrapping_mul(0x100000001b3);
+                }
+            }
+            format!("{hash:016x}")
+        }
+    }
+}
+
+/// Paths are recorded relative to the workspace root and with forward slashes, so that the
+/// baseline can be shared across machines.
+fn relative_path(path: &str, root: Option<&Path>) -> String {
+    let relativized = root.and_then(|root| {
+        let resolved = Path::new(path).absolutize().ok()?;
+        let relative = resolved.strip_prefix(root).ok()?;
+        relative.to_str().map(str::to_string)
+    });
+
+    relativized
+        .unwrap_or_else(|| path.trim_start_matches("./").to_string())
+        .replace('\\', "/")
+}
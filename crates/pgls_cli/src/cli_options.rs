@@ -1,8 +1,8 @@
 use crate::LoggingLevel;
 use crate::logging::LoggingKind;
 use bpaf::Bpaf;
-use pgls_configuration::ConfigurationPathHint;
-use pgls_diagnostics::Severity;
+use pgls_configuration::{ConfigurationPathHint, RuleSelector};
+use pgls_diagnostics::{Error, Severity};
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -51,7 +51,7 @@ pub struct CliOptions {
     /// Allows to change how diagnostics and summary are reported.
     #[bpaf(
         long("reporter"),
-        argument("json|json-pretty|github|junit|summary|gitlab"),
+        argument("json|json-pretty|github|junit|summary|gitlab|sarif"),
         fallback(CliReporter::default())
     )]
     pub reporter: CliReporter,
@@ -135,6 +135,8 @@ pub enum CliReporter {
     JsonPretty,
     /// Only a summary of diagnostics is printed (counts, no individual diagnostics)
     Summary,
+    /// Diagnostics are printed as a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log, e.g. for GitHub code scanning
+    Sarif,
 }
 
 impl CliReporter {
@@ -154,6 +156,7 @@ impl FromStr for CliReporter {
             "json" => Ok(Self::Json),
             "json-pretty" => Ok(Self::JsonPretty),
             "summary" => Ok(Self::Summary),
+            "sarif" => Ok(Self::Sarif),
             _ => Err(format!(
                 "value {s:?} is not valid for the --reporter argument"
             )),
@@ -171,6 +174,7 @@ impl Display for CliReporter {
             CliReporter::Json => f.write_str("json"),
             CliReporter::JsonPretty => f.write_str("json-pretty"),
             CliReporter::Summary => f.write_str("summary"),
+            CliReporter::Sarif => f.write_str("sarif"),
         }
     }
 }
@@ -245,3 +249,66 @@ impl From<MaxDiagnostics> for u32 {
         }
     }
 }
+
+/// Options of the commands that support a baseline of accepted diagnostics
+#[derive(Debug, Clone, Default, Bpaf)]
+pub struct BaselineOptions {
+    /// Suppresses the diagnostics recorded in the given baseline file, and reports the entries
+    /// of the baseline that no longer occur.
+    #[bpaf(long("baseline"), argument("PATH"), optional)]
+    pub baseline: Option<PathBuf>,
+
+    /// Records all current diagnostics in the given baseline file instead of reporting them.
+    /// Entries of files that are not checked in this run are kept.
+    #[bpaf(long("write-baseline"), argument("PATH"), optional)]
+    pub write_baseline: Option<PathBuf>,
+}
+
+impl BaselineOptions {
+    pub(crate) const fn is_enabled(&self) -> bool {
+        self.baseline.is_some() || self.write_baseline.is_some()
+    }
+}
+
+/// Options of the `check` command that apply the migrations to a database
+#[derive(Debug, Clone, Default, Bpaf)]
+pub struct ApplyOptions {
+    /// Applies the migrations of the configured migrations directory to the database of the
+    /// given connection string, and lints the resulting schema. Each migration runs in its own
+    /// transaction and is committed, so only use a disposable database. Statements that can't
+    /// run in a transaction, e.g. `create index concurrently`, run on their own.
+    #[bpaf(long("apply-to"), argument("CONNECTION_STRING"), optional)]
+    pub apply_to: Option<String>,
+
+    /// Reports the locks each statement of the applied migrations acquires.
+    #[bpaf(long("capture-locks"), switch)]
+    pub capture_locks: bool,
+}
+
+/// Options of the commands that report rule diagnostics, to select the rules and diagnostics of a run
+#[derive(Debug, Clone, Default, Bpaf)]
+pub struct RuleSelectionOptions {
+    /// Run only the given rule or group of rules, e.g. `--only=lint/safety` or
+    /// `--only=splinter/performance/noPrimaryKey`. The option can be passed multiple times.
+    /// Rules of the `lint` namespace can be selected without the prefix.
+    #[bpaf(long("only"), argument("GROUP|RULE"))]
+    pub only: Vec<RuleSelector>,
+
+    /// Skip the given rule or group of rules, e.g. `--skip=lint/safety/banDropColumn`.
+    /// The option can be passed multiple times.
+    #[bpaf(long("skip"), argument("GROUP|RULE"))]
+    pub skip: Vec<RuleSelector>,
+
+    /// Drop the diagnostics with a lower severity from the report, so that they are neither
+    /// printed nor affect the exit code.
+    #[bpaf(long("severity-threshold"), argument("info|warn|error"), optional)]
+    pub severity_threshold: Option<Severity>,
+}
+
+impl RuleSelectionOptions {
+    /// Returns `true` if the diagnostic passes the severity threshold
+    pub(crate) fn is_reported(&self, diagnostic: &Error) -> bool {
+        self.severity_threshold
+            .is_none_or(|threshold| diagnostic.severity() >= threshold)
+    }
+}
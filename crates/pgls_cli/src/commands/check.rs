@@ -1,15 +1,19 @@
-use crate::cli_options::CliOptions;
-use crate::commands::get_files_to_process_with_cli_options;
-use crate::execute::{StdinPayload, run_files, run_stdin};
+use crate::apply::apply_migrations;
+use crate::baseline::apply_baseline;
+use crate::cli_options::{ApplyOptions, BaselineOptions, CliOptions, RuleSelectionOptions};
+use crate::commands::{get_files_to_process_with_cli_options, read_stdin_payload};
+use crate::execute::{run_files, run_stdin, watch_files};
+use crate::migrations::check_migrations;
 use crate::reporter::Report;
 use crate::{CliDiagnostic, CliSession, VcsIntegration};
 use crate::{ExecutionConfig, ExecutionMode, VcsTargeting};
 use pgls_configuration::PartialConfiguration;
-use pgls_console::Console;
-use pgls_diagnostics::category;
+use pgls_console::{ConsoleExt, markup};
+use pgls_diagnostics::{Error, category};
 use pgls_fs::FileSystem;
 use pgls_workspace::DynRef;
 use std::ffi::OsString;
+use std::time::Instant;
 
 pub struct CheckArgs {
     pub configuration: Option<PartialConfiguration>,
@@ -18,6 +22,10 @@ pub struct CheckArgs {
     pub staged: bool,
     pub changed: bool,
     pub since: Option<String>,
+    pub watch: bool,
+    pub rule_selection_options: RuleSelectionOptions,
+    pub baseline_options: BaselineOptions,
+    pub apply_options: ApplyOptions,
 }
 
 pub fn check(
@@ -43,14 +51,69 @@ pub fn check(
         u32::MAX
     };
 
-    let mode = ExecutionMode::Check { vcs };
-    let execution = ExecutionConfig::new(mode, max_diagnostics);
+    // The baseline needs to see all diagnostics, the limit is applied once it has been matched.
+    // In watch mode, the report is rebuilt from the diagnostics of all files after each change.
+    // The diagnostics of the migrations as a whole are added to the report of the files.
+    let applies_migrations = args.apply_options.apply_to.is_some();
+    let collected_diagnostics =
+        if args.baseline_options.is_enabled() || args.watch || applies_migrations {
+            u32::MAX
+        } else {
+            max_diagnostics
+        };
+
+    let mode = ExecutionMode::Check {
+        vcs,
+        rule_selection: args.rule_selection_options.clone(),
+    };
+    let execution = ExecutionConfig::new(mode, collected_diagnostics);
 
     if let Some(stdin_path) = args.stdin_file_path.as_deref() {
-        let payload = read_stdin_payload(stdin_path, session.console())?;
+        let payload = read_stdin_payload(stdin_path, session.console(), "check")?;
         run_stdin(&mut session, &execution, payload)
+    } else if args.watch {
+        let watched_paths = paths.clone();
+        watch_files(
+            &mut session,
+            &execution,
+            watched_paths,
+            |session, mut report| {
+                let start = Instant::now();
+                let mut diagnostics = check_migrations(session.fs(), &configuration, &paths)?;
+                diagnostics
+                    .retain(|diagnostic| args.rule_selection_options.is_reported(diagnostic));
+                report = with_migration_diagnostics(report, diagnostics, start);
+                if args.baseline_options.is_enabled() {
+                    report = apply_baseline(session.console(), &args.baseline_options, report)?;
+                }
+                session.report("check", cli_options, &report.truncate(max_diagnostics))?;
+                if cli_options.reporter.is_default() {
+                    session.console().log(markup! {
+                        <Dim>"Watching for changes, press Ctrl+C to stop."</Dim>
+                    });
+                }
+                Ok(())
+            },
+        )
     } else {
-        let report: Report = run_files(&mut session, &execution, paths)?;
+        let mut report: Report = run_files(&mut session, &execution, paths.clone())?;
+
+        let start = Instant::now();
+        let mut diagnostics = check_migrations(session.fs(), &configuration, &paths)?;
+        diagnostics.retain(|diagnostic| args.rule_selection_options.is_reported(diagnostic));
+        if applies_migrations {
+            diagnostics.extend(apply_migrations(
+                &mut session,
+                &configuration,
+                &args.apply_options,
+                &args.rule_selection_options,
+            )?);
+        }
+        report = with_migration_diagnostics(report, diagnostics, start);
+        if args.baseline_options.is_enabled() {
+            report = apply_baseline(session.console(), &args.baseline_options, report)?;
+        }
+        let report = report.truncate(max_diagnostics);
 
         let exit_result = enforce_exit_codes(cli_options, &report);
         session.report("check", cli_options, &report)?;
@@ -58,6 +121,25 @@ pub fn check(
     }
 }
 
+/// Adds the diagnostics of the migrations as a whole to the report of the files.
+fn with_migration_diagnostics(
+    mut report: Report,
+    diagnostics: Vec<Error>,
+    start: Instant,
+) -> Report {
+    if diagnostics.is_empty() {
+        return report;
+    }
+
+    report.diagnostics.extend(diagnostics);
+    Report::new(
+        report.diagnostics,
+        report.duration + start.elapsed(),
+        report.skipped_diagnostics,
+        report.traversal,
+    )
+}
+
 fn resolve_paths(
     fs: &DynRef<'_, dyn FileSystem>,
     configuration: &PartialConfiguration,
@@ -82,21 +164,6 @@ fn resolve_paths(
     Ok(paths)
 }
 
-fn read_stdin_payload(
-    path: &str,
-    console: &mut dyn Console,
-) -> Result<StdinPayload, CliDiagnostic> {
-    let input_code = console.read();
-    if let Some(input_code) = input_code {
-        Ok(StdinPayload {
-            path: path.into(),
-            content: input_code,
-        })
-    } else {
-        Err(CliDiagnostic::missing_argument("stdin", "check"))
-    }
-}
-
 fn enforce_exit_codes(cli_options: &CliOptions, payload: &Report) -> Result<(), CliDiagnostic> {
     let traversal = payload.traversal.as_ref();
     let processed = traversal.map_or(0, |t| t.changed + t.unchanged);
@@ -135,5 +202,48 @@ fn validate_args(args: &CheckArgs) -> Result<(), CliDiagnostic> {
         return Err(CliDiagnostic::incompatible_arguments("changed", "staged"));
     }
 
+    if args.baseline_options.baseline.is_some() && args.baseline_options.write_baseline.is_some() {
+        return Err(CliDiagnostic::incompatible_arguments(
+            "baseline",
+            "write-baseline",
+        ));
+    }
+
+    if args.watch {
+        if args.stdin_file_path.is_some() {
+            return Err(CliDiagnostic::incompatible_arguments(
+                "watch",
+                "stdin-file-path",
+            ));
+        }
+        if args.baseline_options.write_baseline.is_some() {
+            return Err(CliDiagnostic::incompatible_arguments(
+                "watch",
+                "write-baseline",
+            ));
+        }
+    }
+
+    if args.apply_options.apply_to.is_some() {
+        if args.watch {
+            return Err(CliDiagnostic::incompatible_arguments("apply-to", "watch"));
+        }
+        if args.stdin_file_path.is_some() {
+            return Err(CliDiagnostic::incompatible_arguments(
+                "apply-to",
+                "stdin-file-path",
+            ));
+        }
+    } else if args.apply_options.capture_locks {
+        return Err(CliDiagnostic::missing_argument("apply-to", "check"));
+    }
+
+    if args.stdin_file_path.is_some() && args.baseline_options.is_enabled() {
+        return Err(CliDiagnostic::incompatible_arguments(
+            "stdin-file-path",
+            "baseline",
+        ));
+    }
+
     Ok(())
 }
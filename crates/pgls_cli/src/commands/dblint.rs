@@ -1,22 +1,104 @@
-use std::time::Instant;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::cli_options::CliOptions;
+use crate::baseline::apply_baseline;
+use crate::cli_options::{BaselineOptions, CliOptions, RuleSelectionOptions};
 use crate::reporter::Report;
 use crate::{CliDiagnostic, CliSession, VcsIntegration};
 use pgls_analyse::RuleCategoriesBuilder;
 use pgls_configuration::PartialConfiguration;
-use pgls_diagnostics::Error;
+use pgls_console::{ConsoleExt, markup};
 use pgls_diagnostics::category;
+use pgls_diagnostics::{Error, PrintDiagnostic};
 use pgls_workspace::features::diagnostics::{PullDatabaseDiagnosticsParams, PullDiagnosticsResult};
 
+/// How often the schema cache is checked for invalidations in watch mode
+const SCHEMA_CACHE_POLL: Duration = Duration::from_millis(500);
+
+pub struct DblintArgs {
+    pub configuration: Option<PartialConfiguration>,
+    pub rule_selection_options: RuleSelectionOptions,
+    pub baseline_options: BaselineOptions,
+    pub watch: bool,
+    pub poll_interval: Duration,
+}
+
 pub fn dblint(
     mut session: CliSession,
     cli_options: &CliOptions,
-    cli_configuration: Option<PartialConfiguration>,
+    args: DblintArgs,
 ) -> Result<(), CliDiagnostic> {
-    let configuration = session.prepare_with_config(cli_options, cli_configuration)?;
+    let baseline_options = &args.baseline_options;
+    if baseline_options.baseline.is_some() && baseline_options.write_baseline.is_some() {
+        return Err(CliDiagnostic::incompatible_arguments(
+            "baseline",
+            "write-baseline",
+        ));
+    }
+    if args.watch && baseline_options.write_baseline.is_some() {
+        return Err(CliDiagnostic::incompatible_arguments(
+            "watch",
+            "write-baseline",
+        ));
+    }
+
+    let configuration = session.prepare_with_config(cli_options, args.configuration.clone())?;
     session.setup_workspace(configuration, VcsIntegration::Disabled)?;
-    let workspace = session.workspace();
+
+    if args.watch {
+        return watch(&mut session, cli_options, &args);
+    }
+
+    let report = lint_database(&mut session, cli_options, &args)?;
+    let exit_result = enforce_exit_codes(cli_options, &report);
+    session.report("dblint", cli_options, &report)?;
+    exit_result
+}
+
+/// Lints the database again whenever the schema cache of the workspace is invalidated, or when
+/// the poll interval elapses, until the process is interrupted.
+fn watch(
+    session: &mut CliSession,
+    cli_options: &CliOptions,
+    args: &DblintArgs,
+) -> Result<(), CliDiagnostic> {
+    loop {
+        let generation = session.workspace().schema_cache_generation()?;
+
+        // A failing run, e.g. because the database can't be reached, doesn't stop watching
+        match lint_database(session, cli_options, args) {
+            Ok(report) => session.report("dblint", cli_options, &report)?,
+            Err(error) => session
+                .console()
+                .error(markup! {{PrintDiagnostic::simple(&error)}}),
+        }
+        if cli_options.reporter.is_default() {
+            session.console().log(markup! {
+                <Dim>"Watching for schema changes, press Ctrl+C to stop."</Dim>
+            });
+        }
+
+        let start = Instant::now();
+        while start.elapsed() < args.poll_interval
+            && session.workspace().schema_cache_generation()? == generation
+        {
+            thread::sleep(
+                SCHEMA_CACHE_POLL.min(args.poll_interval.saturating_sub(start.elapsed())),
+            );
+        }
+    }
+}
+
+fn lint_database(
+    session: &mut CliSession,
+    cli_options: &CliOptions,
+    args: &DblintArgs,
+) -> Result<Report, CliDiagnostic> {
+    let DblintArgs {
+        rule_selection_options,
+        baseline_options,
+        ..
+    } = args;
 
     let max_diagnostics = if cli_options.reporter.is_default() {
         cli_options.max_diagnostics.into()
@@ -26,28 +108,45 @@ pub fn dblint(
 
     let start = Instant::now();
 
+    // Diagnostics are filtered by the severity threshold and the baseline before the limit is
+    // applied, so all of them need to be pulled
+    let collect_all =
+        rule_selection_options.severity_threshold.is_some() || baseline_options.is_enabled();
+
     let params = PullDatabaseDiagnosticsParams {
         categories: RuleCategoriesBuilder::default().all().build(),
-        max_diagnostics,
-        only: Vec::new(), // Uses configuration settings
-        skip: Vec::new(), // Uses configuration settings
+        max_diagnostics: if collect_all {
+            u32::MAX
+        } else {
+            max_diagnostics
+        },
+        only: rule_selection_options.only.clone(),
+        skip: rule_selection_options.skip.clone(),
     };
 
     let PullDiagnosticsResult {
         diagnostics,
         skipped_diagnostics,
-    } = workspace.pull_db_diagnostics(params)?;
+    } = session.workspace().pull_db_diagnostics(params)?;
 
-    let report = Report::new(
-        diagnostics.into_iter().map(Error::from).collect(),
+    let mut report = Report::new(
+        diagnostics
+            .into_iter()
+            .map(Error::from)
+            .filter(|diagnostic| rule_selection_options.is_reported(diagnostic))
+            .collect(),
         start.elapsed(),
         skipped_diagnostics,
         None,
     );
+    if baseline_options.is_enabled() {
+        report = apply_baseline(session.console(), baseline_options, report)?;
+    }
+    if collect_all {
+        report = report.truncate(max_diagnostics);
+    }
 
-    let exit_result = enforce_exit_codes(cli_options, &report);
-    session.report("dblint", cli_options, &report)?;
-    exit_result
+    Ok(report)
 }
 
 fn enforce_exit_codes(cli_options: &CliOptions, payload: &Report) -> Result<(), CliDiagnostic> {
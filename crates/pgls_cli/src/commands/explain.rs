@@ -0,0 +1,333 @@
+use crate::{CliDiagnostic, CliSession};
+use pgls_analyse::{RegistryVisitor, RuleGroup, RuleMeta, RuleMetadata};
+use pgls_console::{Console, ConsoleExt, markup};
+use serde_json::{Value, json};
+
+/// Handle of the `explain` command. Prints the documentation of a rule, or a table of all rules.
+pub(crate) fn explain(
+    mut session: CliSession,
+    rule: Option<String>,
+    list: bool,
+) -> Result<(), CliDiagnostic> {
+    let rules = collect_rules();
+    let console = session.console();
+
+    match (rule, list) {
+        (Some(_), true) => Err(CliDiagnostic::incompatible_arguments("RULE", "list")),
+        (None, false) => Err(CliDiagnostic::missing_argument("RULE", "explain")),
+        (None, true) => {
+            print_rules_table(console, &rules);
+            Ok(())
+        }
+        (Some(name), false) => {
+            // The rule can be referred to by its name, its group and name, or its full category
+            let suffix = format!("/{name}");
+            let matches: Vec<_> = rules
+                .iter()
+                .filter(|rule| {
+                    let id = rule.id();
+                    id == name || id.ends_with(&suffix)
+                })
+                .collect();
+
+            if matches.is_empty() {
+                return Err(CliDiagnostic::unknown_rule(name));
+            }
+
+            for (index, rule) in matches.into_iter().enumerate() {
+                if index > 0 {
+                    console.log(markup! {""});
+                }
+                print_rule(console, rule);
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// The analysers whose rules can be explained
+#[derive(Debug, Clone, Copy)]
+enum Analyser {
+    Linter,
+    Splinter,
+    Pglinter,
+}
+
+impl Analyser {
+    /// The prefix of the diagnostic categories of the rules
+    const fn category_prefix(self) -> &'static str {
+        match self {
+            Self::Linter => "lint",
+            Self::Splinter => "splinter",
+            Self::Pglinter => "pglinter",
+        }
+    }
+
+    /// The key of the analyser in the configuration file
+    const fn configuration_key(self) -> &'static str {
+        match self {
+            Self::Linter => "linter",
+            Self::Splinter => "splinter",
+            Self::Pglinter => "pglinter",
+        }
+    }
+
+    /// The JSON schema of the rules configuration of the analyser
+    fn rules_schema(self) -> Value {
+        let schema = match self {
+            Self::Linter => schemars::schema_for!(pgls_configuration::linter::Rules),
+            Self::Splinter => schemars::schema_for!(pgls_configuration::splinter::Rules),
+            Self::Pglinter => schemars::schema_for!(pgls_configuration::pglinter::Rules),
+        };
+        serde_json::to_value(schema).unwrap_or_default()
+    }
+}
+
+struct ExplainedRule {
+    analyser: Analyser,
+    group: &'static str,
+    metadata: RuleMetadata,
+}
+
+impl ExplainedRule {
+    fn id(&self) -> String {
+        format!(
+            "{}/{}/{}",
+            self.analyser.category_prefix(),
+            self.group,
+            self.metadata.name
+        )
+    }
+}
+
+struct RulesCollector {
+    analyser: Analyser,
+    rules: Vec<ExplainedRule>,
+}
+
+impl RegistryVisitor for RulesCollector {
+    fn record_rule<R>(&mut self)
+    where
+        R: RuleMeta + 'static,
+    {
+        self.rules.push(ExplainedRule {
+            analyser: self.analyser,
+            group: <R::Group as RuleGroup>::NAME,
+            metadata: R::METADATA,
+        });
+    }
+}
+
+fn collect_rules() -> Vec<ExplainedRule> {
+    let mut collector = RulesCollector {
+        analyser: Analyser::Linter,
+        rules: vec![],
+    };
+    pgls_analyser::visit_registry(&mut collector);
+    collector.analyser = Analyser::Splinter;
+    pgls_splinter::registry::visit_registry(&mut collector);
+    collector.analyser = Analyser::Pglinter;
+    pgls_pglinter::registry::visit_registry(&mut collector);
+
+    collector.rules
+}
+
+fn print_rules_table(console: &mut dyn Console, rules: &[ExplainedRule]) {
+    let groups: Vec<String> = rules
+        .iter()
+        .map(|rule| format!("{}/{}", rule.analyser.category_prefix(), rule.group))
+        .collect();
+
+    let name_width = rules
+        .iter()
+        .map(|rule| rule.metadata.name.len())
+        .chain(std::iter::once("Rule".len()))
+        .max()
+        .unwrap_or_default();
+    let group_width = groups
+        .iter()
+        .map(String::len)
+        .chain(std::iter::once("Group".len()))
+        .max()
+        .unwrap_or_default();
+
+    let header = format!(
+        "{:name_width$}  {:group_width$}  Recommended",
+        "Rule", "Group"
+    );
+    console.log(markup! {<Emphasis>{header}</Emphasis>});
+
+    for (rule, group) in rules.iter().zip(&groups) {
+        let row = format!("{:name_width$}  {group:group_width$}  ", rule.metadata.name);
+        if rule.metadata.recommended {
+            console.log(markup! {{row}<Success>"yes"</Success>});
+        } else {
+            console.log(markup! {{row}<Dim>"no"</Dim>});
+        }
+    }
+}
+
+fn print_rule(console: &mut dyn Console, rule: &ExplainedRule) {
+    let metadata = &rule.metadata;
+
+    console.log(markup! {<Emphasis>{rule.id()}</Emphasis>"\n"});
+
+    let recommended = if metadata.recommended { "yes" } else { "no" };
+    console.log(markup! {
+        "  Severity:     "{metadata.severity.to_string()}"\n"
+        "  Recommended:  "{recommended}
+    });
+    if let Some(reason) = metadata.deprecated {
+        console.log(markup! {
+            "  Deprecated:   "<Warn>{reason}</Warn>
+        });
+    }
+    for (index, source) in metadata.sources.iter().enumerate() {
+        let label = if index == 0 { "Sources:" } else { "" };
+        let source_text = format!(
+            "{label:<14}{source} {} ({})",
+            source.as_rule_name(),
+            source.to_rule_url()
+        );
+        console.log(markup! {"  "{source_text}});
+    }
+    console.log(markup! {""});
+
+    print_docs(console, metadata.docs);
+
+    let level = metadata.severity.to_string();
+    let configuration = json!({
+        rule.analyser.configuration_key(): {
+            "rules": {
+                rule.group: {
+                    metadata.name: level
+                }
+            }
+        }
+    });
+    console.log(markup! {<Emphasis>"How to configure"</Emphasis>"\n"});
+    print_code_block(
+        console,
+        &serde_json::to_string_pretty(&configuration).unwrap_or_default(),
+    );
+
+    console.log(markup! {<Emphasis>"Options"</Emphasis>"\n"});
+    match find_options_schema(&rule.analyser.rules_schema(), rule.group, metadata.name) {
+        Some(schema) => print_code_block(
+            console,
+            &serde_json::to_string_pretty(&schema).unwrap_or_default(),
+        ),
+        None => console.log(markup! {"This rule has no options.\n"}),
+    }
+}
+
+/// Prints the markdown documentation of a rule. Lines of code blocks prefixed with `# ` are only
+/// used to set up the examples, and the configuration section of generated docs is replaced by
+/// the one printed by the command.
+fn print_docs(console: &mut dyn Console, docs: &str) {
+    let mut in_code_block = false;
+    let mut skipped_section_level = None;
+
+    // Docs written as doc comments keep the space that follows `///`
+    let docs = docs.trim_end();
+    let is_doc_comment = docs
+        .lines()
+        .filter(|line| !line.is_empty())
+        .all(|line| line.starts_with(' '));
+
+    for line in docs.lines() {
+        let line = match line.strip_prefix(' ') {
+            Some(stripped) if is_doc_comment => stripped,
+            _ => line,
+        };
+
+        if line.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if !in_code_block && line.starts_with('#') {
+            let level = line.chars().take_while(|c| *c == '#').count();
+            let title = line[level..].trim();
+
+            if skipped_section_level.is_some_and(|skipped| level > skipped) {
+                continue;
+            }
+            skipped_section_level = (title == "Configuration").then_some(level);
+            if skipped_section_level.is_none() {
+                console.log(markup! {<Emphasis>{title}</Emphasis>});
+            }
+            continue;
+        }
+
+        if skipped_section_level.is_some() {
+            continue;
+        }
+
+        if in_code_block {
+            if !line.starts_with("# ") {
+                console.log(markup! {"    "{line}});
+            }
+        } else {
+            console.log(markup! {{line}});
+        }
+    }
+
+    console.log(markup! {""});
+}
+
+fn print_code_block(console: &mut dyn Console, code: &str) {
+    for line in code.lines() {
+        console.log(markup! {"    "{line}});
+    }
+    console.log(markup! {""});
+}
+
+/// Finds the schema of the options of a rule in the JSON schema of the rules configuration.
+///
+/// A rule is configured either with a plain level, or with a level and its options, so the
+/// options are found among the alternatives of the rule's schema.
+fn find_options_schema(rules_schema: &Value, group: &str, rule: &str) -> Option<Value> {
+    let definitions = rules_schema.get("definitions")?;
+
+    let group_schema = rules_schema.get("properties")?.get(group)?;
+    let rule_schema = alternatives(definitions, group_schema)
+        .into_iter()
+        .find_map(|schema| schema.get("properties")?.get(rule))?;
+
+    let options_schema = alternatives(definitions, rule_schema)
+        .into_iter()
+        .find_map(|schema| schema.get("properties")?.get("options"))?;
+
+    alternatives(definitions, options_schema)
+        .into_iter()
+        .find(|schema| schema.get("type").and_then(Value::as_str) != Some("null"))
+        .cloned()
+}
+
+/// Resolves the references and flattens the combinations of a schema
+fn alternatives<'a>(definitions: &'a Value, schema: &'a Value) -> Vec<&'a Value> {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .strip_prefix("#/definitions/")
+            .and_then(|name| definitions.get(name))
+            .map(|resolved| alternatives(definitions, resolved))
+            .unwrap_or_default();
+    }
+
+    let combinations: Vec<&Value> = ["anyOf", "allOf", "oneOf"]
+        .iter()
+        .filter_map(|key| schema.get(*key)?.as_array())
+        .flatten()
+        .collect();
+
+    if combinations.is_empty() {
+        vec![schema]
+    } else {
+        combinations
+            .into_iter()
+            .flat_map(|schema| alternatives(definitions, schema))
+            .collect()
+    }
+}
@@ -1,6 +1,6 @@
 use crate::cli_options::CliOptions;
-use crate::commands::get_files_to_process_with_cli_options;
-use crate::execute::run_files;
+use crate::commands::{get_files_to_process_with_cli_options, read_stdin_payload};
+use crate::execute::{run_files, run_stdin};
 use crate::reporter::Report;
 use crate::{CliDiagnostic, CliSession, VcsIntegration};
 use crate::{ExecutionConfig, ExecutionMode, VcsTargeting};
@@ -14,6 +14,7 @@ pub struct FormatArgs {
     pub configuration: Option<PartialConfiguration>,
     pub paths: Vec<OsString>,
     pub write: bool,
+    pub stdin_file_path: Option<String>,
     pub staged: bool,
     pub changed: bool,
     pub since: Option<String>,
@@ -42,6 +43,11 @@ pub fn format(
     };
     let execution = ExecutionConfig::new(mode, u32::MAX);
 
+    if let Some(stdin_path) = args.stdin_file_path.as_deref() {
+        let payload = read_stdin_payload(stdin_path, session.console(), "format")?;
+        return run_stdin(&mut session, &execution, payload);
+    }
+
     let report: Report = run_files(&mut session, &execution, paths)?;
 
     let exit_result = enforce_exit_codes(cli_options, &report);
@@ -105,5 +111,12 @@ fn validate_args(args: &FormatArgs) -> Result<(), CliDiagnostic> {
         return Err(CliDiagnostic::incompatible_arguments("changed", "staged"));
     }
 
+    if args.stdin_file_path.is_some() && args.write {
+        return Err(CliDiagnostic::incompatible_arguments(
+            "stdin-file-path",
+            "write",
+        ));
+    }
+
     Ok(())
 }
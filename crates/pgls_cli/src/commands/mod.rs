@@ -1,9 +1,14 @@
 use crate::changed::{get_changed_files, get_staged_files};
-use crate::cli_options::{CliOptions, CliReporter, ColorsArg, cli_options};
+use crate::cli_options::{
+    ApplyOptions, BaselineOptions, CliOptions, CliReporter, ColorsArg, RuleSelectionOptions,
+    apply_options, baseline_options, cli_options, rule_selection_options,
+};
+use crate::execute::StdinPayload;
 use crate::logging::LoggingKind;
 use crate::{CliDiagnostic, LoggingLevel, VERSION};
 use bpaf::Bpaf;
 use pgls_configuration::{PartialConfiguration, partial_configuration};
+use pgls_console::Console;
 use pgls_fs::FileSystem;
 use pgls_workspace::DynRef;
 use std::ffi::OsString;
@@ -12,8 +17,10 @@ pub(crate) mod check;
 pub(crate) mod clean;
 pub(crate) mod daemon;
 pub(crate) mod dblint;
+pub(crate) mod explain;
 pub(crate) mod format;
 pub(crate) mod init;
+pub(crate) mod schema_diff;
 pub(crate) mod schema_export;
 pub(crate) mod version;
 
@@ -34,6 +41,28 @@ pub enum PgLSCommand {
 
         #[bpaf(external, hide_usage)]
         cli_options: CliOptions,
+
+        #[bpaf(external, hide_usage)]
+        rule_selection_options: RuleSelectionOptions,
+
+        #[bpaf(external, hide_usage)]
+        baseline_options: BaselineOptions,
+
+        /// Keep running and lint the database again whenever its schema cache is invalidated,
+        /// or when the poll interval elapses.
+        #[bpaf(long("watch"), switch)]
+        watch: bool,
+
+        /// The number of seconds after which the database is linted again in watch mode, even if
+        /// no change of the schema was noticed.
+        #[bpaf(
+            long("poll-interval"),
+            argument("SECONDS"),
+            guard(is_positive, "The poll interval must be at least one second"),
+            fallback(30),
+            display_fallback
+        )]
+        poll_interval: u64,
     },
 
     /// Runs everything to the requested files.
@@ -45,6 +74,15 @@ pub enum PgLSCommand {
         #[bpaf(external, hide_usage)]
         cli_options: CliOptions,
 
+        #[bpaf(external, hide_usage)]
+        rule_selection_options: RuleSelectionOptions,
+
+        #[bpaf(external, hide_usage)]
+        baseline_options: BaselineOptions,
+
+        #[bpaf(external, hide_usage)]
+        apply_options: ApplyOptions,
+
         /// Use this option when you want to format code piped from `stdin`, and print the output to `stdout`.
         ///
         /// The file doesn't need to exist on disk, what matters is the extension of the file. Based on the extension, we know how to check the code.
@@ -68,6 +106,11 @@ pub enum PgLSCommand {
         #[bpaf(long("since"), argument("REF"))]
         since: Option<String>,
 
+        /// Keep running and check the files again whenever they change. Only the files that
+        /// changed are checked again, and the report is printed after each change.
+        #[bpaf(long("watch"), switch)]
+        watch: bool,
+
         /// Single file, single path or list of paths
         #[bpaf(positional("PATH"), many)]
         paths: Vec<OsString>,
@@ -86,6 +129,14 @@ pub enum PgLSCommand {
         #[bpaf(long("write"), switch)]
         write: bool,
 
+        /// Use this option when you want to format code piped from `stdin`, and print the output to `stdout`.
+        ///
+        /// The file doesn't need to exist on disk, what matters is the extension of the file. Based on the extension, we know how to format the code.
+        ///
+        /// Example: `echo 'select 1' | pgls_cli format --stdin-file-path=test.sql`
+        #[bpaf(long("stdin-file-path"), argument("PATH"), hide_usage)]
+        stdin_file_path: Option<String>,
+
         /// When set to true, only the files that have been staged (the ones prepared to be committed)
         /// will be formatted. This option should be used when working locally.
         #[bpaf(long("staged"), switch)]
@@ -106,6 +157,19 @@ pub enum PgLSCommand {
         paths: Vec<OsString>,
     },
 
+    /// Prints the documentation of a rule: its description, examples, severity, sources and
+    /// how to configure it.
+    #[bpaf(command)]
+    Explain {
+        /// List all rules with their group and whether they are recommended.
+        #[bpaf(long("list"), switch)]
+        list: bool,
+
+        /// The name of the rule, e.g. `banDropColumn`, `safety/banDropColumn` or `lint/safety/banDropColumn`
+        #[bpaf(positional("RULE"), optional)]
+        rule: Option<String>,
+    },
+
     /// Starts the daemon server process.
     #[bpaf(command)]
     Start {
@@ -206,6 +270,38 @@ pub enum PgLSCommand {
         output: Option<PathBuf>,
     },
 
+    /// Compares the schemas of two sources and reports the tables, columns, functions, types,
+    /// indexes, policies, roles and extensions that were added, removed or changed.
+    #[bpaf(command("schema-diff"))]
+    SchemaDiff {
+        #[bpaf(external(partial_configuration), hide_usage, optional)]
+        configuration: Option<PartialConfiguration>,
+
+        #[bpaf(external, hide_usage)]
+        cli_options: CliOptions,
+
+        /// A database on the server to replay the migrations on when a source is `migrations`.
+        /// They are replayed onto a temporary database that is created next to it and dropped
+        /// afterwards, so the role needs the `CREATEDB` privilege.
+        #[bpaf(long("replay-on"), argument("URL"))]
+        replay_on: Option<String>,
+
+        /// Print the DDL that turns the schema of FROM into the schema of TO, and analyse it
+        /// with the safety rules.
+        #[bpaf(long("ddl"), switch)]
+        ddl: bool,
+
+        /// The schema to compare from: a connection string, the path to a JSON file written
+        /// by `schema-export`, or `migrations` for the migrations replayed on the server of
+        /// `--replay-on`.
+        #[bpaf(positional("FROM"))]
+        from: String,
+
+        /// The schema to compare to, in the same formats as FROM.
+        #[bpaf(positional("TO"))]
+        to: String,
+    },
+
     #[bpaf(command("__run_server"), hide)]
     RunServer {
         /// Allows to change the prefix applied to the file name of the logs.
@@ -273,11 +369,13 @@ impl PgLSCommand {
             PgLSCommand::Version(cli_options)
             | PgLSCommand::Check { cli_options, .. }
             | PgLSCommand::Dblint { cli_options, .. }
-            | PgLSCommand::Format { cli_options, .. } => Some(cli_options),
+            | PgLSCommand::Format { cli_options, .. }
+            | PgLSCommand::SchemaDiff { cli_options, .. } => Some(cli_options),
             PgLSCommand::LspProxy { .. }
             | PgLSCommand::Start { .. }
             | PgLSCommand::Stop
             | PgLSCommand::Init
+            | PgLSCommand::Explain { .. }
             | PgLSCommand::RunServer { .. }
             | PgLSCommand::Clean
             | PgLSCommand::SchemaExport { .. }
@@ -311,6 +409,14 @@ impl PgLSCommand {
         }
     }
 
+    /// Whether the command keeps running until it is interrupted
+    pub const fn is_watch(&self) -> bool {
+        matches!(
+            self,
+            PgLSCommand::Check { watch: true, .. } | PgLSCommand::Dblint { watch: true, .. }
+        )
+    }
+
     pub const fn has_metrics(&self) -> bool {
         false
     }
@@ -331,6 +437,28 @@ impl PgLSCommand {
     }
 }
 
+/// Rejects options that would make the command busy-loop, e.g. a poll interval of zero
+fn is_positive(value: &u64) -> bool {
+    *value > 0
+}
+
+/// Reads the code piped to the command, which is processed as if it was the file at `path`
+pub(crate) fn read_stdin_payload(
+    path: &str,
+    console: &mut dyn Console,
+    command: &str,
+) -> Result<StdinPayload, CliDiagnostic> {
+    let input_code = console.read();
+    if let Some(input_code) = input_code {
+        Ok(StdinPayload {
+            path: path.into(),
+            content: input_code,
+        })
+    } else {
+        Err(CliDiagnostic::missing_argument("stdin", command))
+    }
+}
+
 pub(crate) fn get_files_to_process_with_cli_options(
     since: Option<&str>,
     changed: bool,
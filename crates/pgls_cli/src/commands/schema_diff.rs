@@ -0,0 +1,432 @@
+//! Compares the schemas of two sources, e.g. a production database and the schema that the
+//! migrations of a branch result in, and reports what differs.
+//!
+//! A source is either a live database, a JSON snapshot written by `schema-export`, or the
+//! migrations of the project replayed onto a temporary database. Optionally, the DDL that turns
+//! the first schema into the second is printed and analysed with the safety rules.
+
+use std::io;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::apply::{connect_options, replay_migrations};
+use crate::cli_options::{CliOptions, CliReporter};
+use crate::reporter::Report;
+use crate::reporter::json::{JsonDiagnostic, to_json_report};
+use crate::{CliDiagnostic, CliSession, VcsIntegration};
+use pgls_analyse::RuleCategoriesBuilder;
+use pgls_configuration::database::PartialDatabaseConfiguration;
+use pgls_configuration::{Merge, PartialConfiguration};
+use pgls_console::{ConsoleExt, markup};
+use pgls_diagnostics::{DiagnosticExt, Error, category};
+use pgls_fs::{OpenOptions, PgLSPath};
+use pgls_schema_cache::{ChangeKind, SchemaCache, SchemaChange, SchemaDiff};
+use pgls_workspace::features::diagnostics::PullFileDiagnosticsParams;
+use pgls_workspace::workspace::OpenFileParams;
+use serde::Serialize;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{Connection, PgConnection};
+use tokio::runtime::Runtime;
+
+/// The name of the source that stands for the replayed migrations
+const MIGRATIONS_SOURCE: &str = "migrations";
+
+/// The path under which the generated DDL is analysed and reported
+const DDL_FILE_NAME: &str = "schema-diff.sql";
+
+pub struct SchemaDiffArgs {
+    pub configuration: Option<PartialConfiguration>,
+    pub from: String,
+    pub to: String,
+    pub replay_on: Option<String>,
+    pub ddl: bool,
+}
+
+/// Where the schema of one side of the comparison comes from
+enum SchemaSource {
+    /// A connection string of a live database
+    Database(String),
+    /// A JSON file written by `schema-export`
+    Snapshot(PathBuf),
+    /// The migrations of the project, applied to a temporary database on the server of
+    /// `--replay-on`
+    Migrations,
+}
+
+impl SchemaSource {
+    fn parse(source: &str) -> Self {
+        if source == MIGRATIONS_SOURCE {
+            Self::Migrations
+        } else if source.starts_with("postgres://") || source.starts_with("postgresql://") {
+            Self::Database(source.to_string())
+        } else {
+            Self::Snapshot(PathBuf::from(source))
+        }
+    }
+}
+
+pub fn schema_diff(
+    mut session: CliSession,
+    cli_options: &CliOptions,
+    args: SchemaDiffArgs,
+) -> Result<(), CliDiagnostic> {
+    let start = Instant::now();
+
+    let from = SchemaSource::parse(&args.from);
+    let to = SchemaSource::parse(&args.to);
+    if matches!(from, SchemaSource::Migrations) && matches!(to, SchemaSource::Migrations) {
+        return Err(CliDiagnostic::incompatible_end_configuration(
+            "Only one side of the comparison can be the replayed migrations.",
+        ));
+    }
+
+    let configuration = session.prepare_with_config(cli_options, args.configuration)?;
+
+    let runtime = Runtime::new().map_err(CliDiagnostic::io_error)?;
+    let from = load_schema(
+        &mut session,
+        cli_options,
+        &configuration,
+        &runtime,
+        from,
+        args.replay_on.as_deref(),
+    )?;
+    let to = load_schema(
+        &mut session,
+        cli_options,
+        &configuration,
+        &runtime,
+        to,
+        args.replay_on.as_deref(),
+    )?;
+
+    let diff = SchemaDiff::new(&from, &to);
+    let ddl = args.ddl.then(|| diff.ddl().join("\n"));
+
+    let diagnostics = match &ddl {
+        Some(ddl) => analyse_ddl(&mut session, configuration, ddl)?,
+        None => vec![],
+    };
+    let report = Report::new(diagnostics, start.elapsed(), 0, None);
+
+    if matches!(
+        cli_options.reporter,
+        CliReporter::Json | CliReporter::JsonPretty
+    ) {
+        let output = JsonOutput {
+            changes: &diff.changes,
+            ddl: ddl.as_deref(),
+            diagnostics: report.diagnostics.iter().map(to_json_report).collect(),
+        };
+        let serialized = if matches!(cli_options.reporter, CliReporter::JsonPretty) {
+            serde_json::to_string_pretty(&output)
+        } else {
+            serde_json::to_string(&output)
+        }
+        .map_err(|e| CliDiagnostic::io_error(io::Error::other(e)))?;
+        session.console().log(markup!({ serialized }));
+    } else {
+        print_changes(&mut session, &diff);
+        if let Some(ddl) = &ddl {
+            session.console().log(markup! {
+                "\n"<Emphasis>"DDL:"</Emphasis>"\n\n"{ddl}
+            });
+        }
+        if args.ddl {
+            session.report("schema-diff", cli_options, &report)?;
+        }
+    }
+
+    if report.errors > 0 {
+        return Err(CliDiagnostic::check_error(category!("check")));
+    }
+    if report.warnings > 0 && cli_options.error_on_warnings {
+        return Err(CliDiagnostic::check_warnings(category!("check")));
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct JsonOutput<'a> {
+    changes: &'a [SchemaChange],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ddl: Option<&'a str>,
+    diagnostics: Vec<JsonDiagnostic>,
+}
+
+fn load_schema(
+    session: &mut CliSession,
+    cli_options: &CliOptions,
+    configuration: &PartialConfiguration,
+    runtime: &Runtime,
+    source: SchemaSource,
+    replay_on: Option<&str>,
+) -> Result<SchemaCache, CliDiagnostic> {
+    match source {
+        SchemaSource::Database(connection_string) => {
+            runtime.block_on(load_schema_cache(&connect_options(&connection_string)?))
+        }
+        SchemaSource::Snapshot(path) => {
+            let mut content = String::new();
+            session
+                .fs()
+                .open_with_options(&path, OpenOptions::default().read(true))
+                .and_then(|mut file| file.read_to_string(&mut content))
+                .map_err(|e| {
+                    CliDiagnostic::io_error(io::Error::other(format!(
+                        "Failed to read the schema snapshot {}: {e}",
+                        path.display()
+                    )))
+                })?;
+            serde_json::from_str(&content).map_err(|e| {
+                CliDiagnostic::io_error(io::Error::other(format!(
+                    "Failed to parse the schema snapshot {}: {e}",
+                    path.display()
+                )))
+            })
+        }
+        SchemaSource::Migrations => {
+            let Some(connection_string) = replay_on else {
+                return Err(CliDiagnostic::missing_argument(
+                    "--replay-on",
+                    "schema-diff",
+                ));
+            };
+
+            let scratch = ScratchDatabase::create(runtime, connection_string)?;
+            let schema = replay_onto(session, cli_options, configuration, runtime, &scratch)?;
+            scratch.remove()?;
+            Ok(schema)
+        }
+    }
+}
+
+/// Replays all migrations onto the empty scratch database and loads the resulting schema
+fn replay_onto(
+    session: &mut CliSession,
+    cli_options: &CliOptions,
+    configuration: &PartialConfiguration,
+    runtime: &Runtime,
+    scratch: &ScratchDatabase<'_>,
+) -> Result<SchemaCache, CliDiagnostic> {
+    let working_directory = session.fs().working_directory().unwrap_or_default();
+    let diagnostics = replay_migrations(
+        &**session.fs(),
+        &working_directory,
+        configuration,
+        &scratch.connect_options(),
+        false,
+    )?;
+    if !diagnostics.is_empty() {
+        let report = Report::new(diagnostics, Default::default(), 0, None);
+        session.report("schema-diff", cli_options, &report)?;
+        return Err(CliDiagnostic::check_error(category!("migrations/apply")));
+    }
+
+    runtime.block_on(load_schema_cache(&scratch.connect_options()))
+}
+
+/// A temporary database on the server of `--replay-on`, so that the migrations are always
+/// replayed onto an empty database. It is dropped once it goes out of scope, so nothing is left
+/// behind even if replaying the migrations or loading the schema fails.
+struct ScratchDatabase<'a> {
+    runtime: &'a Runtime,
+    /// The options of the database of `--replay-on`, which the scratch database is managed from
+    server: PgConnectOptions,
+    name: String,
+    /// Whether the database was already dropped by [ScratchDatabase::remove]
+    removed: bool,
+}
+
+impl<'a> ScratchDatabase<'a> {
+    fn create(runtime: &'a Runtime, connection_string: &str) -> Result<Self, CliDiagnostic> {
+        let server = connect_options(connection_string)?;
+        let name = format!(
+            "pgls_replay_{}_{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis()
+        );
+
+        runtime.block_on(async {
+            let mut conn = connect(&server).await?;
+            let created = sqlx::raw_sql(&format!("create database \"{name}\""))
+                .execute(&mut conn)
+                .await;
+            conn.close().await.ok();
+            created.map_err(|e| {
+                CliDiagnostic::io_error(io::Error::other(format!(
+                    "Failed to create a database to replay the migrations onto: {e}"
+                )))
+            })
+        })?;
+
+        Ok(Self {
+            runtime,
+            server,
+            name,
+            removed: false,
+        })
+    }
+
+    fn connect_options(&self) -> PgConnectOptions {
+        self.server.clone().database(&self.name)
+    }
+
+    /// Drops the database and returns the error if that fails. When the database goes out of
+    /// scope instead, it is dropped as well, but a failure is only logged.
+    fn remove(mut self) -> Result<(), CliDiagnostic> {
+        self.removed = true;
+        self.runtime
+            .block_on(drop_database(&self.server, &self.name))
+    }
+}
+
+impl Drop for ScratchDatabase<'_> {
+    fn drop(&mut self) {
+        if self.removed {
+            return;
+        }
+        if let Err(err) = self
+            .runtime
+            .block_on(drop_database(&self.server, &self.name))
+        {
+            tracing::error!("{err:?}");
+        }
+    }
+}
+
+async fn drop_database(server: &PgConnectOptions, name: &str) -> Result<(), CliDiagnostic> {
+    let mut conn = connect(server).await?;
+    let server_version_num: i32 =
+        sqlx::query_scalar("select current_setting('server_version_num')::int")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap_or_default();
+    // connections of a failed replay might not be closed yet
+    let force = if server_version_num >= 130000 {
+        " with (force)"
+    } else {
+        ""
+    };
+    let dropped = sqlx::raw_sql(&format!("drop database if exists \"{name}\"{force}"))
+        .execute(&mut conn)
+        .await;
+    conn.close().await.ok();
+    dropped.map_err(|e| {
+        CliDiagnostic::io_error(io::Error::other(format!(
+            "Failed to drop the database \"{name}\" the migrations were replayed onto: {e}"
+        )))
+    })?;
+
+    Ok(())
+}
+
+async fn connect(options: &PgConnectOptions) -> Result<PgConnection, CliDiagnostic> {
+    PgConnection::connect_with(options).await.map_err(|e| {
+        CliDiagnostic::io_error(io::Error::other(format!(
+            "Failed to connect to database: {e}"
+        )))
+    })
+}
+
+async fn load_schema_cache(options: &PgConnectOptions) -> Result<SchemaCache, CliDiagnostic> {
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect_with(options.clone())
+        .await
+        .map_err(|e| {
+            CliDiagnostic::io_error(io::Error::other(format!(
+                "Failed to connect to database: {e}"
+            )))
+        })?;
+
+    let schema_cache = SchemaCache::load(&pool).await.map_err(|e| {
+        CliDiagnostic::io_error(io::Error::other(format!(
+            "Failed to load schema cache: {e}"
+        )))
+    });
+    pool.close().await;
+
+    schema_cache
+}
+
+/// Runs the safety rules against the generated DDL. The statements aren't run against a
+/// database, so the analysis doesn't need a connection.
+fn analyse_ddl(
+    session: &mut CliSession,
+    mut configuration: PartialConfiguration,
+    ddl: &str,
+) -> Result<Vec<Error>, CliDiagnostic> {
+    configuration.merge_with(PartialConfiguration {
+        db: Some(PartialDatabaseConfiguration {
+            disable_connection: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    session.setup_workspace(configuration, VcsIntegration::Disabled)?;
+
+    let path = PgLSPath::new(DDL_FILE_NAME);
+    let workspace = session.workspace();
+    workspace.open_file(OpenFileParams {
+        path: path.clone(),
+        content: ddl.to_string(),
+        version: 0,
+    })?;
+    let result = workspace.pull_file_diagnostics(PullFileDiagnosticsParams {
+        path,
+        categories: RuleCategoriesBuilder::default().with_lint().build(),
+        max_diagnostics: u32::MAX,
+        only: vec![],
+        skip: vec![],
+    })?;
+
+    Ok(result
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            Error::from(diagnostic)
+                .with_file_path(DDL_FILE_NAME)
+                .with_file_source_code(ddl)
+        })
+        .collect())
+}
+
+fn print_changes(session: &mut CliSession, diff: &SchemaDiff) {
+    let console = session.console();
+    if diff.is_empty() {
+        console.log(markup! {
+            <Info>"The schemas are identical."</Info>
+        });
+        return;
+    }
+
+    for change in &diff.changes {
+        let kind = change.object_kind.as_str();
+        let name = change.name.as_str();
+        match change.kind {
+            ChangeKind::Added => console.log(markup! {
+                <Success>"+ "{kind}" "{name}</Success>
+            }),
+            ChangeKind::Removed => console.log(markup! {
+                <Error>"- "{kind}" "{name}</Error>
+            }),
+            ChangeKind::Changed => console.log(markup! {
+                <Warn>"~ "{kind}" "{name}</Warn>
+            }),
+        }
+        for detail in &change.details {
+            console.log(markup! {
+                "    "{detail}
+            });
+        }
+    }
+
+    console.log(markup! {
+        "\n"<Info>"Found "{diff.changes.len()}" change(s)."</Info>
+    });
+}
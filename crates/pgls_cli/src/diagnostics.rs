@@ -36,6 +36,8 @@ pub enum CliDiagnostic {
     EmptyArguments(EmptyArguments),
     /// Returned when a subcommand is called with an unsupported combination of arguments
     IncompatibleArguments(IncompatibleArguments),
+    /// Returned when a rule passed as argument doesn't exist
+    UnknownRule(UnknownRule),
     /// Returned by a traversal command when error diagnostics were emitted
     CheckError(CheckError),
     /// Emitted when a file is fixed, but it still contains diagnostics.
@@ -151,6 +153,21 @@ pub struct IncompatibleArguments {
     second_argument: String,
 }
 
+#[derive(Debug, Diagnostic)]
+#[diagnostic(
+    category = "flags/invalid",
+    severity = Error,
+    message(
+        description = "Unknown rule {rule_name}",
+        message("Unknown rule "<Emphasis>{self.rule_name}</Emphasis>)
+    ),
+)]
+pub struct UnknownRule {
+    rule_name: String,
+    #[advice]
+    advice: ListRulesAdvice,
+}
+
 #[derive(Debug, Diagnostic)]
 #[diagnostic(
     severity = Error,
@@ -268,6 +285,23 @@ impl Advices for CliAdvice {
     }
 }
 
+/// Advices pointing to the command that lists all rules
+#[derive(Debug, Default)]
+struct ListRulesAdvice;
+
+impl Advices for ListRulesAdvice {
+    fn record(&self, visitor: &mut dyn Visit) -> std::io::Result<()> {
+        let list_command = format!("{} explain --list", command_name());
+        visitor.record_log(
+            LogCategory::Info,
+            &markup! { "Type the following command to list all rules" },
+        )?;
+        visitor.record_command(&list_command)?;
+
+        Ok(())
+    }
+}
+
 impl CliDiagnostic {
     /// Returned when a subcommand is called with an unsupported combination of arguments
     pub fn incompatible_arguments(
@@ -280,6 +314,14 @@ impl CliDiagnostic {
         })
     }
 
+    /// Returned when a rule passed as argument doesn't exist
+    pub fn unknown_rule(rule_name: impl Into<String>) -> Self {
+        Self::UnknownRule(UnknownRule {
+            rule_name: rule_name.into(),
+            advice: ListRulesAdvice,
+        })
+    }
+
     /// To throw when there's been an error while parsing an argument
     pub fn parse_error_bpaf(source: bpaf::ParseFailure) -> Self {
         Self::ParseError(ParseDiagnostic {
@@ -1,3 +1,5 @@
+use crate::cli_options::RuleSelectionOptions;
+
 #[derive(Debug, Clone)]
 pub struct ExecutionConfig {
     pub mode: ExecutionMode,
@@ -26,8 +28,14 @@ impl ExecutionConfig {
 
 #[derive(Debug, Clone)]
 pub enum ExecutionMode {
-    Check { vcs: VcsTargeting },
-    Format { write: bool, vcs: VcsTargeting },
+    Check {
+        vcs: VcsTargeting,
+        rule_selection: RuleSelectionOptions,
+    },
+    Format {
+        write: bool,
+        vcs: VcsTargeting,
+    },
 }
 
 impl ExecutionMode {
@@ -40,7 +48,7 @@ impl ExecutionMode {
 
     pub fn vcs(&self) -> &VcsTargeting {
         match self {
-            ExecutionMode::Check { vcs } => vcs,
+            ExecutionMode::Check { vcs, .. } => vcs,
             ExecutionMode::Format { vcs, .. } => vcs,
         }
     }
@@ -3,6 +3,7 @@ mod diagnostics;
 mod process_file;
 mod stdin;
 mod walk;
+mod watch;
 
 pub use config::{ExecutionConfig, ExecutionMode, VcsTargeting};
 
@@ -19,8 +20,18 @@ pub fn run_files(
     walk::traverse(session, config, paths)
 }
 
+/// Runs like [run_files], then keeps checking the files that change until the
+/// process is interrupted. The report of every run is passed to `on_report`.
+pub fn watch_files(
+    session: &mut CliSession,
+    config: &ExecutionConfig,
+    paths: Vec<OsString>,
+    on_report: impl FnMut(&mut CliSession, Report) -> Result<(), CliDiagnostic>,
+) -> Result<(), CliDiagnostic> {
+    watch::watch(session, config, paths, on_report)
+}
+
 pub struct StdinPayload {
-    #[allow(dead_code)]
     pub path: PathBuf,
     pub content: String,
 }
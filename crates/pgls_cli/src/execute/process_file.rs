@@ -4,6 +4,7 @@ pub(crate) mod workspace_file;
 
 use crate::execute::config::ExecutionMode;
 use crate::execute::walk::TraversalOptions;
+use crate::reporter::FormattedFile;
 use check::check_file;
 use format::format_file;
 use pgls_diagnostics::Error;
@@ -53,6 +54,8 @@ pub(crate) enum Message {
         diagnostics: Vec<Error>,
         skipped_diagnostics: u32,
     },
+    /// The statements of a file that `format` would change
+    Formatted(FormattedFile),
 }
 
 impl<D> From<D> for Message
@@ -109,8 +112,10 @@ pub(crate) fn process_file(ctx: &TraversalOptions, pgls_path: &PgLSPath) -> File
     tracing::trace_span!("process_file", path = ?pgls_path).in_scope(move || {
         let shared_context = &SharedTraversalOptions::new(ctx);
 
-        match ctx.config.mode {
-            ExecutionMode::Check { .. } => check_file(shared_context, pgls_path.as_path()),
+        match &ctx.config.mode {
+            ExecutionMode::Check { rule_selection, .. } => {
+                check_file(shared_context, pgls_path.as_path(), rule_selection)
+            }
             ExecutionMode::Format { .. } => format_file(shared_context, pgls_path.as_path()),
         }
     })
@@ -1,6 +1,7 @@
 use crate::diagnostics::FormatDiffDiagnostic;
 use crate::execute::process_file::workspace_file::WorkspaceFile;
 use crate::execute::process_file::{FileResult, FileStatus, Message, SharedTraversalOptions};
+use crate::reporter::FormattedFile;
 use pgls_workspace::features::format::PullFileFormattingParams;
 use std::io::Write;
 use std::path::Path;
@@ -62,7 +63,7 @@ fn format_with_guard<'ctx>(
         return Ok(FileStatus::Changed);
     }
 
-    for stmt in format_result.statements {
+    for stmt in &format_result.statements {
         let diff = pgls_text_edit::TextEdit::from_unicode_words(&stmt.original, &stmt.formatted);
         let start_byte: usize = stmt.range.start().into();
         let start_line = format_result.original[..start_byte]
@@ -76,6 +77,14 @@ fn format_with_guard<'ctx>(
         }));
     }
 
+    if !format_result.statements.is_empty() {
+        ctx.push_message(Message::Formatted(FormattedFile {
+            path: path.display().to_string(),
+            content: format_result.original.clone(),
+            statements: format_result.statements,
+        }));
+    }
+
     if diagnostics.is_empty() {
         Ok(FileStatus::Unchanged)
     } else {
@@ -1,13 +1,57 @@
 use crate::execute::StdinPayload;
-use crate::execute::config::ExecutionConfig;
+use crate::execute::config::{ExecutionConfig, ExecutionMode};
 use crate::{CliDiagnostic, CliSession};
 use pgls_console::{ConsoleExt, markup};
+use pgls_diagnostics::{DiagnosticExt, Error, PrintDiagnostic, category};
+use pgls_fs::PgLSPath;
+use pgls_workspace::features::format::PullFileFormattingParams;
+use pgls_workspace::workspace::{CloseFileParams, OpenFileParams};
 
 pub(crate) fn process(
     session: &mut CliSession,
-    _config: &ExecutionConfig,
+    config: &ExecutionConfig,
     payload: StdinPayload,
 ) -> Result<(), CliDiagnostic> {
-    session.console().append(markup! {{payload.content}});
-    Ok(())
+    match config.mode {
+        ExecutionMode::Format { .. } => format(session, payload),
+        ExecutionMode::Check { .. } => {
+            session.console().append(markup! {{payload.content}});
+            Ok(())
+        }
+    }
+}
+
+/// Prints the formatted code. Code that can't be formatted is printed unchanged, so that
+/// editors don't replace it with a partially formatted version, and the reason is reported.
+fn format(session: &mut CliSession, payload: StdinPayload) -> Result<(), CliDiagnostic> {
+    let path = PgLSPath::new(&payload.path);
+    let workspace = session.workspace();
+    workspace.open_file(OpenFileParams {
+        path: path.clone(),
+        content: payload.content.clone(),
+        version: 0,
+    })?;
+    let result = workspace.pull_file_formatting(PullFileFormattingParams {
+        path: path.clone(),
+        range: None,
+    });
+    workspace.close_file(CloseFileParams { path })?;
+    let result = result?;
+
+    let console = session.console();
+    if result.diagnostics.is_empty() {
+        console.append(markup! {{result.formatted}});
+        return Ok(());
+    }
+
+    console.append(markup! {{payload.content}});
+    let file_name = payload.path.display().to_string();
+    for diagnostic in result.diagnostics {
+        let diagnostic = Error::from(diagnostic)
+            .with_file_path(file_name.as_str())
+            .with_file_source_code(payload.content.as_str());
+        console.error(markup! {{PrintDiagnostic::simple(&diagnostic)}});
+    }
+
+    Err(CliDiagnostic::check_error(category!("format")))
 }
@@ -1,7 +1,7 @@
 use super::config::ExecutionConfig;
 use super::process_file::{FileStatus, Message, process_file};
 use crate::execute::diagnostics::PanicDiagnostic;
-use crate::reporter::{Report, TraversalData};
+use crate::reporter::{FormattedFile, Report, TraversalData};
 use crate::{CliDiagnostic, CliSession};
 use crossbeam::channel::{Receiver, Sender, unbounded};
 use pgls_diagnostics::{DiagnosticExt, Error, Resource};
@@ -57,7 +57,7 @@ pub(crate) fn traverse(
 
     let printer = DiagnosticsPrinter::new(config).with_max_diagnostics(max_diagnostics);
 
-    let (duration, evaluated_paths, diagnostics) = thread::scope(|s| {
+    let (duration, evaluated_paths, (diagnostics, formatted_files)) = thread::scope(|s| {
         let handler = thread::Builder::new()
             .name(String::from("pgls::console"))
             .spawn_scoped(s, || printer.run(receiver, recv_files))
@@ -83,11 +83,16 @@ pub(crate) fn traverse(
             },
         );
         // wait for the main thread to finish
-        let diagnostics = handler.join().unwrap();
+        let printed = handler.join().unwrap();
 
-        (elapsed, evaluated_paths, diagnostics)
+        (elapsed, evaluated_paths, printed)
     });
 
+    // the usage of the processed files ranks the completions in the editor
+    if let Err(err) = workspace.persist_usage() {
+        tracing::warn!("Failed to persist usage: {err}");
+    }
+
     let changed = changed.load(Ordering::Relaxed);
     let unchanged = unchanged.load(Ordering::Relaxed);
     let matches = matches.load(Ordering::Relaxed);
@@ -111,7 +116,8 @@ pub(crate) fn traverse(
         duration,
         diagnostics_not_printed,
         Some(traversal),
-    ))
+    )
+    .with_formatted_files(formatted_files))
 }
 
 /// This function will setup the global Rayon thread pool the first time it's called
@@ -212,9 +218,14 @@ impl<'ctx> DiagnosticsPrinter<'ctx> {
         should_print
     }
 
-    fn run(&self, receiver: Receiver<Message>, interner: Receiver<PathBuf>) -> Vec<Error> {
+    fn run(
+        &self,
+        receiver: Receiver<Message>,
+        interner: Receiver<PathBuf>,
+    ) -> (Vec<Error>, Vec<FormattedFile>) {
         let mut paths: FxHashSet<String> = FxHashSet::default();
         let mut diagnostics = vec![];
+        let mut formatted_files = vec![];
 
         while let Ok(msg) = receiver.recv() {
             match msg {
@@ -267,10 +278,11 @@ impl<'ctx> DiagnosticsPrinter<'ctx> {
                         }
                     }
                 }
+                Message::Formatted(formatted_file) => formatted_files.push(formatted_file),
             }
         }
 
-        diagnostics
+        (diagnostics, formatted_files)
     }
 }
 
@@ -0,0 +1,374 @@
+use super::config::{ExecutionConfig, ExecutionMode};
+use super::diagnostics::{ResultExt, ResultIoExt};
+use super::walk;
+use crate::cli_options::RuleSelectionOptions;
+use crate::reporter::{Report, TraversalData};
+use crate::{CliDiagnostic, CliSession};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use path_absolutize::Absolutize;
+use pgls_analyse::RuleCategoriesBuilder;
+use pgls_diagnostics::{DiagnosticExt, Error, Resource, category, serde};
+use pgls_fs::{FileSystem, OpenOptions, PgLSPath};
+use pgls_workspace::Workspace;
+use pgls_workspace::features::diagnostics::PullFileDiagnosticsParams;
+use pgls_workspace::workspace::{
+    ChangeFileParams, CloseFileParams, IsPathIgnoredParams, OpenFileParams,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, channel};
+use std::time::{Duration, Instant};
+
+/// How long to wait for further events once a file changed, so that saving
+/// several files at once results in a single report
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Checks the files, then keeps watching them and checks the files that change
+/// again until the process is interrupted. The report of every run is passed to
+/// `on_report`.
+pub(crate) fn watch(
+    session: &mut CliSession,
+    config: &ExecutionConfig,
+    mut inputs: Vec<OsString>,
+    mut on_report: impl FnMut(&mut CliSession, Report) -> Result<(), CliDiagnostic>,
+) -> Result<(), CliDiagnostic> {
+    let ExecutionMode::Check { rule_selection, .. } = &config.mode else {
+        unreachable!("only the check command can watch files");
+    };
+
+    if inputs.is_empty()
+        && let Some(working_directory) = session.fs().working_directory()
+    {
+        inputs.push(working_directory.into_os_string());
+    }
+
+    let watcher = FileWatcher::new(&inputs)?;
+
+    let report = walk::traverse(session, config, inputs)?;
+    let duration = report.duration;
+    let mut files = CheckedFiles::from_report(report, session.fs().working_directory());
+    on_report(session, files.report(duration))?;
+
+    loop {
+        let changed_paths = watcher.wait()?;
+
+        let start = Instant::now();
+        let mut has_changes = false;
+        for path in changed_paths {
+            has_changes |= files.check_file(session, &path, rule_selection);
+        }
+
+        if has_changes {
+            on_report(session, files.report(start.elapsed()))?;
+        }
+    }
+}
+
+/// Watches the inputs of a command for changes
+struct FileWatcher {
+    // the watcher stops watching once it is dropped
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+    /// Directories whose files are all watched
+    directories: Vec<PathBuf>,
+    /// Files that are watched on their own. Their parent directory is watched
+    /// instead of the file itself, because editors often replace files when
+    /// saving them.
+    files: Vec<PathBuf>,
+}
+
+impl FileWatcher {
+    fn new(inputs: &[OsString]) -> Result<Self, CliDiagnostic> {
+        let (sender, events) = channel();
+        let mut watcher = notify::recommended_watcher(sender).map_err(watch_error)?;
+
+        let mut directories = vec![];
+        let mut files = vec![];
+        for input in inputs {
+            let path = absolute(Path::new(input));
+            if path.is_dir() {
+                watcher
+                    .watch(&path, RecursiveMode::Recursive)
+                    .map_err(watch_error)?;
+                directories.push(path);
+            } else if let Some(parent) = path.parent() {
+                watcher
+                    .watch(parent, RecursiveMode::NonRecursive)
+                    .map_err(watch_error)?;
+                files.push(path);
+            }
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            directories,
+            files,
+        })
+    }
+
+    /// Blocks until watched files change, and returns their paths
+    fn wait(&self) -> Result<BTreeSet<PathBuf>, CliDiagnostic> {
+        let mut changed_paths = BTreeSet::new();
+
+        while changed_paths.is_empty() {
+            let event = self.events.recv().map_err(|_| {
+                CliDiagnostic::io_error(io::Error::other("the file watcher stopped unexpectedly"))
+            })?;
+            self.collect(event, &mut changed_paths)?;
+
+            while let Ok(event) = self.events.recv_timeout(DEBOUNCE) {
+                self.collect(event, &mut changed_paths)?;
+            }
+        }
+
+        Ok(changed_paths)
+    }
+
+    fn collect(
+        &self,
+        event: notify::Result<notify::Event>,
+        changed_paths: &mut BTreeSet<PathBuf>,
+    ) -> Result<(), CliDiagnostic> {
+        let event = event.map_err(watch_error)?;
+        if matches!(event.kind, EventKind::Access(_)) {
+            return Ok(());
+        }
+
+        changed_paths.extend(event.paths.into_iter().filter(|path| self.is_watched(path)));
+
+        Ok(())
+    }
+
+    fn is_watched(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| file == path)
+            || self
+                .directories
+                .iter()
+                .any(|directory| path.starts_with(directory))
+    }
+}
+
+/// The diagnostics of all files checked in watch mode, from which the report is
+/// rebuilt after some of the files were checked again
+struct CheckedFiles {
+    /// The checked files, by their absolute path
+    files: BTreeMap<PathBuf, CheckedFile>,
+    /// Diagnostics of the initial traversal that don't belong to a checked file
+    other_diagnostics: Vec<serde::Diagnostic>,
+    working_directory: Option<PathBuf>,
+}
+
+struct CheckedFile {
+    /// The path of the file, as it is printed and passed to the workspace
+    name: String,
+    /// The version of the file in the workspace. Files are opened in the
+    /// workspace the first time they change, and kept open afterwards.
+    version: Option<i32>,
+    diagnostics: Vec<serde::Diagnostic>,
+}
+
+impl CheckedFiles {
+    fn from_report(report: Report, working_directory: Option<PathBuf>) -> Self {
+        let mut files: BTreeMap<PathBuf, CheckedFile> = report
+            .traversal
+            .into_iter()
+            .flat_map(|traversal| traversal.evaluated_paths)
+            .map(|path| {
+                let file = CheckedFile {
+                    name: path.display().to_string(),
+                    version: None,
+                    diagnostics: vec![],
+                };
+                (absolute(path.as_path()), file)
+            })
+            .collect();
+
+        let mut other_diagnostics = vec![];
+        for diagnostic in report.diagnostics {
+            let path = match diagnostic.location().resource {
+                Some(Resource::File(name)) => Some(absolute(Path::new(name))),
+                _ => None,
+            };
+            let diagnostic = serde::Diagnostic::new(diagnostic);
+            match path.and_then(|path| files.get_mut(&path)) {
+                Some(file) => file.diagnostics.push(diagnostic),
+                None => other_diagnostics.push(diagnostic),
+            }
+        }
+
+        Self {
+            files,
+            other_diagnostics,
+            working_directory,
+        }
+    }
+
+    fn report(&self, duration: Duration) -> Report {
+        let diagnostics = self
+            .files
+            .values()
+            .flat_map(|file| &file.diagnostics)
+            .chain(&self.other_diagnostics)
+            .cloned()
+            .map(Error::from)
+            .collect();
+
+        let traversal = TraversalData {
+            evaluated_paths: self
+                .files
+                .values()
+                .map(|file| PgLSPath::new(&file.name))
+                .collect(),
+            changed: 0,
+            unchanged: self.files.len(),
+            matches: 0,
+            skipped: 0,
+            suggested_fixes_skipped: 0,
+            diagnostics_not_printed: 0,
+            workspace_root: self.working_directory.clone(),
+        };
+
+        Report::new(diagnostics, duration, 0, Some(traversal))
+    }
+
+    /// Checks a file that changed, was created or was removed. Returns whether
+    /// the report changed.
+    fn check_file(
+        &mut self,
+        session: &CliSession,
+        path: &Path,
+        rule_selection: &RuleSelectionOptions,
+    ) -> bool {
+        let fs = &**session.fs();
+        let workspace = session.workspace();
+
+        if !fs.path_is_file(path) {
+            let Some(file) = self.files.remove(path) else {
+                return false;
+            };
+            if file.version.is_some() {
+                workspace
+                    .close_file(CloseFileParams {
+                        path: PgLSPath::new(&file.name),
+                    })
+                    .ok();
+            }
+            return true;
+        }
+
+        let name = match self.files.get(path) {
+            Some(file) => file.name.clone(),
+            None => {
+                let is_sql_file = path
+                    .extension()
+                    .is_some_and(|ext| ext == "sql" || ext == "pg");
+                let is_ignored = workspace
+                    .is_path_ignored(IsPathIgnoredParams {
+                        pgls_path: PgLSPath::new(path),
+                    })
+                    .unwrap_or(true);
+                if !is_sql_file || is_ignored {
+                    return false;
+                }
+
+                self.working_directory
+                    .as_deref()
+                    .and_then(|working_directory| path.strip_prefix(working_directory).ok())
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            }
+        };
+
+        let file = self
+            .files
+            .entry(path.to_path_buf())
+            .or_insert_with(|| CheckedFile {
+                name: name.clone(),
+                version: None,
+                diagnostics: vec![],
+            });
+
+        file.diagnostics = match check_content(fs, workspace, path, file, rule_selection) {
+            Ok(diagnostics) => diagnostics,
+            Err(error) => vec![serde::Diagnostic::new(error)],
+        };
+
+        true
+    }
+}
+
+/// Sends the content of a file to the workspace and pulls its diagnostics
+fn check_content(
+    fs: &dyn FileSystem,
+    workspace: &dyn Workspace,
+    path: &Path,
+    file: &mut CheckedFile,
+    rule_selection: &RuleSelectionOptions,
+) -> Result<Vec<serde::Diagnostic>, Error> {
+    let mut content = String::new();
+    fs.open_with_options(path, OpenOptions::default().read(true))
+        .and_then(|mut handle| handle.read_to_string(&mut content))
+        .with_file_path(file.name.clone())?;
+
+    let pgls_path = PgLSPath::new(&file.name);
+    let version = match file.version {
+        Some(version) => {
+            workspace
+                .change_file(ChangeFileParams {
+                    path: pgls_path.clone(),
+                    version: version + 1,
+                    content: content.clone(),
+                })
+                .with_file_path_and_code(file.name.clone(), category!("internalError/fs"))?;
+            version + 1
+        }
+        None => {
+            workspace
+                .open_file(OpenFileParams {
+                    path: pgls_path.clone(),
+                    version: 0,
+                    content: content.clone(),
+                })
+                .with_file_path_and_code(file.name.clone(), category!("internalError/fs"))?;
+            0
+        }
+    };
+    file.version = Some(version);
+
+    let result = workspace
+        .pull_file_diagnostics(PullFileDiagnosticsParams {
+            path: pgls_path,
+            categories: RuleCategoriesBuilder::default().all().build(),
+            max_diagnostics: u32::MAX,
+            only: rule_selection.only.clone(),
+            skip: rule_selection.skip.clone(),
+        })
+        .with_file_path_and_code(file.name.clone(), category!("check"))?;
+
+    Ok(result
+        .diagnostics
+        .into_iter()
+        .map(Error::from)
+        .filter(|diagnostic| rule_selection.is_reported(diagnostic))
+        .map(|diagnostic| {
+            serde::Diagnostic::new(
+                diagnostic
+                    .with_file_path(&file.name)
+                    .with_file_source_code(&content),
+            )
+        })
+        .collect())
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    path.absolutize()
+        .map_or_else(|_| path.to_path_buf(), |path| path.into_owned())
+}
+
+fn watch_error(error: notify::Error) -> CliDiagnostic {
+    CliDiagnostic::io_error(io::Error::other(error))
+}
@@ -6,13 +6,18 @@
 
 use cli_options::CliOptions;
 use commands::check::{self, CheckArgs};
+use commands::dblint::DblintArgs;
 use commands::format::{self, FormatArgs};
+use commands::schema_diff::SchemaDiffArgs;
 use pgls_configuration::Merge;
 use pgls_configuration::PartialConfiguration;
 use pgls_console::{ColorMode, Console, ConsoleExt, markup};
 use pgls_fs::{ConfigName, FileSystem, OsFileSystem};
 use pgls_workspace::{App, DynRef, Workspace, WorkspaceRef};
+use std::time::Duration;
 
+mod apply;
+mod baseline;
 mod changed;
 mod cli_options;
 mod commands;
@@ -20,6 +25,7 @@ mod diagnostics;
 mod execute;
 mod logging;
 mod metrics;
+mod migrations;
 mod panic;
 mod reporter;
 mod service;
@@ -33,7 +39,7 @@ pub use diagnostics::CliDiagnostic;
 pub use execute::{ExecutionConfig, ExecutionMode, VcsTargeting};
 pub use panic::setup_panic_handler;
 pub use reporter::{ReportConfig, Reporter, TraversalData};
-pub use service::{SocketTransport, open_transport};
+pub use service::{SocketTransport, ensure_daemon_running, open_transport};
 
 pub(crate) use pgls_env::VERSION;
 
@@ -69,15 +75,33 @@ impl<'app> CliSession<'app> {
             PgLSCommand::Dblint {
                 cli_options,
                 configuration,
-            } => commands::dblint::dblint(self, &cli_options, configuration),
+                rule_selection_options,
+                baseline_options,
+                watch,
+                poll_interval,
+            } => commands::dblint::dblint(
+                self,
+                &cli_options,
+                DblintArgs {
+                    configuration,
+                    rule_selection_options,
+                    baseline_options,
+                    watch,
+                    poll_interval: Duration::from_secs(poll_interval),
+                },
+            ),
             PgLSCommand::Check {
                 cli_options,
                 configuration,
+                rule_selection_options,
+                baseline_options,
+                apply_options,
                 paths,
                 stdin_file_path,
                 staged,
                 changed,
                 since,
+                watch,
             } => check::check(
                 self,
                 &cli_options,
@@ -88,6 +112,10 @@ impl<'app> CliSession<'app> {
                     staged,
                     changed,
                     since,
+                    watch,
+                    rule_selection_options,
+                    baseline_options,
+                    apply_options,
                 },
             ),
             PgLSCommand::Format {
@@ -95,6 +123,7 @@ impl<'app> CliSession<'app> {
                 configuration,
                 paths,
                 write,
+                stdin_file_path,
                 staged,
                 changed,
                 since,
@@ -105,11 +134,13 @@ impl<'app> CliSession<'app> {
                     configuration,
                     paths,
                     write,
+                    stdin_file_path,
                     staged,
                     changed,
                     since,
                 },
             ),
+            PgLSCommand::Explain { rule, list } => commands::explain::explain(self, rule, list),
             PgLSCommand::Clean => commands::clean::clean(self),
             PgLSCommand::Start {
                 config_path,
@@ -140,6 +171,24 @@ impl<'app> CliSession<'app> {
                 Some(log_kind),
             ),
             PgLSCommand::PrintSocket => commands::daemon::print_socket(),
+            PgLSCommand::SchemaDiff {
+                configuration,
+                cli_options,
+                replay_on,
+                ddl,
+                from,
+                to,
+            } => commands::schema_diff::schema_diff(
+                self,
+                &cli_options,
+                SchemaDiffArgs {
+                    configuration,
+                    from,
+                    to,
+                    replay_on,
+                    ddl,
+                },
+            ),
             PgLSCommand::SchemaExport {
                 connection_string,
                 output,
@@ -1,8 +1,8 @@
 //! This is the main binary
 
 use pgls_cli::{
-    CliDiagnostic, CliSession, PgLSCommand, open_transport, pg_l_s_command, setup_panic_handler,
-    to_color_mode,
+    CliDiagnostic, CliSession, PgLSCommand, ensure_daemon_running, open_transport, pg_l_s_command,
+    setup_panic_handler, to_color_mode,
 };
 use pgls_console::{ConsoleExt, EnvConsole, markup};
 use pgls_diagnostics::{Diagnostic, PrintDiagnostic, set_bottom_frame};
@@ -52,9 +52,13 @@ fn main() -> ExitCode {
 
 fn run_workspace(console: &mut EnvConsole, command: PgLSCommand) -> Result<(), CliDiagnostic> {
     // If the `--use-server` CLI flag is set, try to open a connection to an
-    // existing server socket
+    // existing server socket. Commands running in watch mode start the server
+    // if it isn't running yet, so that it keeps the state of the workspace
     let workspace = if command.should_use_server() {
         let runtime = Runtime::new()?;
+        if command.is_watch() {
+            ensure_daemon_running(&runtime)?;
+        }
         match open_transport(runtime)? {
             Some(transport) => workspace::client(transport)?,
             None => return Err(CliDiagnostic::server_not_running()),
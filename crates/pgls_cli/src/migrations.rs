@@ -0,0 +1,470 @@
+//! Checks the files of the configured migrations directory against each other.
+//!
+//! The linter looks at one statement at a time, but some mistakes only show across files:
+//! two migrations sharing a sequence number, a new migration that is dated before migrations
+//! that were already merged, a migration that alters a table only created by a later one, files
+//! that don't follow the naming pattern of migrations, and up migrations without their down
+//! migration (or vice versa).
+
+use crate::CliDiagnostic;
+use pgls_configuration::PartialConfiguration;
+use pgls_configuration::migrations::{
+    MigrationDirection, MigrationLayout, MigrationSection, MigrationVersion,
+};
+use pgls_diagnostics::{
+    Category, Diagnostic, DiagnosticExt, Error, MessageAndDescription, Resource, category,
+};
+use pgls_fs::{FileSystem, OpenOptions};
+use pgls_query::NodeEnum;
+use pgls_query::protobuf::{Node, ObjectType, RangeVar};
+use pgls_text_size::TextRange;
+use pgls_workspace::DynRef;
+use pgls_workspace::workspace::server::{get_migration, load_migration_layout};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// A file of the migrations directory that follows the naming pattern of migrations
+#[derive(Debug)]
+pub(crate) struct MigrationFile {
+    pub(crate) path: PathBuf,
+    pub(crate) sequence_number: MigrationVersion,
+    /// The name of the migration, without the sequence number and direction. The up and down
+    /// files of a migration share it.
+    pub(crate) name: String,
+    pub(crate) direction: MigrationDirection,
+    pub(crate) repeatable: bool,
+    pub(crate) content: String,
+    /// The parts of the file that are applied on their own, e.g. the up and down sections of
+    /// a dbmate migration
+    pub(crate) sections: Vec<MigrationSection>,
+}
+
+impl MigrationFile {
+    /// Returns the ranges of the statements that are applied when migrating, in order
+    pub(crate) fn applied_statements(&self) -> Vec<TextRange> {
+        self.sections
+            .iter()
+            .filter(|section| section.direction.is_applied())
+            .flat_map(|section| {
+                pgls_statement_splitter::split(&self.content[section.range])
+                    .ranges
+                    .into_iter()
+                    .map(|range| range + section.range.start())
+            })
+            .collect()
+    }
+}
+
+/// Returns the migrations of the migrations directory in the order they are applied, and the
+/// `.sql` files that aren't migrations.
+pub(crate) fn collect_migrations(
+    fs: &dyn FileSystem,
+    migrations_dir: &Path,
+    layout: &dyn MigrationLayout,
+) -> Result<(Vec<MigrationFile>, Vec<PathBuf>), CliDiagnostic> {
+    let mut files = vec![];
+    let mut directories = vec![migrations_dir.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for path in fs.read_dir(&directory).map_err(CliDiagnostic::io_error)? {
+            if fs.path_is_dir(&path) {
+                directories.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "sql") {
+                files.push(path);
+            }
+        }
+    }
+
+    let mut migrations = vec![];
+    let mut invalid = vec![];
+    for path in files {
+        let Some(migration) = get_migration(fs, &path, migrations_dir, layout) else {
+            invalid.push(path);
+            continue;
+        };
+
+        let mut content = String::new();
+        fs.open_with_options(&path, OpenOptions::default().read(true))
+            .and_then(|mut file| file.read_to_string(&mut content))
+            .map_err(CliDiagnostic::io_error)?;
+        let sections = layout.sections(&migration, &content);
+        migrations.push(MigrationFile {
+            path,
+            sequence_number: migration.sequence_number,
+            name: migration.name,
+            direction: migration.direction,
+            repeatable: migration.repeatable,
+            content,
+            sections,
+        });
+    }
+
+    migrations.sort_by(|a, b| {
+        (&a.sequence_number, &a.name, a.direction, &a.path).cmp(&(
+            &b.sequence_number,
+            &b.name,
+            b.direction,
+            &b.path,
+        ))
+    });
+    invalid.sort();
+
+    Ok((migrations, invalid))
+}
+
+/// Checks the migrations of the configured migrations directory against each other. Nothing is
+/// checked if no migrations directory is configured.
+///
+/// All migrations are taken into account, but only the diagnostics of the migrations within
+/// `paths` are returned, e.g. none if a single file outside the migrations directory is checked.
+pub(crate) fn check_migrations(
+    fs: &DynRef<'_, dyn FileSystem>,
+    configuration: &PartialConfiguration,
+    paths: &[OsString],
+) -> Result<Vec<Error>, CliDiagnostic> {
+    let migrations = configuration.migrations.as_ref();
+    let Some(migrations_dir) = migrations.and_then(|migrations| migrations.migrations_dir.as_ref())
+    else {
+        return Ok(vec![]);
+    };
+    // Migrations up to `after` are already applied, so they are only used as context for the
+    // ones after them
+    let after = MigrationVersion::from(
+        migrations
+            .and_then(|migrations| migrations.after)
+            .unwrap_or(0),
+    );
+    let tool = migrations
+        .and_then(|migrations| migrations.tool)
+        .unwrap_or_default();
+
+    let working_directory = fs.working_directory().unwrap_or_default();
+    let display_name = |path: &Path| {
+        path.strip_prefix(&working_directory)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    };
+
+    let migrations_dir = working_directory.join(migrations_dir);
+    if !fs.path_is_dir(&migrations_dir) {
+        return Ok(vec![]);
+    }
+    let layout = load_migration_layout(&**fs, &migrations_dir, tool);
+    let (migrations, invalid) = collect_migrations(&**fs, &migrations_dir, layout.as_ref())?;
+
+    let mut diagnostics = vec![];
+
+    for path in invalid {
+        diagnostics.push(
+            MigrationDiagnostic::warning(
+                category!("migrations/invalidName"),
+                format!(
+                    "The file doesn't follow the naming pattern of migrations, {}, so it is ignored.",
+                    layout.naming_pattern()
+                ),
+            )
+            .with_file_path(display_name(&path)),
+        );
+    }
+
+    // Duplicate sequence numbers. Repeatable migrations have none.
+    let versioned: Vec<&MigrationFile> = migrations
+        .iter()
+        .filter(|migration| !migration.repeatable)
+        .collect();
+    let mut by_sequence_number: BTreeMap<&MigrationVersion, BTreeSet<&str>> = BTreeMap::new();
+    for migration in &versioned {
+        by_sequence_number
+            .entry(&migration.sequence_number)
+            .or_default()
+            .insert(&migration.name);
+    }
+    for migration in versioned.iter().filter(|m| m.sequence_number > after) {
+        let names = &by_sequence_number[&migration.sequence_number];
+        if names.len() < 2 {
+            continue;
+        }
+        let others: Vec<String> = versioned
+            .iter()
+            .filter(|other| {
+                other.sequence_number == migration.sequence_number && other.name != migration.name
+            })
+            .map(|other| format!("`{}`", display_name(&other.path)))
+            .collect();
+        diagnostics.push(
+            MigrationDiagnostic::error(
+                category!("migrations/duplicateSequenceNumber"),
+                format!(
+                    "The sequence number {} is also used by {}.",
+                    migration.sequence_number,
+                    others.join(", ")
+                ),
+            )
+            .with_file_path(display_name(&migration.path)),
+        );
+    }
+
+    // Up and down migrations without their counterpart
+    let mut directions: HashMap<(&MigrationVersion, &str), HashSet<MigrationDirection>> =
+        HashMap::new();
+    for migration in &migrations {
+        directions
+            .entry((&migration.sequence_number, &migration.name))
+            .or_default()
+            .insert(migration.direction);
+    }
+    for migration in migrations.iter().filter(|m| m.sequence_number > after) {
+        let directions = &directions[&(&migration.sequence_number, migration.name.as_str())];
+        let message = match migration.direction {
+            MigrationDirection::Up if !directions.contains(&MigrationDirection::Down) => {
+                "The up migration has no matching down migration."
+            }
+            MigrationDirection::Down
+                if !directions.iter().any(|direction| direction.is_applied()) =>
+            {
+                "The down migration has no matching up migration."
+            }
+            _ => continue,
+        };
+        diagnostics.push(
+            MigrationDiagnostic::warning(category!("migrations/unpairedMigration"), message)
+                .with_file_path(display_name(&migration.path)),
+        );
+    }
+
+    // New migrations dated before the latest migration of the default branch
+    let default_branch = configuration
+        .vcs
+        .as_ref()
+        .filter(|vcs| vcs.enabled == Some(true))
+        .and_then(|vcs| vcs.default_branch.as_ref());
+    let added = default_branch.and_then(|default_branch| {
+        match fs.get_added_files(default_branch) {
+            Ok(added) => Some((default_branch, added)),
+            Err(err) => {
+                // e.g. git isn't installed, or the default branch doesn't exist locally
+                diagnostics.push(MigrationDiagnostic::warning(
+                    category!("migrations/outOfOrder"),
+                    format!(
+                        "Couldn't determine the migrations added since `{default_branch}`, so they aren't checked for being out of order: {err}"
+                    ),
+                ).into());
+                None
+            }
+        }
+    });
+    if let Some((default_branch, added)) = added {
+        let added: HashSet<PathBuf> = added
+            .into_iter()
+            .map(|path| working_directory.join(path))
+            .collect();
+        let latest = versioned
+            .iter()
+            .filter(|migration| !added.contains(&migration.path))
+            .max_by_key(|migration| &migration.sequence_number);
+
+        if let Some(latest) = latest {
+            for migration in versioned.iter().filter(|migration| {
+                added.contains(&migration.path)
+                    && migration.sequence_number > after
+                    && migration.sequence_number < latest.sequence_number
+            }) {
+                diagnostics.push(
+                    MigrationDiagnostic::warning(
+                        category!("migrations/outOfOrder"),
+                        format!(
+                            "The migration is dated before `{}`, the latest migration on `{default_branch}`. Databases that already applied it might skip this migration.",
+                            display_name(&latest.path)
+                        ),
+                    )
+                    .with_file_path(display_name(&migration.path)),
+                );
+            }
+        }
+    }
+
+    // Migrations that modify relations only created by a later migration
+    let statements: Vec<(&MigrationFile, TextRange, NodeEnum)> = migrations
+        .iter()
+        .flat_map(|migration| {
+            migration
+                .applied_statements()
+                .into_iter()
+                .filter_map(move |range| {
+                    let stmt = pgls_query::parse(&migration.content[range])
+                        .ok()?
+                        .into_root()?;
+                    Some((migration, range, stmt))
+                })
+        })
+        .collect();
+
+    let mut created_by: HashMap<String, &MigrationFile> = HashMap::new();
+    for (migration, _, stmt) in &statements {
+        if let Some(relation) = created_relation(stmt) {
+            created_by.entry(relation).or_insert(migration);
+        }
+    }
+    for (migration, range, stmt) in &statements {
+        if migration.sequence_number <= after {
+            continue;
+        }
+        for relation in modified_relations(stmt) {
+            let Some(creator) = created_by.get(&relation) else {
+                continue;
+            };
+            if creator.sequence_number <= migration.sequence_number {
+                continue;
+            }
+
+            diagnostics.push(
+                MigrationDiagnostic::error(
+                    category!("migrations/usedBeforeCreation"),
+                    format!(
+                        "`{relation}` is only created by the later migration `{}`.",
+                        display_name(&creator.path)
+                    ),
+                )
+                .with_span(*range)
+                .with_file_path(display_name(&migration.path))
+                .with_file_source_code(migration.content.as_str()),
+            );
+        }
+    }
+
+    let checked: Vec<PathBuf> = paths
+        .iter()
+        .map(|path| working_directory.join(path))
+        .collect();
+    diagnostics.retain(|diagnostic| match diagnostic.location().resource {
+        Some(Resource::File(file)) => {
+            let file = working_directory.join(file);
+            checked.iter().any(|path| file.starts_with(path))
+        }
+        _ => true,
+    });
+
+    Ok(diagnostics)
+}
+
+/// Returns the relation a statement creates
+fn created_relation(stmt: &NodeEnum) -> Option<String> {
+    let relation = match stmt {
+        NodeEnum::CreateStmt(stmt) => stmt.relation.as_ref(),
+        NodeEnum::CreateForeignTableStmt(stmt) => stmt
+            .base_stmt
+            .as_ref()
+            .and_then(|stmt| stmt.relation.as_ref()),
+        NodeEnum::ViewStmt(stmt) => stmt.view.as_ref(),
+        NodeEnum::CreateTableAsStmt(stmt) => stmt.into.as_ref().and_then(|into| into.rel.as_ref()),
+        NodeEnum::CreateSeqStmt(stmt) => stmt.sequence.as_ref(),
+        _ => None,
+    };
+    relation.map(qualified_name)
+}
+
+/// Returns the existing relations a statement modifies. Statements that skip missing relations,
+/// e.g. `alter table if exists`, are ignored.
+fn modified_relations(stmt: &NodeEnum) -> Vec<String> {
+    let relations: Vec<&RangeVar> = match stmt {
+        NodeEnum::AlterTableStmt(stmt) if !stmt.missing_ok => stmt.relation.iter().collect(),
+        NodeEnum::RenameStmt(stmt) if !stmt.missing_ok => stmt.relation.iter().collect(),
+        NodeEnum::AlterSeqStmt(stmt) if !stmt.missing_ok => stmt.sequence.iter().collect(),
+        NodeEnum::IndexStmt(stmt) => stmt.relation.iter().collect(),
+        NodeEnum::CreateTrigStmt(stmt) => stmt.relation.iter().collect(),
+        NodeEnum::CreatePolicyStmt(stmt) => stmt.table.iter().collect(),
+        NodeEnum::AlterPolicyStmt(stmt) => stmt.table.iter().collect(),
+        NodeEnum::InsertStmt(stmt) => stmt.relation.iter().collect(),
+        NodeEnum::UpdateStmt(stmt) => stmt.relation.iter().collect(),
+        NodeEnum::DeleteStmt(stmt) => stmt.relation.iter().collect(),
+        NodeEnum::TruncateStmt(stmt) => stmt.relations.iter().filter_map(range_var).collect(),
+        NodeEnum::DropStmt(stmt)
+            if !stmt.missing_ok
+                && matches!(
+                    stmt.remove_type(),
+                    ObjectType::ObjectTable
+                        | ObjectType::ObjectView
+                        | ObjectType::ObjectMatview
+                        | ObjectType::ObjectSequence
+                        | ObjectType::ObjectForeignTable
+                ) =>
+        {
+            return stmt.objects.iter().filter_map(list_name).collect();
+        }
+        _ => vec![],
+    };
+    relations.into_iter().map(qualified_name).collect()
+}
+
+fn range_var(node: &Node) -> Option<&RangeVar> {
+    match node.node.as_ref()? {
+        NodeEnum::RangeVar(range_var) => Some(range_var),
+        _ => None,
+    }
+}
+
+/// Returns the qualified name of a `DROP` object, which is a list of name parts
+fn list_name(node: &Node) -> Option<String> {
+    let NodeEnum::List(list) = node.node.as_ref()? else {
+        return None;
+    };
+    let parts: Vec<&str> = list
+        .items
+        .iter()
+        .filter_map(|item| match item.node.as_ref()? {
+            NodeEnum::String(part) => Some(part.sval.as_str()),
+            _ => None,
+        })
+        .collect();
+    match parts.as_slice() {
+        [name] => Some(format!("public.{name}")),
+        [schema, name] => Some(format!("{schema}.{name}")),
+        _ => None,
+    }
+}
+
+fn qualified_name(relation: &RangeVar) -> String {
+    let schema = if relation.schemaname.is_empty() {
+        "public"
+    } else {
+        &relation.schemaname
+    };
+    format!("{schema}.{}", relation.relname)
+}
+
+#[derive(Debug, Diagnostic)]
+struct MigrationDiagnostic {
+    #[category]
+    category: &'static Category,
+    #[severity]
+    severity: pgls_diagnostics::Severity,
+    #[message]
+    #[description]
+    message: MessageAndDescription,
+    #[location(span)]
+    span: Option<TextRange>,
+}
+
+impl MigrationDiagnostic {
+    fn error(category: &'static Category, message: impl Into<String>) -> Self {
+        Self {
+            category,
+            severity: pgls_diagnostics::Severity::Error,
+            message: message.into().into(),
+            span: None,
+        }
+    }
+
+    fn warning(category: &'static Category, message: impl Into<String>) -> Self {
+        Self {
+            severity: pgls_diagnostics::Severity::Warning,
+            ..Self::error(category, message)
+        }
+    }
+
+    fn with_span(mut self, span: TextRange) -> Self {
+        self.span = Some(span);
+        self
+    }
+}
@@ -1,8 +1,10 @@
 use crate::diagnostics::CliDiagnostic;
-use crate::reporter::{Report, ReportConfig, ReportWriter};
+use crate::reporter::{FormattedFile, Report, ReportConfig, ReportWriter};
 use pgls_console::{Console, ConsoleExt, markup};
 use pgls_diagnostics::display::SourceFile;
+use pgls_diagnostics::location::SourceCode;
 use pgls_diagnostics::{Error, PrintDescription, Resource, Severity};
+use pgls_text_size::TextRange;
 use serde::Serialize;
 
 pub(crate) struct JsonReportWriter {
@@ -33,9 +35,16 @@ impl ReportWriter for JsonReportWriter {
 
         let summary = JsonSummary::from_report(report);
 
+        let formatted = report
+            .formatted_files
+            .iter()
+            .flat_map(to_json_formatted_statements)
+            .collect();
+
         let output = JsonOutput {
             summary,
             diagnostics,
+            formatted,
             command: command_name.to_string(),
         };
 
@@ -55,6 +64,9 @@ impl ReportWriter for JsonReportWriter {
 struct JsonOutput {
     summary: JsonSummary,
     diagnostics: Vec<JsonDiagnostic>,
+    /// The statements that `format` would change
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    formatted: Vec<JsonFormattedStatement>,
     command: String,
 }
 
@@ -90,7 +102,7 @@ impl JsonSummary {
 }
 
 #[derive(Serialize)]
-struct JsonDiagnostic {
+pub(crate) struct JsonDiagnostic {
     severity: &'static str,
     message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -99,6 +111,15 @@ struct JsonDiagnostic {
     location: Option<JsonLocation>,
 }
 
+#[derive(Serialize)]
+struct JsonFormattedStatement {
+    location: JsonLocation,
+    /// The byte offsets of the statement in the file
+    range: TextRange,
+    original: String,
+    formatted: String,
+}
+
 #[derive(Serialize)]
 struct JsonLocation {
     path: String,
@@ -112,7 +133,7 @@ struct JsonPosition {
     column: usize,
 }
 
-fn to_json_report(diagnostic: &Error) -> JsonDiagnostic {
+pub(crate) fn to_json_report(diagnostic: &Error) -> JsonDiagnostic {
     let message = PrintDescription(diagnostic).to_string();
     let category = diagnostic.category().map(|c| c.name().to_string());
     let severity = match diagnostic.severity() {
@@ -133,6 +154,37 @@ fn to_json_report(diagnostic: &Error) -> JsonDiagnostic {
     }
 }
 
+fn to_json_formatted_statements(file: &FormattedFile) -> Vec<JsonFormattedStatement> {
+    let source = SourceFile::new(SourceCode {
+        text: file.content.as_str(),
+        line_starts: None,
+    });
+    file.statements
+        .iter()
+        .map(|statement| {
+            let position = |offset| {
+                source
+                    .location(offset)
+                    .map(|location| JsonPosition {
+                        line: location.line_number.get(),
+                        column: location.column_number.get(),
+                    })
+                    .unwrap_or(JsonPosition { line: 0, column: 0 })
+            };
+            JsonFormattedStatement {
+                location: JsonLocation {
+                    path: file.path.clone(),
+                    start: position(statement.range.start()),
+                    end: position(statement.range.end()),
+                },
+                range: statement.range,
+                original: statement.original.clone(),
+                formatted: statement.formatted.clone(),
+            }
+        })
+        .collect()
+}
+
 fn to_location(diagnostic: &Error) -> Option<JsonLocation> {
     let loc = diagnostic.location();
     let path = match loc.resource {
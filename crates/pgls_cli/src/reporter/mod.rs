@@ -2,6 +2,7 @@ pub(crate) mod github;
 pub(crate) mod gitlab;
 pub(crate) mod json;
 pub(crate) mod junit;
+pub(crate) mod sarif;
 pub(crate) mod summary;
 pub(crate) mod terminal;
 
@@ -10,6 +11,7 @@ use crate::diagnostics::CliDiagnostic;
 use pgls_console::Console;
 use pgls_diagnostics::{Error, Severity};
 use pgls_fs::PgLSPath;
+use pgls_workspace::features::format::StatementFormatResult;
 use std::collections::BTreeSet;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -44,6 +46,7 @@ pub enum ReportMode {
     Json,
     JsonPretty,
     Summary,
+    Sarif,
 }
 
 impl From<CliReporter> for ReportMode {
@@ -56,6 +59,7 @@ impl From<CliReporter> for ReportMode {
             CliReporter::Json => Self::Json,
             CliReporter::JsonPretty => Self::JsonPretty,
             CliReporter::Summary => Self::Summary,
+            CliReporter::Sarif => Self::Sarif,
         }
     }
 }
@@ -72,6 +76,15 @@ pub struct TraversalData {
     pub workspace_root: Option<PathBuf>,
 }
 
+/// The statements of a file whose formatting differs from the original
+#[derive(Debug)]
+pub struct FormattedFile {
+    pub path: String,
+    /// The original content of the file, which the ranges of the statements point into
+    pub content: String,
+    pub statements: Vec<StatementFormatResult>,
+}
+
 #[derive(Debug)]
 pub struct Report {
     pub diagnostics: Vec<Error>,
@@ -80,6 +93,8 @@ pub struct Report {
     pub warnings: u32,
     pub skipped_diagnostics: u32,
     pub traversal: Option<TraversalData>,
+    /// The files that `format` would change, for reporters that print the formatted statements
+    pub formatted_files: Vec<FormattedFile>,
 }
 
 impl Report {
@@ -97,8 +112,39 @@ impl Report {
             warnings,
             skipped_diagnostics,
             traversal,
+            formatted_files: vec![],
         }
     }
+
+    pub(crate) fn with_formatted_files(mut self, formatted_files: Vec<FormattedFile>) -> Self {
+        self.formatted_files = formatted_files;
+        self
+    }
+
+    /// Caps the diagnostics of a report whose diagnostics were collected without a limit, so
+    /// that they could be filtered first.
+    pub(crate) fn truncate(self, max_diagnostics: u32) -> Self {
+        let Self {
+            mut diagnostics,
+            duration,
+            mut skipped_diagnostics,
+            mut traversal,
+            formatted_files,
+            ..
+        } = self;
+
+        if diagnostics.len() > max_diagnostics as usize {
+            let not_printed = (diagnostics.len() - max_diagnostics as usize) as u32;
+            diagnostics.truncate(max_diagnostics as usize);
+            skipped_diagnostics += not_printed;
+            if let Some(traversal) = traversal.as_mut() {
+                traversal.diagnostics_not_printed += not_printed;
+            }
+        }
+
+        Self::new(diagnostics, duration, skipped_diagnostics, traversal)
+            .with_formatted_files(formatted_files)
+    }
 }
 
 pub trait ReportWriter {
@@ -140,6 +186,7 @@ impl Reporter {
             ReportMode::Json => Box::new(json::JsonReportWriter { pretty: false }),
             ReportMode::JsonPretty => Box::new(json::JsonReportWriter { pretty: true }),
             ReportMode::Summary => Box::new(summary::SummaryReportWriter),
+            ReportMode::Sarif => Box::new(sarif::SarifReportWriter),
         };
 
         writer.write(console, command_name, payload, &self.config)
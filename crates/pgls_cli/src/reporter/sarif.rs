@@ -0,0 +1,588 @@
+use crate::diagnostics::CliDiagnostic;
+use crate::reporter::{Report, ReportConfig, ReportWriter};
+use path_absolutize::Absolutize;
+use pgls_analyse::{RegistryVisitor, RuleGroup, RuleMeta};
+use pgls_console::fmt::{Display, Formatter, Termcolor};
+use pgls_console::{Console, ConsoleExt, markup};
+use pgls_diagnostics::display::SourceFile;
+use pgls_diagnostics::termcolor::NoColor;
+use pgls_diagnostics::{Category, Error, LogCategory, PrintDescription, Resource, Severity, Visit};
+use pgls_text_edit::{CompressedOp, DiffOp, TextEdit};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+/// Prints the diagnostics as a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log,
+/// as ingested by GitHub code scanning and most security dashboards.
+pub(crate) struct SarifReportWriter;
+
+impl ReportWriter for SarifReportWriter {
+    fn write(
+        &mut self,
+        console: &mut dyn Console,
+        _command_name: &str,
+        report: &Report,
+        config: &ReportConfig,
+    ) -> Result<(), CliDiagnostic> {
+        let repository_root = report
+            .traversal
+            .as_ref()
+            .and_then(|traversal| traversal.workspace_root.as_deref());
+
+        let known_rules = RuleDescriptors::collect();
+        let mut rules: Vec<SarifRule> = vec![];
+        let mut rule_indices: HashMap<&str, usize> = HashMap::new();
+
+        let results = report
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity() >= config.diagnostic_level)
+            .filter(|d| {
+                if d.tags().is_verbose() {
+                    config.verbose
+                } else {
+                    true
+                }
+            })
+            .map(|diagnostic| {
+                let mut result = to_sarif_result(diagnostic, repository_root);
+
+                if let Some(rule_id) = diagnostic.category().map(|c| c.name()) {
+                    result.rule_index = match rule_indices.get(rule_id) {
+                        Some(index) => Some(*index),
+                        None => known_rules.0.get(rule_id).map(|rule| {
+                            rules.push(rule.clone());
+                            rule_indices.insert(rule_id, rules.len() - 1);
+                            rules.len() - 1
+                        }),
+                    };
+                }
+
+                result
+            })
+            .collect();
+
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: "postgres-language-server",
+                        information_uri: pgls_env::PGLS_WEBSITE,
+                        version: pgls_env::VERSION,
+                        rules,
+                    },
+                },
+                results,
+            }],
+        };
+
+        let serialized = serde_json::to_string_pretty(&log)
+            .map_err(|e| CliDiagnostic::io_error(std::io::Error::other(e)))?;
+
+        console.log(markup!({ serialized }));
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Serialize)]
+struct SarifRun {
+    tool: SarifTool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name: &'static str,
+    information_uri: &'static str,
+    version: &'static str,
+    /// Only the rules referenced by at least one result are listed.
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: String,
+    name: &'static str,
+    short_description: SarifMessage,
+    full_description: SarifMessage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<&'static str>,
+    default_configuration: SarifRuleConfiguration,
+    properties: SarifRuleProperties,
+}
+
+#[derive(Clone, Serialize)]
+struct SarifRuleConfiguration {
+    level: &'static str,
+}
+
+#[derive(Clone, Serialize)]
+struct SarifRuleProperties {
+    recommended: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    sources: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    level: &'static str,
+    message: SarifMessage,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<SarifFix>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    physical_location: Option<SarifPhysicalLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    logical_locations: Vec<SarifLogicalLocation>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<SarifRegion>,
+}
+
+#[derive(Clone, Serialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLogicalLocation {
+    name: String,
+    fully_qualified_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifFix {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<SarifMessage>,
+    artifact_changes: Vec<SarifArtifactChange>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactChange {
+    artifact_location: SarifArtifactLocation,
+    replacements: Vec<SarifReplacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifReplacement {
+    deleted_region: SarifRegion,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inserted_content: Option<SarifMessage>,
+}
+
+/// The descriptors of all analyser, splinter and pglinter rules, keyed by the
+/// category of the diagnostics they emit.
+struct RuleDescriptors(HashMap<String, SarifRule>);
+
+impl RuleDescriptors {
+    fn collect() -> Self {
+        let mut collector = RuleCollector {
+            prefix: "lint",
+            rules: HashMap::new(),
+        };
+        pgls_analyser::visit_registry(&mut collector);
+        collector.prefix = "splinter";
+        pgls_splinter::registry::visit_registry(&mut collector);
+        collector.prefix = "pglinter";
+        pgls_pglinter::registry::visit_registry(&mut collector);
+
+        Self(collector.rules)
+    }
+}
+
+struct RuleCollector {
+    prefix: &'static str,
+    rules: HashMap<String, SarifRule>,
+}
+
+impl RegistryVisitor for RuleCollector {
+    fn record_rule<R>(&mut self)
+    where
+        R: RuleMeta + 'static,
+    {
+        let metadata = R::METADATA;
+        let id = format!(
+            "{}/{}/{}",
+            self.prefix,
+            <R::Group as RuleGroup>::NAME,
+            metadata.name
+        );
+
+        // Everything before the examples describes the rule itself.
+        let description = metadata
+            .docs
+            .split("## Examples")
+            .next()
+            .unwrap_or_default()
+            .lines()
+            .map(|line| line.strip_prefix(' ').unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let description = description.trim();
+        let summary = description.split("\n\n").next().unwrap_or_default();
+
+        let rule = SarifRule {
+            name: metadata.name,
+            short_description: SarifMessage {
+                text: summary.to_string(),
+            },
+            full_description: SarifMessage {
+                text: description.to_string(),
+            },
+            help_uri: id
+                .parse::<&'static Category>()
+                .ok()
+                .and_then(|category| category.link()),
+            default_configuration: SarifRuleConfiguration {
+                level: to_sarif_level(metadata.severity),
+            },
+            properties: SarifRuleProperties {
+                recommended: metadata.recommended,
+                sources: metadata
+                    .sources
+                    .iter()
+                    .map(|source| source.to_namespaced_rule_name())
+                    .collect(),
+            },
+            id: id.clone(),
+        };
+
+        self.rules.insert(id, rule);
+    }
+}
+
+fn to_sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Hint | Severity::Information => "note",
+        Severity::Warning => "warning",
+        Severity::Error | Severity::Fatal => "error",
+    }
+}
+
+fn to_sarif_result(diagnostic: &Error, repository_root: Option<&Path>) -> SarifResult {
+    let location = diagnostic.location();
+
+    let artifact_location = match location.resource {
+        Some(Resource::File(file)) => Some(SarifArtifactLocation {
+            uri: to_uri(file, repository_root),
+        }),
+        _ => None,
+    };
+    let source_file = location.source_code.map(SourceFile::new);
+
+    let mut locations = vec![];
+    if let Some(artifact_location) = &artifact_location {
+        let region = match (location.span, &source_file) {
+            (Some(span), Some(source_file)) => to_region(source_file, span.start(), span.end()),
+            _ => None,
+        };
+        locations.push(SarifLocation {
+            physical_location: Some(SarifPhysicalLocation {
+                artifact_location: artifact_location.clone(),
+                region,
+            }),
+            logical_locations: vec![],
+        });
+    } else if let Some(object) = location.database_object {
+        let fully_qualified_name = match object.schema {
+            Some(schema) => format!("{schema}.{}", object.name),
+            None => object.name.to_string(),
+        };
+        locations.push(SarifLocation {
+            physical_location: None,
+            logical_locations: vec![SarifLogicalLocation {
+                name: object.name.to_string(),
+                fully_qualified_name,
+                kind: object.object_type.map(str::to_string),
+            }],
+        });
+    }
+
+    let fixes = match (artifact_location, location.source_code) {
+        (Some(artifact_location), Some(source_code)) => {
+            let mut collector = FixCollector {
+                source: source_code.text,
+                description: None,
+                fixes: vec![],
+            };
+            // Diagnostics without fixes are reported regardless
+            let _ = diagnostic.advices(&mut collector);
+
+            let source_file = SourceFile::new(source_code);
+            collector
+                .fixes
+                .into_iter()
+                .filter_map(|(description, replacements)| {
+                    let replacements = replacements
+                        .into_iter()
+                        .map(|(start, end, inserted)| {
+                            Some(SarifReplacement {
+                                deleted_region: to_region(
+                                    &source_file,
+                                    start.try_into().ok()?,
+                                    end.try_into().ok()?,
+                                )?,
+                                inserted_content: (!inserted.is_empty())
+                                    .then_some(SarifMessage { text: inserted }),
+                            })
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+
+                    Some(SarifFix {
+                        description: description.map(|text| SarifMessage { text }),
+                        artifact_changes: vec![SarifArtifactChange {
+                            artifact_location: artifact_location.clone(),
+                            replacements,
+                        }],
+                    })
+                })
+                .collect()
+        }
+        _ => vec![],
+    };
+
+    SarifResult {
+        rule_id: diagnostic.category().map(|category| category.name()),
+        rule_index: None,
+        level: to_sarif_level(diagnostic.severity()),
+        message: SarifMessage {
+            text: PrintDescription(diagnostic).to_string(),
+        },
+        locations,
+        fixes,
+    }
+}
+
+/// Paths inside the workspace are reported relative to its root, so that code
+/// scanning tools can map them onto the repository.
+fn to_uri(path: &str, repository_root: Option<&Path>) -> String {
+    let relativized = repository_root.and_then(|root| {
+        let resolved = Path::new(path).absolutize().ok()?;
+        let relative = resolved.strip_prefix(root).ok()?;
+        relative.to_str().map(str::to_string)
+    });
+
+    relativized
+        .unwrap_or_else(|| path.to_string())
+        .replace('\\', "/")
+}
+
+fn to_region(
+    source_file: &SourceFile,
+    start: pgls_text_size::TextSize,
+    end: pgls_text_size::TextSize,
+) -> Option<SarifRegion> {
+    let start = source_file.location(start).ok()?;
+    let end = source_file.location(end).ok()?;
+    Some(SarifRegion {
+        start_line: start.line_number.get(),
+        start_column: start.column_number.get(),
+        end_line: end.line_number.get(),
+        end_column: end.column_number.get(),
+    })
+}
+
+/// A replacement of the byte range `start..end` of the source with the given text.
+type Replacement = (usize, usize, String);
+
+/// Collects the code suggestions of a diagnostic as replacements in its source.
+struct FixCollector<'a> {
+    source: &'a str,
+    /// The last message logged before a diff, such as "Safe fix: ...".
+    description: Option<String>,
+    fixes: Vec<(Option<String>, Vec<Replacement>)>,
+}
+
+impl Visit for FixCollector<'_> {
+    fn record_log(&mut self, _category: LogCategory, text: &dyn Display) -> std::io::Result<()> {
+        let mut buffer = Vec::new();
+        let mut write = Termcolor(NoColor::new(&mut buffer));
+        text.fmt(&mut Formatter::new(&mut write))?;
+        self.description = Some(String::from_utf8_lossy(&buffer).into_owned());
+        Ok(())
+    }
+
+    fn record_diff(&mut self, diff: &TextEdit) -> std::io::Result<()> {
+        self.record_diff_at(diff, 0);
+        Ok(())
+    }
+
+    fn record_diff_with_offset(
+        &mut self,
+        diff: &TextEdit,
+        line_offset: u32,
+    ) -> std::io::Result<()> {
+        let offset = self
+            .source
+            .split_inclusive('\n')
+            .take(line_offset as usize)
+            .map(str::len)
+            .sum();
+        self.record_diff_at(diff, offset);
+        Ok(())
+    }
+}
+
+impl FixCollector<'_> {
+    fn record_diff_at(&mut self, diff: &TextEdit, offset: usize) {
+        if let Some(replacements) = to_replacements(diff, self.source, offset) {
+            self.fixes.push((self.description.take(), replacements));
+        }
+    }
+}
+
+/// Converts a diff against `source[offset..]` into replacements of byte ranges
+/// of `source`, merging adjacent deletions and insertions.
+fn to_replacements(diff: &TextEdit, source: &str, offset: usize) -> Option<Vec<Replacement>> {
+    let mut replacements = vec![];
+    let mut current: Option<Replacement> = None;
+    let mut position = offset;
+
+    for op in diff.iter() {
+        match op {
+            CompressedOp::DiffOp(op @ DiffOp::Equal { .. }) => {
+                replacements.extend(current.take());
+                position += op.text(diff).len();
+            }
+            CompressedOp::DiffOp(op @ DiffOp::Delete { .. }) => {
+                let len = op.text(diff).len();
+                let replacement = current.get_or_insert((position, position, String::new()));
+                replacement.1 += len;
+                position += len;
+            }
+            CompressedOp::DiffOp(op @ DiffOp::Insert { .. }) => {
+                let replacement = current.get_or_insert((position, position, String::new()));
+                replacement.2.push_str(op.text(diff));
+            }
+            CompressedOp::EqualLines { line_count } => {
+                replacements.extend(current.take());
+                position += source
+                    .get(position..)?
+                    .split_inclusive('\n')
+                    .take(line_count.get() as usize + 1)
+                    .map(str::len)
+                    .sum::<usize>();
+            }
+        }
+
+        if position > source.len() {
+            return None;
+        }
+    }
+
+    replacements.extend(current);
+    Some(replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_replacements, to_sarif_result};
+    use pgls_diagnostics::{DatabaseObjectOwned, Diagnostic, Error, serde};
+    use pgls_text_edit::TextEdit;
+
+    #[test]
+    fn converts_diff_to_replacements() {
+        let source = "select 1;\nSELECT  a FROM t;\n";
+        let old = "SELECT  a FROM t;\n";
+        let new = "select a from t;\n";
+        let diff = TextEdit::from_unicode_words(old, new);
+
+        let replacements = to_replacements(&diff, source, 10).unwrap();
+
+        let mut fixed = source.to_string();
+        for (start, end, inserted) in replacements.iter().rev() {
+            fixed.replace_range(start..end, inserted);
+        }
+        assert_eq!(fixed, "select 1;\nselect a from t;\n");
+        assert!(replacements.iter().all(|(start, _, _)| *start >= 10));
+    }
+
+    #[test]
+    fn reports_database_object_as_logical_location() {
+        #[derive(Debug, Diagnostic)]
+        #[diagnostic(
+            category = "splinter/performance/noPrimaryKey",
+            severity = Information,
+            message = "Table has no primary key"
+        )]
+        struct NoPrimaryKey {
+            #[location(database_object)]
+            db_object: DatabaseObjectOwned,
+        }
+
+        // diagnostics of the database linters cross the workspace boundary before reporting
+        let diagnostic = serde::Diagnostic::new(NoPrimaryKey {
+            db_object: DatabaseObjectOwned {
+                schema: Some("public".to_string()),
+                name: "contacts".to_string(),
+                object_type: Some("table".to_string()),
+            },
+        });
+        let json = serde_json::to_string(&diagnostic).unwrap();
+        let diagnostic: serde::Diagnostic = serde_json::from_str(&json).unwrap();
+
+        let result = serde_json::to_value(to_sarif_result(&Error::from(diagnostic), None)).unwrap();
+
+        let logical_location = &result["locations"][0]["logicalLocations"][0];
+        assert_eq!(logical_location["name"], "contacts");
+        assert_eq!(logical_location["fullyQualifiedName"], "public.contacts");
+        assert_eq!(logical_location["kind"], "table");
+        assert!(result["locations"][0]["physicalLocation"].is_null());
+    }
+}
@@ -65,6 +65,14 @@ pub fn open_transport(runtime: Runtime) -> io::Result<Option<impl WorkspaceTrans
     }
 }
 
+/// Starts a daemon instance in the background if none is running yet, so that
+/// [open_transport] can connect to it. A daemon started this way stops once its
+/// clients have disconnected
+pub fn ensure_daemon_running(runtime: &Runtime) -> io::Result<()> {
+    runtime.block_on(ensure_daemon(true, None, None, None))?;
+    Ok(())
+}
+
 type JsonRpcResult = Result<Box<RawValue>, TransportError>;
 
 /// Implementation of [WorkspaceTransport] for types implementing [AsyncRead]
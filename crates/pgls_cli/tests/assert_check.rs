@@ -6,6 +6,7 @@ use std::path::Path;
 #[cfg(target_os = "linux")]
 use std::path::PathBuf;
 use std::process::ExitStatus;
+use tempfile::TempDir;
 const CONFIG_PATH: &str = "tests/fixtures/postgres-language-server.jsonc";
 
 #[test]
@@ -82,6 +83,21 @@ fn check_json_pretty_reporter_snapshot() {
     ]));
 }
 
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "snapshot expectations only validated on unix-like platforms"
+)]
+fn check_sarif_reporter_snapshot() {
+    assert_snapshot!(run_check(&[
+        "--reporter",
+        "sarif",
+        "--disable-db",
+        "tests/fixtures/test.sql",
+        "tests/fixtures/lint.sql"
+    ]));
+}
+
 #[test]
 #[cfg_attr(
     target_os = "windows",
@@ -95,6 +111,49 @@ fn check_summary_reporter_snapshot() {
     ]));
 }
 
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "snapshot expectations only validated on unix-like platforms"
+)]
+fn check_skip_rule_snapshot() {
+    assert_snapshot!(run_check(&[
+        "--disable-db",
+        "--skip",
+        "lint/safety/banDropColumn",
+        "tests/fixtures/test.sql",
+        "tests/fixtures/lint.sql"
+    ]));
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "snapshot expectations only validated on unix-like platforms"
+)]
+fn check_severity_threshold_snapshot() {
+    assert_snapshot!(run_check(&[
+        "--disable-db",
+        "--severity-threshold",
+        "error",
+        "tests/fixtures/test.sql",
+        "tests/fixtures/lint.sql"
+    ]));
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "snapshot expectations only validated on unix-like platforms"
+)]
+fn check_unknown_rule_snapshot() {
+    assert_snapshot!(run_check(&[
+        "--only",
+        "lint/safety/unknownRule",
+        "tests/fixtures/lint.sql"
+    ]));
+}
+
 #[test]
 #[cfg_attr(
     target_os = "windows",
@@ -129,6 +188,328 @@ fn check_directory_traversal_snapshot() {
     ));
 }
 
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "snapshot expectations only validated on unix-like platforms"
+)]
+fn check_baseline_snapshot() {
+    let project = create_temp_project_dir();
+    let project_dir = project.path();
+    std::fs::create_dir_all(project_dir.join("migrations")).unwrap();
+    std::fs::copy(
+        "tests/fixtures/traversal/postgres-language-server.jsonc",
+        project_dir.join("postgres-language-server.jsonc"),
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/0001.sql"),
+        "alter table test drop column id;\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/0002.sql"),
+        "alter table other drop column name;\n",
+    )
+    .unwrap();
+
+    let args = ["--disable-db", "--log-level", "none", "migrations"];
+    let written = run_check_with(
+        &[&args[..], &["--write-baseline", "baseline.json"]].concat(),
+        None,
+        Some(project_dir),
+    );
+    let baseline = std::fs::read_to_string(project_dir.join("baseline.json")).unwrap();
+
+    // Reformatting a statement keeps it in the baseline, new and fixed ones are reported
+    std::fs::write(
+        project_dir.join("migrations/0001.sql"),
+        "ALTER TABLE test\n  DROP COLUMN id;\n\nalter table test drop column name;\n",
+    )
+    .unwrap();
+    std::fs::write(project_dir.join("migrations/0002.sql"), "select 1;\n").unwrap();
+    let checked = run_check_with(
+        &[&args[..], &["--baseline", "baseline.json"]].concat(),
+        None,
+        Some(project_dir),
+    );
+
+    assert_snapshot!(format!("{written}\nbaseline.json:\n{baseline}\n{checked}"));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn check_migrations_snapshot() {
+    use std::process::Command;
+
+    let project = create_temp_project_dir();
+    let project_dir = project.path();
+    std::fs::create_dir_all(project_dir.join("migrations")).unwrap();
+    std::fs::write(
+        project_dir.join("postgres-language-server.jsonc"),
+        r#"{
+  "vcs": { "enabled": true, "clientKind": "git", "defaultBranch": "main" },
+  "migrations": { "migrationsDir": "migrations" }
+}"#,
+    )
+    .unwrap();
+    let migrations = [
+        (
+            "1_create_users.sql",
+            "create table users (id int primary key);\n",
+        ),
+        (
+            "3_add_author.sql",
+            "alter table users add column name text;\nalter table posts add column author_id int;\n",
+        ),
+        (
+            "4_create_posts.sql",
+            "create table posts (id int primary key);\n",
+        ),
+        (
+            "4_create_comments.sql",
+            "create table comments (id int primary key);\n",
+        ),
+        (
+            "5_create_tags.up.sql",
+            "create table tags (id int primary key);\n",
+        ),
+        ("notes.sql", "select 1;\n"),
+    ];
+    for (name, content) in migrations {
+        std::fs::write(project_dir.join("migrations").join(name), content).unwrap();
+    }
+
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(project_dir)
+            .output()
+            .expect("failed to run git")
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    };
+    git(&["init", "--quiet", "--initial-branch=main"]);
+    git(&["add", "."]);
+    git(&["commit", "--quiet", "-m", "initial"]);
+    git(&["checkout", "--quiet", "-b", "feature"]);
+
+    // A migration that is added on a branch, but dated before the ones on the default branch
+    std::fs::write(
+        project_dir.join("migrations/2_create_roles.sql"),
+        "create table roles (id int primary key);\n",
+    )
+    .unwrap();
+    git(&["add", "."]);
+    git(&["commit", "--quiet", "-m", "add roles"]);
+
+    let output = run_check_with(
+        &[
+            "--disable-db",
+            "--log-level",
+            "none",
+            "--only",
+            "lint/safety/banDropColumn",
+            "migrations",
+        ],
+        None,
+        Some(project_dir),
+    );
+
+    // Checking a single migration only reports the diagnostics of that migration
+    let single = run_check_with(
+        &[
+            "--disable-db",
+            "--log-level",
+            "none",
+            "--only",
+            "lint/safety/banDropColumn",
+            "migrations/1_create_users.sql",
+        ],
+        None,
+        Some(project_dir),
+    );
+
+    assert_snapshot!(output);
+    assert!(!single.contains("migrations/"), "{single}");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn check_watch_rechecks_changed_files() {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let project = create_temp_project_dir();
+    let project_dir = project.path();
+    std::fs::create_dir_all(project_dir.join("migrations")).unwrap();
+    std::fs::copy(
+        "tests/fixtures/traversal/postgres-language-server.jsonc",
+        project_dir.join("postgres-language-server.jsonc"),
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/0001.sql"),
+        "alter table test drop column id;\n",
+    )
+    .unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_postgres-language-server"))
+        .args([
+            "check",
+            "--watch",
+            "--disable-db",
+            "--colors",
+            "off",
+            "--log-level",
+            "none",
+            "migrations",
+        ])
+        .current_dir(project_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to run CLI");
+
+    let (sender, lines) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if sender.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+    let next_report = || {
+        let mut report = vec![];
+        loop {
+            let line = lines
+                .recv_timeout(Duration::from_secs(30))
+                .expect("expected a report");
+            if line.starts_with("Watching for changes") {
+                return report.join("\n");
+            }
+            report.push(line);
+        }
+    };
+
+    let initial = next_report();
+
+    // Fixing the file and adding a new one is picked up without restarting
+    std::fs::write(project_dir.join("migrations/0001.sql"), "select 1;\n").unwrap();
+    let fixed = next_report();
+    std::fs::write(
+        project_dir.join("migrations/0002.sql"),
+        "alter table other drop column name;\n",
+    )
+    .unwrap();
+    let added = next_report();
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    // Diagnostics are printed to stderr, the summary of each report to stdout
+    assert!(initial.contains("Checked 1 file"), "{initial}");
+    assert!(initial.contains("Found 2 warnings"), "{initial}");
+    assert!(fixed.contains("Checked 1 file"), "{fixed}");
+    assert!(!fixed.contains("Found"), "{fixed}");
+    assert!(added.contains("Checked 2 files"), "{added}");
+    assert!(added.contains("Found 2 warnings"), "{added}");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn check_watch_reports_migration_diagnostics() {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let project = create_temp_project_dir();
+    let project_dir = project.path();
+    std::fs::create_dir_all(project_dir.join("migrations")).unwrap();
+    std::fs::write(
+        project_dir.join("postgres-language-server.jsonc"),
+        r#"{ "migrations": { "migrationsDir": "migrations" } }"#,
+    )
+    .unwrap();
+    let used_before_creation = "alter table posts add column author_id int;\n";
+    std::fs::write(
+        project_dir.join("migrations/1_add_author.sql"),
+        used_before_creation,
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/2_create_posts.sql"),
+        "create table posts (id int primary key);\n",
+    )
+    .unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_postgres-language-server"))
+        .args([
+            "check",
+            "--watch",
+            "--disable-db",
+            "--colors",
+            "off",
+            "--log-level",
+            "none",
+            "migrations",
+        ])
+        .current_dir(project_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to run CLI");
+
+    let (sender, lines) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if sender.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+    let next_report = || {
+        let mut report = vec![];
+        loop {
+            let line = lines
+                .recv_timeout(Duration::from_secs(30))
+                .expect("expected a report");
+            if line.starts_with("Watching for changes") {
+                return report.join("\n");
+            }
+            report.push(line);
+        }
+    };
+
+    let initial = next_report();
+    std::fs::write(
+        project_dir.join("migrations/1_add_author.sql"),
+        "select 1;\n",
+    )
+    .unwrap();
+    let fixed = next_report();
+    std::fs::write(
+        project_dir.join("migrations/1_add_author.sql"),
+        used_before_creation,
+    )
+    .unwrap();
+    let broken = next_report();
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    // The diagnostics of the migrations as a whole are part of every report
+    assert!(initial.contains("Found 1 error"), "{initial}");
+    assert!(!fixed.contains("error"), "{fixed}");
+    assert!(broken.contains("Found 1 error"), "{broken}");
+}
+
 #[cfg(target_os = "linux")]
 fn get_database_url(pool: &PgPool) -> String {
     let opts = pool.connect_options();
@@ -140,6 +521,15 @@ fn get_database_url(pool: &PgPool) -> String {
     )
 }
 
+/// Creates an empty project directory, which is removed again when the returned [TempDir] is
+/// dropped, also if the test panics
+fn create_temp_project_dir() -> TempDir {
+    tempfile::Builder::new()
+        .prefix("pgls-project-")
+        .tempdir()
+        .expect("failed to create temporary project directory")
+}
+
 #[cfg(target_os = "linux")]
 fn create_temp_sql_file(contents: &str) -> PathBuf {
     let path = std::env::temp_dir().join(format!(
@@ -198,6 +588,141 @@ async fn check_accepts_tls_connection_strings(test_db: PgPool) {
     std::fs::remove_file(sql_file).expect("failed to remove temporary SQL file");
 }
 
+#[cfg(target_os = "linux")]
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn check_applies_migrations_to_database(test_db: PgPool) {
+    let project = create_temp_project_dir();
+    let project_dir = project.path();
+    std::fs::create_dir_all(project_dir.join("migrations")).unwrap();
+    std::fs::write(
+        project_dir.join("postgres-language-server.jsonc"),
+        r#"{ "migrations": { "migrationsDir": "migrations" } }"#,
+    )
+    .unwrap();
+    // Migrations are applied by sequence number, not by the order of their names
+    std::fs::write(
+        project_dir.join("migrations/2_add_email.sql"),
+        "alter table apply_users add column email text;\nalter table apply_missing add column id int;\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/10_never_applied.sql"),
+        "create table apply_later (id int primary key);\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/1_create_users.sql"),
+        "create table apply_users (id int primary key);\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("postgres-language-server")
+        .args([
+            "check",
+            "--disable-db",
+            "--apply-to",
+            &get_database_url(&test_db),
+            "--capture-locks",
+            "--colors",
+            "off",
+            "--log-level",
+            "none",
+            "migrations",
+        ])
+        .current_dir(project_dir)
+        .output()
+        .expect("failed to run CLI");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "stdout:\n{stdout}\nstderr:\n{stderr}"
+    );
+    assert!(
+        stderr.contains("migrations/2_add_email.sql:2:1 migrations/apply")
+            && stderr.contains("relation \"apply_missing\" does not exist"),
+        "expected the failing statement to be reported\nstderr:\n{stderr}"
+    );
+    assert!(
+        stderr.contains("migrations/1_create_users.sql:1:1 migrations/locks")
+            && stderr.contains("AccessExclusiveLock on apply_users"),
+        "expected the captured locks to be reported\nstderr:\n{stderr}"
+    );
+
+    // The failing migration is rolled back, and the ones after it aren't applied
+    let columns: Vec<(String,)> = sqlx::query_as(
+        "select column_name::text from information_schema.columns where table_name = 'apply_users'",
+    )
+    .fetch_all(&test_db)
+    .await
+    .unwrap();
+    assert_eq!(columns, vec![("id".to_string(),)]);
+    let (later,): (Option<String>,) = sqlx::query_as("select to_regclass('apply_later')::text")
+        .fetch_one(&test_db)
+        .await
+        .unwrap();
+    assert_eq!(later, None);
+}
+
+#[cfg(target_os = "linux")]
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn check_applies_statements_that_cannot_run_in_a_transaction(test_db: PgPool) {
+    let project = create_temp_project_dir();
+    let project_dir = project.path();
+    std::fs::create_dir_all(project_dir.join("migrations")).unwrap();
+    std::fs::write(
+        project_dir.join("postgres-language-server.jsonc"),
+        r#"{ "migrations": { "migrationsDir": "migrations" } }"#,
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/1_create_emails.sql"),
+        "create table apply_emails (id int primary key, email text);\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/2_index_emails.sql"),
+        "create index concurrently apply_emails_email_idx on apply_emails (email);\nalter table apply_emails add column name text;\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("postgres-language-server")
+        .args([
+            "check",
+            "--disable-db",
+            "--apply-to",
+            &get_database_url(&test_db),
+            "--capture-locks",
+            "--colors",
+            "off",
+            "--log-level",
+            "none",
+            "migrations",
+        ])
+        .current_dir(project_dir)
+        .output()
+        .expect("failed to run CLI");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("migrations/apply"),
+        "expected all migrations to apply\nstderr:\n{stderr}"
+    );
+    // The statements after the concurrent one still run in a transaction
+    assert!(
+        stderr.contains("migrations/2_index_emails.sql:2:1 migrations/locks"),
+        "expected the captured locks to be reported\nstderr:\n{stderr}"
+    );
+
+    let (index,): (Option<String>,) =
+        sqlx::query_as("select to_regclass('apply_emails_email_idx')::text")
+            .fetch_one(&test_db)
+            .await
+            .unwrap();
+    assert_eq!(index.as_deref(), Some("apply_emails_email_idx"));
+}
+
 fn run_check(args: &[&str]) -> String {
     let mut full_args = vec!["--config-path", CONFIG_PATH, "--log-level", "none"];
     full_args.extend_from_slice(args);
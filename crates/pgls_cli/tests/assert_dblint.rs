@@ -75,6 +75,35 @@ async fn dblint_error_on_warnings_detects_issues_snapshot(test_db: PgPool) {
     assert_snapshot!(output);
 }
 
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "snapshot expectations only validated on unix-like platforms"
+)]
+async fn dblint_skip_rule_snapshot(test_db: PgPool) {
+    // Setup: create a table without primary key and duplicate indexes
+    sqlx::raw_sql(
+        "CREATE TABLE test_no_pk (id int, email text);
+         CREATE INDEX idx_duplicate_a ON test_no_pk (email);
+         CREATE INDEX idx_duplicate_b ON test_no_pk (email);",
+    )
+    .execute(&test_db)
+    .await
+    .expect("Failed to create test schema");
+
+    let url = get_database_url(&test_db);
+    let output = run_dblint(
+        &url,
+        &[
+            "--only",
+            "splinter/performance",
+            "--skip",
+            "splinter/performance/noPrimaryKey",
+        ],
+    );
+    assert_snapshot!(output);
+}
+
 #[test]
 #[cfg_attr(
     target_os = "windows",
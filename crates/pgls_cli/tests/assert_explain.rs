@@ -0,0 +1,73 @@
+use assert_cmd::cargo_bin_cmd;
+use insta::assert_snapshot;
+use std::process::ExitStatus;
+
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "snapshot expectations only validated on unix-like platforms"
+)]
+fn explain_lint_rule_snapshot() {
+    assert_snapshot!(run_explain(&["multipleAlterTable"]));
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "snapshot expectations only validated on unix-like platforms"
+)]
+fn explain_splinter_rule_snapshot() {
+    assert_snapshot!(run_explain(&["splinter/performance/noPrimaryKey"]));
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "snapshot expectations only validated on unix-like platforms"
+)]
+fn explain_unknown_rule_snapshot() {
+    assert_snapshot!(run_explain(&["lint/safety/unknownRule"]));
+}
+
+#[test]
+fn explain_list_contains_all_analysers() {
+    let output = run_explain(&["--list"]);
+
+    for row in [
+        "banDropColumn",
+        "lint/safety",
+        "noPrimaryKey",
+        "splinter/performance",
+        "compositePrimaryKeyTooManyColumns",
+        "pglinter/base",
+    ] {
+        assert!(output.contains(row), "expected {row} in:\n{output}");
+    }
+}
+
+fn run_explain(args: &[&str]) -> String {
+    let mut cmd = cargo_bin_cmd!("postgres-language-server");
+    let mut full_args = vec!["explain"];
+    full_args.extend_from_slice(args);
+
+    let output = cmd.args(full_args).output().expect("failed to run CLI");
+
+    normalize_output(
+        output.status,
+        &String::from_utf8_lossy(&output.stdout),
+        &String::from_utf8_lossy(&output.stderr),
+    )
+}
+
+fn normalize_output(status: ExitStatus, stdout: &str, stderr: &str) -> String {
+    let status_label = if status.success() {
+        "success"
+    } else {
+        "failure"
+    };
+    format!(
+        "status: {status_label}\nstdout:\n{}\nstderr:\n{}\n",
+        stdout.trim_end(),
+        stderr.trim_end()
+    )
+}
@@ -0,0 +1,87 @@
+use assert_cmd::cargo_bin_cmd;
+use serde_json::Value;
+const CONFIG_PATH: &str = "tests/fixtures/postgres-language-server.jsonc";
+
+#[test]
+fn format_stdin_prints_formatted_code() {
+    let output = cargo_bin_cmd!("postgres-language-server")
+        .args([
+            "format",
+            "--config-path",
+            CONFIG_PATH,
+            "--stdin-file-path",
+            "virtual.sql",
+            "--log-level",
+            "none",
+        ])
+        .write_stdin("SELECT a,b FROM t WHERE x=1;\n")
+        .output()
+        .expect("failed to run CLI");
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "select a, b from t where x = 1;"
+    );
+}
+
+#[test]
+fn format_stdin_prints_code_with_syntax_errors_unchanged() {
+    let output = cargo_bin_cmd!("postgres-language-server")
+        .args([
+            "format",
+            "--config-path",
+            CONFIG_PATH,
+            "--stdin-file-path",
+            "virtual.sql",
+            "--log-level",
+            "none",
+        ])
+        .write_stdin("selec 1;\n")
+        .output()
+        .expect("failed to run CLI");
+
+    assert!(!output.status.success(), "{output:?}");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "selec 1;\n");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("virtual.sql:1:1 syntax"),
+        "{output:?}"
+    );
+}
+
+#[test]
+#[cfg_attr(
+    target_os = "windows",
+    ignore = "paths are only validated on unix-like platforms"
+)]
+fn format_json_reporter_lists_formatted_statements() {
+    let output = cargo_bin_cmd!("postgres-language-server")
+        .args([
+            "format",
+            "--config-path",
+            CONFIG_PATH,
+            "--reporter",
+            "json",
+            "--log-level",
+            "none",
+            "tests/fixtures/unformatted.sql",
+        ])
+        .output()
+        .expect("failed to run CLI");
+
+    assert!(!output.status.success(), "{output:?}");
+    let report: Value = serde_json::from_slice(&output.stdout).expect("the output should be JSON");
+    assert_eq!(
+        report["formatted"],
+        serde_json::json!([{
+            "location": {
+                "path": "tests/fixtures/unformatted.sql",
+                "start": { "line": 3, "column": 1 },
+                "end": { "line": 3, "column": 29 }
+            },
+            "range": [11, 39],
+            "original": "SELECT a,b FROM t WHERE x=1;",
+            "formatted": "select a, b from t where x = 1;"
+        }])
+    );
+}
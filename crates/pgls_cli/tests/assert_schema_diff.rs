@@ -0,0 +1,155 @@
+use assert_cmd::cargo_bin_cmd;
+use serde_json::Value;
+use sqlx::PgPool;
+
+/// Get database URL from the pool's connect options
+/// Uses the known docker-compose credentials (postgres:postgres)
+fn get_database_url(pool: &PgPool) -> String {
+    let opts = pool.connect_options();
+    format!(
+        "postgres://postgres:postgres@{}:{}/{}",
+        opts.get_host(),
+        opts.get_port(),
+        opts.get_database().unwrap_or("postgres")
+    )
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn schema_diff_reports_changes_of_migrations(test_db: PgPool) {
+    // The bookkeeping table of sqlx is dropped, so that only the tables of the test remain
+    sqlx::raw_sql(
+        "drop table _sqlx_migrations; create table diff_users (id int primary key, email varchar(100));",
+    )
+        .execute(&test_db)
+        .await
+        .unwrap();
+
+    let project = tempfile::Builder::new()
+        .prefix("pgls-schema-diff-")
+        .tempdir()
+        .expect("failed to create temporary project directory");
+    let project_dir = project.path();
+    std::fs::create_dir_all(project_dir.join("migrations")).unwrap();
+    std::fs::write(
+        project_dir.join("postgres-language-server.jsonc"),
+        r#"{ "migrations": { "migrationsDir": "migrations" } }"#,
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/1_create_users.sql"),
+        "create table diff_users (id int primary key, email varchar(100));\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/2_users.sql"),
+        "alter table diff_users alter column email type text;\nalter table diff_users add column name text;\n",
+    )
+    .unwrap();
+    std::fs::write(
+        project_dir.join("migrations/3_posts.sql"),
+        "create table diff_posts (id int primary key, title text);\ncreate index concurrently diff_posts_title_idx on diff_posts (title);\n",
+    )
+    .unwrap();
+
+    let url = get_database_url(&test_db);
+
+    // The schema before the last migrations is exported
+    let export = cargo_bin_cmd!("postgres-language-server")
+        .args(["schema-export", "-c", &url, "-o", "before.json"])
+        .current_dir(project_dir)
+        .output()
+        .expect("failed to run CLI");
+    assert!(export.status.success(), "{export:?}");
+
+    let schema_diff = || {
+        cargo_bin_cmd!("postgres-language-server")
+            .args([
+                "schema-diff",
+                "before.json",
+                "migrations",
+                "--replay-on",
+                &url,
+                "--ddl",
+                "--reporter",
+                "json",
+                "--log-level",
+                "none",
+            ])
+            .current_dir(project_dir)
+            .output()
+            .expect("failed to run CLI")
+    };
+
+    let scratch_databases = || async {
+        sqlx::query_scalar::<_, i64>(
+            "select count(*) from pg_catalog.pg_database where datname like 'pgls\\_replay\\_%'",
+        )
+        .fetch_one(&test_db)
+        .await
+        .unwrap()
+    };
+
+    // The migrations are replayed onto a temporary database, so the database of
+    // `--replay-on` doesn't need to be empty, and the replay can be repeated
+    let output = schema_diff();
+    assert_eq!(schema_diff().stdout, output.stdout);
+    assert_eq!(scratch_databases().await, 0);
+
+    // The temporary database is dropped after a failed replay, too
+    std::fs::write(
+        project_dir.join("migrations/4_broken.sql"),
+        "select * from missing;\n",
+    )
+    .unwrap();
+    let failed = schema_diff();
+    assert!(!failed.status.success(), "{failed:?}");
+    assert_eq!(scratch_databases().await, 0);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "stdout:\n{stdout}\nstderr:\n{stderr}"
+    );
+
+    let report: Value = serde_json::from_str(&stdout).expect("the output should be JSON");
+    let changes: Vec<(&str, &str, &str)> = report["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| {
+            (
+                change["objectKind"].as_str().unwrap(),
+                change["name"].as_str().unwrap(),
+                change["kind"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("table", "public.diff_posts", "added"),
+            ("column", "public.diff_users.email", "changed"),
+            ("column", "public.diff_users.name", "added"),
+            ("index", "public.diff_posts_pkey", "added"),
+            ("index", "public.diff_posts_title_idx", "added"),
+        ]
+    );
+
+    let ddl = report["ddl"].as_str().unwrap();
+    assert!(
+        ddl.contains("alter table public.diff_users alter column email type text;"),
+        "{ddl}"
+    );
+
+    let categories: Vec<&str> = report["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|diagnostic| diagnostic["category"].as_str().unwrap())
+        .collect();
+    assert!(
+        categories.contains(&"lint/safety/multipleAlterTable"),
+        "{categories:?}"
+    );
+}
@@ -0,0 +1 @@
+alter table test drop column id;
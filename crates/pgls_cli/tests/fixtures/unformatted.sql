@@ -0,0 +1,3 @@
+select 1;
+
+SELECT a,b FROM t WHERE x=1;
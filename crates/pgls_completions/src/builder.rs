@@ -1,5 +1,5 @@
 use crate::{
-    CompletionItemKind, CompletionText,
+    CompletionItemKind, CompletionText, UsageStats,
     item::CompletionItem,
     relevance::{filtering::CompletionFilter, scoring::CompletionScore},
 };
@@ -20,11 +20,16 @@ pub(crate) struct PossibleCompletionItem<'a> {
 pub(crate) struct CompletionBuilder<'a> {
     items: Vec<PossibleCompletionItem<'a>>,
     ctx: &'a TreesitterContext<'a>,
+    usage: Option<&'a UsageStats>,
 }
 
 impl<'a> CompletionBuilder<'a> {
-    pub fn new(ctx: &'a TreesitterContext) -> Self {
-        CompletionBuilder { items: vec![], ctx }
+    pub fn new(ctx: &'a TreesitterContext, usage: Option<&'a UsageStats>) -> Self {
+        CompletionBuilder {
+            items: vec![],
+            ctx,
+            usage,
+        }
     }
 
     pub fn add_item(&mut self, item: PossibleCompletionItem<'a>) {
@@ -41,7 +46,7 @@ impl<'a> CompletionBuilder<'a> {
             .collect();
 
         for item in items.iter_mut() {
-            item.score.calc_score(self.ctx);
+            item.score.calc_score(self.ctx, self.usage);
         }
 
         items.retain(|i| !i.score.should_skip());
@@ -81,6 +86,8 @@ impl<'a> CompletionBuilder<'a> {
 
                     // wonderous Rust syntax ftw
                     sort_text: format!("{idx:0>max_padding$}"),
+                    resolve_data: item.score.resolve_data(),
+                    highlights: item.score.highlights().to_vec(),
                     completion_text: item.completion_text,
                 }
             })
@@ -6,10 +6,12 @@ use crate::{
     builder::CompletionBuilder,
     item::CompletionItem,
     providers::{
-        complete_columns, complete_functions, complete_keywords, complete_policies, complete_roles,
-        complete_schemas, complete_tables,
+        Snippet, complete_columns, complete_enum_values, complete_functions,
+        complete_join_conditions, complete_keywords, complete_policies, complete_roles,
+        complete_schemas, complete_sequences, complete_snippets, complete_tables, complete_types,
     },
     sanitization::SanitizedCompletionParams,
+    usage::UsageStats,
 };
 
 pub const LIMIT: usize = 50;
@@ -20,6 +22,10 @@ pub struct CompletionParams<'a> {
     pub schema: &'a pgls_schema_cache::SchemaCache,
     pub text: String,
     pub tree: &'a tree_sitter::Tree,
+    /// User-defined snippets, offered along the built-in ones.
+    pub snippets: &'a [Snippet],
+    /// How often database objects are used in the workspace, so commonly used ones rank higher.
+    pub usage: Option<&'a UsageStats>,
 }
 
 #[tracing::instrument(level = "debug", skip_all, fields(
@@ -34,6 +40,8 @@ pub fn complete(params: CompletionParams) -> Vec<CompletionItem> {
         .filter(|word| word.chars().all(|c| c.is_alphabetic()))
         .any(|t| t == t.to_ascii_uppercase());
 
+    let snippets = params.snippets;
+    let usage = params.usage;
     let sanitized_params = SanitizedCompletionParams::from(params);
 
     let ctx = TreesitterContext::new(TreeSitterContextParams {
@@ -42,7 +50,7 @@ pub fn complete(params: CompletionParams) -> Vec<CompletionItem> {
         tree: &sanitized_params.tree,
     });
 
-    let mut builder = CompletionBuilder::new(&ctx);
+    let mut builder = CompletionBuilder::new(&ctx, usage);
 
     complete_tables(&ctx, sanitized_params.schema, &mut builder);
     complete_functions(&ctx, sanitized_params.schema, &mut builder);
@@ -50,7 +58,12 @@ pub fn complete(params: CompletionParams) -> Vec<CompletionItem> {
     complete_schemas(&ctx, sanitized_params.schema, &mut builder);
     complete_policies(&ctx, sanitized_params.schema, &mut builder);
     complete_roles(&ctx, sanitized_params.schema, &mut builder);
+    complete_types(&ctx, sanitized_params.schema, &mut builder);
+    complete_enum_values(&ctx, sanitized_params.schema, &mut builder);
+    complete_sequences(&ctx, sanitized_params.schema, &mut builder);
+    complete_join_conditions(&ctx, sanitized_params.schema, &mut builder);
     complete_keywords(&ctx, &mut builder, uses_upper_case);
+    complete_snippets(&ctx, sanitized_params.schema, usage, snippets, &mut builder);
 
     builder.finish()
 }
@@ -14,6 +14,11 @@ pub enum CompletionItemKind {
     Policy,
     Role,
     Keyword,
+    Type,
+    EnumValue,
+    Sequence,
+    JoinCondition,
+    Snippet,
 }
 
 impl Display for CompletionItemKind {
@@ -26,6 +31,11 @@ impl Display for CompletionItemKind {
             CompletionItemKind::Policy => "Policy",
             CompletionItemKind::Role => "Role",
             CompletionItemKind::Keyword => "Keyword",
+            CompletionItemKind::Type => "Type",
+            CompletionItemKind::EnumValue => "EnumValue",
+            CompletionItemKind::Sequence => "Sequence",
+            CompletionItemKind::JoinCondition => "JoinCondition",
+            CompletionItemKind::Snippet => "Snippet",
         };
 
         write!(f, "{txt}")
@@ -61,4 +71,20 @@ pub struct CompletionItem {
     pub detail: Option<String>,
 
     pub completion_text: Option<CompletionText>,
+
+    /// Set if further documentation can be resolved lazily with `resolve_completion_item`.
+    pub resolve_data: Option<CompletionResolveData>,
+
+    /// The ranges of the label that matched the typed text, e.g. to highlight them.
+    pub highlights: Vec<TextRange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+/// Identifies the schema cache item a completion was created for.
+/// Sent back by the client to request the item's documentation.
+pub enum CompletionResolveData {
+    Table { id: i64 },
+    Function { id: i64 },
 }
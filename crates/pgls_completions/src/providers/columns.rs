@@ -1,5 +1,5 @@
 use pgls_schema_cache::{Column, SchemaCache};
-use pgls_treesitter::TreesitterContext;
+use pgls_treesitter::{DerivedRelation, DerivedRelationKind, ResolvedColumn, TreesitterContext};
 
 use crate::{
     CompletionItemKind, CompletionText,
@@ -32,6 +32,55 @@ pub fn complete_columns<'a>(
 
         builder.add_item(item);
     }
+
+    complete_derived_columns(ctx, schema_cache, builder);
+}
+
+/// Columns of CTEs, derived tables and set-returning functions the statement defines.
+fn complete_derived_columns<'a>(
+    ctx: &TreesitterContext<'a>,
+    schema_cache: &'a SchemaCache,
+    builder: &mut CompletionBuilder<'a>,
+) {
+    for relation in ctx.visible_derived_relations() {
+        for col in ctx.query_scope.resolve_columns(relation, schema_cache) {
+            let completion_text = get_derived_completion_text(ctx, relation, &col);
+            let detail = col.type_name.clone();
+            let label = col.name.clone();
+            let description = col.relation.clone();
+
+            let relevance = CompletionRelevanceData::DerivedColumn(col);
+
+            let item = PossibleCompletionItem {
+                label,
+                score: CompletionScore::from(relevance.clone()),
+                filter: CompletionFilter::from(relevance),
+                description,
+                kind: CompletionItemKind::Column,
+                completion_text: Some(completion_text),
+                detail,
+            };
+
+            builder.add_item(item);
+        }
+    }
+}
+
+fn get_derived_completion_text(
+    ctx: &TreesitterContext,
+    relation: &DerivedRelation,
+    col: &ResolvedColumn,
+) -> CompletionText {
+    // derived tables can only be referenced by their alias, so that's what we prefix with
+    let alias = ctx
+        .get_used_alias_for_table(col.relation.as_str())
+        .or_else(|| (relation.kind != DerivedRelationKind::Cte).then(|| relation.name.clone()));
+
+    CompletionText {
+        is_snippet: false,
+        range: get_range_to_replace(ctx),
+        text: with_schema_or_alias(ctx, col.name.as_str(), alias.as_deref()),
+    }
 }
 
 fn get_completion_text(ctx: &TreesitterContext, col: &Column) -> CompletionText {
@@ -53,7 +102,14 @@ mod tests {
 
     use sqlx::PgPool;
 
-    use crate::test_helper::{TestCompletionsCase, TestCompletionsSuite};
+    use crate::{
+        CompletionItemKind,
+        test_helper::{
+            CompletionAssertion, TestCompletionsCase, TestCompletionsSuite, assert_complete_results,
+        },
+    };
+
+    use pgls_test_utils::QueryWithCursorPosition;
 
     #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
     async fn handles_nested_queries(pool: PgPool) {
@@ -597,4 +653,120 @@ mod tests {
             .snapshot("completes_quoted_columns_with_aliases")
             .await;
     }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_derived_columns(pool: PgPool) {
+        let setup = r#"
+            create table public.users (
+                id serial primary key,
+                email text
+            );
+
+            create table public.login_stats (
+                user_id int,
+                logins bigint
+            );
+
+            create function public.stats_for(uid int) returns setof public.login_stats
+            language sql as $$ select * from public.login_stats where user_id = uid $$;
+        "#;
+
+        TestCompletionsSuite::new(&pool, Some(setup))
+            .with_case(
+                TestCompletionsCase::new()
+                    .inside_static_statement(
+                        "with recent as (select id, email as mail from users) <sql> from recent",
+                    )
+                    .type_sql("select mail")
+                    .comment("Should suggest the columns of the CTE"),
+            )
+            .with_case(
+                TestCompletionsCase::new()
+                    .inside_static_statement("<sql> from (select id, email from users) sub")
+                    .type_sql("select sub.email"),
+            )
+            .with_case(
+                TestCompletionsCase::new()
+                    .inside_static_statement("<sql> from (values (1, 'a')) v (num, letter)")
+                    .type_sql("select letter"),
+            )
+            .with_case(
+                TestCompletionsCase::new()
+                    .inside_static_statement("<sql> from stats_for(1) s")
+                    .type_sql("select logins")
+                    .comment("Should suggest the columns of the function's return type"),
+            )
+            .snapshot("completes_derived_columns")
+            .await;
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn suggests_columns_of_target_table_in_ddl(pool: PgPool) {
+        let setup = r#"
+            create table public.users (
+                id serial primary key,
+                email text unique,
+                name text
+            );
+
+            create table public.posts (
+                id serial primary key,
+                title text,
+                user_id int
+            );
+        "#;
+
+        assert_complete_results(
+            format!(
+                "create index idx on posts (id, {});",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind("id".into(), CompletionItemKind::Column),
+                CompletionAssertion::LabelAndKind("title".into(), CompletionItemKind::Column),
+                CompletionAssertion::LabelAndKind("user_id".into(), CompletionItemKind::Column),
+                CompletionAssertion::LabelNotExists("email".into()),
+                CompletionAssertion::LabelNotExists("name".into()),
+            ],
+            Some(setup),
+            &pool,
+        )
+        .await;
+
+        // only primary-key or unique columns can be referenced
+        assert_complete_results(
+            format!(
+                "create table comments (id int, author int references public.users ({}));",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind("email".into(), CompletionItemKind::Column),
+                CompletionAssertion::LabelAndKind("id".into(), CompletionItemKind::Column),
+                CompletionAssertion::LabelNotExists("name".into()),
+                CompletionAssertion::LabelNotExists("title".into()),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+
+        assert_complete_results(
+            format!(
+                "alter table posts add constraint fk foreign key (user_id) references users ({});",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind("email".into(), CompletionItemKind::Column),
+                CompletionAssertion::LabelAndKind("id".into(), CompletionItemKind::Column),
+                CompletionAssertion::LabelNotExists("name".into()),
+                CompletionAssertion::LabelNotExists("user_id".into()),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+    }
 }
@@ -0,0 +1,357 @@
+use pgls_schema_cache::{PostgresType, SchemaCache};
+use pgls_text_size::TextRange;
+use pgls_treesitter::TreesitterContext;
+
+use crate::{
+    CompletionItemKind, CompletionText,
+    builder::{CompletionBuilder, PossibleCompletionItem},
+    providers::helper::{get_range_to_replace, quote_literal, value_node_under_cursor},
+    relevance::{CompletionRelevanceData, filtering::CompletionFilter, scoring::CompletionScore},
+};
+
+pub fn complete_enum_values<'a>(
+    ctx: &TreesitterContext<'a>,
+    schema_cache: &'a SchemaCache,
+    builder: &mut CompletionBuilder<'a>,
+) {
+    let Some(value_node) = value_node_under_cursor(ctx) else {
+        return;
+    };
+
+    for enum_type in compared_enum_types(ctx, schema_cache, value_node) {
+        for value in &enum_type.enums.values {
+            let relevance = CompletionRelevanceData::EnumValue(value);
+
+            let item = PossibleCompletionItem {
+                label: value.clone(),
+                score: CompletionScore::from(relevance.clone()),
+                filter: CompletionFilter::from(relevance),
+                description: if enum_type.schema == "public" {
+                    enum_type.name.clone()
+                } else {
+                    format!("{}.{}", enum_type.schema, enum_type.name)
+                },
+                kind: CompletionItemKind::EnumValue,
+                completion_text: Some(get_completion_text(ctx, value_node, value)),
+                detail: None,
+            };
+
+            builder.add_item(item);
+        }
+    }
+}
+
+fn get_completion_text(
+    ctx: &TreesitterContext,
+    value_node: tree_sitter::Node,
+    value: &str,
+) -> CompletionText {
+    let range = if value_node.kind() == "literal" {
+        TextRange::new(
+            value_node.start_byte().try_into().unwrap(),
+            value_node.end_byte().try_into().unwrap(),
+        )
+    } else {
+        get_range_to_replace(ctx)
+    };
+
+    CompletionText {
+        text: quote_literal(value),
+        range,
+        is_snippet: false,
+    }
+}
+
+/// Finds the enum types of the columns the value under the cursor is compared with or assigned to:
+///
+/// ```sql
+/// select * from orders where status = |;
+/// update orders set status = |;
+/// insert into orders (id, status) values (1, |);
+/// ```
+///
+/// When adding a value to an enum, the labels of that enum are returned:
+///
+/// ```sql
+/// alter type status add value 'pending' before |;
+/// ```
+fn compared_enum_types<'a>(
+    ctx: &TreesitterContext,
+    schema_cache: &'a SchemaCache,
+    value_node: tree_sitter::Node,
+) -> Vec<&'a PostgresType> {
+    let Some(parent) = value_node.parent() else {
+        return vec![];
+    };
+
+    if parent.kind() == "alter_type" {
+        let is_neighbour_value = value_node
+            .prev_sibling()
+            .is_some_and(|p| matches!(p.kind(), "keyword_before" | "keyword_after"));
+
+        return child_of_kind(parent, "type_identifier")
+            .filter(|_| is_neighbour_value)
+            .map(|t| schema_cache.find_types(&node_text(ctx, t), None))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|t| !t.enums.values.is_empty())
+            .collect();
+    }
+
+    let columns = match parent.kind() {
+        "binary_expression" => parent
+            .child_by_field_name("binary_expr_left")
+            .filter(|left| *left != value_node && left.kind() == "object_reference")
+            .map(|left| find_referenced_columns(ctx, schema_cache, left))
+            .unwrap_or_default(),
+
+        "assignment" => parent
+            .child_by_field_name("left")
+            .zip(
+                parent
+                    .parent()
+                    .and_then(|set_values| set_values.parent())
+                    .and_then(|update| child_of_kind(update, "update_target"))
+                    .and_then(|target| child_of_kind(target, "table_reference")),
+            )
+            .map(|(column, table)| find_table_columns(ctx, schema_cache, table, column))
+            .unwrap_or_default(),
+
+        "insert_values" => {
+            let mut cursor = parent.walk();
+            let position = parent
+                .named_children(&mut cursor)
+                .filter(|n| n.kind() != "keyword_values")
+                .position(|n| n == value_node);
+
+            let insert = parent.parent();
+
+            position
+                .zip(insert.and_then(|i| child_of_kind(i, "insert_columns")))
+                .and_then(|(position, columns)| {
+                    let mut cursor = columns.walk();
+                    columns
+                        .named_children(&mut cursor)
+                        .filter(|n| n.kind() == "column_identifier")
+                        .nth(position)
+                })
+                .zip(insert.and_then(|i| child_of_kind(i, "table_reference")))
+                .map(|(column, table)| find_table_columns(ctx, schema_cache, table, column))
+                .unwrap_or_default()
+        }
+
+        _ => vec![],
+    };
+
+    let mut enum_types: Vec<&PostgresType> = columns
+        .into_iter()
+        .filter_map(|col| schema_cache.find_type_by_id(col.type_id))
+        .filter(|t| !t.enums.values.is_empty())
+        .collect();
+
+    enum_types.dedup_by_key(|t| t.id);
+    enum_types
+}
+
+/// Resolves a (possibly qualified) column reference against the relations mentioned in the statement.
+fn find_referenced_columns<'a>(
+    ctx: &TreesitterContext,
+    schema_cache: &'a SchemaCache,
+    reference: tree_sitter::Node,
+) -> Vec<&'a pgls_schema_cache::Column> {
+    let parts = identifier_parts(ctx, reference);
+
+    let Some((column, qualifiers)) = parts.split_last() else {
+        return vec![];
+    };
+
+    let (schema, table) = match qualifiers {
+        [] => (None, None),
+        [table_or_alias] => (
+            None,
+            Some(
+                ctx.get_mentioned_table_for_alias(table_or_alias)
+                    .cloned()
+                    .unwrap_or(table_or_alias.clone()),
+            ),
+        ),
+        [schema, table, ..] => (Some(schema.clone()), Some(table.clone())),
+    };
+
+    schema_cache
+        .find_cols(column, table.as_deref(), schema.as_deref())
+        .into_iter()
+        .filter(|col| {
+            table.is_some()
+                || ctx
+                    .get_mentioned_relations(&Some(col.schema_name.clone()))
+                    .or(ctx.get_mentioned_relations(&None))
+                    .is_some_and(|tables| tables.contains(&col.table_name))
+        })
+        .collect()
+}
+
+fn find_table_columns<'a>(
+    ctx: &TreesitterContext,
+    schema_cache: &'a SchemaCache,
+    table_reference: tree_sitter::Node,
+    column: tree_sitter::Node,
+) -> Vec<&'a pgls_schema_cache::Column> {
+    let table_parts = identifier_parts(ctx, table_reference);
+    let column_name = node_text(ctx, column);
+
+    match table_parts.as_slice() {
+        [table] => schema_cache.find_cols(&column_name, Some(table), None),
+        [schema, table] => schema_cache.find_cols(&column_name, Some(table), Some(schema)),
+        _ => vec![],
+    }
+}
+
+fn identifier_parts(ctx: &TreesitterContext, reference: tree_sitter::Node) -> Vec<String> {
+    let mut cursor = reference.walk();
+    reference
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "any_identifier")
+        .map(|n| node_text(ctx, n))
+        .collect()
+}
+
+fn node_text(ctx: &TreesitterContext, node: tree_sitter::Node) -> String {
+    node.utf8_text(ctx.text.as_bytes())
+        .unwrap_or_default()
+        .replace('"', "")
+}
+
+fn child_of_kind<'a>(node: tree_sitter::Node<'a>, kind: &str) -> Option<tree_sitter::Node<'a>> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).find(|c| c.kind() == kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use pgls_text_size::TextRange;
+    use sqlx::{Executor, PgPool};
+
+    use crate::{
+        CompletionItemKind,
+        test_helper::{CompletionAssertion, assert_complete_results},
+    };
+
+    use pgls_test_utils::QueryWithCursorPosition;
+
+    static SETUP: &str = r#"
+        create type public.order_status as enum ('pending', 'shipped', 'delivered');
+        create type public.priority as enum ('low', 'high');
+
+        create table public.orders (
+            id serial primary key,
+            status order_status not null,
+            urgency priority
+        );
+    "#;
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_enum_values_in_comparisons(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "select * from orders where status = {}",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind(
+                    "delivered".into(),
+                    CompletionItemKind::EnumValue,
+                ),
+                CompletionAssertion::LabelAndKind("pending".into(), CompletionItemKind::EnumValue),
+                CompletionAssertion::LabelAndKind("shipped".into(), CompletionItemKind::EnumValue),
+                // only values of the compared column's enum
+                CompletionAssertion::LabelNotExists("low".into()),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+
+        assert_complete_results(
+            format!(
+                "select * from orders o where o.urgency = 'h{}'",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::CompletionTextAndRange(
+                    "'high'".into(),
+                    TextRange::new(41.into(), 44.into()),
+                ),
+                CompletionAssertion::LabelNotExists("shipped".into()),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_enum_values_for_insert_and_update_targets(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "insert into orders (status, urgency) values ('pending', {})",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind("high".into(), CompletionItemKind::EnumValue),
+                CompletionAssertion::LabelAndKind("low".into(), CompletionItemKind::EnumValue),
+                CompletionAssertion::LabelNotExists("pending".into()),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+
+        assert_complete_results(
+            format!(
+                "update orders set status = sh{}",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![CompletionAssertion::CompletionTextAndRange(
+                "'shipped'".into(),
+                TextRange::new(27.into(), 29.into()),
+            )],
+            None,
+            &pool,
+        )
+        .await;
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_existing_labels_when_adding_enum_values(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "alter type order_status add value 'returned' before '{}';",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind(
+                    "delivered".into(),
+                    CompletionItemKind::EnumValue,
+                ),
+                CompletionAssertion::LabelAndKind("pending".into(), CompletionItemKind::EnumValue),
+                CompletionAssertion::LabelAndKind("shipped".into(), CompletionItemKind::EnumValue),
+                CompletionAssertion::LabelNotExists("low".into()),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+    }
+}
@@ -4,7 +4,10 @@ use pgls_treesitter::TreesitterContext;
 use crate::{
     CompletionItemKind, CompletionText,
     builder::{CompletionBuilder, PossibleCompletionItem},
-    providers::helper::{get_range_to_replace, node_text_surrounded_by_quotes, only_leading_quote},
+    providers::helper::{
+        get_range_to_replace, is_function_signature_position, is_trigger_function_position,
+        node_text_surrounded_by_quotes, only_leading_quote,
+    },
     relevance::{CompletionRelevanceData, filtering::CompletionFilter, scoring::CompletionScore},
 };
 
@@ -20,8 +23,14 @@ pub fn complete_functions<'a>(
     for func in available_functions {
         let relevance = CompletionRelevanceData::Function(func);
 
+        let label = if is_function_signature_position(ctx) {
+            signature(func)
+        } else {
+            func.name.clone()
+        };
+
         let item = PossibleCompletionItem {
-            label: func.name.clone(),
+            label,
             score: CompletionScore::from(relevance.clone()),
             filter: CompletionFilter::from(relevance),
             description: format!("Schema: {}", func.schema),
@@ -35,11 +44,22 @@ pub fn complete_functions<'a>(
 }
 
 fn get_completion_text(ctx: &TreesitterContext, func: &Function) -> CompletionText {
-    let mut text = with_schema_or_alias(ctx, func.name.as_str(), Some(func.schema.as_str()));
-
     let mut range = get_range_to_replace(ctx);
 
-    if ctx.is_invocation {
+    if is_function_signature_position(ctx) {
+        let text = with_schema_or_alias(ctx, signature(func).as_str(), Some(func.schema.as_str()));
+
+        return CompletionText {
+            text,
+            range,
+            is_snippet: false,
+        };
+    }
+
+    let mut text = with_schema_or_alias(ctx, func.name.as_str(), Some(func.schema.as_str()));
+
+    // the trigger syntax requires the parentheses, so they are already there
+    if ctx.is_invocation || is_trigger_function_position(ctx) {
         CompletionText {
             text,
             range,
@@ -71,6 +91,15 @@ fn get_completion_text(ctx: &TreesitterContext, func: &Function) -> CompletionTe
     }
 }
 
+/// The identity signature of the function, e.g. `add(integer, integer)`.
+fn signature(func: &Function) -> String {
+    format!(
+        "{}({})",
+        func.name,
+        func.identity_argument_types.as_deref().unwrap_or_default()
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use pgls_text_size::TextRange;
@@ -366,4 +395,71 @@ mod tests {
         )
         .await;
     }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_identity_signatures_in_drop_function(pool: PgPool) {
+        let setup = r#"
+          create function add_nums(a int, b int) returns int language sql as $$ select a + b $$;
+          create function add_nums(a text) returns text language sql as $$ select a $$;
+        "#;
+
+        pool.execute(setup).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "drop function add_{};",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind(
+                    "add_nums(a integer, b integer)".into(),
+                    CompletionItemKind::Function,
+                ),
+                CompletionAssertion::CompletionTextAndRange(
+                    "add_nums(a text)".into(),
+                    TextRange::new(14.into(), 18.into()),
+                ),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn only_completes_trigger_functions_in_create_trigger(pool: PgPool) {
+        let setup = r#"
+          create table users (id serial primary key, updated_at timestamptz);
+
+          create function set_updated_at() returns trigger language plpgsql as $$
+          begin
+            new.updated_at = now();
+            return new;
+          end;
+          $$;
+
+          create function say_hello() returns text language sql as $$ select 'hello' $$;
+        "#;
+
+        pool.execute(setup).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "create trigger t before update on users for each row execute function s{}();",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::CompletionTextAndRange(
+                    "set_updated_at".into(),
+                    TextRange::new(70.into(), 71.into()),
+                ),
+                CompletionAssertion::LabelNotExists("say_hello".into()),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+    }
 }
@@ -1,5 +1,5 @@
 use pgls_text_size::TextRange;
-use pgls_treesitter::TreesitterContext;
+use pgls_treesitter::{TreesitterContext, WrappingClause};
 
 use crate::{is_sanitized_token_with_quote, remove_sanitized_token};
 
@@ -64,3 +64,163 @@ pub(crate) fn with_schema_or_alias(
         }
     }
 }
+
+/// Whether the cursor is on the name of a type, e.g. `create table t (id |)` or `select 1::|`.
+pub(crate) fn is_type_position(ctx: &TreesitterContext) -> bool {
+    ctx.history_ends_with(&["type", "object_reference", "any_identifier"])
+}
+
+/// The node holding the value that is being typed: either a string literal
+/// (`status = 'ac|'`) or an identifier that has not been quoted yet (`status = ac|`).
+pub(crate) fn value_node_under_cursor<'a>(
+    ctx: &TreesitterContext<'a>,
+) -> Option<tree_sitter::Node<'a>> {
+    let node = ctx.node_under_cursor;
+
+    match node.kind() {
+        "literal" => Some(node),
+        "any_identifier" => node
+            .parent()
+            .filter(|p| p.kind() == "object_reference" && p.named_child_count() == 1),
+        _ => None,
+    }
+}
+
+/// If the cursor is in the first argument of `nextval`, `currval` or `setval`,
+/// returns the argument node.
+pub(crate) fn sequence_function_argument<'a>(
+    ctx: &TreesitterContext<'a>,
+) -> Option<tree_sitter::Node<'a>> {
+    let argument = value_node_under_cursor(ctx)?;
+
+    let term = argument.parent().filter(|p| p.kind() == "term")?;
+    let invocation = term.parent().filter(|p| p.kind() == "invocation")?;
+
+    let mut cursor = invocation.walk();
+    let first_argument = invocation
+        .children(&mut cursor)
+        .find(|c| c.kind() == "term")?;
+    if first_argument != term {
+        return None;
+    }
+
+    let function_name = invocation
+        .child(0)
+        .filter(|c| c.kind() == "function_reference")
+        .and_then(|f| f.named_child(f.named_child_count().checked_sub(1)?))
+        .and_then(|n| n.utf8_text(ctx.text.as_bytes()).ok())?;
+
+    ["nextval", "currval", "setval"]
+        .contains(&function_name.replace('"', "").as_str())
+        .then_some(argument)
+}
+
+/// The table whose columns can be listed at the cursor in DDL statements.
+pub(crate) struct DdlColumnTarget {
+    pub schema: Option<String>,
+    pub table: String,
+    /// `references t (|)` only accepts primary-key or unique columns.
+    pub requires_unique: bool,
+}
+
+impl DdlColumnTarget {
+    pub fn matches(&self, column: &pgls_schema_cache::Column) -> bool {
+        column.table_name == self.table
+            && self
+                .schema
+                .as_ref()
+                .is_none_or(|s| *s == column.schema_name)
+            && (!self.requires_unique || column.is_primary_key || column.is_unique)
+    }
+}
+
+/// Finds the table of `create index ... on t (|)` or `references t (|)` if the cursor
+/// is on one of the listed columns.
+pub(crate) fn ddl_column_target(ctx: &TreesitterContext) -> Option<DdlColumnTarget> {
+    let node = ctx.node_under_cursor;
+    if node.kind() != "column_identifier" {
+        return None;
+    }
+
+    let parent = node.parent()?;
+
+    let (table_reference, requires_unique) = match parent.kind() {
+        "field" => {
+            let create_index = parent
+                .parent()
+                .filter(|p| p.kind() == "index_fields")?
+                .parent()
+                .filter(|p| p.kind() == "create_index")?;
+
+            let mut cursor = create_index.walk();
+            let table = create_index
+                .children(&mut cursor)
+                .find(|c| c.kind() == "table_reference")?;
+
+            (table, false)
+        }
+
+        "column_definition" | "constraint" => {
+            let mut current = node.prev_sibling();
+            while let Some(sibling) = current {
+                if !matches!(sibling.kind(), "(" | "," | "column_identifier") {
+                    break;
+                }
+                current = sibling.prev_sibling();
+            }
+
+            let table = current.filter(|n| n.kind() == "table_reference")?;
+            table
+                .prev_sibling()
+                .filter(|n| n.kind() == "keyword_references")?;
+
+            (table, true)
+        }
+
+        _ => return None,
+    };
+
+    let mut cursor = table_reference.walk();
+    let mut parts: Vec<String> = table_reference
+        .named_children(&mut cursor)
+        .filter_map(|n| n.utf8_text(ctx.text.as_bytes()).ok())
+        .map(|t| t.replace('"', ""))
+        .collect();
+
+    let table = parts.pop()?;
+
+    Some(DdlColumnTarget {
+        schema: parts.pop(),
+        table,
+        requires_unique,
+    })
+}
+
+/// Whether the cursor is on the function of `create trigger ... execute function |`.
+pub(crate) fn is_trigger_function_position(ctx: &TreesitterContext) -> bool {
+    ctx.node_under_cursor
+        .parent()
+        .filter(|p| p.kind() == "function_reference")
+        .and_then(|p| p.parent())
+        .is_some_and(|p| p.kind() == "create_trigger")
+}
+
+/// Statements that refer to an existing function by its signature, so that
+/// overloads can be told apart: `drop function |`, `comment on function |`, ...
+pub(crate) fn is_function_signature_position(ctx: &TreesitterContext) -> bool {
+    matches!(
+        ctx.wrapping_clause_type,
+        Some(WrappingClause::DropFunction)
+            | Some(WrappingClause::CommentOn)
+            | Some(WrappingClause::GrantStatement)
+            | Some(WrappingClause::RevokeStatement)
+    ) && ctx
+        .node_under_cursor
+        .parent()
+        .is_some_and(|p| p.kind() == "function_reference")
+}
+
+/// Wraps the text in single quotes, escaping quotes within.
+pub(crate) fn quote_literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
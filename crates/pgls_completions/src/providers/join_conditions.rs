@@ -0,0 +1,314 @@
+use pgls_schema_cache::{ForeignKey, SchemaCache};
+use pgls_treesitter::{TreesitterContext, context::WrappingClause};
+
+use crate::{
+    CompletionItemKind, CompletionText,
+    builder::{CompletionBuilder, PossibleCompletionItem},
+    providers::helper::get_range_to_replace,
+    relevance::{CompletionRelevanceData, filtering::CompletionFilter, scoring::CompletionScore},
+};
+
+/// A relation in the `FROM` clause, e.g. `public.orders o`.
+struct Relation {
+    schema: Option<String>,
+    table: String,
+    /// The alias if there is one, the table name otherwise.
+    qualifier: String,
+}
+
+impl Relation {
+    fn from_node(ctx: &TreesitterContext, relation: tree_sitter::Node) -> Option<Self> {
+        let table_reference = child_of_kind(relation, "table_reference")?;
+
+        let mut cursor = table_reference.walk();
+        let parts: Vec<String> = table_reference
+            .named_children(&mut cursor)
+            .filter_map(|n| n.utf8_text(ctx.text.as_bytes()).ok())
+            .map(|t| t.replace('"', ""))
+            .collect();
+
+        let (schema, table) = match parts.as_slice() {
+            [table] => (None, table.clone()),
+            [schema, table] => (Some(schema.clone()), table.clone()),
+            _ => return None,
+        };
+
+        Some(Self {
+            qualifier: ctx
+                .get_used_alias_for_table(&table)
+                .unwrap_or(table.clone()),
+            schema,
+            table,
+        })
+    }
+}
+
+/// Completes whole join predicates derived from foreign keys:
+///
+/// ```sql
+/// select * from customers c join orders o on | -- o.customer_id = c.id
+/// ```
+pub fn complete_join_conditions<'a>(
+    ctx: &TreesitterContext<'a>,
+    schema_cache: &'a SchemaCache,
+    builder: &mut CompletionBuilder<'a>,
+) {
+    let Some(join) = join_with_cursor_after_on(ctx) else {
+        return;
+    };
+
+    let Some(joined) = child_of_kind(join, "relation").and_then(|r| Relation::from_node(ctx, r))
+    else {
+        return;
+    };
+
+    for other in preceding_relations(ctx, join) {
+        let outgoing = schema_cache
+            .find_foreign_keys(
+                &joined.table,
+                joined.schema.as_deref(),
+                &other.table,
+                other.schema.as_deref(),
+            )
+            .into_iter()
+            .map(|fk| (fk, condition(fk, &joined, &other)));
+
+        let incoming = schema_cache
+            .find_foreign_keys(
+                &other.table,
+                other.schema.as_deref(),
+                &joined.table,
+                joined.schema.as_deref(),
+            )
+            .into_iter()
+            .map(|fk| (fk, reversed_condition(fk, &joined, &other)));
+
+        for (fk, condition) in outgoing.chain(incoming) {
+            let relevance = CompletionRelevanceData::JoinCondition(condition.clone());
+
+            let item = PossibleCompletionItem {
+                label: condition.clone(),
+                score: CompletionScore::from(relevance.clone()),
+                filter: CompletionFilter::from(relevance),
+                description: fk.name.clone(),
+                kind: CompletionItemKind::JoinCondition,
+                completion_text: Some(CompletionText {
+                    text: condition,
+                    range: get_range_to_replace(ctx),
+                    is_snippet: false,
+                }),
+                detail: None,
+            };
+
+            builder.add_item(item);
+        }
+    }
+}
+
+/// Whether a table has a foreign key to or from one of the relations mentioned in the statement.
+pub(crate) fn references_mentioned_relation(
+    ctx: &TreesitterContext,
+    schema_cache: &SchemaCache,
+    table: &pgls_schema_cache::Table,
+) -> bool {
+    ctx.iter_mentioned_relations().any(|(schema, mentioned)| {
+        !schema_cache
+            .find_foreign_keys(&table.name, Some(&table.schema), mentioned, schema)
+            .is_empty()
+            || !schema_cache
+                .find_foreign_keys(mentioned, schema, &table.name, Some(&table.schema))
+                .is_empty()
+    })
+}
+
+/// `joined.fk_column = other.referenced_column`
+fn condition(fk: &ForeignKey, joined: &Relation, other: &Relation) -> String {
+    fk.column_pairs()
+        .map(|(col, referenced)| {
+            format!(
+                "{}.{col} = {}.{referenced}",
+                joined.qualifier, other.qualifier
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+/// `joined.referenced_column = other.fk_column`
+fn reversed_condition(fk: &ForeignKey, joined: &Relation, other: &Relation) -> String {
+    fk.column_pairs()
+        .map(|(col, referenced)| {
+            format!(
+                "{}.{referenced} = {}.{col}",
+                joined.qualifier, other.qualifier
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+/// Returns the `join` node if the cursor is at the start of its `ON` condition.
+fn join_with_cursor_after_on<'a>(ctx: &TreesitterContext<'a>) -> Option<tree_sitter::Node<'a>> {
+    let Some(WrappingClause::Join { on_node: Some(on) }) = ctx.wrapping_clause_type.as_ref() else {
+        return None;
+    };
+
+    let reference = ctx
+        .node_under_cursor
+        .parent()
+        .filter(|p| p.kind() == "object_reference" && p.named_child_count() == 1)?;
+
+    if reference.prev_sibling()? != *on {
+        return None;
+    }
+
+    reference.parent().filter(|p| p.kind() == "join")
+}
+
+/// The relations of the `FROM` clause that come before the given join.
+fn preceding_relations(ctx: &TreesitterContext, join: tree_sitter::Node) -> Vec<Relation> {
+    let Some(from) = join.parent() else {
+        return vec![];
+    };
+
+    let mut cursor = from.walk();
+    from.named_children(&mut cursor)
+        .take_while(|n| *n != join)
+        .filter_map(|n| match n.kind() {
+            "relation" => Some(n),
+            "join" => child_of_kind(n, "relation"),
+            _ => None,
+        })
+        .filter_map(|r| Relation::from_node(ctx, r))
+        .collect()
+}
+
+fn child_of_kind<'a>(node: tree_sitter::Node<'a>, kind: &str) -> Option<tree_sitter::Node<'a>> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).find(|c| c.kind() == kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use pgls_text_size::TextRange;
+    use sqlx::{Executor, PgPool};
+
+    use crate::{
+        CompletionItemKind,
+        test_helper::{CompletionAssertion, assert_complete_results},
+    };
+
+    use pgls_test_utils::QueryWithCursorPosition;
+
+    static SETUP: &str = r#"
+        create table public.customers (
+            id serial primary key,
+            name text
+        );
+
+        create table public.orders (
+            id serial primary key,
+            customer_id int references public.customers (id)
+        );
+
+        create table public.audit_logs (
+            id serial primary key,
+            message text
+        );
+
+        create table public.products (
+            sku text,
+            variant int,
+            primary key (sku, variant)
+        );
+
+        create table public.order_items (
+            order_id int references public.orders (id),
+            product_sku text,
+            product_variant int,
+            foreign key (product_sku, product_variant) references public.products (sku, variant)
+        );
+    "#;
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_join_conditions_from_foreign_keys(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "select * from customers c join orders o on {}",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![CompletionAssertion::CompletionTextAndRange(
+                "o.customer_id = c.id".into(),
+                TextRange::new(43.into(), 43.into()),
+            )],
+            None,
+            &pool,
+        )
+        .await;
+
+        // the foreign key points the other way, and there are no aliases
+        assert_complete_results(
+            format!(
+                "select * from orders join customers on {}",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![CompletionAssertion::LabelAndKind(
+                "customers.id = orders.customer_id".into(),
+                CompletionItemKind::JoinCondition,
+            )],
+            None,
+            &pool,
+        )
+        .await;
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_join_conditions_for_every_preceding_relation(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "select * from orders o join products p on p.sku = 'x' join order_items i on {}",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind(
+                    "i.order_id = o.id".into(),
+                    CompletionItemKind::JoinCondition,
+                ),
+                CompletionAssertion::LabelAndKind(
+                    "i.product_sku = p.sku and i.product_variant = p.variant".into(),
+                    CompletionItemKind::JoinCondition,
+                ),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn prefers_tables_with_foreign_keys_to_mentioned_relations(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "select * from orders o join {}",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind("customers".into(), CompletionItemKind::Table),
+                CompletionAssertion::LabelAndKind("order_items".into(), CompletionItemKind::Table),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+    }
+}
@@ -1,16 +1,26 @@
 mod columns;
+mod enum_values;
 mod functions;
-mod helper;
+pub(crate) mod helper;
+pub(crate) mod join_conditions;
 mod keywords;
 mod policies;
 mod roles;
 mod schemas;
+mod sequences;
+mod snippets;
 mod tables;
+mod types;
 
 pub use columns::*;
+pub use enum_values::*;
 pub use functions::*;
+pub use join_conditions::*;
 pub use keywords::*;
 pub use policies::*;
 pub use roles::*;
 pub use schemas::*;
+pub use sequences::*;
+pub use snippets::*;
 pub use tables::*;
+pub use types::*;
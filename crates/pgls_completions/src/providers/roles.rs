@@ -90,6 +90,23 @@ mod tests {
         .await;
     }
 
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn works_in_owner_to(pool: PgPool) {
+        TestCompletionsSuite::new(&pool, Some(SETUP))
+            .with_case(
+                TestCompletionsCase::new()
+                    .inside_static_statement("alter view users_view <sql>;")
+                    .type_sql("owner to anon"),
+            )
+            .with_case(
+                TestCompletionsCase::new()
+                    .inside_static_statement("alter sequence users_id_seq <sql>;")
+                    .type_sql("owner to anon"),
+            )
+            .snapshot("works_in_owner_to")
+            .await;
+    }
+
     #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
     async fn works_in_set_role_statement(pool: PgPool) {
         TestCompletionsSuite::new(&pool, Some(SETUP))
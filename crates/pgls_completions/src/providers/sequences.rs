@@ -0,0 +1,164 @@
+use pgls_schema_cache::{SchemaCache, Sequence};
+use pgls_text_size::TextRange;
+use pgls_treesitter::TreesitterContext;
+
+use crate::{
+    CompletionItemKind, CompletionText,
+    builder::{CompletionBuilder, PossibleCompletionItem},
+    providers::helper::{get_range_to_replace, quote_literal, sequence_function_argument},
+    relevance::{CompletionRelevanceData, filtering::CompletionFilter, scoring::CompletionScore},
+};
+
+use super::helper::with_schema_or_alias;
+
+pub fn complete_sequences<'a>(
+    ctx: &TreesitterContext<'a>,
+    schema_cache: &'a SchemaCache,
+    builder: &mut CompletionBuilder<'a>,
+) {
+    let available_sequences = &schema_cache.sequences;
+
+    for sequence in available_sequences {
+        let relevance = CompletionRelevanceData::Sequence(sequence);
+
+        let item = PossibleCompletionItem {
+            label: sequence.name.clone(),
+            score: CompletionScore::from(relevance.clone()),
+            filter: CompletionFilter::from(relevance),
+            description: sequence.schema.clone(),
+            kind: CompletionItemKind::Sequence,
+            completion_text: Some(get_completion_text(ctx, sequence)),
+            detail: None,
+        };
+
+        builder.add_item(item);
+    }
+}
+
+fn get_completion_text(ctx: &TreesitterContext, sequence: &Sequence) -> CompletionText {
+    match sequence_function_argument(ctx) {
+        // `nextval('...')` takes the name of the sequence as text
+        Some(argument) => {
+            let name = if sequence.schema == "public" {
+                sequence.name.clone()
+            } else {
+                format!("{}.{}", sequence.schema, sequence.name)
+            };
+
+            let range = if argument.kind() == "literal" {
+                TextRange::new(
+                    argument.start_byte().try_into().unwrap(),
+                    argument.end_byte().try_into().unwrap(),
+                )
+            } else {
+                get_range_to_replace(ctx)
+            };
+
+            CompletionText {
+                text: quote_literal(&name),
+                range,
+                is_snippet: false,
+            }
+        }
+
+        None => CompletionText {
+            text: with_schema_or_alias(ctx, sequence.name.as_str(), Some(&sequence.schema)),
+            range: get_range_to_replace(ctx),
+            is_snippet: false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pgls_text_size::TextRange;
+    use sqlx::{Executor, PgPool};
+
+    use crate::{
+        CompletionItemKind,
+        test_helper::{CompletionAssertion, assert_complete_results},
+    };
+
+    use pgls_test_utils::QueryWithCursorPosition;
+
+    static SETUP: &str = r#"
+        create schema billing;
+
+        create sequence public.order_numbers;
+        create sequence billing.invoice_numbers;
+        create table public.orders (id int);
+    "#;
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_sequences_in_sequence_statements(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "drop sequence ord{}",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind(
+                    "order_numbers".into(),
+                    CompletionItemKind::Sequence,
+                ),
+                CompletionAssertion::LabelNotExists("orders".into()),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+
+        assert_complete_results(
+            format!(
+                "alter sequence billing.{} restart",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![CompletionAssertion::LabelAndKind(
+                "invoice_numbers".into(),
+                CompletionItemKind::Sequence,
+            )],
+            None,
+            &pool,
+        )
+        .await;
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_sequences_in_sequence_functions(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "select nextval('inv{}')",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![CompletionAssertion::CompletionTextAndRange(
+                "'billing.invoice_numbers'".into(),
+                TextRange::new(15.into(), 20.into()),
+            )],
+            None,
+            &pool,
+        )
+        .await;
+
+        assert_complete_results(
+            format!(
+                "select currval({})",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![CompletionAssertion::CompletionTextAndRange(
+                "'order_numbers'".into(),
+                TextRange::new(15.into(), 15.into()),
+            )],
+            None,
+            &pool,
+        )
+        .await;
+    }
+}
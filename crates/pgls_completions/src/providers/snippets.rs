@@ -0,0 +1,428 @@
+use std::sync::LazyLock;
+
+use pgls_schema_cache::SchemaCache;
+use pgls_treesitter::TreesitterContext;
+
+use crate::{
+    CompletionItemKind, CompletionText,
+    builder::{CompletionBuilder, PossibleCompletionItem},
+    providers::helper::get_range_to_replace,
+    relevance::{CompletionRelevanceData, filtering::CompletionFilter, scoring::CompletionScore},
+    usage::UsageStats,
+};
+
+/// How many tables or columns a placeholder offers at most.
+const MAX_CHOICES: usize = 10;
+
+/// A template offered at the start of a statement.
+///
+/// The body uses the LSP snippet syntax. Placeholders named `table` or `column`,
+/// e.g. `${1:table}`, are turned into a choice of the most used tables, or of the
+/// columns of those tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub name: String,
+    pub description: String,
+    pub body: String,
+}
+
+impl Snippet {
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        body: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            body: body.into(),
+        }
+    }
+}
+
+static BUILTIN_SNIPPETS: LazyLock<Vec<Snippet>> = LazyLock::new(|| {
+    vec![
+        Snippet::new(
+            "create index concurrently",
+            "Build an index without blocking writes",
+            r#"create index concurrently if not exists ${1:index_name}
+  on ${2:table} (${3:column});"#,
+        ),
+        Snippet::new(
+            "add foreign key not valid",
+            "Add a foreign key without a long lock, then validate it",
+            r#"alter table ${1:table}
+  add constraint ${2:constraint_name}
+  foreign key (${3:column}) references ${4:table} (${5:column})
+  not valid;
+
+alter table $1 validate constraint $2;"#,
+        ),
+        Snippet::new(
+            "create policy",
+            "Row level security policy",
+            r#"create policy "${1:policy_name}"
+  on ${2:table}
+  as ${3|permissive,restrictive|}
+  for ${4|all,select,insert,update,delete|}
+  to ${5:authenticated}
+  using (${6:true});"#,
+        ),
+        Snippet::new(
+            "create trigger function",
+            "Trigger function and the trigger calling it",
+            r#"create or replace function ${1:function_name}()
+  returns trigger
+  language plpgsql
+  as \$\$
+begin
+  $0
+  return new;
+end;
+\$\$;
+
+create trigger ${2:trigger_name}
+  ${3|before,after|} ${4|insert,update,delete|} on ${5:table}
+  for each row execute function $1();"#,
+        ),
+        Snippet::new(
+            "set timeouts",
+            "Fail fast instead of queueing behind locks",
+            r#"set lock_timeout = '${1:5s}';
+set statement_timeout = '${2:30s}';
+
+$0"#,
+        ),
+    ]
+});
+
+/// Completes the built-in and user-defined snippets at the start of a statement.
+/// User-defined snippets take precedence over built-in ones with the same name.
+pub fn complete_snippets<'a>(
+    ctx: &TreesitterContext<'a>,
+    schema_cache: &'a SchemaCache,
+    usage: Option<&UsageStats>,
+    snippets: &'a [Snippet],
+    builder: &mut CompletionBuilder<'a>,
+) {
+    if !is_statement_start(ctx) {
+        return;
+    }
+
+    let choices = SchemaChoices::new(schema_cache, usage);
+
+    let builtin = BUILTIN_SNIPPETS
+        .iter()
+        .filter(|b| snippets.iter().all(|s| s.name != b.name));
+
+    for snippet in snippets.iter().chain(builtin) {
+        let relevance = CompletionRelevanceData::Snippet(snippet);
+
+        let item = PossibleCompletionItem {
+            label: snippet.name.clone(),
+            score: CompletionScore::from(relevance.clone()),
+            filter: CompletionFilter::from(relevance),
+            description: snippet.description.clone(),
+            kind: CompletionItemKind::Snippet,
+            detail: None,
+            completion_text: Some(CompletionText {
+                text: choices.apply(&snippet.body),
+                range: get_range_to_replace(ctx),
+                is_snippet: true,
+            }),
+        };
+
+        builder.add_item(item);
+    }
+}
+
+/// Whether the cursor is on the first word of a statement.
+fn is_statement_start(ctx: &TreesitterContext) -> bool {
+    let node = ctx.node_under_cursor;
+
+    // an ERROR or program node might span several words
+    if ctx
+        .get_node_under_cursor_content()
+        .is_some_and(|c| c.trim().contains(char::is_whitespace))
+    {
+        return false;
+    }
+
+    ctx.text
+        .get(..node.start_byte())
+        .and_then(|before| before.rsplit(';').next())
+        .is_some_and(|before| before.trim().is_empty())
+}
+
+/// The tables and columns offered for `${n:table}` and `${n:column}` placeholders.
+struct SchemaChoices {
+    tables: Vec<String>,
+    columns: Vec<String>,
+}
+
+impl SchemaChoices {
+    /// Takes the most used tables outside the system schemas and migration bookkeeping,
+    /// and the most used columns of those tables.
+    fn new(schema_cache: &SchemaCache, usage: Option<&UsageStats>) -> Self {
+        let mut tables: Vec<_> = schema_cache
+            .tables
+            .iter()
+            .filter(|t| is_user_table(&t.schema, &t.name))
+            .map(|t| (usage.map_or(0, |u| u.table_count(t)), t))
+            .collect();
+        tables.sort_by(|(a_count, a), (b_count, b)| {
+            b_count
+                .cmp(a_count)
+                .then_with(|| (&a.schema, &a.name).cmp(&(&b.schema, &b.name)))
+        });
+        tables.truncate(MAX_CHOICES);
+
+        let mut columns: Vec<_> = schema_cache
+            .columns
+            .iter()
+            .filter(|c| {
+                tables
+                    .iter()
+                    .any(|(_, t)| t.schema == c.schema_name && t.name == c.table_name)
+            })
+            .map(|c| (usage.map_or(0, |u| u.column_count(c)), c.name.as_str()))
+            .collect();
+        columns.sort_by(|(a_count, a), (b_count, b)| b_count.cmp(a_count).then(a.cmp(b)));
+
+        let mut column_names: Vec<String> = vec![];
+        for (_, name) in columns {
+            if column_names.len() == MAX_CHOICES {
+                break;
+            }
+            if !column_names.iter().any(|c| c == name) {
+                column_names.push(name.to_string());
+            }
+        }
+
+        Self {
+            tables: tables
+                .into_iter()
+                .map(|(_, t)| {
+                    if t.schema == "public" {
+                        t.name.clone()
+                    } else {
+                        format!("{}.{}", t.schema, t.name)
+                    }
+                })
+                .collect(),
+            columns: column_names,
+        }
+    }
+
+    /// Replaces `${n:table}` and `${n:column}` placeholders in a snippet body with choices.
+    /// Placeholders without any choice are kept as they are.
+    fn apply(&self, body: &str) -> String {
+        let mut result = String::with_capacity(body.len());
+        let mut rest = body;
+
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let Some(end) = rest.find('}') else {
+                break;
+            };
+
+            let placeholder = &rest[..=end];
+            let choices = match placeholder[2..end].split_once(':') {
+                Some((n, "table")) if n.parse::<u32>().is_ok() => Some((n, &self.tables)),
+                Some((n, "column")) if n.parse::<u32>().is_ok() => Some((n, &self.columns)),
+                _ => None,
+            };
+
+            match choices {
+                Some((n, choices)) if !choices.is_empty() => {
+                    let choices: Vec<String> = choices.iter().map(|c| escape_choice(c)).collect();
+                    result.push_str(&format!("${{{n}|{}|}}", choices.join(",")));
+                }
+                _ => result.push_str(placeholder),
+            }
+
+            rest = &rest[end + 1..];
+        }
+
+        result.push_str(rest);
+        result
+    }
+}
+
+fn is_user_table(schema: &str, table: &str) -> bool {
+    !matches!(
+        schema,
+        "pg_catalog" | "information_schema" | "pg_toast" | "supabase_migrations"
+    ) && table != "_sqlx_migrations"
+}
+
+fn escape_choice(choice: &str) -> String {
+    choice
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace('|', "\\|")
+        .replace('}', "\\}")
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{Executor, PgPool};
+
+    use crate::{
+        CompletionItemKind, complete,
+        providers::Snippet,
+        test_helper::{get_test_deps, get_test_params},
+    };
+
+    use pgls_test_utils::QueryWithCursorPosition;
+
+    static SETUP: &str = r#"
+        create table public.users (
+            id serial primary key,
+            email text
+        );
+    "#;
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn offers_builtin_snippets_at_statement_start(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        let query = format!("create{}", QueryWithCursorPosition::cursor_marker());
+        let (tree, cache) = get_test_deps(None, query.as_str().into(), &pool).await;
+        let params = get_test_params(&tree, &cache, query.as_str().into());
+        let items = complete(params);
+
+        let snippet = items
+            .iter()
+            .find(|i| i.label == "create index concurrently")
+            .expect("should offer the index snippet");
+
+        assert_eq!(snippet.kind, CompletionItemKind::Snippet);
+
+        let text = snippet.completion_text.as_ref().unwrap();
+        assert!(text.is_snippet);
+        assert_eq!(
+            text.text,
+            "create index concurrently if not exists ${1:index_name}\n  on ${2|users|} (${3|email,id|});"
+        );
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn takes_a_limited_number_of_choices_from_the_schema_cache(pool: PgPool) {
+        let tables: String = (1..=12)
+            .map(|i| format!("create table public.t{i:02} (c{i:02} int);"))
+            .collect();
+        pool.execute(tables.as_str()).await.unwrap();
+        pool.execute(r#"create table public."odd,name|}" ("a\b" int);"#)
+            .await
+            .unwrap();
+
+        let query = format!("create{}", QueryWithCursorPosition::cursor_marker());
+        let (tree, cache) = get_test_deps(None, query.as_str().into(), &pool).await;
+        let params = get_test_params(&tree, &cache, query.as_str().into());
+        let items = complete(params);
+
+        let snippet = items
+            .iter()
+            .find(|i| i.label == "create index concurrently")
+            .expect("should offer the index snippet");
+
+        assert_eq!(
+            snippet.completion_text.as_ref().unwrap().text,
+            concat!(
+                "create index concurrently if not exists ${1:index_name}\n",
+                r"  on ${2|odd\,name\|\},t01,t02,t03,t04,t05,t06,t07,t08,t09|} ",
+                r"(${3|a\\b,c01,c02,c03,c04,c05,c06,c07,c08,c09|});"
+            )
+        );
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn does_not_offer_snippets_within_statements(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        let query = format!(
+            "select * from users where cre{}",
+            QueryWithCursorPosition::cursor_marker()
+        );
+        let (tree, cache) = get_test_deps(None, query.as_str().into(), &pool).await;
+        let params = get_test_params(&tree, &cache, query.as_str().into());
+        let items = complete(params);
+
+        assert!(items.iter().all(|i| i.kind != CompletionItemKind::Snippet));
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn filters_snippets_by_the_typed_prefix(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        let query = format!("c{}", QueryWithCursorPosition::cursor_marker());
+        let (tree, cache) = get_test_deps(None, query.as_str().into(), &pool).await;
+        let params = get_test_params(&tree, &cache, query.as_str().into());
+        let items = complete(params);
+
+        assert!(items.iter().all(|i| i.kind != CompletionItemKind::Snippet));
+
+        let query = format!("create p{}", QueryWithCursorPosition::cursor_marker());
+        let (tree, cache) = get_test_deps(None, query.as_str().into(), &pool).await;
+        let params = get_test_params(&tree, &cache, query.as_str().into());
+        let items = complete(params);
+
+        assert!(items.iter().all(|i| i.kind != CompletionItemKind::Snippet));
+
+        let query = format!("cre{}", QueryWithCursorPosition::cursor_marker());
+        let (tree, cache) = get_test_deps(None, query.as_str().into(), &pool).await;
+        let params = get_test_params(&tree, &cache, query.as_str().into());
+        let items = complete(params);
+
+        let mut labels: Vec<_> = items
+            .iter()
+            .filter(|i| i.kind == CompletionItemKind::Snippet)
+            .map(|i| i.label.as_str())
+            .collect();
+        labels.sort();
+        assert_eq!(
+            labels,
+            vec![
+                "create index concurrently",
+                "create policy",
+                "create trigger function"
+            ]
+        );
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn prefers_user_defined_snippets(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        let snippets = vec![
+            Snippet::new(
+                "set deleted",
+                "Mark as deleted",
+                "update ${1:table} set deleted_at = now() where id = $2;",
+            ),
+            Snippet::new("set timeouts", "Our timeouts", "set lock_timeout = '1s';"),
+        ];
+
+        let query = format!("se{}", QueryWithCursorPosition::cursor_marker());
+        let (tree, cache) = get_test_deps(None, query.as_str().into(), &pool).await;
+        let mut params = get_test_params(&tree, &cache, query.as_str().into());
+        params.snippets = &snippets;
+        let items = complete(params);
+
+        let set_deleted = items
+            .iter()
+            .find(|i| i.label == "set deleted")
+            .expect("should offer the user-defined snippet");
+        assert_eq!(
+            set_deleted.completion_text.as_ref().unwrap().text,
+            "update ${1|users|} set deleted_at = now() where id = $2;"
+        );
+
+        let timeouts: Vec<_> = items.iter().filter(|i| i.label == "set timeouts").collect();
+        assert_eq!(timeouts.len(), 1);
+        assert_eq!(timeouts[0].description, "Our timeouts");
+    }
+}
@@ -1,11 +1,11 @@
 use pgls_schema_cache::{SchemaCache, Table};
-use pgls_treesitter::TreesitterContext;
+use pgls_treesitter::{TreesitterContext, context::WrappingClause};
 
 use crate::{
     CompletionText,
     builder::{CompletionBuilder, PossibleCompletionItem},
     item::CompletionItemKind,
-    providers::helper::get_range_to_replace,
+    providers::{helper::get_range_to_replace, join_conditions::references_mentioned_relation},
     relevance::{CompletionRelevanceData, filtering::CompletionFilter, scoring::CompletionScore},
 };
 
@@ -17,10 +17,17 @@ pub fn complete_tables<'a>(
     builder: &mut CompletionBuilder<'a>,
 ) {
     let available_tables = &schema_cache.tables;
+    let is_join_target = is_join_target(ctx);
 
     for table in available_tables {
         let relevance = CompletionRelevanceData::Table(table);
 
+        let mut score = CompletionScore::from(relevance.clone());
+        if is_join_target && references_mentioned_relation(ctx, schema_cache, table) {
+            // tables with a foreign key to or from the `FROM` tables are likely join targets
+            score.add_bonus(15);
+        }
+
         let detail: Option<String> = match table.table_kind {
             pgls_schema_cache::TableKind::Ordinary | pgls_schema_cache::TableKind::Partitioned => {
                 None
@@ -31,7 +38,7 @@ pub fn complete_tables<'a>(
 
         let item = PossibleCompletionItem {
             label: table.name.clone(),
-            score: CompletionScore::from(relevance.clone()),
+            score,
             filter: CompletionFilter::from(relevance),
             description: table.schema.to_string(),
             kind: CompletionItemKind::Table,
@@ -43,6 +50,16 @@ pub fn complete_tables<'a>(
     }
 }
 
+/// `select * from orders o join |`
+fn is_join_target(ctx: &TreesitterContext) -> bool {
+    match ctx.wrapping_clause_type.as_ref() {
+        Some(WrappingClause::Join { on_node }) => {
+            on_node.is_none_or(|on| ctx.node_under_cursor.end_byte() < on.start_byte())
+        }
+        _ => false,
+    }
+}
+
 fn get_completion_text(ctx: &TreesitterContext, table: &Table) -> CompletionText {
     let text = with_schema_or_alias(ctx, table.name.as_str(), Some(table.schema.as_str()));
 
@@ -62,7 +79,7 @@ mod tests {
     use sqlx::{Executor, PgPool};
 
     use crate::{
-        CompletionItem, CompletionItemKind, complete,
+        CompletionItem, CompletionItemKind, UsageStats, complete,
         test_helper::{
             CompletionAssertion, TestCompletionsCase, TestCompletionsSuite,
             assert_complete_results, assert_no_complete_results, get_test_deps, get_test_params,
@@ -617,4 +634,66 @@ mod tests {
         )
         .await;
     }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn prefers_tables_used_in_the_workspace(pool: PgPool) {
+        let setup = r#"
+            create schema billing;
+
+            create table public.invoice_templates (id serial primary key);
+            create table billing.invoices (id serial primary key);
+        "#;
+
+        let query = format!(
+            "select * from invo{}",
+            QueryWithCursorPosition::cursor_marker()
+        );
+
+        let (tree, cache) = get_test_deps(Some(setup), query.as_str().into(), &pool).await;
+
+        let items = complete(get_test_params(&tree, &cache, query.as_str().into()));
+        assert_eq!(items[0].label, "invoice_templates");
+
+        let mut usage = UsageStats::default();
+        for _ in 0..5 {
+            usage.record_table(Some("billing"), "invoices");
+        }
+
+        let mut params = get_test_params(&tree, &cache, query.as_str().into());
+        params.usage = Some(&usage);
+        let items = complete(params);
+        assert_eq!(items[0].label, "invoices");
+        assert_eq!(items[0].description, "billing");
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn prefers_tables_commonly_joined_in_the_workspace(pool: PgPool) {
+        let setup = r#"
+            create table public.orders (id serial primary key);
+            create table public.customers (id serial primary key);
+            create table public.shipments (id serial primary key);
+        "#;
+
+        let query = format!(
+            "select * from orders o join {}",
+            QueryWithCursorPosition::cursor_marker()
+        );
+
+        let (tree, cache) = get_test_deps(Some(setup), query.as_str().into(), &pool).await;
+
+        let mut usage = UsageStats::default();
+        usage.record_join((None, "orders"), (Some("public"), "shipments"));
+        usage.record_join((None, "shipments"), (None, "orders"));
+
+        let mut params = get_test_params(&tree, &cache, query.as_str().into());
+        params.usage = Some(&usage);
+        let items = complete(params);
+
+        let tables: Vec<&str> = items
+            .iter()
+            .filter(|i| i.kind == CompletionItemKind::Table)
+            .map(|i| i.label.as_str())
+            .collect();
+        assert_eq!(tables[0], "shipments");
+    }
 }
@@ -0,0 +1,155 @@
+use pgls_schema_cache::{PostgresType, SchemaCache};
+use pgls_treesitter::TreesitterContext;
+
+use crate::{
+    CompletionItemKind, CompletionText,
+    builder::{CompletionBuilder, PossibleCompletionItem},
+    providers::helper::get_range_to_replace,
+    relevance::{CompletionRelevanceData, filtering::CompletionFilter, scoring::CompletionScore},
+};
+
+use super::helper::with_schema_or_alias;
+
+pub fn complete_types<'a>(
+    ctx: &TreesitterContext<'a>,
+    schema_cache: &'a SchemaCache,
+    builder: &mut CompletionBuilder<'a>,
+) {
+    // array types are written as `int4[]`, not by their internal name `_int4`
+    let available_types = schema_cache
+        .types
+        .iter()
+        .filter(|t| !t.name.starts_with('_'));
+
+    for postgres_type in available_types {
+        let relevance = CompletionRelevanceData::Type(postgres_type);
+
+        let item = PossibleCompletionItem {
+            label: postgres_type.name.clone(),
+            score: CompletionScore::from(relevance.clone()),
+            filter: CompletionFilter::from(relevance),
+            description: postgres_type.schema.clone(),
+            kind: CompletionItemKind::Type,
+            completion_text: Some(get_completion_text(ctx, postgres_type)),
+            detail: get_detail(postgres_type),
+        };
+
+        builder.add_item(item);
+    }
+}
+
+fn get_detail(postgres_type: &PostgresType) -> Option<String> {
+    if !postgres_type.enums.values.is_empty() {
+        return Some(format!("Enum: {}", postgres_type.enums.values.join(", ")));
+    }
+
+    // e.g. "character varying" for `varchar`
+    if postgres_type.format != postgres_type.name
+        && postgres_type.format != format!("{}.{}", postgres_type.schema, postgres_type.name)
+    {
+        return Some(postgres_type.format.clone());
+    }
+
+    None
+}
+
+fn get_completion_text(ctx: &TreesitterContext, postgres_type: &PostgresType) -> CompletionText {
+    // builtin types are always on the search path
+    let schema = Some(postgres_type.schema.as_str()).filter(|s| *s != "pg_catalog");
+
+    CompletionText {
+        text: with_schema_or_alias(ctx, postgres_type.name.as_str(), schema),
+        range: get_range_to_replace(ctx),
+        is_snippet: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{Executor, PgPool};
+
+    use crate::{
+        CompletionItemKind,
+        test_helper::{CompletionAssertion, assert_complete_results},
+    };
+
+    use pgls_test_utils::QueryWithCursorPosition;
+
+    static SETUP: &str = r#"
+        create schema billing;
+
+        create type public.mood as enum ('happy', 'sad');
+        create type billing.currency as enum ('eur', 'usd');
+        create type public.address as (street text, city text);
+    "#;
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_types_in_column_definitions(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "create table people (id int4, feeling mo{})",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![
+                CompletionAssertion::LabelAndKind("mood".into(), CompletionItemKind::Type),
+                CompletionAssertion::LabelNotExists("people".into()),
+            ],
+            None,
+            &pool,
+        )
+        .await;
+
+        assert_complete_results(
+            format!(
+                "alter table people add column home addr{}",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![CompletionAssertion::LabelAndKind(
+                "address".into(),
+                CompletionItemKind::Type,
+            )],
+            None,
+            &pool,
+        )
+        .await;
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn completes_types_in_casts(pool: PgPool) {
+        pool.execute(SETUP).await.unwrap();
+
+        assert_complete_results(
+            format!(
+                "select 'usd'::billing.{}",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![CompletionAssertion::LabelAndKind(
+                "currency".into(),
+                CompletionItemKind::Type,
+            )],
+            None,
+            &pool,
+        )
+        .await;
+
+        assert_complete_results(
+            format!(
+                "select cast('usd' as curr{})",
+                QueryWithCursorPosition::cursor_marker()
+            )
+            .as_str(),
+            vec![CompletionAssertion::CompletionTextAndRange(
+                "billing.currency".into(),
+                pgls_text_size::TextRange::new(21.into(), 25.into()),
+            )],
+            None,
+            &pool,
+        )
+        .await;
+    }
+}
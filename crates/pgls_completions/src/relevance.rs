@@ -1,15 +1,24 @@
 pub(crate) mod filtering;
+mod matching;
 pub(crate) mod scoring;
 
-use crate::providers::SqlKeyword;
+use crate::providers::{Snippet, SqlKeyword};
 
 #[derive(Debug, Clone)]
 pub(crate) enum CompletionRelevanceData<'a> {
     Table(&'a pgls_schema_cache::Table),
     Function(&'a pgls_schema_cache::Function),
     Column(&'a pgls_schema_cache::Column),
+    /// An output column of a CTE, derived table or set-returning function.
+    DerivedColumn(pgls_treesitter::ResolvedColumn),
     Schema(&'a pgls_schema_cache::Schema),
     Policy(&'a pgls_schema_cache::Policy),
     Role(&'a pgls_schema_cache::Role),
     Keyword(&'static SqlKeyword),
+    Type(&'a pgls_schema_cache::PostgresType),
+    EnumValue(&'a str),
+    Sequence(&'a pgls_schema_cache::Sequence),
+    /// A join predicate derived from a foreign key, e.g. `o.customer_id = c.id`.
+    JoinCondition(String),
+    Snippet(&'a Snippet),
 }
@@ -6,6 +6,10 @@ use pgls_treesitter::{
 use tree_sitter::{InputEdit, Point, Tree};
 
 use super::CompletionRelevanceData;
+use crate::providers::helper::{
+    ddl_column_target, is_trigger_function_position, is_type_position, sequence_function_argument,
+    value_node_under_cursor,
+};
 
 /// Parses SQL with a keyword injected at the given position, using incremental parsing.
 ///
@@ -81,13 +85,18 @@ impl CompletionFilter<'_> {
             self.check_keyword_requires_prefix(ctx, kw)?;
             self.valid_keyword(ctx, shared_tree)?;
             Some(())
+        } else if let CompletionRelevanceData::Snippet(snippet) = self.data {
+            // only offered at the start of a statement, which the provider already checks
+            self.check_snippet_prefix(ctx, snippet)
         } else {
             self.completable_context(ctx)?;
+            self.check_type_or_sequence_position(ctx)?;
 
             self.check_specific_node_type(ctx)
                 // we want to rely on treesitter more, so checking the clause is a fallback
                 .or_else(|| self.check_clause(ctx))?;
 
+            self.check_ddl_target(ctx)?;
             self.check_invocation(ctx)?;
             self.check_mentioned_schema_or_alias(ctx)?;
 
@@ -96,12 +105,16 @@ impl CompletionFilter<'_> {
     }
 
     fn completable_context(&self, ctx: &TreesitterContext) -> Option<()> {
-        if ctx.wrapping_node_kind.is_none() && ctx.wrapping_clause_type.is_none() {
+        let current_node_kind = ctx.node_under_cursor.kind();
+
+        // `alter view v owner to |` – role positions are unambiguous, whatever the statement
+        if ctx.wrapping_node_kind.is_none()
+            && ctx.wrapping_clause_type.is_none()
+            && current_node_kind != "role_identifier"
+        {
             return None;
         }
 
-        let current_node_kind = ctx.node_under_cursor.kind();
-
         if current_node_kind.starts_with("keyword_")
             || current_node_kind == "="
             || current_node_kind == ","
@@ -119,20 +132,24 @@ impl CompletionFilter<'_> {
         // Here, "email" is a literal node.
         if current_node_kind == "literal" {
             match self.data {
-                CompletionRelevanceData::Column(_) => match ctx.wrapping_clause_type.as_ref() {
-                    Some(WrappingClause::Select)
-                    | Some(WrappingClause::Where)
-                    | Some(WrappingClause::Join { .. })
-                    | Some(WrappingClause::Update)
-                    | Some(WrappingClause::Delete)
-                    | Some(WrappingClause::Insert)
-                    | Some(WrappingClause::DropColumn)
-                    | Some(WrappingClause::AlterColumn)
-                    | Some(WrappingClause::RenameColumn) => {
-                        // the literal is probably a column
+                CompletionRelevanceData::Column(_) | CompletionRelevanceData::DerivedColumn(_) => {
+                    match ctx.wrapping_clause_type.as_ref() {
+                        Some(WrappingClause::Select)
+                        | Some(WrappingClause::Where)
+                        | Some(WrappingClause::Join { .. })
+                        | Some(WrappingClause::Update)
+                        | Some(WrappingClause::Delete)
+                        | Some(WrappingClause::Insert)
+                        | Some(WrappingClause::DropColumn)
+                        | Some(WrappingClause::AlterColumn)
+                        | Some(WrappingClause::RenameColumn) => {
+                            // the literal is probably a column
+                        }
+                        _ => return None,
                     }
-                    _ => return None,
-                },
+                }
+                // `where status = 'ac|'` or `nextval('my_se|')`
+                CompletionRelevanceData::EnumValue(_) | CompletionRelevanceData::Sequence(_) => {}
                 _ => return None,
             }
         }
@@ -162,19 +179,55 @@ impl CompletionFilter<'_> {
         Some(())
     }
 
+    /// Type names and sequence statements only accept a narrow set of objects,
+    /// so we don't want to fall back to the clause-based checks there.
+    fn check_type_or_sequence_position(&self, ctx: &TreesitterContext) -> Option<()> {
+        let is_allowed = if is_type_position(ctx) {
+            matches!(
+                self.data,
+                CompletionRelevanceData::Type(_) | CompletionRelevanceData::Schema(_)
+            )
+        } else if matches!(
+            ctx.wrapping_clause_type,
+            Some(WrappingClause::AlterSequence) | Some(WrappingClause::DropSequence)
+        ) {
+            match self.data {
+                CompletionRelevanceData::Sequence(_) | CompletionRelevanceData::Schema(_) => true,
+                // `alter sequence s owner to |`
+                CompletionRelevanceData::Role(_) => {
+                    ctx.node_under_cursor.kind() == "role_identifier"
+                }
+                _ => false,
+            }
+        } else {
+            !matches!(self.data, CompletionRelevanceData::Type(_))
+        };
+
+        if is_allowed { Some(()) } else { None }
+    }
+
     fn check_specific_node_type(&self, ctx: &TreesitterContext) -> Option<()> {
         let kind = ctx.node_under_cursor.kind();
 
         let is_allowed = match kind {
-            "column_identifier" => matches!(self.data, CompletionRelevanceData::Column(_)),
+            "column_identifier" => matches!(
+                self.data,
+                CompletionRelevanceData::Column(_) | CompletionRelevanceData::DerivedColumn(_)
+            ),
             "role_identifier" => matches!(self.data, CompletionRelevanceData::Role(_)),
             "function_identifier" => matches!(self.data, CompletionRelevanceData::Function(_)),
             "schema_identifier" => matches!(self.data, CompletionRelevanceData::Schema(_)),
             "table_identifier" => matches!(self.data, CompletionRelevanceData::Table(_)),
             "policy_identifier" => matches!(self.data, CompletionRelevanceData::Policy(_)),
 
+            "literal" => match self.data {
+                CompletionRelevanceData::EnumValue(_) => true,
+                CompletionRelevanceData::Sequence(_) => sequence_function_argument(ctx).is_some(),
+                _ => false,
+            },
+
             "any_identifier" => match self.data {
-                CompletionRelevanceData::Column(_) => {
+                CompletionRelevanceData::Column(_) | CompletionRelevanceData::DerivedColumn(_) => {
                     let matches_field = ctx.node_under_cursor_is_within_field(&[
                         "object_reference_1of1",
                         "object_reference_2of2",
@@ -230,6 +283,29 @@ impl CompletionFilter<'_> {
                         "any_identifier",
                     ])
                 }
+
+                CompletionRelevanceData::Type(_) => ctx.node_under_cursor_is_within_field(&[
+                    "object_reference_1of1",
+                    "object_reference_2of2",
+                ]),
+
+                CompletionRelevanceData::EnumValue(_) => value_node_under_cursor(ctx).is_some(),
+
+                // only offered right after `ON`, which the provider already checks
+                CompletionRelevanceData::JoinCondition(_) => true,
+
+                CompletionRelevanceData::Sequence(_) => {
+                    sequence_function_argument(ctx).is_some()
+                        || (matches!(
+                            ctx.wrapping_clause_type,
+                            Some(WrappingClause::AlterSequence)
+                                | Some(WrappingClause::DropSequence)
+                        ) && ctx.node_under_cursor_is_within_field(&[
+                            "object_reference_1of1",
+                            "object_reference_2of2",
+                        ]))
+                }
+
                 _ => false,
             },
 
@@ -239,6 +315,24 @@ impl CompletionFilter<'_> {
         if is_allowed { Some(()) } else { None }
     }
 
+    /// Some DDL positions only accept a subset of the objects of a kind:
+    /// `create index ... on t (|)` takes columns of `t`, `references t (|)` its unique columns,
+    /// and `create trigger ... execute function |` trigger functions.
+    fn check_ddl_target(&self, ctx: &TreesitterContext) -> Option<()> {
+        let is_allowed = match self.data {
+            CompletionRelevanceData::Column(col) => {
+                ddl_column_target(ctx).is_none_or(|target| target.matches(col))
+            }
+            CompletionRelevanceData::DerivedColumn(_) => ddl_column_target(ctx).is_none(),
+            CompletionRelevanceData::Function(f) => {
+                !is_trigger_function_position(ctx) || f.return_type.as_deref() == Some("trigger")
+            }
+            _ => true,
+        };
+
+        if is_allowed { Some(()) } else { None }
+    }
+
     fn check_clause(&self, ctx: &TreesitterContext) -> Option<()> {
         ctx.wrapping_clause_type
             .as_ref()
@@ -293,7 +387,8 @@ impl CompletionFilter<'_> {
                         _ => false,
                     },
 
-                    CompletionRelevanceData::Column(_) => {
+                    CompletionRelevanceData::Column(_)
+                    | CompletionRelevanceData::DerivedColumn(_) => {
                         match clause {
                             WrappingClause::Select
                             | WrappingClause::Update
@@ -431,6 +526,13 @@ impl CompletionFilter<'_> {
                     },
 
                     CompletionRelevanceData::Keyword(_) => true,
+
+                    // these are only completed in the specific nodes checked above
+                    CompletionRelevanceData::Type(_)
+                    | CompletionRelevanceData::EnumValue(_)
+                    | CompletionRelevanceData::Sequence(_)
+                    | CompletionRelevanceData::JoinCondition(_)
+                    | CompletionRelevanceData::Snippet(_) => false,
                 }
             })
             .and_then(|is_ok| if is_ok { Some(()) } else { None })
@@ -442,7 +544,9 @@ impl CompletionFilter<'_> {
         }
 
         match self.data {
-            CompletionRelevanceData::Table(_) | CompletionRelevanceData::Column(_) => return None,
+            CompletionRelevanceData::Table(_)
+            | CompletionRelevanceData::Column(_)
+            | CompletionRelevanceData::DerivedColumn(_) => return None,
             _ => {}
         }
 
@@ -458,6 +562,8 @@ impl CompletionFilter<'_> {
         let matches = match self.data {
             CompletionRelevanceData::Table(table) => table.schema == tail_qualifier,
             CompletionRelevanceData::Function(f) => f.schema == tail_qualifier,
+            CompletionRelevanceData::Type(t) => t.schema == tail_qualifier,
+            CompletionRelevanceData::Sequence(s) => s.schema == tail_qualifier,
             CompletionRelevanceData::Column(col) => {
                 let table = ctx
                     .get_mentioned_table_for_alias(&tail_qualifier)
@@ -468,12 +574,25 @@ impl CompletionFilter<'_> {
                         .head_qualifier_sanitized()
                         .is_none_or(|schema| col.schema_name == schema.as_str())
             }
+            // derived relations can't be schema-qualified
+            CompletionRelevanceData::DerivedColumn(ref col) => {
+                let relation = ctx
+                    .get_mentioned_table_for_alias(&tail_qualifier)
+                    .unwrap_or(&tail_qualifier);
+
+                col.relation == relation.as_str() && ctx.head_qualifier_sanitized().is_none()
+            }
 
             // we should never allow schema suggestions if there already was one.
             CompletionRelevanceData::Schema(_) => false,
             // no policy or row completion if user typed a schema node first.
             CompletionRelevanceData::Policy(_) | CompletionRelevanceData::Role(_) => false,
             CompletionRelevanceData::Keyword(_) => false,
+            // enum values are literals and can't be qualified.
+            CompletionRelevanceData::EnumValue(_) => false,
+            CompletionRelevanceData::JoinCondition(_) | CompletionRelevanceData::Snippet(_) => {
+                false
+            }
         };
 
         if !matches {
@@ -509,6 +628,31 @@ impl CompletionFilter<'_> {
         Some(())
     }
 
+    /// Filters out snippets unless the user has typed the beginning of their name.
+    ///
+    /// A snippet inserts a whole statement, so a single character isn't enough to offer it
+    /// next to the keywords.
+    fn check_snippet_prefix(
+        &self,
+        ctx: &TreesitterContext,
+        snippet: &crate::providers::Snippet,
+    ) -> Option<()> {
+        let content = ctx.get_node_under_cursor_content()?;
+        if content.chars().count() < 2 || crate::sanitization::is_sanitized_token(&content) {
+            return None;
+        }
+
+        if !snippet
+            .name
+            .to_lowercase()
+            .starts_with(&content.to_lowercase())
+        {
+            return None;
+        }
+
+        Some(())
+    }
+
     /// Validates whether a keyword would produce valid SQL at the current cursor position.
     ///
     /// Uses speculative parsing: injects the keyword into the SQL text at the cursor position,
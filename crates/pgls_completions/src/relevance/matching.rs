@@ -0,0 +1,227 @@
+use std::ops::Range;
+
+/// How well the typed input matches the name of a completion item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FuzzyMatch {
+    pub score: i32,
+
+    /// The byte ranges of the name that were matched, e.g. to highlight them.
+    pub ranges: Vec<Range<usize>>,
+}
+
+const MATCH: i32 = 16;
+const WORD_START: i32 = 8;
+const CONSECUTIVE: i32 = 8;
+const GAP: i32 = -3;
+const MAX_LEADING_GAP: i32 = -5;
+
+/// Matches the typed input against a name, case-insensitively.
+///
+/// In `user_order_items`, this finds
+/// - prefixes: `user_o`
+/// - word initials and word prefixes: `uoi`, `oitems`, `usorit`
+/// - any other subsequence: `srdr`
+/// - typos, if nothing else matched: `usre_order`
+///
+/// Words are separated by underscores, dots, spaces, digits, or a change to upper case,
+/// e.g. `userOrderItems`. Matching characters at the start of words and consecutive
+/// characters score higher.
+pub(crate) fn fuzzy_match(name: &str, input: &str) -> Option<FuzzyMatch> {
+    let name_chars: Vec<(usize, char)> = name.char_indices().collect();
+    let input: Vec<char> = input.chars().map(|c| c.to_ascii_lowercase()).collect();
+
+    if input.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            ranges: vec![],
+        });
+    }
+
+    subsequence_match(&name_chars, &input).or_else(|| typo_match(&name_chars, &input))
+}
+
+fn is_word_start(name: &[(usize, char)], idx: usize) -> bool {
+    let Some(prev) = idx.checked_sub(1).map(|i| name[i].1) else {
+        return true;
+    };
+    let current = name[idx].1;
+
+    matches!(prev, '_' | '.' | ' ' | '-' | '"')
+        || (prev.is_ascii_lowercase() && current.is_ascii_uppercase())
+        || (prev.is_ascii_alphabetic() && current.is_ascii_digit())
+}
+
+/// Finds the best way to match all input characters in order.
+fn subsequence_match(name: &[(usize, char)], input: &[char]) -> Option<FuzzyMatch> {
+    if input.len() > name.len() {
+        return None;
+    }
+
+    // best[i][j]: the best score with `input[i]` matched at `name[j]`,
+    // and at which position `input[i - 1]` was matched for it.
+    let mut best: Vec<Vec<Option<(i32, usize)>>> = vec![vec![None; name.len()]; input.len()];
+
+    for (i, input_char) in input.iter().enumerate() {
+        for j in i..name.len() {
+            if name[j].1.to_ascii_lowercase() != *input_char {
+                continue;
+            }
+
+            let bonus = MATCH
+                + if is_word_start(name, j) {
+                    WORD_START
+                } else {
+                    0
+                };
+
+            best[i][j] = if i == 0 {
+                Some((bonus + (-(j as i32)).max(MAX_LEADING_GAP), j))
+            } else {
+                (i - 1..j)
+                    .filter_map(|k| best[i - 1][k].map(|(score, _)| (score, k)))
+                    .map(|(score, k)| {
+                        let transition = if k + 1 == j { CONSECUTIVE } else { GAP };
+                        (score + bonus + transition, k)
+                    })
+                    .max_by_key(|(score, _)| *score)
+            };
+        }
+    }
+
+    let (mut j, score) = best[input.len() - 1]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, entry)| entry.map(|(score, _)| (j, score)))
+        .max_by_key(|(_, score)| *score)?;
+
+    let mut matched = vec![j];
+    for i in (1..input.len()).rev() {
+        j = best[i][j].expect("matched positions have a score").1;
+        matched.push(j);
+    }
+    matched.reverse();
+
+    Some(FuzzyMatch {
+        score,
+        ranges: to_byte_ranges(name, &matched),
+    })
+}
+
+/// Allows for a single wrong, missing, extra or swapped character, or two for longer inputs.
+fn typo_match(name: &[(usize, char)], input: &[char]) -> Option<FuzzyMatch> {
+    if input.len() < 4 {
+        return None;
+    }
+
+    let allowed = if input.len() >= 8 { 2 } else { 1 };
+    let name_lower: Vec<char> = name.iter().map(|(_, c)| c.to_ascii_lowercase()).collect();
+
+    let (prefix_len, distance) = (input.len() - 1..=input.len() + 1)
+        .filter(|len| *len <= name_lower.len())
+        .map(|len| (len, edit_distance(&name_lower[..len], input)))
+        .min_by_key(|(_, distance)| *distance)?;
+
+    if distance > allowed {
+        return None;
+    }
+
+    Some(FuzzyMatch {
+        score: (MATCH / 2) * (input.len() - distance) as i32,
+        ranges: to_byte_ranges(name, &(0..prefix_len).collect::<Vec<_>>()),
+    })
+}
+
+/// The optimal string alignment distance: insertions, deletions, substitutions and
+/// transpositions of adjacent characters each count as one edit.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    dist[a.len()][b.len()]
+}
+
+/// Merges matched character positions into byte ranges.
+fn to_byte_ranges(name: &[(usize, char)], positions: &[usize]) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = vec![];
+
+    for &pos in positions {
+        let (start, c) = name[pos];
+        let end = start + c.len_utf8();
+
+        match ranges.last_mut() {
+            Some(last) if last.end == start => last.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_match;
+
+    fn ranges(name: &str, input: &str) -> Vec<String> {
+        fuzzy_match(name, input)
+            .unwrap_or_else(|| panic!("{input} should match {name}"))
+            .ranges
+            .into_iter()
+            .map(|r| name[r].to_string())
+            .collect()
+    }
+
+    fn score(name: &str, input: &str) -> i32 {
+        fuzzy_match(name, input)
+            .map(|m| m.score)
+            .unwrap_or(i32::MIN)
+    }
+
+    #[test]
+    fn matches_word_initials_and_word_prefixes() {
+        assert_eq!(ranges("user_order_items", "uoi"), vec!["u", "o", "i"]);
+        assert_eq!(ranges("user_order_items", "oitems"), vec!["o", "items"]);
+        assert_eq!(ranges("user_order_items", "usorit"), vec!["us", "or", "it"]);
+        assert_eq!(ranges("userOrderItems", "uoi"), vec!["u", "O", "I"]);
+    }
+
+    #[test]
+    fn prefers_word_boundaries() {
+        assert!(score("user_order_items", "uoi") > score("usual_origin", "uoi"));
+        assert!(score("order_items", "oi") > score("options", "oi"));
+        assert!(score("orders", "ord") > score("user_orders", "ord"));
+    }
+
+    #[test]
+    fn tolerates_typos() {
+        assert_eq!(ranges("users", "usres"), vec!["users"]);
+        assert_eq!(ranges("user_order_items", "usre_order"), vec!["user_order"]);
+        assert!(fuzzy_match("users", "usr").is_some());
+
+        // short inputs would match too much
+        assert!(fuzzy_match("users", "xse").is_none());
+        assert!(fuzzy_match("users", "orders").is_none());
+
+        // actual matches rank higher
+        assert!(score("user_sessions", "usres") > score("users", "usres"));
+    }
+}
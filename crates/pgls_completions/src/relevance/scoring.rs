@@ -1,16 +1,18 @@
-use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
-
+use pgls_text_size::{TextRange, TextSize};
 use pgls_treesitter::context::{TreesitterContext, WrappingClause};
 
-use crate::sanitization;
+use crate::{
+    CompletionResolveData, UsageStats, providers::helper::sequence_function_argument, sanitization,
+};
 
-use super::CompletionRelevanceData;
+use super::{CompletionRelevanceData, matching::fuzzy_match};
 
 #[derive(Debug)]
 pub(crate) struct CompletionScore<'a> {
     score: i32,
     skip: bool,
     data: CompletionRelevanceData<'a>,
+    highlights: Vec<TextRange>,
 }
 
 impl<'a> From<CompletionRelevanceData<'a>> for CompletionScore<'a> {
@@ -19,6 +21,7 @@ impl<'a> From<CompletionRelevanceData<'a>> for CompletionScore<'a> {
             score: 0,
             skip: false,
             data: value,
+            highlights: vec![],
         }
     }
 }
@@ -32,7 +35,30 @@ impl CompletionScore<'_> {
         self.score
     }
 
-    pub fn calc_score(&mut self, ctx: &TreesitterContext) {
+    /// The ranges of the item's name that matched the typed text.
+    pub fn highlights(&self) -> &[TextRange] {
+        &self.highlights
+    }
+
+    /// Adds to the score, for signals that need more than the [TreesitterContext], e.g. the schema cache.
+    pub fn add_bonus(&mut self, bonus: i32) {
+        self.score += bonus;
+    }
+
+    /// Identifies the schema cache item, if the completion can be resolved lazily.
+    pub fn resolve_data(&self) -> Option<CompletionResolveData> {
+        match self.data {
+            CompletionRelevanceData::Table(table) => {
+                Some(CompletionResolveData::Table { id: table.id })
+            }
+            CompletionRelevanceData::Function(function) => {
+                Some(CompletionResolveData::Function { id: function.id })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn calc_score(&mut self, ctx: &TreesitterContext, usage: Option<&UsageStats>) {
         self.check_is_keyword();
         self.check_is_user_defined();
         self.check_matches_schema(ctx);
@@ -43,14 +69,42 @@ impl CompletionScore<'_> {
         self.check_relations_in_stmt(ctx);
         self.check_columns_in_stmt(ctx);
         self.check_is_not_wellknown_migration(ctx);
+
+        if let Some(usage) = usage {
+            self.check_usage(ctx, usage);
+        }
     }
 
     fn check_is_keyword(&mut self) {
-        if matches!(self.data, CompletionRelevanceData::Keyword(_)) {
-            self.score -= 10;
+        match self.data {
+            CompletionRelevanceData::Keyword(_) => self.score -= 10,
+            // snippets insert whole statements, so they are listed after the keywords
+            CompletionRelevanceData::Snippet(_) => self.score -= 100,
+            _ => {}
         }
     }
 
+    /// Prefers objects that are commonly used in the workspace.
+    fn check_usage(&mut self, ctx: &TreesitterContext, usage: &UsageStats) {
+        // logarithmic, so a handful of references already make a difference
+        // and hundreds don't outweigh everything else.
+        let bonus = |count: u32| ((count as f32 + 1.0).log2() * 3.0).min(15.0) as i32;
+
+        self.score += match self.data {
+            CompletionRelevanceData::Table(table) => {
+                let joined_with_mentioned: u32 = ctx
+                    .iter_mentioned_relations()
+                    .map(|mentioned| usage.join_count(mentioned, table))
+                    .sum();
+
+                bonus(usage.table_count(table)) + bonus(joined_with_mentioned)
+            }
+            CompletionRelevanceData::Column(column) => bonus(usage.column_count(column)),
+            CompletionRelevanceData::Function(function) => bonus(usage.function_count(function)),
+            _ => 0,
+        };
+    }
+
     fn check_matches_query_input(&mut self, ctx: &TreesitterContext) {
         let content = match ctx.get_node_under_cursor_content() {
             Some(c) if !sanitization::is_sanitized_token(c.as_str()) => c.replace('"', ""),
@@ -59,17 +113,20 @@ impl CompletionScore<'_> {
             }
         };
 
-        let name = match self.data {
-            CompletionRelevanceData::Function(f) => f.name.as_str().to_ascii_lowercase(),
-            CompletionRelevanceData::Table(t) => t.name.as_str().to_ascii_lowercase(),
-            CompletionRelevanceData::Column(c) => c.name.as_str().to_ascii_lowercase(),
-            CompletionRelevanceData::Schema(s) => s.name.as_str().to_ascii_lowercase(),
-            CompletionRelevanceData::Policy(p) => p.name.as_str().to_ascii_lowercase(),
-            CompletionRelevanceData::Role(r) => r.name.as_str().to_ascii_lowercase(),
-            CompletionRelevanceData::Keyword(k) => k.name.to_ascii_lowercase(),
+        // enum values and sequence names can be typed as string literals: `'ac|'`
+        let content = match self.data {
+            CompletionRelevanceData::EnumValue(_) | CompletionRelevanceData::Sequence(_) => {
+                content.replace('\'', "")
+            }
+            _ => content,
         };
 
-        let fz_matcher = SkimMatcherV2::default();
+        if content.is_empty() || sanitization::is_sanitized_token(content.as_str()) {
+            return;
+        }
+
+        // not lowercased, so camel humps can be matched
+        let name = self.get_item_name().to_string();
 
         let check_against = match &ctx.identifier_qualifiers {
             // If both qualifiers are already written out, we must check the item's name itself.
@@ -101,7 +158,9 @@ impl CompletionScore<'_> {
 
             _ => match self.data {
                 // for columns and functions, we fuzzy match with a possible alias.
-                CompletionRelevanceData::Column(_) | CompletionRelevanceData::Policy(_) => self
+                CompletionRelevanceData::Column(_)
+                | CompletionRelevanceData::DerivedColumn(_)
+                | CompletionRelevanceData::Policy(_) => self
                     .get_table_name()
                     .and_then(|tbl| ctx.get_used_alias_for_table(tbl))
                     .map(|t| format!("{t}.{name}"))
@@ -115,29 +174,40 @@ impl CompletionScore<'_> {
         let content_lower = content.to_ascii_lowercase();
         let check_against_lower = check_against.to_ascii_lowercase();
 
-        match fz_matcher.fuzzy_match(check_against_lower.as_str(), content_lower.as_str()) {
-            Some(score) => {
-                let mut scorei32: i32 = score
-                    .try_into()
-                    .expect("The length of the input exceeds i32 capacity");
+        match fuzzy_match(&check_against, &content_lower) {
+            Some(matched) => {
+                let mut score = matched.score;
 
                 // give a significant bonus for prefix matches since these are much more
                 // likely what the user is looking for
                 if check_against_lower.starts_with(&content_lower) {
-                    scorei32 += 20;
+                    score += 20;
                 }
 
-                // the scoring value isn't linear.
                 // here are a couple of samples:
-                // - item: bytea_string_agg_transfn, input: n, score: 15
-                // - item: numeric_uplus, input: n, score: 31
-                // - item: settings, input: sett, score: 91
-                // - item: user_settings, input: sett, score: 82
+                // - item: settings, input: sett, score: 96
+                // - item: user_settings, input: sett, score: 91
+                // - item: user_order_items, input: uoi, score: 66
+                // - item: users, input: usres (a typo), score: 32
                 self.score += if check_against == name {
-                    scorei32 / 2
+                    score / 2
                 } else {
-                    scorei32 / 3
+                    score / 3
                 };
+
+                // the name comes last in what we matched against, e.g. `alias.name`
+                let offset = check_against.len() - name.len();
+                self.highlights = matched
+                    .ranges
+                    .into_iter()
+                    .filter(|range| range.end > offset)
+                    .map(|range| {
+                        TextRange::new(
+                            TextSize::from((range.start.max(offset) - offset) as u32),
+                            TextSize::from((range.end - offset) as u32),
+                        )
+                    })
+                    .collect();
             }
             None => self.skip = true,
         }
@@ -172,20 +242,28 @@ impl CompletionScore<'_> {
                 WrappingClause::CheckOrUsingClause => 0,
                 _ => -50,
             },
-            CompletionRelevanceData::Column(col) => match clause_type {
-                WrappingClause::Select if has_mentioned_tables => 10,
-                WrappingClause::Select if !has_mentioned_tables => 0,
-                WrappingClause::Where => 10,
-                WrappingClause::CheckOrUsingClause => 0,
-                WrappingClause::Join { on_node }
-                    if on_node
-                        .is_some_and(|on| ctx.node_under_cursor.start_byte() > on.end_byte()) =>
-                {
-                    // Users will probably join on primary keys
-                    if col.is_primary_key { 20 } else { 10 }
+            CompletionRelevanceData::Column(_) | CompletionRelevanceData::DerivedColumn(_) => {
+                match clause_type {
+                    WrappingClause::Select if has_mentioned_tables => 10,
+                    WrappingClause::Select if !has_mentioned_tables => 0,
+                    WrappingClause::Where => 10,
+                    WrappingClause::CheckOrUsingClause => 0,
+                    WrappingClause::Join { on_node }
+                        if on_node.is_some_and(|on| {
+                            ctx.node_under_cursor.start_byte() > on.end_byte()
+                        }) =>
+                    {
+                        // Users will probably join on primary keys
+                        if matches!(self.data, CompletionRelevanceData::Column(col) if col.is_primary_key)
+                        {
+                            20
+                        } else {
+                            10
+                        }
+                    }
+                    _ => -15,
                 }
-                _ => -15,
-            },
+            }
             CompletionRelevanceData::Schema(_) => match clause_type {
                 WrappingClause::From if !has_qualifier => 15,
                 WrappingClause::Join { .. } if !has_qualifier => 15,
@@ -207,6 +285,19 @@ impl CompletionScore<'_> {
             },
 
             CompletionRelevanceData::Keyword(_) => 0,
+
+            // these are only offered in very specific positions, so the clause doesn't tell us much.
+            CompletionRelevanceData::Type(_) | CompletionRelevanceData::Sequence(_) => 0,
+
+            // enum values are only suggested if we know the compared column,
+            // so they are by far the most likely input.
+            CompletionRelevanceData::EnumValue(_) => 60,
+
+            // a predicate derived from a foreign key is what users most likely want after `ON`.
+            CompletionRelevanceData::JoinCondition(_) => 70,
+
+            // only offered at the start of a statement, where there is no clause yet.
+            CompletionRelevanceData::Snippet(_) => 0,
         }
     }
 
@@ -260,6 +351,7 @@ impl CompletionScore<'_> {
         self.score += match self.data {
             CompletionRelevanceData::Function(_) if ctx.is_invocation => 30,
             CompletionRelevanceData::Function(_) if !ctx.is_invocation => -10,
+            CompletionRelevanceData::Sequence(_) if sequence_function_argument(ctx).is_some() => 50,
             _ if ctx.is_invocation => -10,
             _ => 0,
         };
@@ -267,9 +359,10 @@ impl CompletionScore<'_> {
 
     fn check_matches_schema(&mut self, ctx: &TreesitterContext) {
         let schema_from_qualifier = match self.data {
-            CompletionRelevanceData::Table(_) | CompletionRelevanceData::Function(_) => {
-                ctx.tail_qualifier_sanitized()
-            }
+            CompletionRelevanceData::Table(_)
+            | CompletionRelevanceData::Function(_)
+            | CompletionRelevanceData::Type(_)
+            | CompletionRelevanceData::Sequence(_) => ctx.tail_qualifier_sanitized(),
 
             CompletionRelevanceData::Column(_) | CompletionRelevanceData::Policy(_) => {
                 ctx.head_qualifier_sanitized()
@@ -277,7 +370,11 @@ impl CompletionScore<'_> {
 
             CompletionRelevanceData::Schema(_)
             | CompletionRelevanceData::Role(_)
-            | CompletionRelevanceData::Keyword(_) => None,
+            | CompletionRelevanceData::Keyword(_)
+            | CompletionRelevanceData::EnumValue(_)
+            | CompletionRelevanceData::DerivedColumn(_)
+            | CompletionRelevanceData::JoinCondition(_)
+            | CompletionRelevanceData::Snippet(_) => None,
         };
 
         if schema_from_qualifier.is_none() {
@@ -303,10 +400,16 @@ impl CompletionScore<'_> {
             CompletionRelevanceData::Table(t) => t.name.as_str(),
             CompletionRelevanceData::Function(f) => f.name.as_str(),
             CompletionRelevanceData::Column(c) => c.name.as_str(),
+            CompletionRelevanceData::DerivedColumn(ref c) => c.name.as_str(),
             CompletionRelevanceData::Schema(s) => s.name.as_str(),
             CompletionRelevanceData::Policy(p) => p.name.as_str(),
             CompletionRelevanceData::Role(r) => r.name.as_str(),
             CompletionRelevanceData::Keyword(k) => k.name,
+            CompletionRelevanceData::Type(t) => t.name.as_str(),
+            CompletionRelevanceData::EnumValue(v) => v,
+            CompletionRelevanceData::Sequence(s) => s.name.as_str(),
+            CompletionRelevanceData::JoinCondition(ref c) => c.as_str(),
+            CompletionRelevanceData::Snippet(s) => s.name.as_str(),
         }
     }
 
@@ -319,12 +422,19 @@ impl CompletionScore<'_> {
             CompletionRelevanceData::Policy(p) => Some(p.schema_name.as_str()),
             CompletionRelevanceData::Role(_) => None,
             CompletionRelevanceData::Keyword(_) => None,
+            CompletionRelevanceData::Type(t) => Some(t.schema.as_str()),
+            CompletionRelevanceData::EnumValue(_) => None,
+            CompletionRelevanceData::Sequence(s) => Some(s.schema.as_str()),
+            CompletionRelevanceData::DerivedColumn(_) => None,
+            CompletionRelevanceData::JoinCondition(_) => None,
+            CompletionRelevanceData::Snippet(_) => None,
         }
     }
 
     fn get_table_name(&self) -> Option<&str> {
         match self.data {
             CompletionRelevanceData::Column(c) => Some(c.table_name.as_str()),
+            CompletionRelevanceData::DerivedColumn(ref c) => Some(c.relation.as_str()),
             CompletionRelevanceData::Table(t) => Some(t.name.as_str()),
             CompletionRelevanceData::Policy(p) => Some(p.table_name.as_str()),
             _ => None,
@@ -334,6 +444,11 @@ impl CompletionScore<'_> {
     fn check_relations_in_stmt(&mut self, ctx: &TreesitterContext) {
         match self.data {
             CompletionRelevanceData::Table(_) | CompletionRelevanceData::Function(_) => return,
+            // only columns of relations visible at the cursor are offered
+            CompletionRelevanceData::DerivedColumn(_) => {
+                self.score += 45;
+                return;
+            }
             _ => {}
         }
 
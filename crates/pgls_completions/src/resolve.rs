@@ -0,0 +1,96 @@
+use pgls_hover::to_markdown::format_hover_markdown;
+use pgls_schema_cache::SchemaCache;
+
+use crate::CompletionResolveData;
+
+/// The details of a completion item that are only computed once the client asks for them.
+#[derive(Debug, Default)]
+pub struct ResolvedCompletionItem {
+    pub detail: Option<String>,
+    /// Markdown, rendered the same way as on hover.
+    pub documentation: Option<String>,
+}
+
+/// Computes the documentation of a completion item.
+/// Returns `None` if the item is no longer in the schema cache.
+pub fn resolve_completion_item(
+    data: &CompletionResolveData,
+    schema_cache: &SchemaCache,
+) -> Option<ResolvedCompletionItem> {
+    match data {
+        CompletionResolveData::Table { id } => {
+            let table = schema_cache.tables.iter().find(|t| t.id == *id)?;
+
+            Some(ResolvedCompletionItem {
+                detail: Some(format!(
+                    "{}.{} (~{} rows)",
+                    table.schema, table.name, table.live_rows_estimate
+                )),
+                documentation: format_hover_markdown(table, schema_cache).ok(),
+            })
+        }
+
+        CompletionResolveData::Function { id } => {
+            let function = schema_cache.functions.iter().find(|f| f.id == *id)?;
+
+            Some(ResolvedCompletionItem {
+                detail: Some(format!(
+                    "{}.{}({}) → {}",
+                    function.schema,
+                    function.name,
+                    function.argument_types.as_deref().unwrap_or_default(),
+                    function.return_type.as_deref().unwrap_or("void")
+                )),
+                documentation: format_hover_markdown(function, schema_cache).ok(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pgls_test_utils::QueryWithCursorPosition;
+    use sqlx::PgPool;
+
+    use crate::{
+        CompletionItem, CompletionResolveData, complete, resolve_completion_item,
+        test_helper::{get_test_deps, get_test_params},
+    };
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn resolves_function_documentation(pool: PgPool) {
+        let setup = r#"
+          create or replace function cool(a int)
+          returns int
+          language sql
+          security definer
+          as $$ select a $$;
+
+          comment on function cool(int) is 'very cool';
+        "#;
+
+        let query = format!("select coo{}", QueryWithCursorPosition::cursor_marker());
+
+        let (tree, cache) = get_test_deps(Some(setup), query.as_str().into(), &pool).await;
+        let params = get_test_params(&tree, &cache, query.as_str().into());
+
+        let CompletionItem { resolve_data, .. } = complete(params)
+            .into_iter()
+            .find(|item| item.label == "cool")
+            .expect("Should complete the function");
+
+        let data = resolve_data.expect("Functions should be resolvable");
+        assert!(matches!(data, CompletionResolveData::Function { .. }));
+
+        let resolved = resolve_completion_item(&data, &cache).expect("Should resolve");
+
+        assert_eq!(
+            resolved.detail.as_deref(),
+            Some("public.cool(a integer) → integer")
+        );
+
+        let documentation = resolved.documentation.unwrap();
+        assert!(documentation.contains("Comment: 'very cool'"));
+        assert!(documentation.contains("Security DEFINER"));
+    }
+}
@@ -52,6 +52,7 @@ where
             || cursor_before_semicolon(params.tree, params.position)
             || cursor_on_a_dot(&params.text, params.position)
             || cursor_between_parentheses(&params.text, params.position)
+            || cursor_before_empty_parentheses(&params.text, params.position)
             || cursor_after_opened_quote(&params.text, params.position)
         {
             SanitizedCompletionParams::with_adjusted_sql(params)
@@ -285,6 +286,24 @@ fn cursor_between_parentheses(sql: &str, position: TextSize) -> bool {
     head_of_list || end_of_list || between_list_items || after_and_keyword || after_eq_sign
 }
 
+/// Checks if the cursor is in front of an empty argument list,
+/// where the name of a function is expected:
+///
+/// ```sql
+/// create trigger t before insert on users for each row execute function |();
+/// ```
+fn cursor_before_empty_parentheses(sql: &str, position: TextSize) -> bool {
+    let position: usize = position.into();
+
+    let previous_whitespace = position > 0
+        && sql
+            .chars()
+            .nth(position - 1)
+            .is_some_and(|c| c.is_ascii_whitespace());
+
+    previous_whitespace && sql.chars().skip(position).take(2).eq("()".chars())
+}
+
 fn cursor_after_opened_quote(sql: &str, position: TextSize) -> bool {
     let position: usize = position.into();
     let mut opened_quote = false;
@@ -314,8 +333,8 @@ mod tests {
     use crate::{
         CompletionParams, SanitizedCompletionParams,
         sanitization::{
-            cursor_after_opened_quote, cursor_before_semicolon, cursor_between_parentheses,
-            cursor_inbetween_nodes, cursor_on_a_dot,
+            cursor_after_opened_quote, cursor_before_empty_parentheses, cursor_before_semicolon,
+            cursor_between_parentheses, cursor_inbetween_nodes, cursor_on_a_dot,
             cursor_prepared_to_write_token_after_last_node,
         },
     };
@@ -336,6 +355,8 @@ mod tests {
             schema: leaked_cache,
             text: input.into(),
             tree: leaked_tree,
+            snippets: &[],
+            usage: None,
         }
     }
 
@@ -596,6 +617,29 @@ mod tests {
         assert!(!cursor_between_parentheses("(a)", TextSize::new(2)));
     }
 
+    #[test]
+    fn before_empty_parentheses() {
+        let input = "create trigger t before insert on users for each row execute function ();";
+
+        // execute function |()
+        assert!(cursor_before_empty_parentheses(input, TextSize::new(70)));
+
+        // execute function (|)
+        assert!(!cursor_before_empty_parentheses(input, TextSize::new(71)));
+
+        // execute function| ()
+        assert!(!cursor_before_empty_parentheses(
+            "execute function ()",
+            TextSize::new(16)
+        ));
+
+        // function |(a) <- not an empty argument list
+        assert!(!cursor_before_empty_parentheses(
+            "function (a)",
+            TextSize::new(9)
+        ));
+    }
+
     #[test]
     fn after_single_quote() {
         // select "|    <-- right after single quote
@@ -72,6 +72,8 @@ pub(crate) fn get_test_params<'a>(
         schema: schema_cache,
         tree,
         text,
+        snippets: &[],
+        usage: None,
     }
 }
 
@@ -518,6 +520,8 @@ impl TestCompletionsCase {
             position: (pos as u32).into(),
             schema,
             tree: &tree,
+            snippets: &[],
+            usage: None,
         };
 
         let items = complete(params);
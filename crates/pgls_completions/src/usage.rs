@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use pgls_schema_cache::{Column, Function, Table};
+use serde::{Deserialize, Serialize};
+
+/// How often tables, columns and functions are referenced by the SQL in the workspace.
+///
+/// References are recorded as written: `schema.name` if qualified, `name` otherwise.
+/// Unqualified references are attributed to the `public` schema, since that's where
+/// they resolve to with the default `search_path`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageStats {
+    tables: HashMap<String, u32>,
+
+    /// Keyed by `schema.table.column` or `table.column`, or by `column` if the table is
+    /// ambiguous.
+    columns: HashMap<String, u32>,
+
+    functions: HashMap<String, u32>,
+
+    /// How often two tables are joined with each other, in both directions.
+    joins: HashMap<String, HashMap<String, u32>>,
+}
+
+impl UsageStats {
+    pub fn record_table(&mut self, schema: Option<&str>, name: &str) {
+        *self.tables.entry(qualified(schema, name)).or_default() += 1;
+    }
+
+    /// Records a column of `table`, given as `(schema, name)`, or of an unknown table.
+    pub fn record_column(&mut self, table: Option<(Option<&str>, &str)>, name: &str) {
+        let key = match table {
+            Some((schema, table)) => qualified(Some(&qualified(schema, table)), name),
+            None => name.to_string(),
+        };
+        *self.columns.entry(key).or_default() += 1;
+    }
+
+    pub fn record_function(&mut self, schema: Option<&str>, name: &str) {
+        *self.functions.entry(qualified(schema, name)).or_default() += 1;
+    }
+
+    /// Records a join between two tables, each given as `(schema, name)`.
+    pub fn record_join(&mut self, left: (Option<&str>, &str), right: (Option<&str>, &str)) {
+        let left = qualified(left.0, left.1);
+        let right = qualified(right.0, right.1);
+        if left == right {
+            return;
+        }
+
+        for (a, b) in [(&left, &right), (&right, &left)] {
+            *self
+                .joins
+                .entry(a.clone())
+                .or_default()
+                .entry(b.clone())
+                .or_default() += 1;
+        }
+    }
+
+    /// Adds the counts of `other` to these stats.
+    pub fn merge(&mut self, other: &UsageStats) {
+        for (map, other) in [
+            (&mut self.tables, &other.tables),
+            (&mut self.columns, &other.columns),
+            (&mut self.functions, &other.functions),
+        ] {
+            for (key, count) in other {
+                *map.entry(key.clone()).or_default() += count;
+            }
+        }
+
+        for (table, joined) in &other.joins {
+            let entry = self.joins.entry(table.clone()).or_default();
+            for (key, count) in joined {
+                *entry.entry(key.clone()).or_default() += count;
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+            && self.columns.is_empty()
+            && self.functions.is_empty()
+            && self.joins.is_empty()
+    }
+
+    pub(crate) fn table_count(&self, table: &Table) -> u32 {
+        count(&self.tables, &table.schema, &table.name, &["public"])
+    }
+
+    pub(crate) fn column_count(&self, column: &Column) -> u32 {
+        let table = qualified(Some(&column.schema_name), &column.table_name);
+        let unqualified_table = (column.schema_name == "public")
+            .then(|| {
+                self.columns
+                    .get(&qualified(Some(&column.table_name), &column.name))
+            })
+            .flatten();
+
+        self.columns
+            .get(&qualified(Some(&table), &column.name))
+            .into_iter()
+            .chain(unqualified_table)
+            .chain(self.columns.get(&column.name))
+            .sum()
+    }
+
+    pub(crate) fn function_count(&self, function: &Function) -> u32 {
+        // unqualified calls also resolve to built-in functions
+        count(
+            &self.functions,
+            &function.schema,
+            &function.name,
+            &["public", "pg_catalog"],
+        )
+    }
+
+    /// How often `table` is joined with a relation mentioned as `(schema, name)`.
+    pub(crate) fn join_count(&self, mentioned: (Option<&str>, &str), table: &Table) -> u32 {
+        let (schema, name) = mentioned;
+        let keys = match schema {
+            Some("public") | None => vec![name.to_string(), qualified(Some("public"), name)],
+            Some(schema) => vec![qualified(Some(schema), name)],
+        };
+
+        keys.iter()
+            .filter_map(|key| self.joins.get(key))
+            .map(|joined| count(joined, &table.schema, &table.name, &["public"]))
+            .sum()
+    }
+}
+
+fn qualified(qualifier: Option<&str>, name: &str) -> String {
+    match qualifier {
+        Some(qualifier) => format!("{qualifier}.{name}"),
+        None => name.to_string(),
+    }
+}
+
+/// Counts the references to `schema.name`, plus the unqualified ones if `schema` is on the
+/// default `search_path`.
+fn count(map: &HashMap<String, u32>, schema: &str, name: &str, search_path: &[&str]) -> u32 {
+    let qualified = map
+        .get(&qualified(Some(schema), name))
+        .copied()
+        .unwrap_or_default();
+
+    if search_path.contains(&schema) {
+        qualified + map.get(name).copied().unwrap_or_default()
+    } else {
+        qualified
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pgls_schema_cache::{Column, ColumnClassKind, Table};
+
+    use super::UsageStats;
+
+    fn table(schema: &str, name: &str) -> Table {
+        Table {
+            schema: schema.into(),
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    fn column(schema: &str, table: &str, name: &str) -> Column {
+        Column {
+            name: name.into(),
+            table_name: table.into(),
+            table_oid: 0,
+            class_kind: ColumnClassKind::OrdinaryTable,
+            number: 1,
+            schema_name: schema.into(),
+            type_id: 0,
+            type_name: None,
+            is_nullable: true,
+            is_primary_key: false,
+            is_unique: false,
+            default_expr: None,
+            varchar_length: None,
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn keys_joins_and_columns_by_schema() {
+        let mut usage = UsageStats::default();
+        usage.record_join((None, "orders"), (Some("billing"), "invoices"));
+        usage.record_column(Some((Some("billing"), "invoices")), "total");
+        usage.record_column(Some((None, "invoices")), "id");
+
+        assert_eq!(
+            usage.join_count((None, "orders"), &table("billing", "invoices")),
+            1
+        );
+        assert_eq!(
+            usage.join_count((None, "orders"), &table("public", "invoices")),
+            0
+        );
+        assert_eq!(
+            usage.join_count((Some("public"), "orders"), &table("billing", "invoices")),
+            1
+        );
+        assert_eq!(
+            usage.join_count((Some("app"), "orders"), &table("billing", "invoices")),
+            0
+        );
+
+        assert_eq!(
+            usage.column_count(&column("billing", "invoices", "total")),
+            1
+        );
+        assert_eq!(
+            usage.column_count(&column("public", "invoices", "total")),
+            0
+        );
+        assert_eq!(usage.column_count(&column("public", "invoices", "id")), 1);
+        assert_eq!(usage.column_count(&column("billing", "invoices", "id")), 0);
+    }
+}
@@ -0,0 +1,55 @@
+use bpaf::Bpaf;
+use pgls_configuration_macros::{Merge, Partial};
+use serde::{Deserialize, Serialize};
+
+/// The configuration for completions.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Partial, PartialEq, Serialize)]
+#[partial(derive(Bpaf, Clone, Eq, PartialEq, Merge))]
+#[partial(cfg_attr(feature = "schema", derive(schemars::JsonSchema)))]
+#[partial(serde(rename_all = "camelCase", default, deny_unknown_fields))]
+pub struct CompletionsConfiguration {
+    /// Custom snippets offered at the start of a statement.
+    #[partial(bpaf(pure(Default::default()), optional, hide))]
+    pub snippets: Snippets,
+
+    /// Don't store the usage of tables, columns and functions on disk.
+    /// By default, it is written to the cache directory so that completions are ranked by it
+    /// right away on the next start.
+    #[partial(bpaf(long("disable-usage-persistence"), switch, fallback(Some(false))))]
+    pub disable_usage_persistence: bool,
+}
+
+/// A list of user-defined snippets.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Snippets(pub Vec<SnippetConfiguration>);
+
+impl crate::Merge for Snippets {
+    /// Snippets of `other` replace the ones with the same name.
+    fn merge_with(&mut self, other: Self) {
+        for snippet in other.0 {
+            match self.0.iter_mut().find(|s| s.name == snippet.name) {
+                Some(existing) => *existing = snippet,
+                None => self.0.push(snippet),
+            }
+        }
+    }
+}
+
+/// A user-defined snippet.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SnippetConfiguration {
+    /// The label shown in the completion list.
+    pub name: String,
+
+    /// Shown next to the snippet in the completion list.
+    #[serde(default)]
+    pub description: String,
+
+    /// The text to insert. Supports tab stops (`$1`), placeholders (`${1:name}`) and choices
+    /// (`${1|a,b|}`). Placeholders named `table` or `column` are turned into a choice of the
+    /// most used tables in the database, or of the columns of those tables.
+    pub body: String,
+}
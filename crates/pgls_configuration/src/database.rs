@@ -49,6 +49,23 @@ pub struct DatabaseConfiguration {
     #[partial(bpaf(long("conn_timeout_secs"), fallback(Some(10)), debug_fallback))]
     pub conn_timeout_secs: u16,
 
+    /// The name of a channel to `LISTEN` on for schema changes.
+    /// Every notification refreshes the schema cache for the object type named by its
+    /// payload, e.g. `ALTER TABLE` or `CREATE FUNCTION`. An empty payload refreshes everything.
+    /// Typically fed by an event trigger on `ddl_command_end`.
+    #[partial(bpaf(long("schema-cache-listen-channel")))]
+    pub schema_cache_listen_channel: Option<String>,
+
+    /// Don't store the schema cache on disk.
+    /// By default, the schema cache is written to the cache directory so that it can be used
+    /// right away on the next start while it is revalidated against the database.
+    #[partial(bpaf(
+        long("disable-schema-cache-persistence"),
+        switch,
+        fallback(Some(false))
+    ))]
+    pub disable_schema_cache_persistence: bool,
+
     /// Actively disable all database-related features.
     #[partial(bpaf(long("disable-db"), switch, fallback(Some(false))))]
     #[partial(cfg_attr(feature = "schema", schemars(skip)))]
@@ -72,6 +89,14 @@ impl std::fmt::Debug for DatabaseConfiguration {
                 &self.allow_statement_executions_against,
             )
             .field("conn_timeout_secs", &self.conn_timeout_secs)
+            .field(
+                "schema_cache_listen_channel",
+                &self.schema_cache_listen_channel,
+            )
+            .field(
+                "disable_schema_cache_persistence",
+                &self.disable_schema_cache_persistence,
+            )
             .field("disable_connection", &self.disable_connection)
             .finish()
     }
@@ -89,6 +114,8 @@ impl Default for DatabaseConfiguration {
             database: "postgres".to_string(),
             allow_statement_executions_against: Default::default(),
             conn_timeout_secs: 10,
+            schema_cache_listen_channel: None,
+            disable_schema_cache_persistence: false,
         }
     }
 }
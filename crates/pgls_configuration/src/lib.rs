@@ -6,6 +6,7 @@
 // which generates `pgls_configuration::Merge` paths.
 extern crate self as pgls_configuration;
 
+pub mod completions;
 pub mod database;
 pub mod diagnostics;
 pub mod files;
@@ -29,6 +30,10 @@ use std::path::PathBuf;
 
 use crate::vcs::{PartialVcsConfiguration, VcsConfiguration, partial_vcs_configuration};
 use bpaf::Bpaf;
+pub use completions::{
+    CompletionsConfiguration, PartialCompletionsConfiguration, SnippetConfiguration, Snippets,
+    partial_completions_configuration,
+};
 use database::{
     DatabaseConfiguration, PartialDatabaseConfiguration, partial_database_configuration,
 };
@@ -113,6 +118,13 @@ pub struct Configuration {
     #[partial(type, bpaf(external(partial_format_configuration), optional))]
     pub format: FormatConfiguration,
 
+    /// The configuration for completions
+    #[partial(
+        type,
+        bpaf(external(partial_completions_configuration), optional, hide)
+    )]
+    pub completions: CompletionsConfiguration,
+
     /// The configuration for pglinter
     #[partial(type, bpaf(external(partial_pglinter_configuration), optional))]
     pub pglinter: PglinterConfiguration,
@@ -166,6 +178,7 @@ impl PartialConfiguration {
                 enabled: Some(false), // Disabled by default during beta
                 ..Default::default()
             }),
+            completions: None,
             pglinter: Some(PartialPglinterConfiguration {
                 enabled: Some(false), // Disabled by default since pglinter extension might not be installed
                 ..Default::default()
@@ -188,6 +201,8 @@ impl PartialConfiguration {
                 allow_statement_executions_against: Default::default(),
                 conn_timeout_secs: Some(10),
                 disable_connection: Some(false),
+                schema_cache_listen_channel: None,
+                disable_schema_cache_persistence: Some(false),
             }),
         }
     }
@@ -0,0 +1,331 @@
+use super::{Migration, MigrationDirection, MigrationSection, MigrationVersion};
+use pgls_text_size::{TextRange, TextSize};
+use std::fmt::Debug;
+use std::path::{Component, Path};
+
+/// How the files of a migration tool are named, ordered and split into sections
+pub trait MigrationLayout: Debug + Send + Sync {
+    /// Returns the migration a file belongs to, or `None` if it isn't a migration file.
+    /// `path` is relative to the migrations directory.
+    fn migration(&self, path: &Path) -> Option<Migration>;
+
+    /// Describes how migration files are named, e.g. `<number>_<name>.sql`
+    fn naming_pattern(&self) -> &'static str;
+
+    /// Returns the sections of a migration file that are applied on their own. Statements
+    /// outside of all sections aren't applied.
+    fn sections(&self, migration: &Migration, content: &str) -> Vec<MigrationSection> {
+        vec![MigrationSection {
+            direction: migration.direction,
+            range: TextRange::up_to(TextSize::of(content)),
+        }]
+    }
+}
+
+/// `<number>_<name>.sql` files, or `<number>_<name>` directories
+#[derive(Debug)]
+pub struct DefaultLayout;
+
+impl MigrationLayout for DefaultLayout {
+    fn migration(&self, path: &Path) -> Option<Migration> {
+        // in the "root" pattern, all files are directly within the migrations directory
+        // and their names follow <timestamp>_<name>.sql.
+        // this is used by supabase
+        let file_name = path.file_name().and_then(|os_str| os_str.to_str())?;
+        if let Some(migration) = parse_migration_name(file_name) {
+            // golang-migrate uses `<number>_<name>.up.sql`
+            return Some(with_direction_suffix(migration));
+        }
+
+        // in the "subdirectory" pattern, each migration is in a subdirectory named <timestamp>_<name>
+        // this is used by prisma and drizzle
+        let migration = path
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .and_then(|os_str| os_str.to_str())
+            .and_then(parse_migration_name)?;
+        let direction = match file_name {
+            "up.sql" => MigrationDirection::Up,
+            "down.sql" => MigrationDirection::Down,
+            _ => MigrationDirection::Unspecified,
+        };
+        Some(Migration {
+            direction,
+            ..migration
+        })
+    }
+
+    fn naming_pattern(&self) -> &'static str {
+        "`<number>_<name>.sql`"
+    }
+}
+
+/// `V<version>__<name>.sql` versioned, `U<version>__<name>.sql` undo and `R__<name>.sql`
+/// repeatable migrations of Flyway. The parts of versions are separated by dots or
+/// underscores, e.g. `V1.1__<name>.sql` or `V2024_01_01__<name>.sql`.
+#[derive(Debug)]
+pub struct FlywayLayout;
+
+impl MigrationLayout for FlywayLayout {
+    fn migration(&self, path: &Path) -> Option<Migration> {
+        let file_name = path.file_name().and_then(|os_str| os_str.to_str())?;
+        let stem = file_name.strip_suffix(".sql")?;
+
+        if let Some(name) = stem.strip_prefix("R__") {
+            return Some(Migration {
+                sequence_number: MigrationVersion::max(),
+                name: name.to_string(),
+                direction: MigrationDirection::Unspecified,
+                repeatable: true,
+            });
+        }
+
+        // undo migrations are optional, so versioned migrations don't require one
+        let (direction, versioned) = if let Some(versioned) = stem.strip_prefix('V') {
+            (MigrationDirection::Unspecified, versioned)
+        } else {
+            (MigrationDirection::Down, stem.strip_prefix('U')?)
+        };
+        let (version, name) = versioned.split_once("__")?;
+        let version = version
+            .split(['.', '_'])
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        Some(Migration {
+            sequence_number: MigrationVersion::new(version),
+            name: name.to_string(),
+            direction,
+            repeatable: false,
+        })
+    }
+
+    fn naming_pattern(&self) -> &'static str {
+        "`V<version>__<name>.sql`, `U<version>__<name>.sql` or `R__<name>.sql`"
+    }
+}
+
+/// The `deploy/`, `revert/` and `verify/` scripts of Sqitch. Changes are ordered by the
+/// `sqitch.plan` of the migrations directory.
+#[derive(Debug)]
+pub struct SqitchLayout {
+    /// The changes of the plan in order. Reworked changes are listed once per version.
+    changes: Vec<String>,
+}
+
+/// The file of the migrations directory that lists the changes of Sqitch
+pub const SQITCH_PLAN_FILE_NAME: &str = "sqitch.plan";
+
+impl SqitchLayout {
+    pub fn new(changes: Vec<String>) -> Self {
+        Self { changes }
+    }
+
+    /// Parses the changes of a `sqitch.plan`, skipping pragmas, tags and comments
+    pub fn from_plan(plan: &str) -> Self {
+        Self::new(
+            plan.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with(['%', '@', '#']))
+                .filter_map(|line| line.split_whitespace().next())
+                .map(|change| change.trim_start_matches(['+', '-']).to_string())
+                .collect(),
+        )
+    }
+}
+
+impl MigrationLayout for SqitchLayout {
+    fn migration(&self, path: &Path) -> Option<Migration> {
+        let mut components = path.components();
+        let direction = match components.next()? {
+            Component::Normal(dir) if dir == "deploy" => MigrationDirection::Up,
+            Component::Normal(dir) if dir == "revert" => MigrationDirection::Down,
+            Component::Normal(dir) if dir == "verify" => MigrationDirection::Verify,
+            _ => return None,
+        };
+
+        // change names can contain slashes, which map to subdirectories
+        let script = components
+            .map(|component| component.as_os_str().to_str())
+            .collect::<Option<Vec<_>>>()?
+            .join("/");
+        let name = script.strip_suffix(".sql")?;
+
+        // the scripts of a reworked change are kept as `<change>@<tag>.sql` for its first
+        // version, while `<change>.sql` is the latest one
+        let index = match name.split_once('@') {
+            Some((change, _tag)) => self.changes.iter().position(|c| c == change),
+            None => self.changes.iter().rposition(|c| c == name),
+        }?;
+
+        Some(Migration {
+            sequence_number: MigrationVersion::from(index as u64 + 1),
+            name: name.to_string(),
+            direction,
+            repeatable: false,
+        })
+    }
+
+    fn naming_pattern(&self) -> &'static str {
+        "`deploy/<change>.sql`, `revert/<change>.sql` or `verify/<change>.sql` for the changes of `sqitch.plan`"
+    }
+}
+
+/// `<number>_<name>.up.sql` and `<number>_<name>.down.sql` files of golang-migrate
+#[derive(Debug)]
+pub struct GolangMigrateLayout;
+
+impl MigrationLayout for GolangMigrateLayout {
+    fn migration(&self, path: &Path) -> Option<Migration> {
+        let migration = path
+            .file_name()
+            .and_then(|os_str| os_str.to_str())
+            .and_then(parse_migration_name)
+            .map(with_direction_suffix)?;
+
+        (migration.direction != MigrationDirection::Unspecified).then_some(migration)
+    }
+
+    fn naming_pattern(&self) -> &'static str {
+        "`<number>_<name>.up.sql` or `<number>_<name>.down.sql`"
+    }
+}
+
+/// `<number>_<name>.sql` files of dbmate, with a `-- migrate:up` and a `-- migrate:down`
+/// section
+#[derive(Debug)]
+pub struct DbmateLayout;
+
+impl MigrationLayout for DbmateLayout {
+    fn migration(&self, path: &Path) -> Option<Migration> {
+        path.file_name()
+            .and_then(|os_str| os_str.to_str())
+            .and_then(parse_migration_name)
+    }
+
+    fn naming_pattern(&self) -> &'static str {
+        "`<number>_<name>.sql`"
+    }
+
+    fn sections(&self, migration: &Migration, content: &str) -> Vec<MigrationSection> {
+        marker_sections(migration, content, |marker| {
+            if marker.starts_with("migrate:up") {
+                Some(MigrationDirection::Up)
+            } else if marker.starts_with("migrate:down") {
+                Some(MigrationDirection::Down)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Liquibase formatted SQL files, named `<number>_<name>.sql` or `<number>-<name>.sql`. Every
+/// `--changeset` is a section. Their rollbacks are written in comments, so they aren't
+/// sections of their own.
+#[derive(Debug)]
+pub struct LiquibaseLayout;
+
+impl MigrationLayout for LiquibaseLayout {
+    fn migration(&self, path: &Path) -> Option<Migration> {
+        let file_name = path.file_name().and_then(|os_str| os_str.to_str())?;
+        let name = file_name.strip_suffix(".sql")?;
+        let (sequence_number, name) = name.split_once(['_', '-'])?;
+
+        Some(Migration {
+            sequence_number: parse_sequence_number(sequence_number)?,
+            name: name.to_string(),
+            direction: MigrationDirection::Unspecified,
+            repeatable: false,
+        })
+    }
+
+    fn naming_pattern(&self) -> &'static str {
+        "`<number>_<name>.sql` or `<number>-<name>.sql`"
+    }
+
+    fn sections(&self, migration: &Migration, content: &str) -> Vec<MigrationSection> {
+        marker_sections(migration, content, |marker| {
+            marker
+                .starts_with("changeset")
+                .then_some(MigrationDirection::Up)
+        })
+    }
+}
+
+/// Parses `<number>_<name>`, optionally followed by `.sql`
+fn parse_migration_name(name: &str) -> Option<Migration> {
+    let (sequence_number, full_name) = name.split_once('_')?;
+    let name = full_name
+        .strip_suffix(".sql")
+        .unwrap_or(full_name)
+        .to_string();
+    Some(Migration {
+        sequence_number: parse_sequence_number(sequence_number)?,
+        name,
+        direction: MigrationDirection::Unspecified,
+        repeatable: false,
+    })
+}
+
+fn parse_sequence_number(sequence_number: &str) -> Option<MigrationVersion> {
+    // remove leading zeros to support numeric
+    sequence_number
+        .trim_start_matches('0')
+        .parse::<u64>()
+        .ok()
+        .map(MigrationVersion::from)
+}
+
+/// Moves a `.up` or `.down` suffix of the name to the direction of the migration
+fn with_direction_suffix(migration: Migration) -> Migration {
+    let (name, direction) = if let Some(name) = migration.name.strip_suffix(".up") {
+        (name.to_string(), MigrationDirection::Up)
+    } else if let Some(name) = migration.name.strip_suffix(".down") {
+        (name.to_string(), MigrationDirection::Down)
+    } else {
+        return migration;
+    };
+    Migration {
+        name,
+        direction,
+        ..migration
+    }
+}
+
+/// Splits a file into sections that start at a marker comment, e.g. `-- migrate:up`.
+/// `direction_of` receives the comment without the leading dashes and whitespace. Files
+/// without markers are a single section.
+fn marker_sections(
+    migration: &Migration,
+    content: &str,
+    direction_of: impl Fn(&str) -> Option<MigrationDirection>,
+) -> Vec<MigrationSection> {
+    let mut sections: Vec<MigrationSection> = vec![];
+    let mut offset = TextSize::from(0);
+    for line in content.split_inclusive('\n') {
+        let marker = line
+            .trim_start()
+            .strip_prefix("--")
+            .map(|comment| comment.trim_start())
+            .and_then(&direction_of);
+        if let Some(direction) = marker {
+            if let Some(previous) = sections.last_mut() {
+                previous.range = TextRange::new(previous.range.start(), offset);
+            }
+            sections.push(MigrationSection {
+                direction,
+                range: TextRange::new(offset, TextSize::of(content)),
+            });
+        }
+        offset += TextSize::of(line);
+    }
+
+    if sections.is_empty() {
+        return vec![MigrationSection {
+            direction: migration.direction,
+            range: TextRange::up_to(TextSize::of(content)),
+        }];
+    }
+    sections
+}
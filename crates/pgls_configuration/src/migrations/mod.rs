@@ -0,0 +1,167 @@
+mod layouts;
+
+pub use layouts::{
+    DbmateLayout, DefaultLayout, FlywayLayout, GolangMigrateLayout, LiquibaseLayout,
+    MigrationLayout, SQITCH_PLAN_FILE_NAME, SqitchLayout,
+};
+
+use bpaf::Bpaf;
+use pgls_configuration_macros::{Merge, Partial};
+use pgls_text_size::TextRange;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The configuration of the filesystem
+#[derive(Clone, Debug, Deserialize, Eq, Partial, PartialEq, Serialize, Default)]
+#[partial(derive(Bpaf, Clone, Eq, PartialEq, Merge))]
+#[partial(serde(rename_all = "camelCase", default, deny_unknown_fields))]
+#[partial(cfg_attr(feature = "schema", derive(schemars::JsonSchema)))]
+pub struct MigrationsConfiguration {
+    /// The directory where the migration files are stored
+    #[partial(bpaf(long("migrations-dir")))]
+    pub migrations_dir: String,
+
+    /// Ignore any migrations before this timestamp
+    #[partial(bpaf(long("after")))]
+    pub after: u64,
+
+    /// The migration tool, which determines how migration files are named and laid out.
+    /// Defaults to `<number>_<name>.sql` files or `<number>_<name>` directories.
+    #[partial(bpaf(
+        long("migrations-tool"),
+        argument("default|flyway|sqitch|golangMigrate|dbmate|liquibase")
+    ))]
+    pub tool: MigrationTool,
+}
+
+/// The migration tools whose layout of migration files is supported
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Merge, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationTool {
+    /// `<number>_<name>.sql` files, or `<number>_<name>` directories with the files of a
+    /// migration, as used by Supabase, Prisma and Drizzle.
+    #[default]
+    Default,
+    /// `V<version>__<name>.sql` versioned, `U<version>__<name>.sql` undo and `R__<name>.sql`
+    /// repeatable migrations. Versions like `1.1` or `2024_01_01` are compared part by part.
+    Flyway,
+    /// `deploy/`, `revert/` and `verify/` scripts of the changes listed in `sqitch.plan`.
+    Sqitch,
+    /// `<number>_<name>.up.sql` and `<number>_<name>.down.sql` files.
+    GolangMigrate,
+    /// `<number>_<name>.sql` files with `-- migrate:up` and `-- migrate:down` sections.
+    Dbmate,
+    /// Liquibase formatted SQL files, named `<number>_<name>.sql` or `<number>-<name>.sql`,
+    /// with `--changeset` sections.
+    Liquibase,
+}
+
+impl MigrationTool {
+    /// Returns the layout of the migration files of the tool. `sqitch_plan` is the content of
+    /// the `sqitch.plan` that orders the changes of Sqitch, and is ignored by other tools.
+    pub fn layout(&self, sqitch_plan: Option<&str>) -> Arc<dyn MigrationLayout> {
+        match self {
+            Self::Default => Arc::new(DefaultLayout),
+            Self::Flyway => Arc::new(FlywayLayout),
+            Self::Sqitch => Arc::new(SqitchLayout::from_plan(sqitch_plan.unwrap_or_default())),
+            Self::GolangMigrate => Arc::new(GolangMigrateLayout),
+            Self::Dbmate => Arc::new(DbmateLayout),
+            Self::Liquibase => Arc::new(LiquibaseLayout),
+        }
+    }
+}
+
+impl FromStr for MigrationTool {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(Self::Default),
+            "flyway" => Ok(Self::Flyway),
+            "sqitch" => Ok(Self::Sqitch),
+            "golangMigrate" => Ok(Self::GolangMigrate),
+            "dbmate" => Ok(Self::Dbmate),
+            "liquibase" => Ok(Self::Liquibase),
+            _ => Err(
+                "Value not supported for MigrationTool. Use 'default', 'flyway', 'sqitch', 'golangMigrate', 'dbmate' or 'liquibase'.",
+            ),
+        }
+    }
+}
+
+/// A migration, as identified from the path of one of its files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    /// The version that orders the migration. Repeatable migrations, which are applied after
+    /// all other migrations, use [MigrationVersion::max].
+    pub sequence_number: MigrationVersion,
+    /// The name of the migration, without the sequence number and direction. The up and down
+    /// files of a migration share it.
+    pub name: String,
+    pub direction: MigrationDirection,
+    /// Whether the migration is applied again whenever it changes, instead of once
+    pub repeatable: bool,
+}
+
+/// The version of a migration, e.g. `20240101120000`, or `1.2` for a Flyway migration named
+/// `V1.2__<name>.sql`. Versions are compared part by part, and trailing zero parts are
+/// ignored, so `1` and `1.0` are the same version.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MigrationVersion(Vec<u64>);
+
+impl MigrationVersion {
+    /// The version of repeatable migrations, which is after all other versions
+    pub fn max() -> Self {
+        Self(vec![u64::MAX])
+    }
+
+    pub fn new(mut parts: Vec<u64>) -> Self {
+        while parts.len() > 1 && parts.last() == Some(&0) {
+            parts.pop();
+        }
+        Self(parts)
+    }
+}
+
+impl From<u64> for MigrationVersion {
+    fn from(value: u64) -> Self {
+        Self(vec![value])
+    }
+}
+
+impl fmt::Display for MigrationVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(u64::to_string).collect();
+        f.write_str(&parts.join("."))
+    }
+}
+
+/// Whether a migration file, or a section of it, sets up, reverts or verifies its changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MigrationDirection {
+    Up,
+    Down,
+    /// Scripts that check that the up migration was applied
+    Verify,
+    /// Files that aren't split into up and down migrations, e.g. because their down migration
+    /// is optional or bundled as a section
+    Unspecified,
+}
+
+impl MigrationDirection {
+    /// Whether the changes are applied to the database when migrating
+    pub const fn is_applied(&self) -> bool {
+        matches!(self, Self::Up | Self::Unspecified)
+    }
+}
+
+/// A part of a migration file that is applied on its own, e.g. the up or down section of a
+/// dbmate migration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationSection {
+    pub direction: MigrationDirection,
+    pub range: TextRange,
+}
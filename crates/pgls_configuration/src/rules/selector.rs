@@ -34,6 +34,15 @@ pub enum RuleSelector {
     Rule(AnalyzerGroup, &'static str),
 }
 
+impl RuleSelector {
+    /// The group of the selector, which determines the analyzer it applies to
+    pub const fn group(&self) -> AnalyzerGroup {
+        match self {
+            Self::Group(group) | Self::Rule(group, _) => *group,
+        }
+    }
+}
+
 impl From<RuleSelector> for RuleFilter<'static> {
     fn from(value: RuleSelector) -> Self {
         match value {
@@ -17,6 +17,11 @@ pub struct TypecheckConfiguration {
     /// If not specified, defaults to ["public"].
     #[partial(bpaf(long("search_path")))]
     pub search_path: StringSet,
+    /// Executes the DDL statements of a file, such as `CREATE TABLE` or `ALTER TABLE`, one after
+    /// another. They run inside a transaction that is rolled back, so their errors are reported
+    /// before the migration is applied. Disabled by default.
+    #[partial(bpaf(hide))]
+    pub check_ddl: bool,
 }
 
 impl Default for TypecheckConfiguration {
@@ -24,6 +29,7 @@ impl Default for TypecheckConfiguration {
         Self {
             enabled: true,
             search_path: ["public".to_string()].into_iter().collect(),
+            check_ddl: false,
         }
     }
 }
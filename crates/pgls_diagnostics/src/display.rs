@@ -139,22 +139,8 @@ impl<D: Diagnostic + ?Sized> fmt::Display for PrintHeader<'_, D> {
             fmt.write_str(" ")?;
         }
 
-        // Print the database object if present (e.g., "table public.contacts")
-        if let Some(db_obj) = location.database_object {
-            if let Some(obj_type) = db_obj.object_type {
-                fmt.write_markup(markup! {
-                    <Dim>{obj_type}" "</Dim>
-                })?;
-            }
-            if let Some(schema) = db_obj.schema {
-                fmt.write_markup(markup! {
-                    <Emphasis>{schema}"."</Emphasis>
-                })?;
-            }
-            fmt.write_markup(markup! {
-                <Emphasis>{db_obj.name}</Emphasis>" "
-            })?;
-        }
+        // The database object of the location isn't printed, the diagnostics reported on one
+        // already name it in their message and advices
 
         // Print the category of the diagnostic, with a hyperlink if
         // the category has an associated link
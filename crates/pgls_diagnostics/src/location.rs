@@ -16,7 +16,9 @@ pub struct DatabaseObject<'a> {
 }
 
 /// Owned version of DatabaseObject for use in diagnostic structs
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
 pub struct DatabaseObjectOwned {
     /// Optional schema name
     pub schema: Option<String>,
@@ -401,6 +403,16 @@ impl<'a> AsDatabaseObject for DatabaseObject<'a> {
     }
 }
 
+impl DatabaseObject<'_> {
+    pub fn to_owned(self) -> DatabaseObjectOwned {
+        DatabaseObjectOwned {
+            schema: self.schema.map(str::to_owned),
+            name: self.name.to_owned(),
+            object_type: self.object_type.map(str::to_owned),
+        }
+    }
+}
+
 impl AsDatabaseObject for DatabaseObjectOwned {
     fn as_database_object(&self) -> Option<DatabaseObject<'_>> {
         Some(DatabaseObject {
@@ -9,8 +9,9 @@ use serde::{
 };
 
 use crate::{
-    Advices as _, Backtrace, Category, DiagnosticTags, LogCategory, Resource, Severity, SourceCode,
-    Visit, diagnostic::DiagnosticTag, diagnostic::internal::AsDiagnostic,
+    Advices as _, Backtrace, Category, DatabaseObjectOwned, DiagnosticTags, LogCategory, Resource,
+    Severity, SourceCode, Visit, diagnostic::DiagnosticTag, diagnostic::internal::AsDiagnostic,
+    location::AsDatabaseObject,
 };
 
 /// Serializable representation for a [Diagnostic](super::Diagnostic).
@@ -128,6 +129,7 @@ impl super::Diagnostic for Diagnostic {
             .resource(&self.location.path)
             .span(&self.location.span)
             .source_code(&self.location.source_code)
+            .database_object(&self.location.database_object)
             .build()
     }
 
@@ -160,6 +162,8 @@ struct Location {
     path: Option<Resource<String>>,
     span: Option<TextRange>,
     source_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    database_object: Option<DatabaseObjectOwned>,
 }
 
 impl From<super::Location<'_>> for Location {
@@ -170,6 +174,7 @@ impl From<super::Location<'_>> for Location {
             source_code: loc
                 .source_code
                 .map(|source_code| source_code.text.to_string()),
+            database_object: loc.database_object.map(super::DatabaseObject::to_owned),
         }
     }
 }
@@ -295,7 +300,7 @@ impl super::Advices for Advice {
             Advice::Frame(location) => visitor.record_frame(super::Location {
                 resource: location.path.as_ref().map(super::Resource::as_deref),
                 span: location.span,
-                database_object: None,
+                database_object: location.database_object.as_database_object(),
                 source_code: location.source_code.as_deref().map(|text| SourceCode {
                     text,
                     line_starts: None,
@@ -546,4 +551,31 @@ mod tests {
         assert_eq!(db_obj.name, "contacts");
         assert_eq!(db_obj.object_type, Some("table"));
     }
+
+    #[test]
+    fn test_database_object_survives_serialization() {
+        use crate::{DatabaseObjectOwned, Diagnostic};
+
+        #[derive(Debug, Diagnostic)]
+        #[diagnostic(severity = Error, category = "lint")]
+        struct TestDatabaseObjectDiagnostic {
+            #[location(database_object)]
+            db_object: DatabaseObjectOwned,
+        }
+
+        let diag = super::Diagnostic::new(TestDatabaseObjectDiagnostic {
+            db_object: DatabaseObjectOwned {
+                schema: Some("public".to_string()),
+                name: "contacts".to_string(),
+                object_type: Some("table".to_string()),
+            },
+        });
+        let json = serde_json::to_string(&diag).unwrap();
+        let diag: super::Diagnostic = serde_json::from_str(&json).unwrap();
+
+        let db_obj = diag.location().database_object.unwrap();
+        assert_eq!(db_obj.schema, Some("public"));
+        assert_eq!(db_obj.name, "contacts");
+        assert_eq!(db_obj.object_type, Some("table"));
+    }
 }
@@ -125,8 +125,16 @@ define_categories! {
     "stdin",
     "check",
     "format",
+    "baseline",
     "configuration",
     "database/connection",
+    "migrations/apply",
+    "migrations/locks",
+    "migrations/duplicateSequenceNumber",
+    "migrations/outOfOrder",
+    "migrations/usedBeforeCreation",
+    "migrations/invalidName",
+    "migrations/unpairedMigration",
     "internalError/io",
     "internalError/runtime",
     "internalError/fs",
@@ -61,6 +61,9 @@ pub trait FileSystem: Send + Sync + RefUnwindSafe {
     /// Checks if the given path is a symlink
     fn path_is_symlink(&self, path: &Path) -> bool;
 
+    /// Returns the paths of the files and directories directly inside the given directory
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
     /// This method accepts a directory path (`search_dir`) and a list of filenames (`file_names`),
     /// It looks for the files in the specified directory in the order they appear in the list.
     /// If a file is not found in the initial directory, the search may continue into the parent
@@ -169,6 +172,11 @@ pub trait FileSystem: Send + Sync + RefUnwindSafe {
 
     fn get_changed_files(&self, base: &str) -> io::Result<Vec<String>>;
 
+    /// Returns the files that were added since `base`, including files that are staged, only in
+    /// the work tree, or untracked. Unlike [FileSystem::get_changed_files], files that were
+    /// modified or renamed are not included.
+    fn get_added_files(&self, base: &str) -> io::Result<Vec<String>>;
+
     fn get_staged_files(&self) -> io::Result<Vec<String>>;
 
     fn resolve_configuration(
@@ -360,10 +368,18 @@ where
         T::path_is_symlink(self, path)
     }
 
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        T::read_dir(self, path)
+    }
+
     fn get_changed_files(&self, base: &str) -> io::Result<Vec<String>> {
         T::get_changed_files(self, base)
     }
 
+    fn get_added_files(&self, base: &str) -> io::Result<Vec<String>> {
+        T::get_added_files(self, base)
+    }
+
     fn get_staged_files(&self) -> io::Result<Vec<String>> {
         T::get_staged_files(self)
     }
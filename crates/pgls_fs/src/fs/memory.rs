@@ -23,6 +23,9 @@ type OnGetChangedFiles = Option<
     >,
 >;
 
+/// Unlike the other callbacks, it can be called repeatedly, e.g. once per run in watch mode
+type OnGetAddedFiles = Arc<dyn Fn() -> Vec<String> + Send + Sync + RefUnwindSafe + 'static>;
+
 /// Fully in-memory file system, stores the content of all known files in a hashmap
 pub struct MemoryFileSystem {
     files: AssertUnwindSafe<RwLock<FxHashMap<PathBuf, FileEntry>>>,
@@ -30,6 +33,7 @@ pub struct MemoryFileSystem {
     allow_write: bool,
     on_get_staged_files: OnGetChangedFiles,
     on_get_changed_files: OnGetChangedFiles,
+    on_get_added_files: OnGetAddedFiles,
 }
 
 impl Default for MemoryFileSystem {
@@ -44,6 +48,7 @@ impl Default for MemoryFileSystem {
             on_get_changed_files: Some(Arc::new(AssertUnwindSafe(Mutex::new(Some(Box::new(
                 Vec::new,
             )))))),
+            on_get_added_files: Arc::new(Vec::new),
         }
     }
 }
@@ -115,6 +120,13 @@ impl MemoryFileSystem {
         self.on_get_changed_files = Some(Arc::new(AssertUnwindSafe(Mutex::new(Some(cfn)))));
     }
 
+    pub fn set_on_get_added_files(
+        &mut self,
+        cfn: Box<dyn Fn() -> Vec<String> + Send + Sync + RefUnwindSafe + 'static>,
+    ) {
+        self.on_get_added_files = Arc::from(cfn);
+    }
+
     pub fn set_on_get_staged_files(
         &mut self,
         cfn: Box<dyn FnOnce() -> Vec<String> + Send + RefUnwindSafe + 'static>,
@@ -209,6 +221,31 @@ impl FileSystem for MemoryFileSystem {
         false
     }
 
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let files = self.files.0.read();
+
+        // directories only exist implicitly as the parents of files
+        let mut entries: Vec<PathBuf> = files
+            .keys()
+            .filter_map(|file| {
+                let relative = file.strip_prefix(path).ok()?;
+                let child = relative.components().next()?;
+                Some(path.join(child))
+            })
+            .collect();
+        entries.sort();
+        entries.dedup();
+
+        if entries.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("directory {path:?} does not exists in memory filesystem"),
+            ));
+        }
+
+        Ok(entries)
+    }
+
     fn get_changed_files(&self, _base: &str) -> io::Result<Vec<String>> {
         let cb_arc = self.on_get_changed_files.as_ref().unwrap().clone();
 
@@ -219,6 +256,10 @@ impl FileSystem for MemoryFileSystem {
         Ok(cb())
     }
 
+    fn get_added_files(&self, _base: &str) -> io::Result<Vec<String>> {
+        Ok((self.on_get_added_files)())
+    }
+
     fn get_staged_files(&self) -> io::Result<Vec<String>> {
         let cb_arc = self.on_get_staged_files.as_ref().unwrap().clone();
 
@@ -510,6 +551,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn read_dir() {
+        let mut fs = MemoryFileSystem::default();
+
+        fs.insert(PathBuf::from("migrations/0001_users.sql"), "");
+        fs.insert(PathBuf::from("migrations/0002_posts/up.sql"), "");
+        fs.insert(PathBuf::from("migrations/0002_posts/down.sql"), "");
+        fs.insert(PathBuf::from("other.sql"), "");
+
+        assert_eq!(
+            fs.read_dir(Path::new("migrations")).unwrap(),
+            vec![
+                PathBuf::from("migrations/0001_users.sql"),
+                PathBuf::from("migrations/0002_posts"),
+            ]
+        );
+        assert!(fs.path_is_dir(Path::new("migrations/0002_posts")));
+
+        let error = fs.read_dir(Path::new("missing")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn get_added_files_repeatedly() {
+        let mut fs = MemoryFileSystem::default();
+        assert!(fs.get_added_files("main").unwrap().is_empty());
+
+        fs.set_on_get_added_files(Box::new(|| vec!["migrations/0002_posts.sql".to_string()]));
+
+        // watch mode asks for the added files on every run
+        for _ in 0..2 {
+            assert_eq!(
+                fs.get_added_files("main").unwrap(),
+                vec!["migrations/0002_posts.sql".to_string()]
+            );
+        }
+    }
+
     #[test]
     fn traversal() {
         let mut fs = MemoryFileSystem::default();
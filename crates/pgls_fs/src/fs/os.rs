@@ -37,6 +37,29 @@ impl OsFileSystem {
             })),
         }
     }
+
+    /// Runs git in the working directory and returns the lines it printed
+    fn git(&self, args: &[&str]) -> io::Result<Vec<String>> {
+        let mut command = Command::new("git");
+        command.args(args);
+        if let Some(working_directory) = &self.working_directory {
+            command.current_dir(working_directory);
+        }
+
+        let output = command.output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.to_string())
+            .collect())
+    }
 }
 
 impl Default for OsFileSystem {
@@ -90,6 +113,12 @@ impl FileSystem for OsFileSystem {
         path.is_symlink()
     }
 
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect()
+    }
+
     fn get_changed_files(&self, base: &str) -> io::Result<Vec<String>> {
         let output = Command::new("git")
             .arg("diff")
@@ -110,6 +139,38 @@ impl FileSystem for OsFileSystem {
             .collect())
     }
 
+    fn get_added_files(&self, base: &str) -> io::Result<Vec<String>> {
+        // renamed files are reported as added, so that a migration that was renamed to a new
+        // sequence number counts as a new migration
+        let committed = self.git(&[
+            "diff",
+            "--name-only",
+            "--relative",
+            "--no-renames",
+            "--diff-filter=A",
+            &format!("{base}...HEAD"),
+        ])?;
+        // files that were added to the index or the work tree, but aren't committed yet
+        let uncommitted = self.git(&[
+            "diff",
+            "--name-only",
+            "--relative",
+            "--no-renames",
+            "--diff-filter=A",
+            "HEAD",
+        ])?;
+        let untracked = self.git(&["ls-files", "--others", "--exclude-standard"])?;
+
+        let mut files: Vec<String> = committed
+            .into_iter()
+            .chain(uncommitted)
+            .chain(untracked)
+            .collect();
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
     fn get_staged_files(&self) -> io::Result<Vec<String>> {
         let output = Command::new("git")
             .arg("diff")
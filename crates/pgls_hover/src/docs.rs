@@ -0,0 +1,82 @@
+/// An entry of the offline documentation corpus in `pgls_hover_codegen/vendor`.
+#[derive(Debug)]
+pub struct DocEntry {
+    pub name: &'static str,
+    /// `server_version_num` of the release that introduced the item, `None` if it is available in every supported release.
+    pub since: Option<i64>,
+    pub syntax: Option<&'static str>,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocKind {
+    Keyword,
+    Statement,
+    Function,
+    Setting,
+}
+
+pgls_hover_codegen::docs_codegen!();
+
+impl DocKind {
+    fn corpus(self) -> &'static [DocEntry] {
+        match self {
+            DocKind::Keyword => KEYWORDS,
+            DocKind::Statement => STATEMENTS,
+            DocKind::Function => FUNCTIONS,
+            DocKind::Setting => SETTINGS,
+        }
+    }
+
+    pub(crate) fn find(self, name: &str) -> Option<&'static DocEntry> {
+        let name = name.replace('"', "").to_lowercase();
+        let corpus = self.corpus();
+
+        corpus
+            .binary_search_by(|entry| entry.name.cmp(name.as_str()))
+            .ok()
+            .map(|idx| &corpus[idx])
+    }
+}
+
+/// Formats a `server_version_num` as a release name, e.g. `90300` as `9.3` and `140000` as `14`.
+pub(crate) fn format_version_num(version_num: i64) -> String {
+    let major = version_num / 10000;
+
+    if major >= 10 {
+        major.to_string()
+    } else {
+        format!("{major}.{}", version_num / 100 % 100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpora_are_sorted_for_lookup() {
+        for corpus in [KEYWORDS, STATEMENTS, FUNCTIONS, SETTINGS] {
+            assert!(corpus.windows(2).all(|w| w[0].name < w[1].name));
+        }
+    }
+
+    #[test]
+    fn finds_entries_case_insensitively() {
+        assert_eq!(DocKind::Keyword.find("LATERAL").unwrap().since, Some(90300));
+        assert_eq!(
+            DocKind::Function.find("jsonb_path_query").unwrap().since,
+            Some(120000)
+        );
+        assert!(DocKind::Statement.find("CREATE TABLE").is_some());
+        assert!(DocKind::Setting.find("\"lock_timeout\"").is_some());
+        assert!(DocKind::Setting.find("not_a_setting").is_none());
+    }
+
+    #[test]
+    fn formats_version_nums() {
+        assert_eq!(format_version_num(90300), "9.3");
+        assert_eq!(format_version_num(90600), "9.6");
+        assert_eq!(format_version_num(140000), "14");
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt::Write;
+
+use pgls_schema_cache::SchemaCache;
+use pgls_treesitter::{ResolvedColumn, TreesitterContext};
+
+use crate::{contextual_priority::ContextualPriority, to_markdown::ToHoverMarkdown};
+
+impl ToHoverMarkdown for ResolvedColumn {
+    fn hover_headline<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<(), std::fmt::Error> {
+        write!(writer, "`{}.{}`", self.relation, self.name)
+    }
+
+    fn hover_body<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        match self.type_name.as_ref() {
+            Some(tname) => write!(writer, "{tname}")?,
+            None => write!(writer, "unknown type")?,
+        }
+
+        write!(writer, " - derived column")?;
+        writeln!(writer)?;
+
+        Ok(true)
+    }
+
+    fn hover_footer<W: Write>(
+        &self,
+        _writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        Ok(false)
+    }
+}
+
+impl ContextualPriority for ResolvedColumn {
+    fn relevance_score(&self, ctx: &TreesitterContext) -> f32 {
+        // derived columns are only looked up for relations visible at the cursor,
+        // so they beat columns of tables the statement merely mentions.
+        let mut score = 200.0;
+
+        if let Some(relation_or_alias) = ctx.tail_qualifier_sanitized() {
+            let relation = ctx
+                .get_mentioned_table_for_alias(&relation_or_alias)
+                .unwrap_or(&relation_or_alias);
+
+            if relation == self.relation.as_str() {
+                score += 50.0;
+            }
+        }
+
+        score
+    }
+}
@@ -0,0 +1,127 @@
+use std::fmt::Write;
+
+use pgls_schema_cache::SchemaCache;
+use pgls_treesitter::TreesitterContext;
+
+use crate::{
+    contextual_priority::ContextualPriority,
+    docs::{DocEntry, DocKind, format_version_num},
+    to_markdown::ToHoverMarkdown,
+};
+
+/// A documentation entry for a keyword, statement, built-in function or setting.
+#[derive(Debug)]
+pub struct Documentation {
+    pub kind: DocKind,
+    pub entry: &'static DocEntry,
+}
+
+impl ToHoverMarkdown for Documentation {
+    fn hover_headline<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<(), std::fmt::Error> {
+        match self.kind {
+            DocKind::Keyword => write!(writer, "`{}` (keyword)", self.entry.name.to_uppercase()),
+            DocKind::Statement => {
+                write!(writer, "`{}` (statement)", self.entry.name.to_uppercase())
+            }
+            DocKind::Function => write!(writer, "`{}` (built-in function)", self.entry.name),
+            DocKind::Setting => write!(writer, "`{}` (setting)", self.entry.name),
+        }
+    }
+
+    fn hover_body<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        if let Some(syntax) = self.entry.syntax {
+            writeln!(writer, "{syntax}")?;
+            writeln!(writer)?;
+        }
+
+        write!(writer, "{}", self.entry.description)?;
+        writeln!(writer)?;
+
+        Ok(true)
+    }
+
+    fn hover_footer<W: Write>(
+        &self,
+        writer: &mut W,
+        schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        let Some(since) = self.entry.since else {
+            return Ok(false);
+        };
+
+        writeln!(writer)?;
+        write!(
+            writer,
+            "Available since PostgreSQL {}",
+            format_version_num(since)
+        )?;
+
+        if let Some(server_version) = schema_cache
+            .version
+            .version_num
+            .filter(|version_num| *version_num < since)
+        {
+            write!(
+                writer,
+                " - not available on the connected server (PostgreSQL {})",
+                format_version_num(server_version)
+            )?;
+        }
+
+        Ok(true)
+    }
+}
+
+impl ContextualPriority for Documentation {
+    // there is at most one entry per hovered node.
+    fn relevance_score(&self, _ctx: &TreesitterContext) -> f32 {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pgls_schema_cache::SchemaCache;
+
+    use crate::{docs::DocKind, to_markdown::ToHoverMarkdown};
+
+    use super::Documentation;
+
+    fn footer(kind: DocKind, name: &str, version_num: Option<i64>) -> String {
+        let mut schema_cache = SchemaCache::default();
+        schema_cache.version.version_num = version_num;
+
+        let docs = Documentation {
+            kind,
+            entry: kind.find(name).unwrap(),
+        };
+
+        let mut footer = String::new();
+        docs.hover_footer(&mut footer, &schema_cache).unwrap();
+        footer
+    }
+
+    #[test]
+    fn warns_if_server_is_older_than_the_item() {
+        assert_eq!(
+            footer(DocKind::Setting, "idle_session_timeout", Some(130004)),
+            "\nAvailable since PostgreSQL 14 - not available on the connected server (PostgreSQL 13)"
+        );
+        assert_eq!(
+            footer(DocKind::Setting, "idle_session_timeout", Some(160002)),
+            "\nAvailable since PostgreSQL 14"
+        );
+        assert_eq!(
+            footer(DocKind::Keyword, "lateral", None),
+            "\nAvailable since PostgreSQL 9.3"
+        );
+    }
+}
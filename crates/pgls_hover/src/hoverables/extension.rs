@@ -0,0 +1,68 @@
+use std::fmt::Write;
+
+use pgls_schema_cache::{Extension, SchemaCache};
+use pgls_treesitter::TreesitterContext;
+
+use crate::{contextual_priority::ContextualPriority, to_markdown::ToHoverMarkdown};
+
+impl ToHoverMarkdown for Extension {
+    fn hover_headline<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<(), std::fmt::Error> {
+        write!(writer, "`{}`", self.name)?;
+
+        if let Some(schema) = &self.schema {
+            write!(writer, " in `{schema}`")?;
+        }
+
+        Ok(())
+    }
+
+    fn hover_body<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        if let Some(comment) = &self.comment {
+            write!(writer, "Comment: '{comment}'")?;
+            writeln!(writer)?;
+        }
+
+        match &self.installed_version {
+            Some(installed) if *installed != self.default_version => {
+                write!(
+                    writer,
+                    "Installed: {installed} - available: {}",
+                    self.default_version
+                )?;
+            }
+            Some(installed) => write!(writer, "Installed: {installed}")?,
+            None => write!(
+                writer,
+                "Not installed - available: {}",
+                self.default_version
+            )?,
+        }
+
+        writeln!(writer)?;
+
+        Ok(true)
+    }
+
+    fn hover_footer<W: Write>(
+        &self,
+        _writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        Ok(false)
+    }
+}
+
+impl ContextualPriority for Extension {
+    // there are no extensions with duplicate names.
+    fn relevance_score(&self, _ctx: &TreesitterContext) -> f32 {
+        0.0
+    }
+}
@@ -37,6 +37,11 @@ impl ToHoverMarkdown for Function {
         writer: &mut W,
         _schema_cache: &SchemaCache,
     ) -> Result<bool, std::fmt::Error> {
+        if let Some(comment) = &self.comment {
+            write!(writer, "Comment: '{comment}'")?;
+            writeln!(writer)?;
+        }
+
         let kind_text = match self.kind {
             pgls_schema_cache::ProcKind::Function => "Function",
             pgls_schema_cache::ProcKind::Procedure => "Procedure",
@@ -0,0 +1,71 @@
+use std::fmt::Write;
+
+use pgls_schema_cache::{Index, SchemaCache};
+use pgls_treesitter::TreesitterContext;
+
+use crate::{contextual_priority::ContextualPriority, to_markdown::ToHoverMarkdown};
+
+impl ToHoverMarkdown for Index {
+    fn body_markdown_type(&self) -> &'static str {
+        "sql"
+    }
+
+    fn hover_headline<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<(), std::fmt::Error> {
+        write!(writer, "`{}.{}`", self.schema, self.name)?;
+
+        if self.is_primary {
+            write!(writer, " - 🔑 primary key")?;
+        } else if self.is_unique {
+            write!(writer, " - unique")?;
+        }
+
+        Ok(())
+    }
+
+    fn hover_body<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        write!(writer, "{}", self.definition)?;
+        writeln!(writer)?;
+
+        Ok(true)
+    }
+
+    fn hover_footer<W: Write>(
+        &self,
+        _writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        Ok(false)
+    }
+}
+
+impl ContextualPriority for Index {
+    fn relevance_score(&self, ctx: &TreesitterContext) -> f32 {
+        let mut score = 0.0;
+
+        if ctx
+            .get_mentioned_relations(&Some(self.schema.clone()))
+            .is_some_and(|t| t.contains(&self.table_name))
+        {
+            score += 200.0;
+        } else if ctx
+            .get_mentioned_relations(&None)
+            .is_some_and(|t| t.contains(&self.table_name))
+        {
+            score += 150.0;
+        }
+
+        if self.schema == "public" && score == 0.0 {
+            score += 10.0;
+        }
+
+        score
+    }
+}
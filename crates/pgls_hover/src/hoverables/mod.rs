@@ -3,23 +3,39 @@ use pgls_schema_cache::SchemaCache;
 use crate::{contextual_priority::ContextualPriority, to_markdown::ToHoverMarkdown};
 
 mod column;
+mod derived_column;
+mod documentation;
+mod extension;
 mod function;
+mod index;
+mod policy;
 mod postgres_type;
 mod role;
 mod schema;
+mod sequence;
 mod table;
+mod trigger;
 
 mod test_helper;
 
+pub use documentation::Documentation;
+
 /// Mapper type that will be used for filtering and turning data to markdown.
 #[derive(Debug)]
 pub enum Hoverable<'a> {
     Table(&'a pgls_schema_cache::Table),
     Column(&'a pgls_schema_cache::Column),
+    DerivedColumn(pgls_treesitter::ResolvedColumn),
     Function(&'a pgls_schema_cache::Function),
     Role(&'a pgls_schema_cache::Role),
     Schema(&'a pgls_schema_cache::Schema),
     PostgresType(&'a pgls_schema_cache::PostgresType),
+    Policy(&'a pgls_schema_cache::Policy),
+    Trigger(&'a pgls_schema_cache::Trigger),
+    Index(&'a pgls_schema_cache::Index),
+    Sequence(&'a pgls_schema_cache::Sequence),
+    Extension(&'a pgls_schema_cache::Extension),
+    Documentation(Documentation),
 }
 
 impl<'a> From<&'a pgls_schema_cache::Schema> for Hoverable<'a> {
@@ -40,6 +56,12 @@ impl<'a> From<&'a pgls_schema_cache::Column> for Hoverable<'a> {
     }
 }
 
+impl From<pgls_treesitter::ResolvedColumn> for Hoverable<'_> {
+    fn from(value: pgls_treesitter::ResolvedColumn) -> Self {
+        Hoverable::DerivedColumn(value)
+    }
+}
+
 impl<'a> From<&'a pgls_schema_cache::Function> for Hoverable<'a> {
     fn from(value: &'a pgls_schema_cache::Function) -> Self {
         Hoverable::Function(value)
@@ -58,15 +80,58 @@ impl<'a> From<&'a pgls_schema_cache::PostgresType> for Hoverable<'a> {
     }
 }
 
+impl<'a> From<&'a pgls_schema_cache::Policy> for Hoverable<'a> {
+    fn from(value: &'a pgls_schema_cache::Policy) -> Self {
+        Hoverable::Policy(value)
+    }
+}
+
+impl<'a> From<&'a pgls_schema_cache::Trigger> for Hoverable<'a> {
+    fn from(value: &'a pgls_schema_cache::Trigger) -> Self {
+        Hoverable::Trigger(value)
+    }
+}
+
+impl<'a> From<&'a pgls_schema_cache::Index> for Hoverable<'a> {
+    fn from(value: &'a pgls_schema_cache::Index) -> Self {
+        Hoverable::Index(value)
+    }
+}
+
+impl<'a> From<&'a pgls_schema_cache::Sequence> for Hoverable<'a> {
+    fn from(value: &'a pgls_schema_cache::Sequence) -> Self {
+        Hoverable::Sequence(value)
+    }
+}
+
+impl<'a> From<&'a pgls_schema_cache::Extension> for Hoverable<'a> {
+    fn from(value: &'a pgls_schema_cache::Extension) -> Self {
+        Hoverable::Extension(value)
+    }
+}
+
+impl From<Documentation> for Hoverable<'_> {
+    fn from(value: Documentation) -> Self {
+        Hoverable::Documentation(value)
+    }
+}
+
 impl ContextualPriority for Hoverable<'_> {
     fn relevance_score(&self, ctx: &pgls_treesitter::TreesitterContext) -> f32 {
         match self {
             Hoverable::Table(table) => table.relevance_score(ctx),
             Hoverable::Column(column) => column.relevance_score(ctx),
+            Hoverable::DerivedColumn(column) => column.relevance_score(ctx),
             Hoverable::Function(function) => function.relevance_score(ctx),
             Hoverable::Role(role) => role.relevance_score(ctx),
             Hoverable::Schema(schema) => schema.relevance_score(ctx),
             Hoverable::PostgresType(type_) => type_.relevance_score(ctx),
+            Hoverable::Policy(policy) => policy.relevance_score(ctx),
+            Hoverable::Trigger(trigger) => trigger.relevance_score(ctx),
+            Hoverable::Index(index) => index.relevance_score(ctx),
+            Hoverable::Sequence(sequence) => sequence.relevance_score(ctx),
+            Hoverable::Extension(extension) => extension.relevance_score(ctx),
+            Hoverable::Documentation(docs) => docs.relevance_score(ctx),
         }
     }
 }
@@ -84,6 +149,9 @@ impl ToHoverMarkdown for Hoverable<'_> {
             Hoverable::Column(column) => {
                 ToHoverMarkdown::hover_headline(*column, writer, schema_cache)
             }
+            Hoverable::DerivedColumn(column) => {
+                ToHoverMarkdown::hover_headline(column, writer, schema_cache)
+            }
             Hoverable::Function(function) => {
                 ToHoverMarkdown::hover_headline(*function, writer, schema_cache)
             }
@@ -94,6 +162,24 @@ impl ToHoverMarkdown for Hoverable<'_> {
             Hoverable::PostgresType(type_) => {
                 ToHoverMarkdown::hover_headline(*type_, writer, schema_cache)
             }
+            Hoverable::Policy(policy) => {
+                ToHoverMarkdown::hover_headline(*policy, writer, schema_cache)
+            }
+            Hoverable::Trigger(trigger) => {
+                ToHoverMarkdown::hover_headline(*trigger, writer, schema_cache)
+            }
+            Hoverable::Index(index) => {
+                ToHoverMarkdown::hover_headline(*index, writer, schema_cache)
+            }
+            Hoverable::Sequence(sequence) => {
+                ToHoverMarkdown::hover_headline(*sequence, writer, schema_cache)
+            }
+            Hoverable::Extension(extension) => {
+                ToHoverMarkdown::hover_headline(*extension, writer, schema_cache)
+            }
+            Hoverable::Documentation(docs) => {
+                ToHoverMarkdown::hover_headline(docs, writer, schema_cache)
+            }
         }
     }
 
@@ -105,6 +191,9 @@ impl ToHoverMarkdown for Hoverable<'_> {
         match self {
             Hoverable::Table(table) => ToHoverMarkdown::hover_body(*table, writer, schema_cache),
             Hoverable::Column(column) => ToHoverMarkdown::hover_body(*column, writer, schema_cache),
+            Hoverable::DerivedColumn(column) => {
+                ToHoverMarkdown::hover_body(column, writer, schema_cache)
+            }
             Hoverable::Function(function) => {
                 ToHoverMarkdown::hover_body(*function, writer, schema_cache)
             }
@@ -113,6 +202,20 @@ impl ToHoverMarkdown for Hoverable<'_> {
             Hoverable::PostgresType(type_) => {
                 ToHoverMarkdown::hover_body(*type_, writer, schema_cache)
             }
+            Hoverable::Policy(policy) => ToHoverMarkdown::hover_body(*policy, writer, schema_cache),
+            Hoverable::Trigger(trigger) => {
+                ToHoverMarkdown::hover_body(*trigger, writer, schema_cache)
+            }
+            Hoverable::Index(index) => ToHoverMarkdown::hover_body(*index, writer, schema_cache),
+            Hoverable::Sequence(sequence) => {
+                ToHoverMarkdown::hover_body(*sequence, writer, schema_cache)
+            }
+            Hoverable::Extension(extension) => {
+                ToHoverMarkdown::hover_body(*extension, writer, schema_cache)
+            }
+            Hoverable::Documentation(docs) => {
+                ToHoverMarkdown::hover_body(docs, writer, schema_cache)
+            }
         }
     }
 
@@ -126,6 +229,9 @@ impl ToHoverMarkdown for Hoverable<'_> {
             Hoverable::Column(column) => {
                 ToHoverMarkdown::hover_footer(*column, writer, schema_cache)
             }
+            Hoverable::DerivedColumn(column) => {
+                ToHoverMarkdown::hover_footer(column, writer, schema_cache)
+            }
             Hoverable::Function(function) => {
                 ToHoverMarkdown::hover_footer(*function, writer, schema_cache)
             }
@@ -136,6 +242,22 @@ impl ToHoverMarkdown for Hoverable<'_> {
             Hoverable::PostgresType(type_) => {
                 ToHoverMarkdown::hover_footer(*type_, writer, schema_cache)
             }
+            Hoverable::Policy(policy) => {
+                ToHoverMarkdown::hover_footer(*policy, writer, schema_cache)
+            }
+            Hoverable::Trigger(trigger) => {
+                ToHoverMarkdown::hover_footer(*trigger, writer, schema_cache)
+            }
+            Hoverable::Index(index) => ToHoverMarkdown::hover_footer(*index, writer, schema_cache),
+            Hoverable::Sequence(sequence) => {
+                ToHoverMarkdown::hover_footer(*sequence, writer, schema_cache)
+            }
+            Hoverable::Extension(extension) => {
+                ToHoverMarkdown::hover_footer(*extension, writer, schema_cache)
+            }
+            Hoverable::Documentation(docs) => {
+                ToHoverMarkdown::hover_footer(docs, writer, schema_cache)
+            }
         }
     }
 
@@ -143,10 +265,17 @@ impl ToHoverMarkdown for Hoverable<'_> {
         match self {
             Hoverable::Table(table) => table.body_markdown_type(),
             Hoverable::Column(column) => column.body_markdown_type(),
+            Hoverable::DerivedColumn(column) => column.body_markdown_type(),
             Hoverable::Function(function) => function.body_markdown_type(),
             Hoverable::Role(role) => role.body_markdown_type(),
             Hoverable::Schema(schema) => schema.body_markdown_type(),
             Hoverable::PostgresType(type_) => type_.body_markdown_type(),
+            Hoverable::Policy(policy) => policy.body_markdown_type(),
+            Hoverable::Trigger(trigger) => trigger.body_markdown_type(),
+            Hoverable::Index(index) => index.body_markdown_type(),
+            Hoverable::Sequence(sequence) => sequence.body_markdown_type(),
+            Hoverable::Extension(extension) => extension.body_markdown_type(),
+            Hoverable::Documentation(docs) => docs.body_markdown_type(),
         }
     }
 
@@ -154,10 +283,17 @@ impl ToHoverMarkdown for Hoverable<'_> {
         match self {
             Hoverable::Table(table) => table.footer_markdown_type(),
             Hoverable::Column(column) => column.footer_markdown_type(),
+            Hoverable::DerivedColumn(column) => column.footer_markdown_type(),
             Hoverable::Function(function) => function.footer_markdown_type(),
             Hoverable::Role(role) => role.footer_markdown_type(),
             Hoverable::Schema(schema) => schema.footer_markdown_type(),
             Hoverable::PostgresType(type_) => type_.footer_markdown_type(),
+            Hoverable::Policy(policy) => policy.footer_markdown_type(),
+            Hoverable::Trigger(trigger) => trigger.footer_markdown_type(),
+            Hoverable::Index(index) => index.footer_markdown_type(),
+            Hoverable::Sequence(sequence) => sequence.footer_markdown_type(),
+            Hoverable::Extension(extension) => extension.footer_markdown_type(),
+            Hoverable::Documentation(docs) => docs.footer_markdown_type(),
         }
     }
 }
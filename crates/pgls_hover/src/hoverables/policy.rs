@@ -0,0 +1,112 @@
+use std::fmt::Write;
+
+use pgls_schema_cache::{Policy, PolicyCommand, SchemaCache};
+use pgls_treesitter::TreesitterContext;
+
+use crate::{contextual_priority::ContextualPriority, to_markdown::ToHoverMarkdown};
+
+impl ToHoverMarkdown for Policy {
+    fn footer_markdown_type(&self) -> &'static str {
+        "sql"
+    }
+
+    fn hover_headline<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<(), std::fmt::Error> {
+        write!(
+            writer,
+            "`{}` on `{}.{}`",
+            self.name, self.schema_name, self.table_name
+        )
+    }
+
+    fn hover_body<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        let command = match self.command {
+            PolicyCommand::Select => "SELECT",
+            PolicyCommand::Insert => "INSERT",
+            PolicyCommand::Update => "UPDATE",
+            PolicyCommand::Delete => "DELETE",
+            PolicyCommand::All => "ALL",
+        };
+
+        let kind = if self.is_permissive {
+            "Permissive"
+        } else {
+            "Restrictive"
+        };
+
+        write!(writer, "{kind} - for {command}")?;
+        writeln!(writer)?;
+
+        if self.role_names.is_empty() {
+            write!(writer, "Roles: public")?;
+        } else {
+            write!(writer, "Roles: {}", self.role_names.join(", "))?;
+        }
+        writeln!(writer)?;
+
+        Ok(true)
+    }
+
+    fn hover_footer<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        if self.security_qualification.is_none() && self.with_check.is_none() {
+            return Ok(false);
+        }
+
+        if let Some(using) = &self.security_qualification {
+            write!(writer, "using {}", parenthesized(using))?;
+            writeln!(writer)?;
+        }
+
+        if let Some(check) = &self.with_check {
+            write!(writer, "with check {}", parenthesized(check))?;
+            writeln!(writer)?;
+        }
+
+        Ok(true)
+    }
+}
+
+/// Postgres deparses most expressions with parentheses already, e.g. `(owner_id = 1)`, but not `true`.
+fn parenthesized(expr: &str) -> String {
+    if expr.starts_with('(') && expr.ends_with(')') {
+        expr.to_string()
+    } else {
+        format!("({expr})")
+    }
+}
+
+impl ContextualPriority for Policy {
+    fn relevance_score(&self, ctx: &TreesitterContext) -> f32 {
+        let mut score = 0.0;
+
+        // policy names are only unique per table
+        if ctx
+            .get_mentioned_relations(&Some(self.schema_name.clone()))
+            .is_some_and(|t| t.contains(&self.table_name))
+        {
+            score += 200.0;
+        } else if ctx
+            .get_mentioned_relations(&None)
+            .is_some_and(|t| t.contains(&self.table_name))
+        {
+            score += 150.0;
+        }
+
+        if self.schema_name == "public" && score == 0.0 {
+            score += 10.0;
+        }
+
+        score
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt::Write;
+
+use pgls_schema_cache::{SchemaCache, Sequence};
+use pgls_treesitter::TreesitterContext;
+
+use crate::{contextual_priority::ContextualPriority, to_markdown::ToHoverMarkdown};
+
+impl ToHoverMarkdown for Sequence {
+    fn hover_headline<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<(), std::fmt::Error> {
+        write!(
+            writer,
+            "`{}.{}` ({})",
+            self.schema, self.name, self.data_type
+        )
+    }
+
+    fn hover_body<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        write!(
+            writer,
+            "{}..{}, starts at {}, increments by {}",
+            self.min_value, self.max_value, self.start_value, self.increment
+        )?;
+
+        if self.cycle {
+            write!(writer, " - cycles")?;
+        }
+
+        writeln!(writer)?;
+
+        Ok(true)
+    }
+
+    fn hover_footer<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        match (&self.owned_by_table, &self.owned_by_column) {
+            (Some(table), Some(column)) => {
+                writeln!(writer)?;
+                write!(writer, "Owned by: {table}.{column}")?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+impl ContextualPriority for Sequence {
+    fn relevance_score(&self, _ctx: &TreesitterContext) -> f32 {
+        let mut score = 0.0;
+
+        if self.schema == "public" {
+            score += 10.0;
+        }
+
+        score
+    }
+}
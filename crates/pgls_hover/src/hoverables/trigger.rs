@@ -0,0 +1,94 @@
+use std::fmt::Write;
+
+use pgls_schema_cache::{SchemaCache, Trigger, TriggerAffected, TriggerEvent, TriggerTiming};
+use pgls_treesitter::TreesitterContext;
+
+use crate::{contextual_priority::ContextualPriority, to_markdown::ToHoverMarkdown};
+
+impl ToHoverMarkdown for Trigger {
+    fn hover_headline<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<(), std::fmt::Error> {
+        write!(
+            writer,
+            "`{}` on `{}.{}`",
+            self.name, self.table_schema, self.table_name
+        )
+    }
+
+    fn hover_body<W: Write>(
+        &self,
+        writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        let timing = match self.timing {
+            TriggerTiming::Before => "BEFORE",
+            TriggerTiming::After => "AFTER",
+            TriggerTiming::Instead => "INSTEAD OF",
+        };
+
+        let events = self
+            .events
+            .iter()
+            .map(|e| match e {
+                TriggerEvent::Insert => "INSERT",
+                TriggerEvent::Delete => "DELETE",
+                TriggerEvent::Update => "UPDATE",
+                TriggerEvent::Truncate => "TRUNCATE",
+            })
+            .collect::<Vec<_>>()
+            .join(" OR ");
+
+        let affected = match self.affected {
+            TriggerAffected::Row => "ROW",
+            TriggerAffected::Statement => "STATEMENT",
+        };
+
+        write!(writer, "{timing} {events} - for each {affected}")?;
+        writeln!(writer)?;
+
+        write!(
+            writer,
+            "Executes: {}.{}()",
+            self.proc_schema, self.proc_name
+        )?;
+        writeln!(writer)?;
+
+        Ok(true)
+    }
+
+    fn hover_footer<W: Write>(
+        &self,
+        _writer: &mut W,
+        _schema_cache: &SchemaCache,
+    ) -> Result<bool, std::fmt::Error> {
+        Ok(false)
+    }
+}
+
+impl ContextualPriority for Trigger {
+    fn relevance_score(&self, ctx: &TreesitterContext) -> f32 {
+        let mut score = 0.0;
+
+        // trigger names are only unique per table
+        if ctx
+            .get_mentioned_relations(&Some(self.table_schema.clone()))
+            .is_some_and(|t| t.contains(&self.table_name))
+        {
+            score += 200.0;
+        } else if ctx
+            .get_mentioned_relations(&None)
+            .is_some_and(|t| t.contains(&self.table_name))
+        {
+            score += 150.0;
+        }
+
+        if self.table_schema == "public" && score == 0.0 {
+            score += 10.0;
+        }
+
+        score
+    }
+}
@@ -8,11 +8,15 @@ pub(crate) enum HoveredNode {
     Column((Option<String>, Option<String>, String)),
     Role(String),
     PostgresType((Option<String>, String)),
-
-    #[allow(unused)]
-    Trigger((Option<String>, String)),
-    #[allow(dead_code)]
-    Policy((Option<String>, String)),
+    Trigger(String),
+    Policy(String),
+    Index((Option<String>, String)),
+    Sequence((Option<String>, String)),
+    Extension(String),
+    /// The keyword, preceded by the leading keywords of the statement if it is one of them,
+    /// e.g. `create table` when hovering `create` or `table` in `create table ...`.
+    Keyword((Option<String>, String)),
+    Setting(String),
 }
 
 impl HoveredNode {
@@ -26,6 +30,13 @@ impl HoveredNode {
         let under_cursor = &ctx.node_under_cursor;
 
         match under_cursor.kind() {
+            // `create index my_idx on ...`, the grammar parses the name as a column
+            "column_identifier"
+                if ctx.history_ends_with(&["create_index", "column_identifier"]) =>
+            {
+                Some(HoveredNode::Index((None, node_content)))
+            }
+
             "column_identifier" => Some(HoveredNode::Column((
                 ctx.head_qualifier_sanitized(),
                 ctx.tail_qualifier_sanitized(),
@@ -35,10 +46,8 @@ impl HoveredNode {
                 ctx.tail_qualifier_sanitized(),
                 node_content,
             ))),
-            "policy_identifier" => Some(HoveredNode::Policy((
-                ctx.tail_qualifier_sanitized(),
-                node_content,
-            ))),
+            "policy_identifier" => Some(HoveredNode::Policy(node_content)),
+
             "table_identifier" => Some(HoveredNode::Table((
                 ctx.tail_qualifier_sanitized(),
                 node_content,
@@ -122,6 +131,74 @@ impl HoveredNode {
                 )))
             }
 
+            "any_identifier" if ctx.history_ends_with(&["create_policy", "any_identifier"]) => {
+                Some(HoveredNode::Policy(node_content))
+            }
+
+            "any_identifier"
+                if ctx.history_ends_with(&[
+                    "create_trigger",
+                    "object_reference",
+                    "any_identifier",
+                ]) =>
+            {
+                Some(HoveredNode::Trigger(node_content))
+            }
+
+            "any_identifier"
+                if ctx.history_ends_with(&["drop_index", "any_identifier"])
+                    || ctx.history_ends_with(&["alter_index", "any_identifier"]) =>
+            {
+                Some(HoveredNode::Index((None, node_content)))
+            }
+
+            "any_identifier"
+                if ctx.history_ends_with(&["object_reference", "any_identifier"])
+                    && matches!(
+                        ctx.wrapping_clause_type,
+                        Some(WrappingClause::AlterSequence) | Some(WrappingClause::DropSequence)
+                    ) =>
+            {
+                Some(HoveredNode::Sequence((
+                    ctx.tail_qualifier_sanitized(),
+                    node_content,
+                )))
+            }
+
+            "any_identifier"
+                if ctx.history_ends_with(&["create_extension", "any_identifier"])
+                    || ctx.history_ends_with(&["drop_extension", "any_identifier"]) =>
+            {
+                Some(HoveredNode::Extension(node_content))
+            }
+
+            "any_identifier"
+                if ctx.history_ends_with(&["set_statement", "any_identifier"])
+                    || ctx.history_ends_with(&["reset_statement", "any_identifier"])
+                    || (ctx.history_ends_with(&["statement", "any_identifier"])
+                        && is_show_statement(ctx)) =>
+            {
+                Some(HoveredNode::Setting(node_content))
+            }
+
+            // `current_setting('lock_timeout')`
+            "literal" if is_function_argument(ctx, &["current_setting", "set_config"]) => Some(
+                HoveredNode::Setting(node_content.trim_matches('\'').to_string()),
+            ),
+
+            // `nextval('my_seq')`
+            "literal" if is_function_argument(ctx, &["nextval", "currval", "setval"]) => {
+                let name = node_content.trim_matches('\'');
+
+                match name.split_once('.') {
+                    Some((schema, name)) => Some(HoveredNode::Sequence((
+                        Some(schema.replace('"', "")),
+                        name.replace('"', ""),
+                    ))),
+                    None => Some(HoveredNode::Sequence((None, name.replace('"', "")))),
+                }
+            }
+
             // quoted columns
             "literal" if ctx.history_ends_with(&["select_expression", "term", "literal"]) => {
                 Some(HoveredNode::Column((
@@ -131,11 +208,79 @@ impl HoveredNode {
                 )))
             }
 
+            kind if kind.starts_with("keyword_") => Some(HoveredNode::Keyword((
+                leading_statement_keywords(ctx),
+                node_content,
+            ))),
+
             _ => None,
         }
     }
 }
 
+/// Whether the node under the cursor is a literal argument of one of the given functions.
+fn is_function_argument(
+    ctx: &pgls_treesitter::context::TreesitterContext,
+    function_names: &[&str],
+) -> bool {
+    if !ctx.history_ends_with(&["invocation", "term", "literal"]) {
+        return false;
+    }
+
+    ctx.node_under_cursor
+        .parent()
+        .and_then(|term| term.parent())
+        .and_then(|invocation| invocation.child(0))
+        .filter(|c| c.kind() == "function_reference")
+        .and_then(|f| f.named_child(f.named_child_count().checked_sub(1)?))
+        .and_then(|n| n.utf8_text(ctx.text.as_bytes()).ok())
+        .is_some_and(|name| function_names.contains(&name.replace('"', "").as_str()))
+}
+
+/// Keywords that modify a statement without being part of its name, e.g. `or replace`.
+static STATEMENT_MODIFIERS: &[&str] = &[
+    "keyword_or",
+    "keyword_replace",
+    "keyword_unique",
+    "keyword_temp",
+    "keyword_temporary",
+    "keyword_unlogged",
+    "keyword_external",
+    "keyword_constraint",
+];
+
+/// The leading keywords of the statement, if the keyword under the cursor is one of them.
+/// For `create or replace function`, this is `create function`.
+fn leading_statement_keywords(ctx: &pgls_treesitter::context::TreesitterContext) -> Option<String> {
+    let under_cursor = &ctx.node_under_cursor;
+    let statement = under_cursor.parent()?;
+
+    let mut cursor = statement.walk();
+    let mut keywords = vec![];
+    let mut is_leading = false;
+
+    for child in statement
+        .children(&mut cursor)
+        .take_while(|child| child.kind().starts_with("keyword_"))
+    {
+        is_leading |= child.id() == under_cursor.id();
+
+        if !STATEMENT_MODIFIERS.contains(&child.kind()) {
+            keywords.push(child.utf8_text(ctx.text.as_bytes()).ok()?.to_lowercase());
+        }
+    }
+
+    is_leading.then(|| keywords.join(" "))
+}
+
+/// `show lock_timeout` has no dedicated node, it's a `statement` starting with `show`.
+fn is_show_statement(ctx: &pgls_treesitter::context::TreesitterContext) -> bool {
+    ctx.node_under_cursor
+        .parent()
+        .and_then(|statement| statement.child(0))
+        .is_some_and(|first| first.kind() == "keyword_show")
+}
+
 fn looks_like_sql_param(content: &str) -> bool {
     (content.starts_with("$") && !content.starts_with("$$"))
         || (content.starts_with(":") && !content.starts_with("::"))
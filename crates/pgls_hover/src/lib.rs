@@ -1,16 +1,20 @@
 use pgls_schema_cache::SchemaCache;
 use pgls_text_size::TextSize;
-use pgls_treesitter::TreeSitterContextParams;
+use pgls_treesitter::{ResolvedColumn, TreeSitterContextParams, TreesitterContext};
 
 use crate::{
-    contextual_priority::prioritize_by_context, hoverables::Hoverable, hovered_node::HoveredNode,
+    contextual_priority::prioritize_by_context,
+    docs::DocKind,
+    hoverables::{Documentation, Hoverable},
+    hovered_node::HoveredNode,
     to_markdown::format_hover_markdown,
 };
 
 mod contextual_priority;
+mod docs;
 mod hoverables;
 mod hovered_node;
-mod to_markdown;
+pub mod to_markdown;
 
 pub struct OnHoverParams<'a> {
     pub position: TextSize,
@@ -25,13 +29,28 @@ pub struct OnHoverParams<'a> {
     position = params.position.to_string()
 ))]
 pub fn on_hover(params: OnHoverParams) -> Option<Vec<String>> {
-    let ctx = pgls_treesitter::context::TreesitterContext::new(TreeSitterContextParams {
+    let ctx = TreesitterContext::new(TreeSitterContextParams {
         position: params.position,
         text: params.stmt_sql,
         tree: params.ts_tree,
     });
 
     if let Some(hovered_node) = HoveredNode::get(&ctx) {
+        // built-in documentation isn't ranked against the schema cache, it always comes first.
+        let documentation = match &hovered_node {
+            HoveredNode::Function((maybe_schema, function_name))
+                if maybe_schema.as_deref().is_none_or(|s| s == "pg_catalog") =>
+            {
+                find_documentation(DocKind::Function, function_name)
+            }
+            HoveredNode::Keyword((statement, keyword)) => statement
+                .as_deref()
+                .and_then(find_statement_documentation)
+                .or_else(|| find_documentation(DocKind::Keyword, keyword)),
+            HoveredNode::Setting(setting) => find_documentation(DocKind::Setting, setting),
+            _ => None,
+        };
+
         let items: Vec<Hoverable> = match hovered_node {
             HoveredNode::Table(node_identification) => match node_identification {
                 (None, n) => params
@@ -55,6 +74,10 @@ pub fn on_hover(params: OnHoverParams) -> Option<Vec<String>> {
                     .find_cols(&column_name, None, None)
                     .into_iter()
                     .map(Hoverable::from)
+                    .chain(
+                        find_derived_columns(&ctx, params.schema_cache, None, &column_name)
+                            .map(Hoverable::from),
+                    )
                     .collect(),
 
                 (None, Some(table_or_alias), column_name) => {
@@ -69,6 +92,15 @@ pub fn on_hover(params: OnHoverParams) -> Option<Vec<String>> {
                         .find_cols(&column_name, Some(actual_table), None)
                         .into_iter()
                         .map(Hoverable::from)
+                        .chain(
+                            find_derived_columns(
+                                &ctx,
+                                params.schema_cache,
+                                Some(actual_table),
+                                &column_name,
+                            )
+                            .map(Hoverable::from),
+                        )
                         .collect()
                 }
 
@@ -123,11 +155,51 @@ pub fn on_hover(params: OnHoverParams) -> Option<Vec<String>> {
                     .unwrap_or_default(),
             },
 
-            HoveredNode::Policy(_) | HoveredNode::Trigger(_) => return None,
+            HoveredNode::Policy(policy_name) => params
+                .schema_cache
+                .find_policies(&policy_name, None)
+                .into_iter()
+                .map(Hoverable::from)
+                .collect(),
+
+            HoveredNode::Trigger(trigger_name) => params
+                .schema_cache
+                .find_triggers(&trigger_name, None)
+                .into_iter()
+                .map(Hoverable::from)
+                .collect(),
+
+            HoveredNode::Index((maybe_schema, index_name)) => params
+                .schema_cache
+                .find_indexes(&index_name, maybe_schema.as_deref())
+                .into_iter()
+                .map(Hoverable::from)
+                .collect(),
+
+            HoveredNode::Sequence((maybe_schema, sequence_name)) => params
+                .schema_cache
+                .find_sequences(&sequence_name, maybe_schema.as_deref())
+                .into_iter()
+                .map(Hoverable::from)
+                .collect(),
+
+            HoveredNode::Extension(extension_name) => params
+                .schema_cache
+                .find_extension(&extension_name)
+                .map(Hoverable::from)
+                .map(|e| vec![e])
+                .unwrap_or_default(),
+
+            HoveredNode::Keyword(_) | HoveredNode::Setting(_) => vec![],
         };
 
-        let markdown_blocks: Vec<String> = prioritize_by_context(items, &ctx)
+        // the built-in documentation of a function is only shown if the schema cache doesn't know it,
+        // e.g. without a connection or if the connected server predates the function.
+        let documentation = documentation.filter(|_| items.is_empty());
+
+        let markdown_blocks: Vec<String> = documentation
             .into_iter()
+            .chain(prioritize_by_context(items, &ctx))
             .map(|item| format_hover_markdown(&item, params.schema_cache))
             .filter_map(Result::ok)
             .collect();
@@ -138,6 +210,38 @@ pub fn on_hover(params: OnHoverParams) -> Option<Vec<String>> {
     }
 }
 
+fn find_documentation(kind: DocKind, name: &str) -> Option<Hoverable<'static>> {
+    kind.find(name)
+        .map(|entry| Hoverable::from(Documentation { kind, entry }))
+}
+
+/// Finds the documentation of the longest documented prefix of the statement's leading keywords,
+/// e.g. `drop table` for `drop table if exists`.
+fn find_statement_documentation(statement: &str) -> Option<Hoverable<'static>> {
+    let keywords: Vec<&str> = statement.split(' ').collect();
+
+    (1..=keywords.len())
+        .rev()
+        .find_map(|len| find_documentation(DocKind::Statement, &keywords[..len].join(" ")))
+}
+
+/// Output columns of the CTEs, derived tables and set-returning functions visible at the cursor.
+fn find_derived_columns(
+    ctx: &TreesitterContext,
+    schema_cache: &SchemaCache,
+    relation: Option<&str>,
+    column_name: &str,
+) -> impl Iterator<Item = ResolvedColumn> {
+    let column_name = column_name.replace('"', "");
+
+    ctx.visible_derived_relations()
+        .filter(|r| relation.is_none_or(|name| name.replace('"', "") == r.name))
+        .flat_map(|r| ctx.query_scope.resolve_columns(r, schema_cache))
+        .filter(move |c| c.name == column_name)
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +270,31 @@ mod tests {
 
         assert!(hover.is_none());
     }
+
+    #[test]
+    fn shows_builtin_function_docs_without_schema_cache() {
+        let query = "select jsonb_path_qu{}ery('{\"a\": 1}', '$.a');";
+        let position = query.find("{}").unwrap();
+        let sql = query.replace("{}", "");
+
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&pgls_treesitter_grammar::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(&sql, None).unwrap();
+        let schema_cache = SchemaCache::default();
+
+        let hover = on_hover(OnHoverParams {
+            position: TextSize::new(position as u32),
+            schema_cache: &schema_cache,
+            stmt_sql: &sql,
+            ast: None,
+            ts_tree: &tree,
+        })
+        .unwrap();
+
+        assert_eq!(hover.len(), 1);
+        assert!(hover[0].starts_with("### `jsonb_path_query` (built-in function)"));
+        assert!(hover[0].contains("Available since PostgreSQL 12"));
+    }
 }
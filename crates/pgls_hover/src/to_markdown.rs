@@ -2,7 +2,8 @@ use std::fmt::Write;
 
 use pgls_schema_cache::SchemaCache;
 
-pub(crate) trait ToHoverMarkdown {
+/// Renders a schema cache item as the markdown shown on hover.
+pub trait ToHoverMarkdown {
     fn body_markdown_type(&self) -> &'static str {
         "plain"
     }
@@ -30,7 +31,7 @@ pub(crate) trait ToHoverMarkdown {
     ) -> Result<bool, std::fmt::Error>; // returns true if something was written
 }
 
-pub(crate) fn format_hover_markdown<T: ToHoverMarkdown>(
+pub fn format_hover_markdown<T: ToHoverMarkdown>(
     item: &T,
     schema_cache: &SchemaCache,
 ) -> Result<String, std::fmt::Error> {
@@ -261,6 +261,29 @@ async fn test_no_hover_on_keyword(test_db: PgPool) {
     test_hover_at_cursor("no_hover_keyword", query, Some(setup), &test_db).await;
 }
 
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn test_no_hover_on_undocumented_keyword(test_db: PgPool) {
+    let setup = r#"
+        create table users (
+            id serial primary key,
+            email varchar(255) not null
+        );
+    "#;
+
+    let query = format!(
+        "select id fr{}om users",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor(
+        "no_hover_undocumented_keyword",
+        query,
+        Some(setup),
+        &test_db,
+    )
+    .await;
+}
+
 #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
 async fn shortens_lengthy_functions(test_db: PgPool) {
     let setup = r#"
@@ -645,3 +668,189 @@ async fn no_hover_results_over_params(test_db: PgPool) {
         test_hover_at_cursor("questionmark-param", query, None, &test_db).await;
     }
 }
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn hover_on_derived_columns(test_db: PgPool) {
+    let setup = r#"
+        create table users (
+            id serial primary key,
+            email text
+        );
+    "#;
+
+    test_db.execute(setup).await.unwrap();
+
+    {
+        let query = format!(
+            "with recent as (select id, email as mail from users) select ma{}il from recent;",
+            QueryWithCursorPosition::cursor_marker()
+        );
+        test_hover_at_cursor("derived_column_cte", query, None, &test_db).await;
+    }
+    {
+        let query = format!(
+            "select sub.i{}d from (select id, email from users) sub;",
+            QueryWithCursorPosition::cursor_marker()
+        );
+        test_hover_at_cursor("derived_column_subquery", query, None, &test_db).await;
+    }
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn hover_on_policy(test_db: PgPool) {
+    let setup = r#"
+        create table users (
+            id serial primary key,
+            owner_id int
+        );
+
+        create policy owners_only on users
+            as restrictive
+            for update
+            to postgres
+            using (owner_id = 1)
+            with check (owner_id = 1);
+    "#;
+
+    let query = format!(
+        "drop policy owners_o{}nly on users;",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor("policy", query, Some(setup), &test_db).await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn hover_on_trigger(test_db: PgPool) {
+    let setup = r#"
+        create table users (
+            id serial primary key
+        );
+
+        create function audit() returns trigger language plpgsql as $$
+        begin
+            return new;
+        end;
+        $$;
+
+        create trigger audit_users before insert or update on users
+            for each row execute function audit();
+    "#;
+
+    let query = format!(
+        "create or replace trigger audit_u{}sers after insert on users for each row execute function audit();",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor("trigger", query, Some(setup), &test_db).await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn hover_on_index(test_db: PgPool) {
+    let setup = r#"
+        create table users (
+            id serial primary key,
+            email text
+        );
+
+        create unique index users_email_idx on users (lower(email));
+    "#;
+
+    let query = format!(
+        "drop index users_ema{}il_idx;",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor("index", query, Some(setup), &test_db).await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn hover_on_sequence(test_db: PgPool) {
+    let setup = r#"
+        create table orders (
+            id serial primary key
+        );
+    "#;
+
+    test_db.execute(setup).await.unwrap();
+
+    {
+        let query = format!(
+            "select nextval('orders_id{}_seq');",
+            QueryWithCursorPosition::cursor_marker()
+        );
+        test_hover_at_cursor("sequence_in_nextval", query, None, &test_db).await;
+    }
+    {
+        let query = format!(
+            "alter sequence public.orders_id{}_seq restart;",
+            QueryWithCursorPosition::cursor_marker()
+        );
+        test_hover_at_cursor("sequence_in_alter_sequence", query, None, &test_db).await;
+    }
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn hover_on_extension(test_db: PgPool) {
+    let query = format!(
+        "drop extension plp{}gsql;",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor("extension", query, None, &test_db).await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn hover_on_keywords(test_db: PgPool) {
+    let query = format!(
+        "select u.id, p.title from users u cross join lat{}eral (select * from posts where user_id = u.id) p;",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor("keyword_lateral", query, None, &test_db).await;
+
+    let query = format!(
+        "select count(*) fil{}ter (where id > 1) from users;",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor("keyword_filter", query, None, &test_db).await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn hover_on_statement_keywords(test_db: PgPool) {
+    let query = format!(
+        "crea{}te table public.audit_log (id bigint);",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor("statement_create_table", query, None, &test_db).await;
+
+    let query = format!(
+        "create unique in{}dex audit_log_id_idx on public.audit_log (id);",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor("statement_create_index", query, None, &test_db).await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn hover_on_settings(test_db: PgPool) {
+    let query = format!(
+        "set lock_ti{}meout = '5s';",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor("setting_in_set", query, None, &test_db).await;
+
+    let query = format!("show work_m{}em;", QueryWithCursorPosition::cursor_marker());
+
+    test_hover_at_cursor("setting_in_show", query, None, &test_db).await;
+
+    let query = format!(
+        "select current_setting('statement_t{}imeout');",
+        QueryWithCursorPosition::cursor_marker()
+    );
+
+    test_hover_at_cursor("setting_in_current_setting", query, None, &test_db).await;
+}
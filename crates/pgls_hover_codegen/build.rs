@@ -0,0 +1,25 @@
+use std::env;
+use std::path::PathBuf;
+
+static VENDOR_FILES: &[&str] = &[
+    "keywords.md",
+    "statements.md",
+    "functions.md",
+    "settings.md",
+];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?);
+    let vendor_dir = manifest_dir.join("vendor");
+
+    println!(
+        "cargo:rustc-env=PGLS_HOVER_DOCS_DIR={}",
+        vendor_dir.display()
+    );
+
+    for file in VENDOR_FILES {
+        println!("cargo:rerun-if-changed={}", vendor_dir.join(file).display());
+    }
+
+    Ok(())
+}
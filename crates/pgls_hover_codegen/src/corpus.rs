@@ -0,0 +1,92 @@
+use anyhow::{Context, Result, bail};
+use std::path;
+
+pub(crate) struct Entry {
+    pub(crate) name: String,
+    pub(crate) since: Option<i64>,
+    pub(crate) syntax: Option<String>,
+    pub(crate) description: String,
+}
+
+/// Reads one of the markdown files in `vendor/` and returns its entries sorted by name.
+pub(crate) fn read_corpus(file_name: &str) -> Result<Vec<Entry>> {
+    // use the environment variable set by the build script to locate the vendor directory
+    let file = path::PathBuf::from(env!("PGLS_HOVER_DOCS_DIR")).join(file_name);
+    let data = std::fs::read_to_string(&file)
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+
+    let mut entries = parse_corpus(&data).with_context(|| format!("Invalid {file_name}"))?;
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if let Some(w) = entries.windows(2).find(|w| w[0].name == w[1].name) {
+        bail!("Duplicate entry `{}` in {file_name}", w[0].name);
+    }
+
+    Ok(entries)
+}
+
+fn parse_corpus(data: &str) -> Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+
+    for block in data.split("\n## ") {
+        let block = block.trim_start_matches("## ").trim();
+        if block.is_empty() {
+            continue;
+        }
+
+        let mut lines = block.lines();
+        let name = lines
+            .next()
+            .context("Missing entry name")?
+            .trim()
+            .to_string();
+
+        let mut since = None;
+        let mut syntax = None;
+        let mut description = Vec::new();
+
+        for line in lines {
+            if let Some(version) = line.strip_prefix("since:") {
+                since = Some(
+                    parse_version(version.trim())
+                        .with_context(|| format!("Invalid `since` of `{name}`"))?,
+                );
+            } else if let Some(s) = line.strip_prefix("syntax:") {
+                syntax = Some(s.trim().to_string());
+            } else if !line.trim().is_empty() {
+                description.push(line.trim());
+            }
+        }
+
+        if description.is_empty() {
+            bail!("Entry `{name}` has no description");
+        }
+
+        entries.push(Entry {
+            name,
+            since,
+            syntax,
+            description: description.join(" "),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Converts a version such as `9.3` or `14` into the `server_version_num` format, e.g. `90300` or `140000`.
+fn parse_version(version: &str) -> Result<i64> {
+    let mut parts = version.split('.');
+
+    let major: i64 = parts.next().context("Empty version")?.parse()?;
+    let minor: i64 = parts.next().map(str::parse).transpose()?.unwrap_or(0);
+
+    if parts.next().is_some() {
+        bail!("Expected a version like `9.3` or `14`, got `{version}`");
+    }
+
+    Ok(if major >= 10 {
+        major * 10000
+    } else {
+        major * 10000 + minor * 100
+    })
+}
@@ -0,0 +1,55 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::corpus::{Entry, read_corpus};
+
+const CORPORA: &[(&str, &str)] = &[
+    ("KEYWORDS", "keywords.md"),
+    ("STATEMENTS", "statements.md"),
+    ("FUNCTIONS", "functions.md"),
+    ("SETTINGS", "settings.md"),
+];
+
+pub fn docs_mod() -> TokenStream {
+    let statics = CORPORA.iter().map(|(ident, file_name)| {
+        let entries = read_corpus(file_name).unwrap();
+        let ident = format_ident!("{}", ident);
+        let entries = entries.iter().map(entry_tokens);
+
+        quote! {
+            pub static #ident: &[DocEntry] = &[#(#entries),*];
+        }
+    });
+
+    quote! {
+        #(#statics)*
+    }
+}
+
+fn entry_tokens(entry: &Entry) -> TokenStream {
+    let Entry {
+        name,
+        since,
+        syntax,
+        description,
+    } = entry;
+
+    let since = match since {
+        Some(since) => quote! { Some(#since) },
+        None => quote! { None },
+    };
+
+    let syntax = match syntax {
+        Some(syntax) => quote! { Some(#syntax) },
+        None => quote! { None },
+    };
+
+    quote! {
+        DocEntry {
+            name: #name,
+            since: #since,
+            syntax: #syntax,
+            description: #description,
+        }
+    }
+}
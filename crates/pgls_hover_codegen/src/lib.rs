@@ -0,0 +1,9 @@
+mod corpus;
+mod docs;
+
+use docs::docs_mod;
+
+#[proc_macro]
+pub fn docs_codegen(_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    docs_mod().into()
+}
@@ -33,9 +33,9 @@ pub(crate) fn server_capabilities(capabilities: &ClientCapabilities) -> ServerCa
             },
         )),
         completion_provider: Some(CompletionOptions {
-            // currently not supporting the completionItem/resolve request.
-            // The request is used to get more information about a simple CompletionItem.
-            resolve_provider: None,
+            // Documentation of tables and functions is only computed on completionItem/resolve,
+            // which keeps the initial payload small on large schemas.
+            resolve_provider: Some(true),
 
             trigger_characters: Some(vec![
                 ".".to_owned(),
@@ -4,9 +4,14 @@ use crate::{
     session::Session,
 };
 use anyhow::Result;
-use pgls_workspace::{WorkspaceError, features::completions::GetCompletionsParams};
+use pgls_completions::CompletionResolveData;
+use pgls_workspace::{
+    WorkspaceError,
+    features::completions::{GetCompletionsParams, ResolveCompletionParams},
+};
 use tower_lsp::lsp_types::{
-    self, CompletionItem, CompletionItemLabelDetails, InsertTextFormat, TextEdit,
+    self, CompletionItem, CompletionItemLabelDetails, Documentation, InsertTextFormat,
+    MarkupContent, MarkupKind, TextEdit,
 };
 
 #[tracing::instrument(level = "debug", skip(session), err)]
@@ -70,6 +75,10 @@ pub fn get_completions(
                 })
             }),
             kind: Some(to_lsp_types_completion_item_kind(i.kind)),
+            // picked up again by `completionItem/resolve`
+            data: i
+                .resolve_data
+                .and_then(|data| serde_json::to_value(data).ok()),
             ..CompletionItem::default()
         })
         .collect();
@@ -82,6 +91,43 @@ pub fn get_completions(
     ))
 }
 
+/// Fills in the documentation that `get_completions` leaves out to keep the payload small.
+#[tracing::instrument(level = "debug", skip(session), err)]
+pub fn resolve_completion(
+    session: &Session,
+    mut item: CompletionItem,
+) -> Result<CompletionItem, LspError> {
+    let Some(data) = item
+        .data
+        .clone()
+        .and_then(|data| serde_json::from_value::<CompletionResolveData>(data).ok())
+    else {
+        return Ok(item);
+    };
+
+    let result = match session
+        .workspace
+        .resolve_completion(ResolveCompletionParams { data })
+    {
+        Ok(result) => result,
+        Err(WorkspaceError::DatabaseConnectionError(_)) => return Ok(item),
+        Err(e) => return Err(e.into()),
+    };
+
+    if let Some(detail) = result.detail {
+        item.detail = Some(detail);
+    }
+
+    if let Some(documentation) = result.documentation {
+        item.documentation = Some(Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: documentation,
+        }));
+    }
+
+    Ok(item)
+}
+
 fn to_lsp_types_completion_item_kind(
     pg_comp_kind: pgls_completions::CompletionItemKind,
 ) -> lsp_types::CompletionItemKind {
@@ -93,5 +139,14 @@ fn to_lsp_types_completion_item_kind(
         pgls_completions::CompletionItemKind::Policy => lsp_types::CompletionItemKind::CONSTANT,
         pgls_completions::CompletionItemKind::Role => lsp_types::CompletionItemKind::CONSTANT,
         pgls_completions::CompletionItemKind::Keyword => lsp_types::CompletionItemKind::KEYWORD,
+        pgls_completions::CompletionItemKind::Type => lsp_types::CompletionItemKind::TYPE_PARAMETER,
+        pgls_completions::CompletionItemKind::EnumValue => {
+            lsp_types::CompletionItemKind::ENUM_MEMBER
+        }
+        pgls_completions::CompletionItemKind::Sequence => lsp_types::CompletionItemKind::VARIABLE,
+        pgls_completions::CompletionItemKind::JoinCondition => {
+            lsp_types::CompletionItemKind::REFERENCE
+        }
+        pgls_completions::CompletionItemKind::Snippet => lsp_types::CompletionItemKind::SNIPPET,
     }
 }
@@ -8,7 +8,9 @@ use futures::FutureExt;
 use futures::future::ready;
 use pgls_configuration::database::PartialDatabaseConfiguration;
 use pgls_fs::{ConfigName, FileSystem, OsFileSystem};
-use pgls_workspace::workspace::{RegisterProjectFolderParams, UnregisterProjectFolderParams};
+use pgls_workspace::workspace::{
+    FilesChangedParams, RegisterProjectFolderParams, UnregisterProjectFolderParams,
+};
 use pgls_workspace::{DynRef, Workspace, workspace};
 use rustc_hash::FxHashMap;
 use serde_json::json;
@@ -73,11 +75,13 @@ impl LSPServer {
                 Some(base_path) => {
                     let watchers = ConfigName::file_names()
                         .iter()
-                        .map(|config_name| FileSystemWatcher {
+                        // SQL files, so that e.g. added migrations are picked up
+                        .chain(&["**/*.sql"])
+                        .map(|pattern| FileSystemWatcher {
                             glob_pattern: GlobPattern::String(format!(
                                 "{}/{}",
                                 base_path.display(),
-                                config_name
+                                pattern
                             )),
                             kind: Some(WatchKind::all()),
                         })
@@ -143,6 +147,7 @@ impl LanguageServer for LSPServer {
         info!("Attempting to load the configuration",);
 
         futures::join!(self.session.load_workspace_settings(None));
+        self.session.index_usage();
 
         let msg = format!("Server initialized with PID: {}", std::process::id());
         self.session
@@ -158,6 +163,9 @@ impl LanguageServer for LSPServer {
 
     #[tracing::instrument(level = "info", skip_all)]
     async fn shutdown(&self) -> LspResult<()> {
+        if let Err(err) = self.session.workspace.persist_usage() {
+            error!("Failed to persist usage: {err}");
+        }
         Ok(())
     }
 
@@ -166,12 +174,27 @@ impl LanguageServer for LSPServer {
         let extra_config: Option<pgls_configuration::PartialConfiguration> =
             serde_json::from_value(params.settings).ok();
         self.session.load_workspace_settings(extra_config).await;
+        self.session.index_usage();
         self.setup_capabilities().await;
         self.session.update_all_diagnostics().await;
     }
 
     #[tracing::instrument(level = "trace", skip_all)]
     async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        let changed_paths = params
+            .changes
+            .iter()
+            .filter_map(|change| change.uri.to_file_path().ok())
+            .collect();
+        if let Err(err) = self.session.workspace.files_changed(FilesChangedParams {
+            paths: changed_paths,
+        }) {
+            error!(
+                "Failed to notify the workspace about changed files: {}",
+                err
+            );
+        }
+
         let file_paths = params
             .changes
             .iter()
@@ -286,6 +309,14 @@ impl LanguageServer for LSPServer {
         }
     }
 
+    #[tracing::instrument(level = "trace", skip_all)]
+    async fn completion_resolve(&self, item: CompletionItem) -> LspResult<CompletionItem> {
+        match handlers::completions::resolve_completion(&self.session, item) {
+            Ok(result) => LspResult::Ok(result),
+            Err(e) => LspResult::Err(into_lsp_error(e)),
+        }
+    }
+
     #[tracing::instrument(level = "trace", skip(self))]
     async fn code_action(&self, params: CodeActionParams) -> LspResult<Option<CodeActionResponse>> {
         match handlers::code_actions::get_actions(&self.session, params) {
@@ -490,12 +521,41 @@ impl ServerFactory {
         workspace_method!(builder, get_file_content);
         workspace_method!(builder, open_file);
         workspace_method!(builder, change_file);
+        workspace_method!(builder, files_changed);
         workspace_method!(builder, close_file);
         workspace_method!(builder, pull_file_diagnostics);
+        workspace_method!(builder, pull_db_diagnostics);
         workspace_method!(builder, get_completions);
+        workspace_method!(builder, resolve_completion);
         workspace_method!(builder, register_project_folder);
         workspace_method!(builder, unregister_project_folder);
         workspace_method!(builder, invalidate_schema_cache);
+        workspace_method!(builder, index_usage);
+
+        // `schema_cache_generation` takes no parameters
+        builder = builder.custom_method(
+            "pgls/schema_cache_generation",
+            |server: &LSPServer, (): ()| {
+                ready(
+                    server
+                        .session
+                        .workspace
+                        .schema_cache_generation()
+                        .map_err(into_lsp_error),
+                )
+            },
+        );
+
+        // `persist_usage` takes no parameters
+        builder = builder.custom_method("pgls/persist_usage", |server: &LSPServer, (): ()| {
+            ready(
+                server
+                    .session
+                    .workspace
+                    .persist_usage()
+                    .map_err(into_lsp_error),
+            )
+        });
 
         let (service, socket) = builder.finish();
         ServerConnection { socket, service }
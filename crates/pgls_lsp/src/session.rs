@@ -14,7 +14,9 @@ use pgls_workspace::PartialConfigurationExt;
 use pgls_workspace::Workspace;
 use pgls_workspace::configuration::{LoadedConfiguration, load_configuration};
 use pgls_workspace::features;
-use pgls_workspace::workspace::{RegisterProjectFolderParams, UpdateSettingsParams};
+use pgls_workspace::workspace::{
+    IndexUsageParams, RegisterProjectFolderParams, UpdateSettingsParams,
+};
 use pgls_workspace::{DynRef, WorkspaceError};
 use rustc_hash::FxHashMap;
 use serde_json::Value;
@@ -384,6 +386,20 @@ impl Session {
             .and_then(|c| c.workspace_folders.as_ref())
     }
 
+    /// Indexes the usage of database objects in the workspace's SQL files in the background
+    pub(crate) fn index_usage(&self) {
+        let Some(path) = self.fs.working_directory() else {
+            return;
+        };
+
+        let workspace = Arc::clone(&self.workspace);
+        tokio::task::spawn_blocking(move || {
+            if let Err(err) = workspace.index_usage(IndexUsageParams { path }) {
+                error!("Failed to index usage: {}", err);
+            }
+        });
+    }
+
     /// Returns the base path of the workspace on the filesystem if it has one
     pub(crate) fn base_path(&self) -> Option<PathBuf> {
         let initialize_params = self.initialize_params.get()?;
@@ -33,8 +33,10 @@ use tower_lsp::lsp_types as lsp;
 use tower_lsp::lsp_types::CodeActionContext;
 use tower_lsp::lsp_types::CodeActionParams;
 use tower_lsp::lsp_types::CodeActionResponse;
+use tower_lsp::lsp_types::CompletionItem;
 use tower_lsp::lsp_types::CompletionParams;
 use tower_lsp::lsp_types::CompletionResponse;
+use tower_lsp::lsp_types::Documentation;
 use tower_lsp::lsp_types::ExecuteCommandParams;
 use tower_lsp::lsp_types::PartialResultParams;
 use tower_lsp::lsp_types::Position;
@@ -323,6 +325,15 @@ impl Server {
         .await
     }
 
+    async fn resolve_completion(&mut self, item: CompletionItem) -> Result<Option<CompletionItem>> {
+        self.request::<CompletionItem, CompletionItem>(
+            "completionItem/resolve",
+            "_resolve_completion",
+            item,
+        )
+        .await
+    }
+
     /// Basic implementation of the `pgls/shutdown` request for tests
     async fn pgls_shutdown(&mut self) -> Result<()> {
         self.request::<_, ()>("pgls/shutdown", "_pgls_shutdown", ())
@@ -616,6 +627,117 @@ async fn test_completions(test_db: PgPool) -> Result<()> {
     Ok(())
 }
 
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn test_completion_resolve(test_db: PgPool) -> Result<()> {
+    let factory = ServerFactory::default();
+    let mut fs = MemoryFileSystem::default();
+
+    let setup = r#"
+            create table public.users (
+                id serial primary key,
+                name varchar(255) not null
+            );
+        "#;
+
+    test_db
+        .execute(setup)
+        .await
+        .expect("Failed to setup test database");
+
+    let mut conf = PartialConfiguration::init();
+    conf.merge_with(PartialConfiguration {
+        db: Some(PartialDatabaseConfiguration {
+            database: Some(
+                test_db
+                    .connect_options()
+                    .get_database()
+                    .unwrap()
+                    .to_string(),
+            ),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    fs.insert(
+        url!("postgres-language-server.jsonc")
+            .to_file_path()
+            .unwrap(),
+        serde_json::to_string_pretty(&conf).unwrap(),
+    );
+
+    let (service, client) = factory
+        .create_with_fs(None, DynRef::Owned(Box::new(fs)))
+        .into_inner();
+
+    let (stream, sink) = client.split();
+    let mut server = Server::new(service);
+
+    let (sender, _) = channel(CHANNEL_BUFFER_SIZE);
+    let reader = tokio::spawn(client_handler(stream, sink, sender));
+
+    server.initialize().await?;
+    server.initialized().await?;
+
+    server.load_configuration().await?;
+
+    server.open_document("select * from us;\n").await?;
+
+    let res = server
+        .get_completion(CompletionParams {
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+            context: None,
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: url!("document.sql"),
+                },
+                position: Position {
+                    line: 0,
+                    character: 16,
+                },
+            },
+        })
+        .await?;
+
+    let list = match res.context("expected a completion response")? {
+        CompletionResponse::List(l) => l,
+        other => bail!("expected CompletionResponse::List, got {other:?}"),
+    };
+
+    let users = list
+        .items
+        .into_iter()
+        .find(|item| item.label == "users")
+        .context("expected the users table to be completed")?;
+
+    assert!(
+        users.documentation.is_none(),
+        "documentation should only be sent on resolve"
+    );
+    assert!(users.data.is_some(), "expected resolve data");
+
+    let resolved = server
+        .resolve_completion(users)
+        .await?
+        .context("expected a resolved completion item")?;
+
+    let Some(Documentation::MarkupContent(documentation)) = resolved.documentation else {
+        bail!("expected markdown documentation");
+    };
+
+    assert!(documentation.value.contains("`public.users`"));
+    assert!(
+        documentation
+            .value
+            .contains("- name: varchar(255) - not null")
+    );
+
+    server.shutdown().await?;
+    reader.abort();
+
+    Ok(())
+}
+
 #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
 async fn test_completions_is_incomplete_when_truncated(test_db: PgPool) -> Result<()> {
     let factory = ServerFactory::default();
@@ -1160,6 +1282,8 @@ async fn test_invalidate_schema_cache(test_db: PgPool) -> Result<()> {
         db: Some(PartialDatabaseConfiguration {
             database: Some(database),
             host: Some(host),
+            // the test changes the schema, so a cache persisted by a previous run would be stale
+            disable_schema_cache_persistence: Some(true),
             ..Default::default()
         }),
         ..Default::default()
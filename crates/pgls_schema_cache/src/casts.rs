@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "db")]
+use sqlx::PgPool;
+
+#[cfg(feature = "db")]
+use crate::schema_cache::SchemaCacheItem;
+
+/// `CastContext` describes in which situations Postgres applies a cast on its own.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum CastContext {
+    /// The cast is only applied when written out, e.g. `x::int4`.
+    #[default]
+    Explicit,
+
+    /// The cast is applied implicitly when assigning to a target column.
+    Assignment,
+
+    /// The cast is applied implicitly in any expression.
+    Implicit,
+}
+
+impl From<char> for CastContext {
+    fn from(value: char) -> Self {
+        match value {
+            'e' => Self::Explicit,
+            'a' => Self::Assignment,
+            'i' => Self::Implicit,
+            _ => panic!("Invalid cast context"),
+        }
+    }
+}
+
+impl From<i8> for CastContext {
+    fn from(value: i8) -> Self {
+        char::from(u8::try_from(value).unwrap()).into()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum CastMethod {
+    /// The cast calls the function referenced by `function_id`.
+    #[default]
+    Function,
+
+    /// The cast goes through the input/output functions of both types.
+    InOut,
+
+    /// The types are binary-coercible, no conversion is needed.
+    Binary,
+}
+
+impl From<char> for CastMethod {
+    fn from(value: char) -> Self {
+        match value {
+            'f' => Self::Function,
+            'i' => Self::InOut,
+            'b' => Self::Binary,
+            _ => panic!("Invalid cast method"),
+        }
+    }
+}
+
+impl From<i8> for CastMethod {
+    fn from(value: i8) -> Self {
+        char::from(u8::try_from(value).unwrap()).into()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Cast {
+    /// The Id (`oid`).
+    pub id: i64,
+
+    /// Refers to the source type's ID in the `pg_type` table.
+    pub source_type_id: i64,
+
+    /// Refers to the target type's ID in the `pg_type` table.
+    pub target_type_id: i64,
+
+    /// The ID of the function performing the cast. `None` if `method` is not `Function`.
+    pub function_id: Option<i64>,
+
+    pub context: CastContext,
+
+    pub method: CastMethod,
+}
+
+#[cfg(feature = "db")]
+impl SchemaCacheItem for Cast {
+    type Item = Cast;
+
+    async fn load(pool: &PgPool) -> Result<Vec<Cast>, sqlx::Error> {
+        sqlx::query_file_as!(Cast, "src/queries/casts.sql")
+            .fetch_all(pool)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "db"))]
+mod tests {
+    use sqlx::PgPool;
+
+    use crate::{CastContext, CastMethod, SchemaCache};
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn loads_casts(test_db: PgPool) {
+        let cache = SchemaCache::load(&test_db)
+            .await
+            .expect("Failed to load Schema Cache");
+
+        let int4 = cache.find_type("int4", Some("pg_catalog")).unwrap();
+        let int8 = cache.find_type("int8", Some("pg_catalog")).unwrap();
+
+        let widening = cache.find_cast(int4.id, int8.id).expect("int4 -> int8");
+        assert_eq!(widening.context, CastContext::Implicit);
+        assert_eq!(widening.method, CastMethod::Function);
+        assert!(widening.function_id.is_some());
+
+        let narrowing = cache.find_cast(int8.id, int4.id).expect("int8 -> int4");
+        assert_eq!(narrowing.context, CastContext::Assignment);
+    }
+}
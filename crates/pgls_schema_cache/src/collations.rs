@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "db")]
+use sqlx::PgPool;
+
+#[cfg(feature = "db")]
+use crate::schema_cache::SchemaCacheItem;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum CollationProvider {
+    /// Uses the database's default collation.
+    #[default]
+    Default,
+    Libc,
+    Icu,
+    /// The builtin provider, available since Postgres 17.
+    Builtin,
+}
+
+impl From<char> for CollationProvider {
+    fn from(value: char) -> Self {
+        match value {
+            'd' => Self::Default,
+            'c' => Self::Libc,
+            'i' => Self::Icu,
+            'b' => Self::Builtin,
+            _ => panic!("Invalid collation provider"),
+        }
+    }
+}
+
+impl From<i8> for CollationProvider {
+    fn from(value: i8) -> Self {
+        char::from(u8::try_from(value).unwrap()).into()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Collation {
+    /// The Id (`oid`).
+    pub id: i64,
+
+    /// The name of the schema the collation belongs to.
+    pub schema: String,
+
+    pub name: String,
+
+    pub provider: CollationProvider,
+
+    /// Non-deterministic collations may consider strings with different bytes equal.
+    pub is_deterministic: bool,
+
+    /// The encoding the collation is usable with. `None` if it works with any encoding.
+    pub encoding: Option<String>,
+
+    /// `LC_COLLATE` for this collation. Not set for ICU collations on newer servers.
+    pub collate: Option<String>,
+
+    /// `LC_CTYPE` for this collation. Not set for ICU collations on newer servers.
+    pub ctype: Option<String>,
+
+    pub comment: Option<String>,
+}
+
+#[cfg(feature = "db")]
+impl SchemaCacheItem for Collation {
+    type Item = Collation;
+
+    async fn load(pool: &PgPool) -> Result<Vec<Collation>, sqlx::Error> {
+        sqlx::query_file_as!(Collation, "src/queries/collations.sql")
+            .fetch_all(pool)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "db"))]
+mod tests {
+    use sqlx::PgPool;
+
+    use crate::{CollationProvider, SchemaCache};
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn loads_collations(test_db: PgPool) {
+        let cache = SchemaCache::load(&test_db)
+            .await
+            .expect("Failed to load Schema Cache");
+
+        let default = cache
+            .find_collation("default", Some("pg_catalog"))
+            .expect("default collation not found");
+        assert_eq!(default.provider, CollationProvider::Default);
+        assert!(default.is_deterministic);
+
+        let c = cache
+            .find_collation("\"C\"", None)
+            .expect("C collation not found");
+        assert_eq!(c.provider, CollationProvider::Libc);
+        assert_eq!(c.collate.as_deref(), Some("C"));
+    }
+}
@@ -0,0 +1,1290 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    Column, Extension, Function, Index, Policy, PolicyCommand, PostgresType, ProcKind,
+    ReplicaIdentity, Role, SchemaCache, Table, TableKind,
+};
+
+/// The kinds of objects that are compared by [SchemaDiff]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffObjectKind {
+    Extension,
+    Role,
+    Type,
+    Table,
+    Column,
+    Function,
+    Index,
+    Policy,
+}
+
+impl DiffObjectKind {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Extension => "extension",
+            Self::Role => "role",
+            Self::Type => "type",
+            Self::Table => "table",
+            Self::Column => "column",
+            Self::Function => "function",
+            Self::Index => "index",
+            Self::Policy => "policy",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A difference of a single object between two schemas
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaChange {
+    pub object_kind: DiffObjectKind,
+    /// The qualified name of the object, e.g. `public.users.email` for a column
+    pub name: String,
+    pub kind: ChangeKind,
+    /// The properties that differ, for changed objects, e.g. `type: integer -> bigint`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+    /// The statements that apply the change. Changes that can't be expressed with the
+    /// information of the schema cache, e.g. the definition of a view, are written as comments.
+    pub ddl: Vec<String>,
+}
+
+/// The differences between two schemas, e.g. a database before and after a set of migrations.
+///
+/// Objects of the system schemas are ignored. Columns, indexes and policies of tables that are
+/// added or removed as a whole aren't listed on their own.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SchemaDiff {
+    pub changes: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    /// Compares the schema `from` with the schema `to`
+    pub fn new(from: &SchemaCache, to: &SchemaCache) -> Self {
+        let mut differ = Differ {
+            from,
+            to,
+            changes: vec![],
+        };
+        differ.extensions();
+        differ.roles();
+        differ.types();
+        differ.tables();
+        differ.functions();
+        differ.indexes();
+        differ.policies();
+
+        Self {
+            changes: differ.changes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the statements that turn the schema `from` into the schema `to`.
+    ///
+    /// Objects are created in the order they depend on each other, e.g. types before the
+    /// tables that use them, and removed in the reverse order.
+    pub fn ddl(&self) -> Vec<&str> {
+        let mut created: Vec<&SchemaChange> = self
+            .changes
+            .iter()
+            .filter(|change| change.kind != ChangeKind::Removed)
+            .collect();
+        created.sort_by_key(|change| change.object_kind);
+
+        let mut removed: Vec<&SchemaChange> = self
+            .changes
+            .iter()
+            .filter(|change| change.kind == ChangeKind::Removed)
+            .collect();
+        removed.sort_by_key(|change| std::cmp::Reverse(change.object_kind));
+
+        removed
+            .into_iter()
+            .chain(created)
+            .flat_map(|change| change.ddl.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+struct Differ<'a> {
+    from: &'a SchemaCache,
+    to: &'a SchemaCache,
+    changes: Vec<SchemaChange>,
+}
+
+impl<'a> Differ<'a> {
+    fn push(
+        &mut self,
+        object_kind: DiffObjectKind,
+        name: String,
+        kind: ChangeKind,
+        details: Vec<String>,
+        ddl: Vec<String>,
+    ) {
+        self.changes.push(SchemaChange {
+            object_kind,
+            name,
+            kind,
+            details,
+            ddl,
+        });
+    }
+
+    fn extensions(&mut self) {
+        let installed = |cache: &'a SchemaCache| {
+            by_key(
+                cache
+                    .extensions
+                    .iter()
+                    .filter(|extension| extension.installed_version.is_some()),
+                |extension: &Extension| extension.name.clone(),
+            )
+        };
+
+        for (name, diff) in compare(installed(self.from), installed(self.to)) {
+            let ident = quote_ident(&name);
+            match diff {
+                Compared::Added(extension) => {
+                    let schema = extension
+                        .schema
+                        .as_deref()
+                        .map(|schema| format!(" with schema {}", quote_ident(schema)))
+                        .unwrap_or_default();
+                    self.push(
+                        DiffObjectKind::Extension,
+                        name,
+                        ChangeKind::Added,
+                        vec![],
+                        vec![format!("create extension if not exists {ident}{schema};")],
+                    );
+                }
+                Compared::Removed(_) => self.push(
+                    DiffObjectKind::Extension,
+                    name,
+                    ChangeKind::Removed,
+                    vec![],
+                    vec![format!("drop extension {ident};")],
+                ),
+                Compared::Both(from, to) => {
+                    if from.installed_version == to.installed_version {
+                        continue;
+                    }
+                    let from_version = from.installed_version.clone().unwrap_or_default();
+                    let to_version = to.installed_version.clone().unwrap_or_default();
+                    self.push(
+                        DiffObjectKind::Extension,
+                        name,
+                        ChangeKind::Changed,
+                        vec![format!("version: {from_version} -> {to_version}")],
+                        vec![format!(
+                            "alter extension {ident} update to {};",
+                            quote_literal(&to_version)
+                        )],
+                    );
+                }
+            }
+        }
+    }
+
+    fn roles(&mut self) {
+        // `pg_*` roles are predefined by Postgres
+        let roles = |cache: &'a SchemaCache| {
+            by_key(
+                cache
+                    .roles
+                    .iter()
+                    .filter(|role| !role.name.starts_with("pg_")),
+                |role: &Role| role.name.clone(),
+            )
+        };
+
+        for (name, diff) in compare(roles(self.from), roles(self.to)) {
+            let ident = quote_ident(&name);
+            match diff {
+                Compared::Added(role) => self.push(
+                    DiffObjectKind::Role,
+                    name,
+                    ChangeKind::Added,
+                    vec![],
+                    vec![format!("create role {ident} with {};", role_options(role))],
+                ),
+                Compared::Removed(_) => self.push(
+                    DiffObjectKind::Role,
+                    name,
+                    ChangeKind::Removed,
+                    vec![],
+                    vec![format!("drop role {ident};")],
+                ),
+                Compared::Both(from, to) => {
+                    let details: Vec<String> = role_attributes(from)
+                        .into_iter()
+                        .zip(role_attributes(to))
+                        .filter(|((_, from), (_, to))| from != to)
+                        .map(|((attribute, from), (_, to))| format!("{attribute}: {from} -> {to}"))
+                        .collect();
+                    if details.is_empty() {
+                        continue;
+                    }
+                    self.push(
+                        DiffObjectKind::Role,
+                        name,
+                        ChangeKind::Changed,
+                        details,
+                        vec![format!("alter role {ident} with {};", role_options(to))],
+                    );
+                }
+            }
+        }
+    }
+
+    fn types(&mut self) {
+        // array types are created along with their element type
+        let types = |cache: &'a SchemaCache| {
+            by_key(
+                cache
+                    .types
+                    .iter()
+                    .filter(|ty| !is_system_schema(&ty.schema) && !ty.format.ends_with("[]")),
+                |ty: &PostgresType| qualified(&ty.schema, &ty.name),
+            )
+        };
+
+        for (name, diff) in compare(types(self.from), types(self.to)) {
+            let ident = qualified_ident(&name);
+            match diff {
+                Compared::Added(ty) => {
+                    let ddl = if !ty.enums.values.is_empty() {
+                        let values: Vec<String> =
+                            ty.enums.values.iter().map(|v| quote_literal(v)).collect();
+                        format!("create type {ident} as enum ({});", values.join(", "))
+                    } else if !ty.attributes.attrs.is_empty() {
+                        let attributes: Vec<String> = ty
+                            .attributes
+                            .attrs
+                            .iter()
+                            .map(|attr| {
+                                format!(
+                                    "{} {}",
+                                    quote_ident(&attr.name),
+                                    type_name(self.to, attr.type_id)
+                                )
+                            })
+                            .collect();
+                        format!("create type {ident} as ({});", attributes.join(", "))
+                    } else {
+                        format!("-- the definition of the type {ident} is not known")
+                    };
+                    self.push(
+                        DiffObjectKind::Type,
+                        name,
+                        ChangeKind::Added,
+                        vec![],
+                        vec![ddl],
+                    );
+                }
+                Compared::Removed(_) => self.push(
+                    DiffObjectKind::Type,
+                    name,
+                    ChangeKind::Removed,
+                    vec![],
+                    vec![format!("drop type {ident};")],
+                ),
+                Compared::Both(from, to) => {
+                    let mut details = vec![];
+                    let mut ddl = vec![];
+
+                    for (index, value) in to.enums.values.iter().enumerate() {
+                        if from.enums.values.contains(value) {
+                            continue;
+                        }
+                        details.push(format!("added value {}", quote_literal(value)));
+                        let position = match index.checked_sub(1) {
+                            Some(previous) => {
+                                format!(" after {}", quote_literal(&to.enums.values[previous]))
+                            }
+                            None => String::new(),
+                        };
+                        ddl.push(format!(
+                            "alter type {ident} add value {}{position};",
+                            quote_literal(value)
+                        ));
+                    }
+                    for value in &from.enums.values {
+                        if !to.enums.values.contains(value) {
+                            details.push(format!("removed value {}", quote_literal(value)));
+                            ddl.push(format!(
+                                "-- the value {} can't be removed from the enum {ident}",
+                                quote_literal(value)
+                            ));
+                        }
+                    }
+
+                    let from_attributes = by_key(from.attributes.attrs.iter(), |attr| {
+                        (attr.name.clone(), type_name(self.from, attr.type_id))
+                    });
+                    let to_attributes = by_key(to.attributes.attrs.iter(), |attr| {
+                        (attr.name.clone(), type_name(self.to, attr.type_id))
+                    });
+                    for ((attribute, ty), diff) in compare(from_attributes, to_attributes) {
+                        let attribute_ident = quote_ident(&attribute);
+                        match diff {
+                            Compared::Added(_) => {
+                                details.push(format!("added attribute {attribute} {ty}"));
+                                ddl.push(format!(
+                                    "alter type {ident} add attribute {attribute_ident} {ty};"
+                                ));
+                            }
+                            Compared::Removed(_) => {
+                                details.push(format!("removed attribute {attribute} {ty}"));
+                                ddl.push(format!(
+                                    "alter type {ident} drop attribute {attribute_ident};"
+                                ));
+                            }
+                            Compared::Both(_, _) => {}
+                        }
+                    }
+
+                    if details.is_empty() {
+                        continue;
+                    }
+                    self.push(
+                        DiffObjectKind::Type,
+                        name,
+                        ChangeKind::Changed,
+                        details,
+                        ddl,
+                    );
+                }
+            }
+        }
+    }
+
+    fn tables(&mut self) {
+        let tables = |cache: &'a SchemaCache| {
+            by_key(
+                cache
+                    .tables
+                    .iter()
+                    .filter(|table| !is_system_schema(&table.schema)),
+                |table: &Table| qualified(&table.schema, &table.name),
+            )
+        };
+
+        for (name, diff) in compare(tables(self.from), tables(self.to)) {
+            let ident = qualified_ident(&name);
+            match diff {
+                Compared::Added(table) => {
+                    let ddl = match table.table_kind {
+                        TableKind::Ordinary => {
+                            let columns: Vec<String> = table_columns(self.to, table)
+                                .map(|column| format!("  {}", column_definition(self.to, column)))
+                                .collect();
+                            let mut ddl = vec![format!(
+                                "create table {ident} (\n{}\n);",
+                                columns.join(",\n")
+                            )];
+                            ddl.extend(row_security_ddl(&ident, None, table));
+                            ddl
+                        }
+                        _ => vec![format!(
+                            "-- the definition of the {} {ident} is not known",
+                            table_kind_name(&table.table_kind)
+                        )],
+                    };
+                    self.push(DiffObjectKind::Table, name, ChangeKind::Added, vec![], ddl);
+                }
+                Compared::Removed(table) => {
+                    let kind = table_kind_name(&table.table_kind);
+                    self.push(
+                        DiffObjectKind::Table,
+                        name,
+                        ChangeKind::Removed,
+                        vec![],
+                        vec![format!("drop {kind} {ident};")],
+                    );
+                }
+                Compared::Both(from, to) => {
+                    let mut details = vec![];
+                    if from.table_kind != to.table_kind {
+                        details.push(format!(
+                            "kind: {} -> {}",
+                            table_kind_name(&from.table_kind),
+                            table_kind_name(&to.table_kind)
+                        ));
+                    }
+                    if from.rls_enabled != to.rls_enabled {
+                        details.push(format!(
+                            "row level security: {} -> {}",
+                            from.rls_enabled, to.rls_enabled
+                        ));
+                    }
+                    if from.rls_forced != to.rls_forced {
+                        details.push(format!(
+                            "forced row level security: {} -> {}",
+                            from.rls_forced, to.rls_forced
+                        ));
+                    }
+                    if from.replica_identity != to.replica_identity {
+                        details.push(format!(
+                            "replica identity: {} -> {}",
+                            replica_identity_name(&from.replica_identity),
+                            replica_identity_name(&to.replica_identity)
+                        ));
+                    }
+
+                    if !details.is_empty() {
+                        let mut ddl = row_security_ddl(&ident, Some(from), to);
+                        if from.table_kind != to.table_kind {
+                            ddl.push(format!(
+                                "-- the {} {ident} has to be recreated as a {}",
+                                table_kind_name(&from.table_kind),
+                                table_kind_name(&to.table_kind)
+                            ));
+                        }
+                        if from.replica_identity != to.replica_identity {
+                            ddl.push(match to.replica_identity {
+                                ReplicaIdentity::Index => format!(
+                                    "-- the replica identity of {ident} uses an index that is not known"
+                                ),
+                                _ => format!(
+                                    "alter table {ident} replica identity {};",
+                                    replica_identity_name(&to.replica_identity)
+                                ),
+                            });
+                        }
+                        self.push(
+                            DiffObjectKind::Table,
+                            name.clone(),
+                            ChangeKind::Changed,
+                            details,
+                            ddl,
+                        );
+                    }
+
+                    if is_table(&from.table_kind) && is_table(&to.table_kind) {
+                        self.columns(&name, from, to);
+                    } else {
+                        self.view_columns(&name, from, to);
+                    }
+                }
+            }
+        }
+    }
+
+    fn columns(&mut self, table_name: &str, from_table: &Table, to_table: &Table) {
+        let table_ident = qualified_ident(table_name);
+        let from_columns = by_key(table_columns(self.from, from_table), |column: &Column| {
+            column.name.clone()
+        });
+        let to_columns = by_key(table_columns(self.to, to_table), |column: &Column| {
+            column.name.clone()
+        });
+
+        for (column_name, diff) in compare(from_columns, to_columns) {
+            let name = format!("{table_name}.{column_name}");
+            let ident = quote_ident(&column_name);
+            match diff {
+                Compared::Added(column) => self.push(
+                    DiffObjectKind::Column,
+                    name,
+                    ChangeKind::Added,
+                    vec![],
+                    vec![format!(
+                        "alter table {table_ident} add column {};",
+                        column_definition(self.to, column)
+                    )],
+                ),
+                Compared::Removed(_) => self.push(
+                    DiffObjectKind::Column,
+                    name,
+                    ChangeKind::Removed,
+                    vec![],
+                    vec![format!("alter table {table_ident} drop column {ident};")],
+                ),
+                Compared::Both(from, to) => {
+                    let mut details = vec![];
+                    let mut ddl = vec![];
+
+                    let from_type = column_type(self.from, from);
+                    let to_type = column_type(self.to, to);
+                    if from_type != to_type {
+                        details.push(format!("type: {from_type} -> {to_type}"));
+                        ddl.push(format!(
+                            "alter table {table_ident} alter column {ident} type {to_type};"
+                        ));
+                    }
+                    if from.is_nullable != to.is_nullable {
+                        details.push(format!(
+                            "nullable: {} -> {}",
+                            from.is_nullable, to.is_nullable
+                        ));
+                        let action = if to.is_nullable { "drop" } else { "set" };
+                        ddl.push(format!(
+                            "alter table {table_ident} alter column {ident} {action} not null;"
+                        ));
+                    }
+                    if from.default_expr != to.default_expr {
+                        details.push(format!(
+                            "default: {} -> {}",
+                            from.default_expr.as_deref().unwrap_or("none"),
+                            to.default_expr.as_deref().unwrap_or("none")
+                        ));
+                        ddl.push(match &to.default_expr {
+                            Some(default) => format!(
+                                "alter table {table_ident} alter column {ident} set default {default};"
+                            ),
+                            None => format!(
+                                "alter table {table_ident} alter column {ident} drop default;"
+                            ),
+                        });
+                    }
+
+                    if details.is_empty() {
+                        continue;
+                    }
+                    self.push(
+                        DiffObjectKind::Column,
+                        name,
+                        ChangeKind::Changed,
+                        details,
+                        ddl,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Views are reported as changed when their columns change, since their definition isn't
+    /// known
+    fn view_columns(&mut self, view_name: &str, from_view: &Table, to_view: &Table) {
+        let columns = |cache: &SchemaCache, view: &Table| {
+            table_columns(cache, view)
+                .map(|column| format!("{} {}", column.name, column_type(cache, column)))
+                .collect::<Vec<_>>()
+        };
+        let from_columns = columns(self.from, from_view);
+        let to_columns = columns(self.to, to_view);
+        if from_columns == to_columns {
+            return;
+        }
+
+        let kind = table_kind_name(&to_view.table_kind);
+        self.push(
+            DiffObjectKind::Table,
+            view_name.to_string(),
+            ChangeKind::Changed,
+            vec![format!(
+                "columns: {} -> {}",
+                from_columns.join(", "),
+                to_columns.join(", ")
+            )],
+            vec![format!(
+                "-- the definition of the {kind} {} is not known",
+                qualified_ident(view_name)
+            )],
+        );
+    }
+
+    fn functions(&mut self) {
+        let functions = |cache: &'a SchemaCache| {
+            by_key(
+                cache
+                    .functions
+                    .iter()
+                    .filter(|function| !is_system_schema(&function.schema)),
+                |function: &Function| {
+                    format!(
+                        "{}({})",
+                        qualified(&function.schema, &function.name),
+                        function.identity_argument_types.as_deref().unwrap_or("")
+                    )
+                },
+            )
+        };
+
+        for (name, diff) in compare(functions(self.from), functions(self.to)) {
+            match diff {
+                Compared::Added(function) => self.push(
+                    DiffObjectKind::Function,
+                    name,
+                    ChangeKind::Added,
+                    vec![],
+                    vec![function_definition(function)],
+                ),
+                Compared::Removed(function) => self.push(
+                    DiffObjectKind::Function,
+                    name,
+                    ChangeKind::Removed,
+                    vec![],
+                    vec![drop_function(function)],
+                ),
+                Compared::Both(from, to) => {
+                    if from.definition == to.definition {
+                        continue;
+                    }
+                    let mut details = vec![];
+                    if from.return_type != to.return_type {
+                        details.push(format!(
+                            "return type: {} -> {}",
+                            from.return_type.as_deref().unwrap_or("none"),
+                            to.return_type.as_deref().unwrap_or("none")
+                        ));
+                    }
+                    if from.body != to.body {
+                        details.push("body changed".to_string());
+                    }
+                    if details.is_empty() {
+                        details.push("definition changed".to_string());
+                    }
+
+                    // `create or replace` can't change the return type
+                    let mut ddl = vec![];
+                    if from.return_type != to.return_type {
+                        ddl.push(drop_function(from));
+                    }
+                    ddl.push(function_definition(to));
+
+                    self.push(
+                        DiffObjectKind::Function,
+                        name,
+                        ChangeKind::Changed,
+                        details,
+                        ddl,
+                    );
+                }
+            }
+        }
+    }
+
+    fn indexes(&mut self) {
+        let indexes = |cache: &'a SchemaCache| {
+            by_key(
+                cache
+                    .indexes
+                    .iter()
+                    .filter(|index| !is_system_schema(&index.schema)),
+                |index: &Index| qualified(&index.schema, &index.name),
+            )
+        };
+
+        for (name, diff) in compare(indexes(self.from), indexes(self.to)) {
+            match diff {
+                Compared::Added(index) => self.push(
+                    DiffObjectKind::Index,
+                    name,
+                    ChangeKind::Added,
+                    vec![],
+                    create_index(index),
+                ),
+                Compared::Removed(index) => {
+                    if !has_table(self.to, &index.schema, &index.table_name) {
+                        continue;
+                    }
+                    self.push(
+                        DiffObjectKind::Index,
+                        name,
+                        ChangeKind::Removed,
+                        vec![],
+                        vec![drop_index(index)],
+                    );
+                }
+                Compared::Both(from, to) => {
+                    if from.definition == to.definition && from.is_primary == to.is_primary {
+                        continue;
+                    }
+                    let mut ddl = vec![drop_index(from)];
+                    ddl.extend(create_index(to));
+                    self.push(
+                        DiffObjectKind::Index,
+                        name,
+                        ChangeKind::Changed,
+                        vec![format!(
+                            "definition: {} -> {}",
+                            from.definition, to.definition
+                        )],
+                        ddl,
+                    );
+                }
+            }
+        }
+    }
+
+    fn policies(&mut self) {
+        let policies = |cache: &'a SchemaCache| {
+            by_key(
+                cache
+                    .policies
+                    .iter()
+                    .filter(|policy| !is_system_schema(&policy.schema_name)),
+                |policy: &Policy| {
+                    format!(
+                        "{}.{}",
+                        qualified(&policy.schema_name, &policy.table_name),
+                        policy.name
+                    )
+                },
+            )
+        };
+
+        for (name, diff) in compare(policies(self.from), policies(self.to)) {
+            match diff {
+                Compared::Added(policy) => self.push(
+                    DiffObjectKind::Policy,
+                    name,
+                    ChangeKind::Added,
+                    vec![],
+                    vec![create_policy(policy)],
+                ),
+                Compared::Removed(policy) => {
+                    if !has_table(self.to, &policy.schema_name, &policy.table_name) {
+                        continue;
+                    }
+                    self.push(
+                        DiffObjectKind::Policy,
+                        name,
+                        ChangeKind::Removed,
+                        vec![],
+                        vec![drop_policy(policy)],
+                    );
+                }
+                Compared::Both(from, to) => {
+                    let mut details = vec![];
+                    if from.is_permissive != to.is_permissive {
+                        details.push(format!(
+                            "permissive: {} -> {}",
+                            from.is_permissive, to.is_permissive
+                        ));
+                    }
+                    if from.command != to.command {
+                        details.push(format!(
+                            "command: {} -> {}",
+                            policy_command(&from.command),
+                            policy_command(&to.command)
+                        ));
+                    }
+                    if from.role_names != to.role_names {
+                        details.push(format!(
+                            "roles: {} -> {}",
+                            from.role_names.join(", "),
+                            to.role_names.join(", ")
+                        ));
+                    }
+                    if from.security_qualification != to.security_qualification {
+                        details.push(format!(
+                            "using: {} -> {}",
+                            from.security_qualification.as_deref().unwrap_or("none"),
+                            to.security_qualification.as_deref().unwrap_or("none")
+                        ));
+                    }
+                    if from.with_check != to.with_check {
+                        details.push(format!(
+                            "with check: {} -> {}",
+                            from.with_check.as_deref().unwrap_or("none"),
+                            to.with_check.as_deref().unwrap_or("none")
+                        ));
+                    }
+                    if details.is_empty() {
+                        continue;
+                    }
+                    self.push(
+                        DiffObjectKind::Policy,
+                        name,
+                        ChangeKind::Changed,
+                        details,
+                        vec![drop_policy(from), create_policy(to)],
+                    );
+                }
+            }
+        }
+    }
+}
+
+enum Compared<'a, T> {
+    Added(&'a T),
+    Removed(&'a T),
+    Both(&'a T, &'a T),
+}
+
+fn by_key<'a, T, K: Ord>(
+    items: impl Iterator<Item = &'a T>,
+    key: impl Fn(&T) -> K,
+) -> BTreeMap<K, &'a T> {
+    items.map(|item| (key(item), item)).collect()
+}
+
+/// Pairs the objects of both schemas by their key, in the order of the keys
+fn compare<'a, K: Ord + Clone, T>(
+    from: BTreeMap<K, &'a T>,
+    to: BTreeMap<K, &'a T>,
+) -> Vec<(K, Compared<'a, T>)> {
+    let mut compared: BTreeMap<K, Compared<'a, T>> = BTreeMap::new();
+    for (key, item) in &from {
+        let diff = match to.get(key) {
+            Some(to) => Compared::Both(*item, *to),
+            None => Compared::Removed(*item),
+        };
+        compared.insert(key.clone(), diff);
+    }
+    for (key, item) in to {
+        if !from.contains_key(&key) {
+            compared.insert(key, Compared::Added(item));
+        }
+    }
+    compared.into_iter().collect()
+}
+
+fn is_system_schema(schema: &str) -> bool {
+    schema.starts_with("pg_") || schema == "information_schema"
+}
+
+fn has_table(cache: &SchemaCache, schema: &str, name: &str) -> bool {
+    cache
+        .tables
+        .iter()
+        .any(|table| table.schema == schema && table.name == name)
+}
+
+fn table_columns<'a>(
+    cache: &'a SchemaCache,
+    table: &'a Table,
+) -> impl Iterator<Item = &'a Column> + 'a {
+    cache
+        .columns
+        .iter()
+        .filter(move |column| column.schema_name == table.schema && column.table_name == table.name)
+}
+
+fn qualified(schema: &str, name: &str) -> String {
+    format!("{schema}.{name}")
+}
+
+/// Quotes both parts of a `schema.name` key
+fn qualified_ident(name: &str) -> String {
+    match name.split_once('.') {
+        Some((schema, name)) => format!("{}.{}", quote_ident(schema), quote_ident(name)),
+        None => quote_ident(name),
+    }
+}
+
+/// Reserved keywords that are likely to be used as names
+const RESERVED_KEYWORDS: &[&str] = &[
+    "all",
+    "analyse",
+    "analyze",
+    "and",
+    "any",
+    "array",
+    "as",
+    "asc",
+    "both",
+    "case",
+    "cast",
+    "check",
+    "collate",
+    "column",
+    "constraint",
+    "create",
+    "default",
+    "desc",
+    "distinct",
+    "do",
+    "else",
+    "end",
+    "except",
+    "false",
+    "fetch",
+    "for",
+    "foreign",
+    "from",
+    "grant",
+    "group",
+    "having",
+    "in",
+    "intersect",
+    "into",
+    "leading",
+    "limit",
+    "not",
+    "null",
+    "offset",
+    "on",
+    "only",
+    "or",
+    "order",
+    "primary",
+    "references",
+    "select",
+    "table",
+    "then",
+    "to",
+    "true",
+    "union",
+    "unique",
+    "user",
+    "using",
+    "when",
+    "where",
+    "window",
+    "with",
+];
+
+fn quote_ident(ident: &str) -> String {
+    let is_plain = ident
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !RESERVED_KEYWORDS.contains(&ident);
+
+    if is_plain {
+        ident.to_string()
+    } else {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn type_name(cache: &SchemaCache, type_id: i64) -> String {
+    cache
+        .find_type_by_id(type_id)
+        .map(|ty| ty.format.clone())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn column_type(cache: &SchemaCache, column: &Column) -> String {
+    let name = cache
+        .find_type_by_id(column.type_id)
+        .map(|ty| ty.format.clone())
+        .or_else(|| column.type_name.clone())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    match column.varchar_length {
+        Some(length) => format!("{name}({length})"),
+        None => name,
+    }
+}
+
+fn column_definition(cache: &SchemaCache, column: &Column) -> String {
+    let mut definition = format!(
+        "{} {}",
+        quote_ident(&column.name),
+        column_type(cache, column)
+    );
+    if let Some(default) = &column.default_expr {
+        definition.push_str(&format!(" default {default}"));
+    }
+    if !column.is_nullable {
+        definition.push_str(" not null");
+    }
+    definition
+}
+
+fn is_table(kind: &TableKind) -> bool {
+    matches!(kind, TableKind::Ordinary | TableKind::Partitioned)
+}
+
+fn table_kind_name(kind: &TableKind) -> &'static str {
+    match kind {
+        TableKind::Ordinary | TableKind::Partitioned => "table",
+        TableKind::View => "view",
+        TableKind::MaterializedView => "materialized view",
+    }
+}
+
+fn replica_identity_name(identity: &ReplicaIdentity) -> &'static str {
+    match identity {
+        ReplicaIdentity::Default => "default",
+        ReplicaIdentity::Index => "index",
+        ReplicaIdentity::Full => "full",
+        ReplicaIdentity::Nothing => "nothing",
+    }
+}
+
+/// The statements that change the row level security of a table from `from`, or the defaults
+/// of a new table, to `to`
+fn row_security_ddl(ident: &str, from: Option<&Table>, to: &Table) -> Vec<String> {
+    let (rls_enabled, rls_forced) =
+        from.map_or((false, false), |from| (from.rls_enabled, from.rls_forced));
+
+    let mut ddl = vec![];
+    if rls_enabled != to.rls_enabled {
+        let action = if to.rls_enabled { "enable" } else { "disable" };
+        ddl.push(format!("alter table {ident} {action} row level security;"));
+    }
+    if rls_forced != to.rls_forced {
+        let action = if to.rls_forced { "force" } else { "no force" };
+        ddl.push(format!("alter table {ident} {action} row level security;"));
+    }
+    ddl
+}
+
+fn role_attributes(role: &Role) -> [(&'static str, bool); 5] {
+    [
+        ("superuser", role.is_super_user),
+        ("createdb", role.can_create_db),
+        ("createrole", role.can_create_roles),
+        ("login", role.can_login),
+        ("bypassrls", role.can_bypass_rls),
+    ]
+}
+
+fn role_options(role: &Role) -> String {
+    role_attributes(role)
+        .into_iter()
+        .map(|(attribute, enabled)| {
+            if enabled {
+                attribute.to_string()
+            } else {
+                format!("no{attribute}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn function_definition(function: &Function) -> String {
+    match &function.definition {
+        Some(definition) => format!("{};", definition.trim_end().trim_end_matches(';')),
+        None => format!(
+            "-- the definition of the function {}.{} is not known",
+            quote_ident(&function.schema),
+            quote_ident(&function.name)
+        ),
+    }
+}
+
+fn drop_function(function: &Function) -> String {
+    let kind = match function.kind {
+        ProcKind::Procedure => "procedure",
+        ProcKind::Aggregate => "aggregate",
+        _ => "function",
+    };
+    format!(
+        "drop {kind} {}.{}({});",
+        quote_ident(&function.schema),
+        quote_ident(&function.name),
+        function.identity_argument_types.as_deref().unwrap_or("")
+    )
+}
+
+fn create_index(index: &Index) -> Vec<String> {
+    let mut ddl = vec![format!("{};", index.definition)];
+    if index.is_primary {
+        ddl.push(format!(
+            "alter table {}.{} add constraint {} primary key using index {};",
+            quote_ident(&index.schema),
+            quote_ident(&index.table_name),
+            quote_ident(&index.name),
+            quote_ident(&index.name)
+        ));
+    }
+    ddl
+}
+
+fn drop_index(index: &Index) -> String {
+    // the index of a primary key belongs to its constraint, which is named after it by default
+    if index.is_primary {
+        format!(
+            "alter table {}.{} drop constraint {};",
+            quote_ident(&index.schema),
+            quote_ident(&index.table_name),
+            quote_ident(&index.name)
+        )
+    } else {
+        format!(
+            "drop index {}.{};",
+            quote_ident(&index.schema),
+            quote_ident(&index.name)
+        )
+    }
+}
+
+fn policy_command(command: &PolicyCommand) -> &'static str {
+    match command {
+        PolicyCommand::Select => "select",
+        PolicyCommand::Insert => "insert",
+        PolicyCommand::Update => "update",
+        PolicyCommand::Delete => "delete",
+        PolicyCommand::All => "all",
+    }
+}
+
+fn create_policy(policy: &Policy) -> String {
+    let mut ddl = format!(
+        "create policy {} on {}.{} as {} for {}",
+        quote_ident(&policy.name),
+        quote_ident(&policy.schema_name),
+        quote_ident(&policy.table_name),
+        if policy.is_permissive {
+            "permissive"
+        } else {
+            "restrictive"
+        },
+        policy_command(&policy.command)
+    );
+    if !policy.role_names.is_empty() {
+        let roles: Vec<String> = policy
+            .role_names
+            .iter()
+            .map(|role| quote_ident(role))
+            .collect();
+        ddl.push_str(&format!(" to {}", roles.join(", ")));
+    }
+    if let Some(using) = &policy.security_qualification {
+        ddl.push_str(&format!(" using ({using})"));
+    }
+    if let Some(with_check) = &policy.with_check {
+        ddl.push_str(&format!(" with check ({with_check})"));
+    }
+    ddl.push(';');
+    ddl
+}
+
+fn drop_policy(policy: &Policy) -> String {
+    format!(
+        "drop policy {} on {}.{};",
+        quote_ident(&policy.name),
+        quote_ident(&policy.schema_name),
+        quote_ident(&policy.table_name)
+    )
+}
+
+#[cfg(all(test, feature = "db"))]
+mod tests {
+    use sqlx::{Executor, PgPool};
+
+    use crate::{ChangeKind, DiffObjectKind, SchemaCache, SchemaDiff};
+
+    const SETUP: &str = r#"
+        create type public.mood as enum ('happy', 'sad');
+
+        create table public.accounts (
+            id bigint primary key,
+            name text not null default 'anonymous',
+            mood public.mood
+        );
+        alter table public.accounts enable row level security;
+
+        create index accounts_name_idx on public.accounts (name);
+
+        create policy accounts_select on public.accounts for select to public using (true);
+
+        create function public.add_one(i int) returns int language sql as $$ select i + 1 $$;
+    "#;
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn reports_added_and_changed_objects(test_db: PgPool) {
+        test_db.execute(SETUP).await.unwrap();
+        let before = SchemaCache::load(&test_db).await.unwrap();
+
+        test_db
+            .execute(
+                r#"
+                alter type public.mood add value 'excited';
+                alter table public.accounts alter column name drop not null;
+                alter table public.accounts add column email text;
+                alter table public.accounts drop column mood;
+                drop index public.accounts_name_idx;
+                "#,
+            )
+            .await
+            .unwrap();
+        let after = SchemaCache::load(&test_db).await.unwrap();
+
+        let changes: Vec<_> = SchemaDiff::new(&before, &after)
+            .changes
+            .into_iter()
+            .map(|change| (change.object_kind, change.name, change.kind, change.details))
+            .collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    DiffObjectKind::Type,
+                    "public.mood".to_string(),
+                    ChangeKind::Changed,
+                    vec!["added value 'excited'".to_string()]
+                ),
+                (
+                    DiffObjectKind::Column,
+                    "public.accounts.email".to_string(),
+                    ChangeKind::Added,
+                    vec![]
+                ),
+                (
+                    DiffObjectKind::Column,
+                    "public.accounts.mood".to_string(),
+                    ChangeKind::Removed,
+                    vec![]
+                ),
+                (
+                    DiffObjectKind::Column,
+                    "public.accounts.name".to_string(),
+                    ChangeKind::Changed,
+                    vec!["nullable: false -> true".to_string()]
+                ),
+                (
+                    DiffObjectKind::Index,
+                    "public.accounts_name_idx".to_string(),
+                    ChangeKind::Removed,
+                    vec![]
+                ),
+            ]
+        );
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn ddl_turns_one_schema_into_the_other(test_db: PgPool) {
+        let empty = SchemaCache::load(&test_db).await.unwrap();
+        test_db.execute(SETUP).await.unwrap();
+        let populated = SchemaCache::load(&test_db).await.unwrap();
+
+        // removes everything that was created
+        let removal = SchemaDiff::new(&populated, &empty);
+        test_db
+            .execute(removal.ddl().join("\n").as_str())
+            .await
+            .unwrap();
+        let removed = SchemaCache::load(&test_db).await.unwrap();
+        assert!(
+            SchemaDiff::new(&empty, &removed).is_empty(),
+            "{:#?}",
+            SchemaDiff::new(&empty, &removed)
+        );
+
+        // and creates it again
+        let creation = SchemaDiff::new(&empty, &populated);
+        assert!(creation.ddl().iter().all(|ddl| !ddl.starts_with("--")));
+        test_db
+            .execute(creation.ddl().join("\n").as_str())
+            .await
+            .unwrap();
+        let created = SchemaCache::load(&test_db).await.unwrap();
+        assert!(
+            SchemaDiff::new(&populated, &created).is_empty(),
+            "{:#?}",
+            SchemaDiff::new(&populated, &created)
+        );
+    }
+}
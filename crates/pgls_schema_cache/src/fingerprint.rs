@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "db")]
+use sqlx::PgPool;
+
+/// A cheap summary of the database catalog.
+///
+/// If two fingerprints are equal, the schema most likely did not change in between,
+/// so a previously loaded `SchemaCache` can be reused.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaFingerprint {
+    /// The highest transaction ID that inserted or updated a relevant catalog row.
+    pub catalog_xmin: i64,
+
+    /// The number of rows in `pg_class`.
+    pub class_count: i64,
+
+    /// The number of rows in `pg_proc`.
+    pub proc_count: i64,
+
+    /// The number of rows in `pg_policy`.
+    pub policy_count: i64,
+
+    /// The number of rows in `pg_trigger`.
+    pub trigger_count: i64,
+
+    /// The number of rows in `pg_description`.
+    pub description_count: i64,
+
+    /// The number of rows in `pg_constraint`.
+    pub constraint_count: i64,
+
+    /// The number of rows in `pg_extension`.
+    pub extension_count: i64,
+
+    /// The number of rows in `pg_index`.
+    pub index_count: i64,
+
+    /// The number of rows in `pg_operator`.
+    pub operator_count: i64,
+
+    /// The number of rows in `pg_cast`.
+    pub cast_count: i64,
+
+    /// The number of rows in `pg_collation`.
+    pub collation_count: i64,
+
+    /// The number of rows in `pg_sequence`.
+    pub sequence_count: i64,
+
+    /// A hash of all roles and role memberships.
+    pub roles_hash: String,
+
+    pub server_version_num: i64,
+}
+
+#[cfg(feature = "db")]
+impl SchemaFingerprint {
+    pub async fn load(pool: &PgPool) -> Result<SchemaFingerprint, sqlx::Error> {
+        sqlx::query_file_as!(SchemaFingerprint, "src/queries/fingerprint.sql")
+            .fetch_one(pool)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "db"))]
+mod tests {
+    use sqlx::{Executor, PgPool};
+
+    use crate::SchemaFingerprint;
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn fingerprint_changes_with_schema(test_db: PgPool) {
+        let initial = SchemaFingerprint::load(&test_db).await.unwrap();
+        assert_eq!(SchemaFingerprint::load(&test_db).await.unwrap(), initial);
+
+        test_db
+            .execute("create table public.fingerprinted (id int);")
+            .await
+            .unwrap();
+        let created = SchemaFingerprint::load(&test_db).await.unwrap();
+        assert_ne!(created, initial);
+
+        test_db
+            .execute("drop table public.fingerprinted;")
+            .await
+            .unwrap();
+        let dropped = SchemaFingerprint::load(&test_db).await.unwrap();
+        assert_ne!(dropped, created);
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn fingerprint_changes_with_objects_outside_of_pg_class(test_db: PgPool) {
+        test_db
+            .execute(
+                r#"
+                create table public.guarded (id int);
+                create table public.referenced (id int primary key);
+                create type public.mood as enum ('happy');
+                create function public.noop() returns trigger language plpgsql as $$ begin return new; end $$;
+                "#,
+            )
+            .await
+            .unwrap();
+
+        let statements = [
+            "create policy guarded_policy on public.guarded using (true);",
+            "drop policy guarded_policy on public.guarded;",
+            "create trigger guarded_trigger before insert on public.guarded for each row execute function public.noop();",
+            "drop trigger guarded_trigger on public.guarded;",
+            "alter type public.mood add value 'sad';",
+            "comment on table public.guarded is 'guarded rows';",
+            "comment on table public.guarded is 'still guarded rows';",
+            "comment on table public.guarded is null;",
+            "alter table public.guarded add constraint guarded_unique unique (id);",
+            "alter table public.guarded add constraint guarded_fk foreign key (id) references public.referenced (id);",
+            "alter table public.guarded drop constraint guarded_fk;",
+            "create index guarded_idx on public.guarded (id);",
+            "drop index public.guarded_idx;",
+            "create function public.mood_eq(public.mood, public.mood) returns boolean language sql as 'select $1 = $2';",
+            "create operator public.=== (leftarg = public.mood, rightarg = public.mood, function = public.mood_eq);",
+            "drop operator public.=== (public.mood, public.mood);",
+            "create cast (public.mood as text) with inout;",
+            "drop cast (public.mood as text);",
+            "create collation public.guarded_collation from \"C\";",
+            "drop collation public.guarded_collation;",
+            "create sequence public.guarded_seq;",
+            "alter sequence public.guarded_seq increment by 2;",
+            "drop sequence public.guarded_seq;",
+            "create role fingerprinted_role;",
+            "alter role fingerprinted_role createdb;",
+            "drop role fingerprinted_role;",
+        ];
+
+        let mut previous = SchemaFingerprint::load(&test_db).await.unwrap();
+        for statement in statements {
+            test_db.execute(statement).await.unwrap();
+            let current = SchemaFingerprint::load(&test_db).await.unwrap();
+            assert_ne!(current, previous, "{statement}");
+            previous = current;
+        }
+    }
+}
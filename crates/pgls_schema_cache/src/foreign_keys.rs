@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "db")]
+use sqlx::PgPool;
+
+#[cfg(feature = "db")]
+use crate::schema_cache::SchemaCacheItem;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ForeignKey {
+    /// The Id (`oid`) of the constraint.
+    pub id: i64,
+
+    /// The name of the constraint.
+    pub name: String,
+
+    /// The schema of the referencing table.
+    pub schema: String,
+
+    /// The referencing table.
+    pub table_name: String,
+    pub table_id: i64,
+
+    /// The referencing columns, in the order of the constraint.
+    pub columns: Vec<String>,
+
+    pub referenced_schema: String,
+    pub referenced_table_name: String,
+    pub referenced_table_id: i64,
+
+    /// The referenced columns; the n-th one is referenced by the n-th of `columns`.
+    pub referenced_columns: Vec<String>,
+}
+
+impl ForeignKey {
+    /// The pairs of referencing and referenced columns.
+    pub fn column_pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.columns
+            .iter()
+            .map(String::as_str)
+            .zip(self.referenced_columns.iter().map(String::as_str))
+    }
+}
+
+#[cfg(feature = "db")]
+impl SchemaCacheItem for ForeignKey {
+    type Item = ForeignKey;
+
+    async fn load(pool: &PgPool) -> Result<Vec<ForeignKey>, sqlx::Error> {
+        sqlx::query_file_as!(ForeignKey, "src/queries/foreign_keys.sql")
+            .fetch_all(pool)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "db"))]
+mod tests {
+    use sqlx::{Executor, PgPool};
+
+    use crate::SchemaCache;
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn loads_foreign_keys(test_db: PgPool) {
+        let setup = r#"
+            create schema shop;
+
+            create table public.customers (
+                id serial primary key
+            );
+
+            create table shop.products (
+                sku text,
+                variant int,
+                primary key (sku, variant)
+            );
+
+            create table shop.order_items (
+                id serial primary key,
+                customer_id int references public.customers (id),
+                product_variant int,
+                product_sku text,
+                foreign key (product_sku, product_variant) references shop.products (sku, variant)
+            );
+        "#;
+
+        test_db
+            .execute(setup)
+            .await
+            .expect("Failed to setup test database");
+
+        let cache = SchemaCache::load(&test_db)
+            .await
+            .expect("Failed to load Schema Cache");
+
+        let to_customers = cache
+            .foreign_keys
+            .iter()
+            .find(|fk| fk.referenced_table_name == "customers")
+            .expect("foreign key to customers not found");
+
+        assert_eq!(to_customers.schema, "shop");
+        assert_eq!(to_customers.table_name, "order_items");
+        assert_eq!(to_customers.referenced_schema, "public");
+        assert_eq!(
+            to_customers.column_pairs().collect::<Vec<_>>(),
+            vec![("customer_id", "id")]
+        );
+
+        let to_products = cache
+            .foreign_keys
+            .iter()
+            .find(|fk| fk.referenced_table_name == "products")
+            .expect("foreign key to products not found");
+
+        assert_eq!(
+            to_products.column_pairs().collect::<Vec<_>>(),
+            vec![("product_sku", "sku"), ("product_variant", "variant")]
+        );
+    }
+}
@@ -91,7 +91,7 @@ impl From<Option<JsonValue>> for FunctionArgs {
     }
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Function {
     /// The Id (`oid`).
@@ -140,6 +140,9 @@ pub struct Function {
 
     /// Is the function's security set to `Definer` (true) or `Invoker` (false)?
     pub security_definer: bool,
+
+    /// Comment of the function, if any.
+    pub comment: Option<String>,
 }
 
 #[cfg(feature = "db")]
@@ -6,13 +6,18 @@ use crate::schema_cache::SchemaCacheItem;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Index {
     pub id: i64,
     pub schema: String,
     pub name: String,
     pub table_name: String,
+
+    /// The `CREATE INDEX` statement, as reconstructed by `pg_get_indexdef`.
+    pub definition: String,
+    pub is_unique: bool,
+    pub is_primary: bool,
 }
 
 #[cfg(feature = "db")]
@@ -2,10 +2,16 @@
 
 #![allow(dead_code)]
 
+mod casts;
+mod collations;
 mod columns;
+mod diff;
 mod extensions;
+mod fingerprint;
+mod foreign_keys;
 mod functions;
 mod indexes;
+mod operators;
 mod policies;
 mod roles;
 mod schema_cache;
@@ -16,15 +22,21 @@ mod triggers;
 mod types;
 mod versions;
 
+pub use casts::{Cast, CastContext, CastMethod};
+pub use collations::{Collation, CollationProvider};
 pub use columns::*;
+pub use diff::{ChangeKind, DiffObjectKind, SchemaChange, SchemaDiff};
 pub use extensions::Extension;
+pub use fingerprint::SchemaFingerprint;
+pub use foreign_keys::ForeignKey;
 pub use functions::{Behavior, Function, FunctionArg, FunctionArgs, ProcKind};
 pub use indexes::Index;
+pub use operators::{Operator, OperatorKind};
 pub use policies::{Policy, PolicyCommand};
 pub use roles::*;
-pub use schema_cache::SchemaCache;
+pub use schema_cache::{SchemaCache, SchemaCacheObjectKind};
 pub use schemas::Schema;
 pub use sequences::Sequence;
 pub use tables::{ReplicaIdentity, Table, TableKind};
-pub use triggers::{Trigger, TriggerAffected, TriggerEvent};
+pub use triggers::{Trigger, TriggerAffected, TriggerEvent, TriggerTiming};
 pub use types::{PostgresType, PostgresTypeAttribute};
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "db")]
+use sqlx::PgPool;
+
+#[cfg(feature = "db")]
+use crate::schema_cache::SchemaCacheItem;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum OperatorKind {
+    /// A binary operator, e.g. `a || b`.
+    #[default]
+    Infix,
+
+    /// A unary operator written before its operand, e.g. `-a`.
+    Prefix,
+
+    /// A unary operator written after its operand. Removed in Postgres 14.
+    Postfix,
+}
+
+impl From<char> for OperatorKind {
+    fn from(value: char) -> Self {
+        match value {
+            'b' => Self::Infix,
+            'l' => Self::Prefix,
+            'r' => Self::Postfix,
+            _ => panic!("Invalid operator kind"),
+        }
+    }
+}
+
+impl From<i8> for OperatorKind {
+    fn from(value: i8) -> Self {
+        char::from(u8::try_from(value).unwrap()).into()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Operator {
+    /// The Id (`oid`).
+    pub id: i64,
+
+    /// The name of the schema the operator belongs to.
+    pub schema: String,
+
+    /// The operator symbol, e.g. `||` or `->>`.
+    pub name: String,
+
+    pub kind: OperatorKind,
+
+    /// Refers to the left operand's type ID in the `pg_type` table. `None` for prefix operators.
+    pub left_type_id: Option<i64>,
+
+    /// Refers to the right operand's type ID in the `pg_type` table. `None` for postfix operators.
+    pub right_type_id: Option<i64>,
+
+    /// Refers to the result type's ID in the `pg_type` table.
+    pub result_type_id: i64,
+
+    /// The ID of the function implementing the operator.
+    pub function_id: i64,
+
+    pub comment: Option<String>,
+}
+
+#[cfg(feature = "db")]
+impl SchemaCacheItem for Operator {
+    type Item = Operator;
+
+    async fn load(pool: &PgPool) -> Result<Vec<Operator>, sqlx::Error> {
+        sqlx::query_file_as!(Operator, "src/queries/operators.sql")
+            .fetch_all(pool)
+            .await
+    }
+}
+
+#[cfg(all(test, feature = "db"))]
+mod tests {
+    use sqlx::{Executor, PgPool};
+
+    use crate::{OperatorKind, SchemaCache};
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn loads_operators(test_db: PgPool) {
+        let setup = r#"
+            create function public.text_similar(a text, b text)
+            returns boolean
+            language sql
+            immutable
+            as $$ select lower(a) = lower(b) $$;
+
+            create operator public.~~~ (
+                leftarg = text,
+                rightarg = text,
+                function = public.text_similar
+            );
+        "#;
+
+        test_db
+            .execute(setup)
+            .await
+            .expect("Failed to setup test database");
+
+        let cache = SchemaCache::load(&test_db)
+            .await
+            .expect("Failed to load Schema Cache");
+
+        let text = cache.find_type("text", Some("pg_catalog")).unwrap();
+        let bool = cache.find_type("bool", Some("pg_catalog")).unwrap();
+
+        let custom = cache.find_operators("~~~", Some("public"));
+        assert_eq!(custom.len(), 1);
+        let custom = custom[0];
+        assert_eq!(custom.kind, OperatorKind::Infix);
+        assert_eq!(custom.left_type_id, Some(text.id));
+        assert_eq!(custom.right_type_id, Some(text.id));
+        assert_eq!(custom.result_type_id, bool.id);
+
+        let negation = cache
+            .find_operators("-", Some("pg_catalog"))
+            .into_iter()
+            .find(|o| o.kind == OperatorKind::Prefix)
+            .expect("prefix minus not found");
+        assert_eq!(negation.left_type_id, None);
+        assert!(negation.right_type_id.is_some());
+    }
+}
@@ -0,0 +1,9 @@
+select
+  c.oid :: int8 as "id!",
+  c.castsource :: int8 as "source_type_id!",
+  c.casttarget :: int8 as "target_type_id!",
+  nullif(c.castfunc, 0) :: int8 as function_id,
+  c.castcontext as "context!",
+  c.castmethod as "method!"
+from
+  pg_catalog.pg_cast c;
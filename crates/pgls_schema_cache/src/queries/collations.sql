@@ -0,0 +1,13 @@
+select
+  c.oid :: int8 as "id!",
+  n.nspname as "schema!",
+  c.collname as "name!",
+  c.collprovider as "provider!",
+  c.collisdeterministic as "is_deterministic!",
+  nullif(pg_encoding_to_char(c.collencoding), '') as encoding,
+  c.collcollate :: text as collate,
+  c.collctype :: text as ctype,
+  obj_description(c.oid, 'pg_collation') as comment
+from
+  pg_catalog.pg_collation c
+  join pg_catalog.pg_namespace n on n.oid = c.collnamespace;
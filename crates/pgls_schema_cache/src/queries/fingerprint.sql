@@ -0,0 +1,38 @@
+-- xmin only grows when catalog rows are inserted or updated, the counts catch drops
+select
+  greatest(
+    (select max(xmin :: text :: int8) from pg_catalog.pg_class),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_proc),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_attribute),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_type),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_namespace),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_policy),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_trigger),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_enum),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_description),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_constraint),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_extension),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_index),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_operator),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_cast),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_collation),
+    (select max(xmin :: text :: int8) from pg_catalog.pg_sequence)
+  ) as "catalog_xmin!",
+  (select count(*) from pg_catalog.pg_class) as "class_count!",
+  (select count(*) from pg_catalog.pg_proc) as "proc_count!",
+  (select count(*) from pg_catalog.pg_policy) as "policy_count!",
+  (select count(*) from pg_catalog.pg_trigger) as "trigger_count!",
+  (select count(*) from pg_catalog.pg_description) as "description_count!",
+  (select count(*) from pg_catalog.pg_constraint) as "constraint_count!",
+  (select count(*) from pg_catalog.pg_extension) as "extension_count!",
+  (select count(*) from pg_catalog.pg_index) as "index_count!",
+  (select count(*) from pg_catalog.pg_operator) as "operator_count!",
+  (select count(*) from pg_catalog.pg_cast) as "cast_count!",
+  (select count(*) from pg_catalog.pg_collation) as "collation_count!",
+  (select count(*) from pg_catalog.pg_sequence) as "sequence_count!",
+  -- pg_authid is only readable by superusers, so roles and their memberships are hashed instead
+  md5(concat(
+    (select string_agg(r :: text, ',' order by r.oid) from pg_catalog.pg_roles r),
+    (select string_agg(m :: text, ',' order by m.roleid, m.member) from pg_catalog.pg_auth_members m)
+  )) as "roles_hash!",
+  current_setting('server_version_num') :: int8 as "server_version_num!";
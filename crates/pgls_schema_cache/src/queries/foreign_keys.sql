@@ -0,0 +1,38 @@
+select
+  c.oid :: int8 as "id!",
+  c.conname as "name!",
+  n.nspname as "schema!",
+  t.relname as "table_name!",
+  c.conrelid :: int8 as "table_id!",
+  array(
+    select
+      a.attname :: text
+    from
+      unnest(c.conkey) with ordinality as k(attnum, position)
+      join pg_catalog.pg_attribute a on a.attrelid = c.conrelid
+      and a.attnum = k.attnum
+    order by
+      k.position
+  ) as "columns!",
+  rn.nspname as "referenced_schema!",
+  rt.relname as "referenced_table_name!",
+  c.confrelid :: int8 as "referenced_table_id!",
+  array(
+    select
+      a.attname :: text
+    from
+      unnest(c.confkey) with ordinality as k(attnum, position)
+      join pg_catalog.pg_attribute a on a.attrelid = c.confrelid
+      and a.attnum = k.attnum
+    order by
+      k.position
+  ) as "referenced_columns!"
+from
+  pg_catalog.pg_constraint c
+  join pg_catalog.pg_class t on t.oid = c.conrelid
+  join pg_catalog.pg_namespace n on n.oid = t.relnamespace
+  join pg_catalog.pg_class rt on rt.oid = c.confrelid
+  join pg_catalog.pg_namespace rn on rn.oid = rt.relnamespace
+where
+  c.contype = 'f'
+  and n.nspname not in ('pg_catalog', 'information_schema', 'pg_toast');
@@ -62,7 +62,8 @@ select
     when f.provolatile = 's' then 'STABLE'
     when f.provolatile = 'v' then 'VOLATILE'
   end as behavior,
-  f.prosecdef as "security_definer!"
+  f.prosecdef as "security_definer!",
+  obj_description(f.oid, 'pg_proc') as comment
 from
   functions f
   left join pg_namespace n on f.pronamespace = n.oid
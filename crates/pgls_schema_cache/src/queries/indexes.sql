@@ -2,7 +2,10 @@ SELECT
     c.oid::bigint as "id!",
     n.nspname as "schema!",
     c.relname as "name!",
-    t.relname as "table_name!"
+    t.relname as "table_name!",
+    pg_catalog.pg_get_indexdef(c.oid) as "definition!",
+    i.indisunique as "is_unique!",
+    i.indisprimary as "is_primary!"
 FROM pg_catalog.pg_class c
 JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
 JOIN pg_catalog.pg_index i ON i.indexrelid = c.oid
@@ -0,0 +1,13 @@
+select
+  o.oid :: int8 as "id!",
+  n.nspname as "schema!",
+  o.oprname as "name!",
+  o.oprkind as "kind!",
+  nullif(o.oprleft, 0) :: int8 as left_type_id,
+  nullif(o.oprright, 0) :: int8 as right_type_id,
+  o.oprresult :: int8 as "result_type_id!",
+  o.oprcode :: oid :: int8 as "function_id!",
+  obj_description(o.oid, 'pg_operator') as comment
+from
+  pg_catalog.pg_operator o
+  join pg_catalog.pg_namespace n on n.oid = o.oprnamespace;
@@ -1,8 +1,28 @@
 SELECT
     c.oid::bigint as "id!",
     n.nspname as "schema!",
-    c.relname as "name!"
+    c.relname as "name!",
+    pg_catalog.format_type(s.seqtypid, null) as "data_type!",
+    s.seqstart as "start_value!",
+    s.seqmin as "min_value!",
+    s.seqmax as "max_value!",
+    s.seqincrement as "increment!",
+    s.seqcycle as "cycle!",
+    owner_table.relname as "owned_by_table?",
+    owner_column.attname::text as "owned_by_column?"
 FROM pg_catalog.pg_class c
 JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+JOIN pg_catalog.pg_sequence s ON s.seqrelid = c.oid
+-- `serial` and `identity` columns as well as `owned by` create an auto or internal dependency
+LEFT JOIN pg_catalog.pg_depend d
+    ON d.classid = 'pg_catalog.pg_class'::regclass
+    AND d.objid = c.oid
+    AND d.refclassid = 'pg_catalog.pg_class'::regclass
+    AND d.refobjsubid > 0
+    AND d.deptype IN ('a', 'i')
+LEFT JOIN pg_catalog.pg_class owner_table ON owner_table.oid = d.refobjid
+LEFT JOIN pg_catalog.pg_attribute owner_column
+    ON owner_column.attrelid = d.refobjid
+    AND owner_column.attnum = d.refobjsubid
 WHERE c.relkind = 'S'
   AND n.nspname NOT IN ('pg_catalog', 'information_schema', 'pg_toast')
@@ -1,10 +1,15 @@
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "db")]
 use sqlx::postgres::PgPool;
+use strum::{EnumIter, IntoEnumIterator};
 
+use crate::casts::Cast;
+use crate::collations::Collation;
 use crate::columns::Column;
+use crate::foreign_keys::ForeignKey;
 use crate::functions::Function;
 use crate::indexes::Index;
+use crate::operators::Operator;
 use crate::policies::Policy;
 use crate::schemas::Schema;
 use crate::sequences::Sequence;
@@ -13,7 +18,7 @@ use crate::types::PostgresType;
 use crate::versions::Version;
 use crate::{Extension, Role, Trigger};
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(default)]
 pub struct SchemaCache {
@@ -29,6 +34,99 @@ pub struct SchemaCache {
     pub roles: Vec<Role>,
     pub indexes: Vec<Index>,
     pub sequences: Vec<Sequence>,
+    pub operators: Vec<Operator>,
+    pub casts: Vec<Cast>,
+    pub collations: Vec<Collation>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+/// The kinds of database objects the [SchemaCache] holds, which can be reloaded independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum SchemaCacheObjectKind {
+    Schemas,
+    Tables,
+    Columns,
+    Functions,
+    Types,
+    Policies,
+    Triggers,
+    Roles,
+    Extensions,
+    Indexes,
+    Sequences,
+    Operators,
+    Casts,
+    Collations,
+    ForeignKeys,
+}
+
+impl SchemaCacheObjectKind {
+    /// Returns the kinds affected by DDL on the given object type, e.g. `TABLE` or `materialized view`.
+    ///
+    /// Accepts the spellings used in command tags, by `pg_event_trigger_ddl_commands()`
+    /// and by the `OBJECT_*` constants of the Postgres parser.
+    pub fn affected_by_object_type(object_type: &str) -> Vec<Self> {
+        let normalized = object_type
+            .trim()
+            .trim_start_matches("OBJECT_")
+            .replace('_', " ")
+            .to_uppercase();
+
+        match normalized.as_str() {
+            "TABLE" | "VIEW" | "MATVIEW" | "MATERIALIZED VIEW" | "FOREIGN TABLE" | "COLUMN"
+            | "TABCONSTRAINT" | "TABLE CONSTRAINT" | "RULE" => vec![
+                Self::Tables,
+                Self::Columns,
+                Self::Indexes,
+                Self::Sequences,
+                Self::Triggers,
+                Self::Policies,
+                Self::ForeignKeys,
+            ],
+            "INDEX" => vec![Self::Indexes, Self::Columns],
+            "FUNCTION" | "PROCEDURE" | "ROUTINE" | "AGGREGATE" => vec![Self::Functions],
+            "TYPE" | "DOMAIN" | "DOMCONSTRAINT" | "DOMAIN CONSTRAINT" => vec![Self::Types],
+            "POLICY" => vec![Self::Policies],
+            "TRIGGER" => vec![Self::Triggers],
+            "SEQUENCE" => vec![Self::Sequences],
+            "ROLE" => vec![Self::Roles],
+            "OPERATOR" => vec![Self::Operators],
+            "CAST" => vec![Self::Casts],
+            "COLLATION" => vec![Self::Collations],
+            // dropping a schema or an extension can affect anything
+            "SCHEMA" | "EXTENSION" => Self::iter().collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Returns the kinds affected by a command, e.g. `ALTER TABLE` or `CREATE FUNCTION`.
+    /// An empty command tag affects all kinds.
+    pub fn affected_by_command_tag(command_tag: &str) -> Vec<Self> {
+        let command_tag = command_tag.trim().to_uppercase();
+
+        if command_tag.is_empty() {
+            return Self::iter().collect();
+        }
+
+        // the verb is a single word, the rest is the object type, e.g. `MATERIALIZED VIEW`
+        let (verb, object_type) = command_tag
+            .split_once(' ')
+            .unwrap_or((command_tag.as_str(), ""));
+
+        match (verb, object_type) {
+            // privileges decide which tables and functions are visible
+            ("GRANT" | "REVOKE", _) | ("ALTER", "DEFAULT PRIVILEGES") => {
+                vec![Self::Tables, Self::Columns, Self::Functions]
+            }
+            // `CREATE TABLE AS` and `SELECT INTO` create a table from a query
+            ("CREATE", "TABLE AS") | ("SELECT", "INTO") => Self::affected_by_object_type("TABLE"),
+            ("IMPORT", "FOREIGN SCHEMA") => Self::affected_by_object_type("FOREIGN TABLE"),
+            ("CREATE" | "ALTER" | "DROP" | "COMMENT", object_type) => {
+                Self::affected_by_object_type(object_type)
+            }
+            _ => vec![],
+        }
+    }
 }
 
 impl SchemaCache {
@@ -47,6 +145,10 @@ impl SchemaCache {
             extensions,
             indexes,
             sequences,
+            operators,
+            casts,
+            collations,
+            foreign_keys,
         ) = futures_util::try_join!(
             Schema::load(pool),
             Table::load(pool),
@@ -60,6 +162,10 @@ impl SchemaCache {
             Extension::load(pool),
             Index::load(pool),
             Sequence::load(pool),
+            Operator::load(pool),
+            Cast::load(pool),
+            Collation::load(pool),
+            ForeignKey::load(pool),
         )?;
 
         let version = versions
@@ -80,9 +186,49 @@ impl SchemaCache {
             extensions,
             indexes,
             sequences,
+            operators,
+            casts,
+            collations,
+            foreign_keys,
         })
     }
 
+    /// Reloads the given kinds of objects, leaving the rest of the cache untouched.
+    #[cfg(feature = "db")]
+    pub async fn reload(
+        &mut self,
+        pool: &PgPool,
+        kinds: &[SchemaCacheObjectKind],
+    ) -> Result<(), sqlx::Error> {
+        for kind in kinds {
+            match kind {
+                SchemaCacheObjectKind::Schemas => self.schemas = Schema::load(pool).await?,
+                SchemaCacheObjectKind::Tables => self.tables = Table::load(pool).await?,
+                SchemaCacheObjectKind::Columns => self.columns = Column::load(pool).await?,
+                SchemaCacheObjectKind::Functions => self.functions = Function::load(pool).await?,
+                SchemaCacheObjectKind::Types => self.types = PostgresType::load(pool).await?,
+                SchemaCacheObjectKind::Policies => self.policies = Policy::load(pool).await?,
+                SchemaCacheObjectKind::Triggers => self.triggers = Trigger::load(pool).await?,
+                SchemaCacheObjectKind::Roles => self.roles = Role::load(pool).await?,
+                SchemaCacheObjectKind::Extensions => {
+                    self.extensions = Extension::load(pool).await?
+                }
+                SchemaCacheObjectKind::Indexes => self.indexes = Index::load(pool).await?,
+                SchemaCacheObjectKind::Sequences => self.sequences = Sequence::load(pool).await?,
+                SchemaCacheObjectKind::Operators => self.operators = Operator::load(pool).await?,
+                SchemaCacheObjectKind::Casts => self.casts = Cast::load(pool).await?,
+                SchemaCacheObjectKind::Collations => {
+                    self.collations = Collation::load(pool).await?
+                }
+                SchemaCacheObjectKind::ForeignKeys => {
+                    self.foreign_keys = ForeignKey::load(pool).await?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn find_schema(&self, name: &str) -> Option<&Schema> {
         let sanitized_name = Self::sanitize_identifier(name);
         self.schemas.iter().find(|s| s.name == sanitized_name)
@@ -129,6 +275,36 @@ impl SchemaCache {
         self.schemas.iter().find(|s| s.id == id)
     }
 
+    /// Returns the foreign keys from `table` to `referenced_table`.
+    pub fn find_foreign_keys(
+        &self,
+        table: &str,
+        schema: Option<&str>,
+        referenced_table: &str,
+        referenced_schema: Option<&str>,
+    ) -> Vec<&ForeignKey> {
+        let matches = |name: &str, schema: Option<&str>, fk_name: &str, fk_schema: &str| {
+            Self::sanitize_identifier(name) == fk_name
+                && schema
+                    .map(Self::sanitize_identifier)
+                    .as_deref()
+                    .is_none_or(|s| s == fk_schema)
+        };
+
+        self.foreign_keys
+            .iter()
+            .filter(|fk| {
+                matches(table, schema, &fk.table_name, &fk.schema)
+                    && matches(
+                        referenced_table,
+                        referenced_schema,
+                        &fk.referenced_table_name,
+                        &fk.referenced_schema,
+                    )
+            })
+            .collect()
+    }
+
     pub fn find_index_by_id(&self, id: i64) -> Option<&Index> {
         self.indexes.iter().find(|i| i.id == id)
     }
@@ -183,6 +359,36 @@ impl SchemaCache {
             .collect()
     }
 
+    pub fn find_operators(&self, name: &str, schema: Option<&str>) -> Vec<&Operator> {
+        self.operators
+            .iter()
+            .filter(|o| {
+                o.name == name
+                    && schema
+                        .map(Self::sanitize_identifier)
+                        .as_deref()
+                        .is_none_or(|s| s == o.schema.as_str())
+            })
+            .collect()
+    }
+
+    pub fn find_cast(&self, source_type_id: i64, target_type_id: i64) -> Option<&Cast> {
+        self.casts
+            .iter()
+            .find(|c| c.source_type_id == source_type_id && c.target_type_id == target_type_id)
+    }
+
+    pub fn find_collation(&self, name: &str, schema: Option<&str>) -> Option<&Collation> {
+        let sanitized_name = Self::sanitize_identifier(name);
+        self.collations.iter().find(|c| {
+            c.name == sanitized_name
+                && schema
+                    .map(Self::sanitize_identifier)
+                    .as_deref()
+                    .is_none_or(|s| s == c.schema.as_str())
+        })
+    }
+
     pub fn find_roles(&self, name: &str) -> Vec<&Role> {
         let sanitized_name = Self::sanitize_identifier(name);
         self.roles
@@ -191,6 +397,67 @@ impl SchemaCache {
             .collect()
     }
 
+    pub fn find_policies(&self, name: &str, table: Option<&str>) -> Vec<&Policy> {
+        let sanitized_name = Self::sanitize_identifier(name);
+        self.policies
+            .iter()
+            .filter(|p| {
+                p.name == sanitized_name
+                    && table
+                        .map(Self::sanitize_identifier)
+                        .as_deref()
+                        .is_none_or(|t| t == p.table_name.as_str())
+            })
+            .collect()
+    }
+
+    pub fn find_triggers(&self, name: &str, table: Option<&str>) -> Vec<&Trigger> {
+        let sanitized_name = Self::sanitize_identifier(name);
+        self.triggers
+            .iter()
+            .filter(|t| {
+                t.name == sanitized_name
+                    && table
+                        .map(Self::sanitize_identifier)
+                        .as_deref()
+                        .is_none_or(|tbl| tbl == t.table_name.as_str())
+            })
+            .collect()
+    }
+
+    pub fn find_indexes(&self, name: &str, schema: Option<&str>) -> Vec<&Index> {
+        let sanitized_name = Self::sanitize_identifier(name);
+        self.indexes
+            .iter()
+            .filter(|i| {
+                i.name == sanitized_name
+                    && schema
+                        .map(Self::sanitize_identifier)
+                        .as_deref()
+                        .is_none_or(|s| s == i.schema.as_str())
+            })
+            .collect()
+    }
+
+    pub fn find_sequences(&self, name: &str, schema: Option<&str>) -> Vec<&Sequence> {
+        let sanitized_name = Self::sanitize_identifier(name);
+        self.sequences
+            .iter()
+            .filter(|seq| {
+                seq.name == sanitized_name
+                    && schema
+                        .map(Self::sanitize_identifier)
+                        .as_deref()
+                        .is_none_or(|s| s == seq.schema.as_str())
+            })
+            .collect()
+    }
+
+    pub fn find_extension(&self, name: &str) -> Option<&Extension> {
+        let sanitized_name = Self::sanitize_identifier(name);
+        self.extensions.iter().find(|e| e.name == sanitized_name)
+    }
+
     fn sanitize_identifier(identifier: &str) -> String {
         identifier.replace('"', "")
     }
@@ -209,7 +476,9 @@ mod tests {
 
     use sqlx::{Executor, PgPool};
 
-    use crate::SchemaCache;
+    use strum::IntoEnumIterator;
+
+    use crate::{SchemaCache, SchemaCacheObjectKind};
 
     #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
     async fn it_loads(test_db: PgPool) {
@@ -246,4 +515,75 @@ mod tests {
 
         assert_eq!(set.len(), cache.columns.len());
     }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn it_reloads_only_requested_kinds(test_db: PgPool) {
+        let mut cache = SchemaCache::load(&test_db)
+            .await
+            .expect("Couldn't load Schema Cache");
+
+        let setup = r#"
+        create table public.reloaded (id serial primary key);
+
+        create function public.not_reloaded() returns int language sql as $$ select 1 $$;
+        "#;
+
+        test_db.execute(setup).await.unwrap();
+
+        cache
+            .reload(
+                &test_db,
+                &SchemaCacheObjectKind::affected_by_command_tag("CREATE TABLE"),
+            )
+            .await
+            .expect("Couldn't reload Schema Cache");
+
+        assert_eq!(cache.find_tables("reloaded", Some("public")).len(), 1);
+        assert_eq!(cache.find_cols("id", Some("reloaded"), None).len(), 1);
+        assert!(cache.find_functions("not_reloaded", None).is_empty());
+    }
+
+    #[test]
+    fn it_maps_command_tags_to_object_kinds() {
+        assert_eq!(
+            SchemaCacheObjectKind::affected_by_command_tag("CREATE FUNCTION"),
+            vec![SchemaCacheObjectKind::Functions]
+        );
+        assert_eq!(
+            SchemaCacheObjectKind::affected_by_command_tag("create index"),
+            vec![
+                SchemaCacheObjectKind::Indexes,
+                SchemaCacheObjectKind::Columns
+            ]
+        );
+        assert_eq!(
+            SchemaCacheObjectKind::affected_by_object_type("OBJECT_MATVIEW"),
+            SchemaCacheObjectKind::affected_by_object_type("materialized view")
+        );
+        assert_eq!(
+            SchemaCacheObjectKind::affected_by_command_tag("CREATE TABLE AS"),
+            SchemaCacheObjectKind::affected_by_object_type("TABLE")
+        );
+        assert_eq!(
+            SchemaCacheObjectKind::affected_by_command_tag("SELECT INTO"),
+            SchemaCacheObjectKind::affected_by_object_type("TABLE")
+        );
+        assert_eq!(
+            SchemaCacheObjectKind::affected_by_command_tag("CREATE MATERIALIZED VIEW"),
+            SchemaCacheObjectKind::affected_by_object_type("TABLE")
+        );
+        assert_eq!(
+            SchemaCacheObjectKind::affected_by_command_tag("IMPORT FOREIGN SCHEMA"),
+            SchemaCacheObjectKind::affected_by_object_type("TABLE")
+        );
+        assert_eq!(
+            SchemaCacheObjectKind::affected_by_command_tag("ALTER DEFAULT PRIVILEGES"),
+            SchemaCacheObjectKind::affected_by_command_tag("GRANT")
+        );
+        assert!(SchemaCacheObjectKind::affected_by_command_tag("INSERT 0 1").is_empty());
+        assert_eq!(
+            SchemaCacheObjectKind::affected_by_command_tag("").len(),
+            SchemaCacheObjectKind::iter().count()
+        );
+    }
 }
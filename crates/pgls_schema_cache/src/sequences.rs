@@ -6,12 +6,25 @@ use crate::schema_cache::SchemaCacheItem;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Sequence {
     pub id: i64,
     pub schema: String,
     pub name: String,
+
+    /// e.g. `bigint` or `integer`.
+    pub data_type: String,
+    pub start_value: i64,
+    pub min_value: i64,
+    pub max_value: i64,
+    pub increment: i64,
+    /// Does the sequence wrap around when it reaches its bounds?
+    pub cycle: bool,
+
+    /// The table of the column the sequence is owned by, e.g. via `serial` or `OWNED BY`.
+    pub owned_by_table: Option<String>,
+    pub owned_by_column: Option<String>,
 }
 
 #[cfg(feature = "db")]
@@ -24,3 +37,57 @@ impl SchemaCacheItem for Sequence {
             .await
     }
 }
+
+#[cfg(all(test, feature = "db"))]
+mod tests {
+    use sqlx::{Executor, PgPool};
+
+    use crate::SchemaCache;
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn loads_sequences_with_owner_column(test_db: PgPool) {
+        let setup = r#"
+            create table public.orders (
+                id serial primary key
+            );
+
+            create sequence public.invoice_numbers
+                as integer
+                increment by 5
+                minvalue 100
+                maxvalue 1000
+                start with 100
+                cycle;
+        "#;
+
+        test_db
+            .execute(setup)
+            .await
+            .expect("Failed to setup test database");
+
+        let cache = SchemaCache::load(&test_db)
+            .await
+            .expect("Failed to load Schema Cache");
+
+        let serial = cache
+            .find_sequences("orders_id_seq", Some("public"))
+            .pop()
+            .expect("serial sequence not found");
+
+        assert_eq!(serial.owned_by_table.as_deref(), Some("orders"));
+        assert_eq!(serial.owned_by_column.as_deref(), Some("id"));
+        assert_eq!(serial.data_type, "integer");
+
+        let invoices = cache
+            .find_sequences("invoice_numbers", None)
+            .pop()
+            .expect("sequence not found");
+
+        assert_eq!(invoices.owned_by_column, None);
+        assert_eq!(invoices.start_value, 100);
+        assert_eq!(invoices.min_value, 100);
+        assert_eq!(invoices.max_value, 1000);
+        assert_eq!(invoices.increment, 5);
+        assert!(invoices.cycle);
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     context::ancestors::ScopeTracker,
     helper, parts_of_reference_query,
     queries::{self, QueryResult, TreeSitterQueriesExecutor},
+    scope::{DerivedRelation, DerivedRelationKind, QueryScope},
 };
 use pgls_text_size::TextSize;
 use tree_sitter::Language;
@@ -33,8 +34,15 @@ pub enum WrappingClause<'a> {
     SetStatement,
     AlterRole,
     DropRole,
+    AlterSequence,
+    DropSequence,
     RevokeStatement,
     GrantStatement,
+    CreateIndex,
+    DropFunction,
+    AlterType,
+    CommentOn,
+    CreateTrigger,
 
     CreatePolicy,
     AlterPolicy,
@@ -130,6 +138,9 @@ pub struct TreesitterContext<'a> {
     pub previous_clause: Option<tree_sitter::Node<'a>>,
     pub current_clause: Option<tree_sitter::Node<'a>>,
 
+    /// The CTEs, derived tables and set-returning functions the statement defines.
+    pub query_scope: QueryScope,
+
     mentioned_relations: HashMap<Option<String>, HashSet<String>>,
     mentioned_table_aliases: HashMap<String, String>,
     mentioned_columns: HashMap<Option<WrappingClause<'a>>, HashSet<MentionedColumn>>,
@@ -153,6 +164,7 @@ impl<'a> TreesitterContext<'a> {
             mentioned_table_aliases: HashMap::new(),
             mentioned_columns: HashMap::new(),
             scope_tracker: ScopeTracker::new(),
+            query_scope: QueryScope::new(params.tree.root_node(), params.text),
 
             possible_keywords_at_position: vec![],
             current_clause: None,
@@ -290,6 +302,12 @@ impl<'a> TreesitterContext<'a> {
             self.position = cmp::min(self.position, self.text.len().saturating_sub(1));
         }
 
+        // If the whole file fails to parse, the root is an ERROR node whose children
+        // are plain tokens, so the root itself has to open the scope.
+        if current_node.kind() == "ERROR" {
+            self.scope_tracker.register(current_node, self.position);
+        }
+
         cursor.goto_first_child_for_byte(self.position);
 
         self.gather_context_from_node(cursor, current_node);
@@ -393,6 +411,8 @@ impl<'a> TreesitterContext<'a> {
             "drop_table" => Some(WrappingClause::DropTable),
             "alter_role" => Some(WrappingClause::AlterRole),
             "drop_role" => Some(WrappingClause::DropRole),
+            "alter_sequence" => Some(WrappingClause::AlterSequence),
+            "drop_sequence" => Some(WrappingClause::DropSequence),
             "drop_column" => Some(WrappingClause::DropColumn),
             "alter_column" => Some(WrappingClause::AlterColumn),
             "rename_column" => Some(WrappingClause::RenameColumn),
@@ -400,6 +420,11 @@ impl<'a> TreesitterContext<'a> {
             "set_statement" => Some(WrappingClause::SetStatement),
             "revoke_statement" => Some(WrappingClause::RevokeStatement),
             "grant_statement" => Some(WrappingClause::GrantStatement),
+            "create_index" => Some(WrappingClause::CreateIndex),
+            "drop_function" => Some(WrappingClause::DropFunction),
+            "alter_type" => Some(WrappingClause::AlterType),
+            "comment_statement" => Some(WrappingClause::CommentOn),
+            "create_trigger" => Some(WrappingClause::CreateTrigger),
             "column_definitions" => Some(WrappingClause::ColumnDefinitions),
             "create_policy" => Some(WrappingClause::CreatePolicy),
             "alter_policy" => Some(WrappingClause::AlterPolicy),
@@ -485,6 +510,18 @@ impl<'a> TreesitterContext<'a> {
             .is_within_one_of_fields(names)
     }
 
+    /// The CTEs, derived tables and set-returning functions that can be referenced at the cursor.
+    pub fn visible_derived_relations(&self) -> impl Iterator<Item = &DerivedRelation> {
+        self.query_scope.relations().iter().filter(|relation| {
+            relation.is_visible_at(self.position)
+                // a CTE is only in scope if the query selects from it
+                && (relation.kind != DerivedRelationKind::Cte
+                    || self
+                        .get_mentioned_relations(&None)
+                        .is_some_and(|tables| tables.contains(&relation.name)))
+        })
+    }
+
     pub fn get_mentioned_relations(&self, key: &Option<String>) -> Option<&HashSet<String>> {
         if let Some(key) = key.as_ref() {
             let sanitized_key = key.replace('"', "");
@@ -499,6 +536,17 @@ impl<'a> TreesitterContext<'a> {
         }
     }
 
+    /// Returns all mentioned relations as `(schema, table)` pairs.
+    pub fn iter_mentioned_relations(&self) -> impl Iterator<Item = (Option<&str>, &str)> {
+        self.mentioned_relations
+            .iter()
+            .flat_map(|(schema, tables)| {
+                tables
+                    .iter()
+                    .map(move |table| (schema.as_deref(), table.as_str()))
+            })
+    }
+
     pub fn get_mentioned_table_for_alias(&self, key: &str) -> Option<&String> {
         let sanitized_key = key.replace('"', "");
 
@@ -883,6 +931,29 @@ mod tests {
         assert_eq!(ctx.wrapping_clause_type, Some(WrappingClause::Select));
     }
 
+    #[test]
+    fn does_not_fail_if_the_whole_file_is_an_error() {
+        let query = format!(
+            "alter view users_view o{};",
+            QueryWithCursorPosition::cursor_marker()
+        );
+
+        let (position, text) = QueryWithCursorPosition::from(query).get_text_and_position();
+
+        let tree = get_tree(text.as_str());
+        assert_eq!(tree.root_node().kind(), "ERROR");
+
+        let params = TreeSitterContextParams {
+            position: (position as u32).into(),
+            text: &text,
+            tree: &tree,
+        };
+
+        let ctx = TreesitterContext::new(params);
+
+        assert_eq!(ctx.wrapping_clause_type, None);
+    }
+
     #[test]
     fn verifies_node_has_field_name() {
         let query = format!(
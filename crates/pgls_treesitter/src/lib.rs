@@ -1,7 +1,9 @@
 pub mod context;
 pub mod helper;
 pub mod queries;
+pub mod scope;
 
 pub use context::*;
 pub use helper::*;
 pub use queries::*;
+pub use scope::*;
@@ -0,0 +1,718 @@
+use std::ops::Range;
+
+use pgls_schema_cache::SchemaCache;
+
+/// Recursive CTEs and CTEs referencing each other are only followed this deep.
+const MAX_RESOLUTION_DEPTH: usize = 8;
+
+/// The relations a statement defines for itself: CTEs, derived tables, `VALUES` lists
+/// and set-returning functions in `FROM`.
+///
+/// Their output columns aren't part of the schema cache, so we derive them from the
+/// statement and resolve their types against the schema cache where possible.
+#[derive(Debug, Default)]
+pub struct QueryScope {
+    relations: Vec<DerivedRelation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerivedRelationKind {
+    /// `with recent as (select ...)`
+    Cte,
+    /// `from (select ...) sub`
+    Subquery,
+    /// `from (values (1, 'a')) v(num, letter)`
+    Values,
+    /// `from generate_series(1, 3) g`
+    Function {
+        schema: Option<String>,
+        name: String,
+    },
+}
+
+#[derive(Debug)]
+pub struct DerivedRelation {
+    /// The name the relation is referenced by: the name of the CTE or the alias.
+    pub name: String,
+    pub kind: DerivedRelationKind,
+
+    /// The byte range of the query the relation is part of.
+    scope: Range<usize>,
+    /// The byte range of the relation's own definition.
+    definition: Range<usize>,
+    /// The explicit column names, e.g. `with recent (id, total) as (...)`.
+    column_aliases: Vec<String>,
+    items: Vec<SelectItem>,
+    /// The relations the derived query selects from.
+    sources: Vec<SourceRelation>,
+}
+
+/// An output column of a [DerivedRelation].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedColumn {
+    pub relation: String,
+    pub name: String,
+    /// `None` if the type could not be resolved.
+    pub type_name: Option<String>,
+}
+
+#[derive(Debug)]
+struct SelectItem {
+    /// The output name, if it can be determined.
+    name: Option<String>,
+    expression: Expression,
+}
+
+#[derive(Debug)]
+enum Expression {
+    /// `u.id`
+    Reference {
+        qualifier: Option<String>,
+        column: String,
+    },
+    /// `*` or `u.*`
+    AllFields {
+        qualifier: Option<String>,
+    },
+    /// A cast or a literal, the type is known without looking anything up.
+    Typed(String),
+    /// `lower(name)`
+    Invocation {
+        schema: Option<String>,
+        name: String,
+    },
+    Unknown,
+}
+
+#[derive(Debug)]
+struct SourceRelation {
+    schema: Option<String>,
+    name: String,
+    alias: Option<String>,
+}
+
+impl SourceRelation {
+    fn is_referenced_by(&self, qualifier: &str) -> bool {
+        self.alias.as_deref().unwrap_or(self.name.as_str()) == qualifier
+    }
+}
+
+impl DerivedRelation {
+    /// Whether the relation can be referenced at the given byte offset.
+    pub fn is_visible_at(&self, position: usize) -> bool {
+        self.scope.start <= position
+            && position <= self.scope.end
+            && !(self.definition.start <= position && position <= self.definition.end)
+    }
+}
+
+impl QueryScope {
+    pub fn new(root: tree_sitter::Node, sql: &str) -> Self {
+        let mut relations = vec![];
+        collect_relations(root, sql, &mut relations);
+        Self { relations }
+    }
+
+    pub fn relations(&self) -> &[DerivedRelation] {
+        &self.relations
+    }
+
+    pub fn find_relation(&self, name: &str) -> Option<&DerivedRelation> {
+        let name = name.replace('"', "");
+        self.relations.iter().find(|r| r.name == name)
+    }
+
+    /// Returns the output columns of the relation, with their types where they are resolvable.
+    pub fn resolve_columns(
+        &self,
+        relation: &DerivedRelation,
+        schema_cache: &SchemaCache,
+    ) -> Vec<ResolvedColumn> {
+        self.resolve(relation, schema_cache, 0)
+            .into_iter()
+            .map(|(name, type_name)| ResolvedColumn {
+                relation: relation.name.clone(),
+                name,
+                type_name,
+            })
+            .collect()
+    }
+
+    fn resolve(
+        &self,
+        relation: &DerivedRelation,
+        schema_cache: &SchemaCache,
+        depth: usize,
+    ) -> Vec<(String, Option<String>)> {
+        if depth > MAX_RESOLUTION_DEPTH {
+            return vec![];
+        }
+
+        let mut columns: Vec<(String, Option<String>)> = match &relation.kind {
+            DerivedRelationKind::Function { schema, name } => {
+                let function = schema_cache
+                    .find_functions(name, schema.as_deref())
+                    .into_iter()
+                    .next();
+
+                match function {
+                    Some(f) => match f.return_type_relation_id {
+                        Some(relation_id) => schema_cache
+                            .columns
+                            .iter()
+                            .filter(|c| c.table_oid == relation_id)
+                            .map(|c| (c.name.clone(), c.type_name.clone()))
+                            .collect(),
+
+                        // a function returning a scalar yields a single column named like the relation
+                        None => vec![(relation.name.clone(), f.return_type.clone())],
+                    },
+                    None => vec![],
+                }
+            }
+
+            _ => relation
+                .items
+                .iter()
+                .flat_map(|item| self.resolve_item(relation, item, schema_cache, depth))
+                .collect(),
+        };
+
+        for (column, alias) in columns.iter_mut().zip(relation.column_aliases.iter()) {
+            column.0 = alias.clone();
+        }
+
+        columns
+    }
+
+    fn resolve_item(
+        &self,
+        relation: &DerivedRelation,
+        item: &SelectItem,
+        schema_cache: &SchemaCache,
+        depth: usize,
+    ) -> Vec<(String, Option<String>)> {
+        let name = || item.name.clone().unwrap_or("?column?".into());
+
+        match &item.expression {
+            Expression::Reference { qualifier, column } => {
+                let type_name = relation
+                    .sources
+                    .iter()
+                    .filter(|s| qualifier.as_deref().is_none_or(|q| s.is_referenced_by(q)))
+                    .flat_map(|s| self.source_columns(s, schema_cache, depth))
+                    .find(|(name, _)| name == column)
+                    .and_then(|(_, type_name)| type_name);
+
+                vec![(name(), type_name)]
+            }
+
+            Expression::AllFields { qualifier } => relation
+                .sources
+                .iter()
+                .filter(|s| qualifier.as_deref().is_none_or(|q| s.is_referenced_by(q)))
+                .flat_map(|s| self.source_columns(s, schema_cache, depth))
+                .collect(),
+
+            Expression::Typed(type_name) => vec![(name(), Some(type_name.clone()))],
+
+            Expression::Invocation {
+                schema,
+                name: fn_name,
+            } => {
+                let type_name = schema_cache
+                    .find_functions(fn_name, schema.as_deref())
+                    .into_iter()
+                    .find_map(|f| f.return_type.clone());
+
+                vec![(name(), type_name)]
+            }
+
+            Expression::Unknown => vec![(name(), None)],
+        }
+    }
+
+    fn source_columns(
+        &self,
+        source: &SourceRelation,
+        schema_cache: &SchemaCache,
+        depth: usize,
+    ) -> Vec<(String, Option<String>)> {
+        if source.schema.is_none()
+            && let Some(derived) = self.find_relation(&source.name)
+        {
+            return self.resolve(derived, schema_cache, depth + 1);
+        }
+
+        schema_cache
+            .columns
+            .iter()
+            .filter(|c| {
+                c.table_name == source.name
+                    && source.schema.as_ref().is_none_or(|s| *s == c.schema_name)
+            })
+            .map(|c| (c.name.clone(), c.type_name.clone()))
+            .collect()
+    }
+}
+
+fn collect_relations(node: tree_sitter::Node, sql: &str, relations: &mut Vec<DerivedRelation>) {
+    match node.kind() {
+        "with_query" => {
+            if let Some(relation) = cte(node, sql) {
+                relations.push(relation);
+            }
+        }
+        "relation" => {
+            if let Some(relation) = derived_from_item(node, sql) {
+                relations.push(relation);
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_relations(child, sql, relations);
+    }
+}
+
+/// `recent (id, total) as (select ...)`
+fn cte(node: tree_sitter::Node, sql: &str) -> Option<DerivedRelation> {
+    let mut cursor = node.walk();
+    let children: Vec<tree_sitter::Node> = node.children(&mut cursor).collect();
+
+    let name = children
+        .iter()
+        .find(|c| c.kind() == "any_identifier")
+        .map(|n| text(*n, sql))?;
+
+    let column_aliases = children
+        .iter()
+        .take_while(|c| c.kind() != "keyword_as")
+        .filter(|c| c.kind() == "any_identifier")
+        .skip(1)
+        .map(|n| text(*n, sql))
+        .collect();
+
+    let query = children.iter().find(|c| c.kind() == "statement")?;
+
+    Some(DerivedRelation {
+        name,
+        kind: DerivedRelationKind::Cte,
+        scope: enclosing_query(node).byte_range(),
+        definition: node.byte_range(),
+        column_aliases,
+        items: select_items(*query, sql),
+        sources: source_relations(*query, sql),
+    })
+}
+
+/// `(select ...) sub`, `(values ...) v (a, b)` or `my_function() f`
+fn derived_from_item(node: tree_sitter::Node, sql: &str) -> Option<DerivedRelation> {
+    let alias = child_of_kind(node, "alias")
+        .and_then(|a| child_of_kind(a, "any_identifier"))
+        .map(|n| text(n, sql));
+
+    let column_aliases: Vec<String> = child_of_kind(node, "list")
+        .map(|list| {
+            let mut cursor = list.walk();
+            list.named_children(&mut cursor)
+                .filter_map(|c| child_of_kind(c, "column_identifier"))
+                .map(|n| text(n, sql))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if let Some(subquery) = child_of_kind(node, "subquery") {
+        return Some(DerivedRelation {
+            name: alias?,
+            kind: DerivedRelationKind::Subquery,
+            scope: enclosing_query(node).byte_range(),
+            definition: node.byte_range(),
+            column_aliases,
+            items: select_items(subquery, sql),
+            sources: source_relations(subquery, sql),
+        });
+    }
+
+    if let Some(values) = child_of_kind(node, "values") {
+        let first_row = child_of_kind(values, "list")?;
+
+        let mut cursor = first_row.walk();
+        let items = first_row
+            .named_children(&mut cursor)
+            .enumerate()
+            .map(|(idx, value)| SelectItem {
+                // that's what Postgres names them
+                name: Some(format!("column{}", idx + 1)),
+                expression: expression(value, sql),
+            })
+            .collect();
+
+        return Some(DerivedRelation {
+            name: alias?,
+            kind: DerivedRelationKind::Values,
+            scope: enclosing_query(node).byte_range(),
+            definition: node.byte_range(),
+            column_aliases,
+            items,
+            sources: vec![],
+        });
+    }
+
+    if let Some(invocation) = child_of_kind(node, "invocation") {
+        let (schema, name) = function_name(invocation, sql)?;
+
+        return Some(DerivedRelation {
+            name: alias.unwrap_or(name.clone()),
+            kind: DerivedRelationKind::Function { schema, name },
+            scope: enclosing_query(node).byte_range(),
+            definition: node.byte_range(),
+            column_aliases,
+            items: vec![],
+            sources: vec![],
+        });
+    }
+
+    None
+}
+
+/// The items of the `select` clause of a statement or subquery.
+fn select_items(query: tree_sitter::Node, sql: &str) -> Vec<SelectItem> {
+    let Some(select_expression) =
+        child_of_kind(query, "select").and_then(|s| child_of_kind(s, "select_expression"))
+    else {
+        return vec![];
+    };
+
+    let mut cursor = select_expression.walk();
+    select_expression
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "term")
+        .filter_map(|term| {
+            let value = term.named_child(0)?;
+
+            let alias = child_of_kind(term, "alias")
+                .and_then(|a| child_of_kind(a, "any_identifier"))
+                .map(|n| text(n, sql));
+
+            let expression = expression(value, sql);
+
+            Some(SelectItem {
+                name: alias.or_else(|| default_name(value, &expression, sql)),
+                expression,
+            })
+        })
+        .collect()
+}
+
+/// The name Postgres gives to an unaliased output column.
+fn default_name(value: tree_sitter::Node, expression: &Expression, sql: &str) -> Option<String> {
+    match expression {
+        Expression::Reference { column, .. } => Some(column.clone()),
+        Expression::Invocation { name, .. } => Some(name.clone()),
+        Expression::Typed(type_name) if value.kind() == "cast" => {
+            // `x::int` is named after `x`, `1::int` after the type
+            match child_of_kind(value, "object_reference").map(|r| expression_of_reference(r, sql))
+            {
+                Some(Expression::Reference { column, .. }) => Some(column),
+                _ => Some(type_name.clone()),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn expression(value: tree_sitter::Node, sql: &str) -> Expression {
+    match value.kind() {
+        "object_reference" => expression_of_reference(value, sql),
+
+        "all_fields" => Expression::AllFields {
+            qualifier: child_of_kind(value, "table_identifier").map(|n| text(n, sql)),
+        },
+
+        "cast" => child_of_kind(value, "type")
+            .map(|t| Expression::Typed(text(t, sql)))
+            .unwrap_or(Expression::Unknown),
+
+        "literal" => literal_type(&text(value, sql))
+            .map(|t| Expression::Typed(t.into()))
+            .unwrap_or(Expression::Unknown),
+
+        "invocation" => function_name(value, sql)
+            .map(|(schema, name)| Expression::Invocation { schema, name })
+            .unwrap_or(Expression::Unknown),
+
+        _ => Expression::Unknown,
+    }
+}
+
+fn expression_of_reference(reference: tree_sitter::Node, sql: &str) -> Expression {
+    let parts = identifier_parts(reference, sql);
+
+    match parts.as_slice() {
+        [column] => Expression::Reference {
+            qualifier: None,
+            column: column.clone(),
+        },
+        [.., qualifier, column] => Expression::Reference {
+            qualifier: Some(qualifier.clone()),
+            column: column.clone(),
+        },
+        [] => Expression::Unknown,
+    }
+}
+
+/// The type Postgres resolves an undecorated literal to in a select list.
+fn literal_type(literal: &str) -> Option<&'static str> {
+    let literal = literal.to_lowercase();
+
+    if literal.starts_with('\'') {
+        Some("text")
+    } else if literal == "true" || literal == "false" {
+        Some("boolean")
+    } else if literal.parse::<i32>().is_ok() {
+        Some("integer")
+    } else if literal.parse::<i64>().is_ok() {
+        Some("bigint")
+    } else if literal.parse::<f64>().is_ok() {
+        Some("numeric")
+    } else {
+        None
+    }
+}
+
+fn function_name(invocation: tree_sitter::Node, sql: &str) -> Option<(Option<String>, String)> {
+    let reference = child_of_kind(invocation, "function_reference")?;
+
+    match identifier_parts(reference, sql).as_slice() {
+        [name] => Some((None, name.clone())),
+        [schema, name] => Some((Some(schema.clone()), name.clone())),
+        _ => None,
+    }
+}
+
+/// The relations in the `FROM` clause of a statement or subquery, including joined ones.
+fn source_relations(query: tree_sitter::Node, sql: &str) -> Vec<SourceRelation> {
+    let Some(from) = child_of_kind(query, "from") else {
+        return vec![];
+    };
+
+    let mut cursor = from.walk();
+    from.named_children(&mut cursor)
+        .filter_map(|n| match n.kind() {
+            "relation" => Some(n),
+            "join" => child_of_kind(n, "relation"),
+            _ => None,
+        })
+        .filter_map(|relation| {
+            let alias = child_of_kind(relation, "alias")
+                .and_then(|a| child_of_kind(a, "any_identifier"))
+                .map(|n| text(n, sql));
+
+            match child_of_kind(relation, "table_reference") {
+                Some(table_reference) => match identifier_parts(table_reference, sql).as_slice() {
+                    [name] => Some(SourceRelation {
+                        schema: None,
+                        name: name.clone(),
+                        alias,
+                    }),
+                    [schema, name] => Some(SourceRelation {
+                        schema: Some(schema.clone()),
+                        name: name.clone(),
+                        alias,
+                    }),
+                    _ => None,
+                },
+
+                // derived tables are referenced by their alias
+                None => {
+                    let name = alias.or_else(|| {
+                        child_of_kind(relation, "invocation")
+                            .and_then(|i| function_name(i, sql))
+                            .map(|(_, name)| name)
+                    })?;
+
+                    Some(SourceRelation {
+                        schema: None,
+                        name,
+                        alias: None,
+                    })
+                }
+            }
+        })
+        .collect()
+}
+
+/// The statement or subquery a CTE or `FROM` item belongs to.
+fn enclosing_query(node: tree_sitter::Node) -> tree_sitter::Node {
+    let mut current = node;
+
+    while let Some(parent) = current.parent() {
+        if matches!(parent.kind(), "statement" | "subquery") {
+            return parent;
+        }
+        current = parent;
+    }
+
+    current
+}
+
+fn identifier_parts(reference: tree_sitter::Node, sql: &str) -> Vec<String> {
+    let mut cursor = reference.walk();
+    reference
+        .named_children(&mut cursor)
+        .map(|n| text(n, sql))
+        .collect()
+}
+
+fn child_of_kind<'a>(node: tree_sitter::Node<'a>, kind: &str) -> Option<tree_sitter::Node<'a>> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).find(|c| c.kind() == kind)
+}
+
+fn text(node: tree_sitter::Node, sql: &str) -> String {
+    node.utf8_text(sql.as_bytes())
+        .unwrap_or_default()
+        .replace('"', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use pgls_schema_cache::{Column, ColumnClassKind, Function, SchemaCache};
+
+    use super::{QueryScope, ResolvedColumn};
+
+    fn column(table_oid: i64, table: &str, name: &str, type_name: &str) -> Column {
+        Column {
+            name: name.into(),
+            table_name: table.into(),
+            table_oid,
+            class_kind: ColumnClassKind::OrdinaryTable,
+            number: 1,
+            schema_name: "public".into(),
+            type_id: 0,
+            type_name: Some(type_name.into()),
+            is_nullable: true,
+            is_primary_key: false,
+            is_unique: false,
+            default_expr: None,
+            varchar_length: None,
+            comment: None,
+        }
+    }
+
+    fn schema_cache() -> SchemaCache {
+        SchemaCache {
+            columns: vec![
+                column(1, "users", "id", "int4"),
+                column(1, "users", "email", "text"),
+                column(2, "user_stats", "user_id", "int4"),
+                column(2, "user_stats", "logins", "int8"),
+            ],
+            functions: vec![
+                Function {
+                    name: "stats_for".into(),
+                    schema: "public".into(),
+                    return_type: Some("user_stats".into()),
+                    return_type_relation_id: Some(2),
+                    is_set_returning_function: true,
+                    ..Default::default()
+                },
+                Function {
+                    name: "lower".into(),
+                    schema: "pg_catalog".into(),
+                    return_type: Some("text".into()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn scope(sql: &str) -> QueryScope {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&pgls_treesitter_grammar::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(sql, None).unwrap();
+        QueryScope::new(tree.root_node(), sql)
+    }
+
+    fn resolve(scope: &QueryScope, relation: &str) -> Vec<(String, Option<String>)> {
+        let relation = scope.find_relation(relation).expect("relation not found");
+        scope
+            .resolve_columns(relation, &schema_cache())
+            .into_iter()
+            .map(
+                |ResolvedColumn {
+                     name, type_name, ..
+                 }| (name, type_name),
+            )
+            .collect()
+    }
+
+    fn col(name: &str, type_name: Option<&str>) -> (String, Option<String>) {
+        (name.into(), type_name.map(String::from))
+    }
+
+    #[test]
+    fn resolves_cte_columns() {
+        let scope = scope(
+            "with recent as (select u.id, email as mail, lower(email), 1::int8, 'x' from users u) select * from recent",
+        );
+
+        assert_eq!(
+            resolve(&scope, "recent"),
+            vec![
+                col("id", Some("int4")),
+                col("mail", Some("text")),
+                col("lower", Some("text")),
+                col("int8", Some("int8")),
+                col("?column?", Some("text")),
+            ]
+        );
+    }
+
+    #[test]
+    fn applies_column_aliases() {
+        let scope = scope(
+            "with recent (uid, address) as (select * from users) select * from (values (1, 'a')) v (num, letter)",
+        );
+
+        assert_eq!(
+            resolve(&scope, "recent"),
+            vec![col("uid", Some("int4")), col("address", Some("text"))]
+        );
+        assert_eq!(
+            resolve(&scope, "v"),
+            vec![col("num", Some("integer")), col("letter", Some("text"))]
+        );
+    }
+
+    #[test]
+    fn resolves_nested_derived_tables() {
+        let scope = scope(
+            "with base as (select id from users) select * from (select b.id, s.logins from base b join stats_for(1) s on true) sub",
+        );
+
+        assert_eq!(
+            resolve(&scope, "sub"),
+            vec![col("id", Some("int4")), col("logins", Some("int8"))]
+        );
+        assert_eq!(
+            resolve(&scope, "s"),
+            vec![col("user_id", Some("int4")), col("logins", Some("int8"))]
+        );
+    }
+
+    #[test]
+    fn stops_at_recursive_ctes() {
+        let scope = scope(
+            "with recursive tree as (select id from tree union all select id from users) select * from tree",
+        );
+
+        assert_eq!(resolve(&scope, "tree"), vec![col("id", None)]);
+    }
+}
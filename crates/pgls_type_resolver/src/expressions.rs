@@ -0,0 +1,525 @@
+use pgls_query::NodeEnum;
+use pgls_query::protobuf::{AExprKind, SqlValueFunctionOp, SubLinkType, TypeName};
+use pgls_schema_cache::{CastContext, Function, FunctionArg, Operator, OperatorKind, SchemaCache};
+
+use crate::util::get_string_from_node;
+
+/// Pseudo-types that accept any concrete type as input.
+const POLYMORPHIC_TYPES: &[&str] = &[
+    "any",
+    "anyelement",
+    "anyarray",
+    "anynonarray",
+    "anyenum",
+    "anyrange",
+    "anymultirange",
+    "anycompatible",
+    "anycompatiblearray",
+    "anycompatiblenonarray",
+    "anycompatiblerange",
+    "anycompatiblemultirange",
+];
+
+/// The inferred type of an expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExprType {
+    /// Refers to the type's ID in the `pg_type` table.
+    Known(i64),
+
+    /// An untyped literal, e.g. `'foo'` or `NULL`. Its type is decided by the context it is used in.
+    Unknown,
+}
+
+impl ExprType {
+    pub fn type_id(&self) -> Option<i64> {
+        match self {
+            ExprType::Known(id) => Some(*id),
+            ExprType::Unknown => None,
+        }
+    }
+}
+
+/// A relation whose columns can be referenced by the expression.
+#[derive(Debug, Clone, Default)]
+pub struct ScopeRelation {
+    pub schema: Option<String>,
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+impl ScopeRelation {
+    fn is_referenced_by(&self, schema: Option<&str>, name: &str) -> bool {
+        match (schema, &self.alias) {
+            (None, Some(alias)) => alias == name,
+            _ => {
+                self.name == name
+                    && schema.is_none_or(|s| self.schema.as_deref().is_none_or(|own| own == s))
+            }
+        }
+    }
+}
+
+/// Infers the type of an expression that does not reference any columns.
+pub fn infer_expr_type(node: &NodeEnum, schema_cache: &SchemaCache) -> Option<ExprType> {
+    ExprTypeResolver::new(schema_cache).infer(node)
+}
+
+/// Infers the result type of pg_query expression nodes the way Postgres would,
+/// by resolving operators, functions and casts against the schema cache.
+pub struct ExprTypeResolver<'a> {
+    schema_cache: &'a SchemaCache,
+    relations: Vec<ScopeRelation>,
+}
+
+impl<'a> ExprTypeResolver<'a> {
+    pub fn new(schema_cache: &'a SchemaCache) -> Self {
+        Self {
+            schema_cache,
+            relations: vec![],
+        }
+    }
+
+    /// Makes the columns of the given relations available to column references.
+    pub fn with_relations(mut self, relations: Vec<ScopeRelation>) -> Self {
+        self.relations.extend(relations);
+        self
+    }
+
+    /// Makes the relations in the `FROM` clause of the statement available to column references.
+    pub fn with_from_clause(mut self, from_clause: &[pgls_query::protobuf::Node]) -> Self {
+        for node in from_clause {
+            if let Some(node) = node.node.as_ref() {
+                collect_relations(node, &mut self.relations);
+            }
+        }
+        self
+    }
+
+    pub fn infer(&self, node: &NodeEnum) -> Option<ExprType> {
+        match node {
+            NodeEnum::AConst(n) => {
+                if n.isnull {
+                    return Some(ExprType::Unknown);
+                }
+                match n.val.as_ref()? {
+                    pgls_query::protobuf::a_const::Val::Ival(_) => self.builtin("int4"),
+                    pgls_query::protobuf::a_const::Val::Fval(_) => self.builtin("numeric"),
+                    pgls_query::protobuf::a_const::Val::Boolval(_) => self.builtin("bool"),
+                    pgls_query::protobuf::a_const::Val::Sval(_) => Some(ExprType::Unknown),
+                    pgls_query::protobuf::a_const::Val::Bsval(_) => self.builtin("bit"),
+                }
+            }
+            NodeEnum::TypeCast(n) => self.resolve_type_name(n.type_name.as_ref()?),
+            NodeEnum::CollateClause(n) => self.infer(n.arg.as_ref()?.node.as_ref()?),
+            NodeEnum::NamedArgExpr(n) => self.infer(n.arg.as_ref()?.node.as_ref()?),
+            NodeEnum::ColumnRef(n) => self.resolve_column_ref(&n.fields),
+            NodeEnum::AExpr(n) => match n.kind() {
+                AExprKind::AexprOp => {
+                    let name = n.name.last().map(get_string_from_node)?;
+                    let schema = (n.name.len() > 1).then(|| get_string_from_node(&n.name[0]));
+                    let left = self.infer_boxed(n.lexpr.as_deref())?;
+                    let right = self.infer_boxed(n.rexpr.as_deref())?;
+                    self.resolve_operator_call(schema.as_deref(), &name, left, right)
+                }
+                AExprKind::AexprNullif => self.infer_boxed(n.lexpr.as_deref())?,
+                AExprKind::Undefined => None,
+                _ => self.builtin("bool"),
+            },
+            NodeEnum::BoolExpr(_) | NodeEnum::NullTest(_) | NodeEnum::BooleanTest(_) => {
+                self.builtin("bool")
+            }
+            NodeEnum::SubLink(n) => match n.sub_link_type() {
+                SubLinkType::ExprSublink | SubLinkType::ArraySublink => {
+                    let NodeEnum::SelectStmt(select) = n.subselect.as_ref()?.node.as_ref()? else {
+                        return None;
+                    };
+                    let target = select.target_list.first()?;
+                    let NodeEnum::ResTarget(target) = target.node.as_ref()? else {
+                        return None;
+                    };
+
+                    let inner = ExprTypeResolver {
+                        schema_cache: self.schema_cache,
+                        relations: self.relations.clone(),
+                    }
+                    .with_from_clause(&select.from_clause);
+
+                    let element = inner.infer(target.val.as_ref()?.node.as_ref()?)?;
+                    if n.sub_link_type() == SubLinkType::ArraySublink {
+                        self.array_of(element)
+                    } else {
+                        Some(element)
+                    }
+                }
+                SubLinkType::Undefined | SubLinkType::CteSublink => None,
+                _ => self.builtin("bool"),
+            },
+            NodeEnum::FuncCall(n) => {
+                let name = n.funcname.last().map(get_string_from_node)?;
+                let schema = (n.funcname.len() > 1).then(|| get_string_from_node(&n.funcname[0]));
+                let args = n
+                    .args
+                    .iter()
+                    .map(|a| self.infer(a.node.as_ref()?))
+                    .collect::<Option<Vec<_>>>()?;
+                self.resolve_function_call(schema.as_deref(), &name, &args)
+            }
+            NodeEnum::CoalesceExpr(n) => self.common_type(&n.args),
+            NodeEnum::MinMaxExpr(n) => self.common_type(&n.args),
+            NodeEnum::CaseExpr(n) => {
+                let mut results: Vec<pgls_query::protobuf::Node> = n
+                    .args
+                    .iter()
+                    .filter_map(|w| match w.node.as_ref()? {
+                        NodeEnum::CaseWhen(w) => w.result.as_deref().cloned(),
+                        _ => None,
+                    })
+                    .collect();
+                if let Some(default) = n.defresult.as_deref() {
+                    results.push(default.clone());
+                }
+                self.common_type(&results)
+            }
+            NodeEnum::AArrayExpr(n) => {
+                let element = self.common_type(&n.elements)?;
+                self.array_of(element)
+            }
+            NodeEnum::RowExpr(_) => self.builtin("record"),
+            NodeEnum::SqlvalueFunction(n) => self.builtin(match n.op() {
+                SqlValueFunctionOp::SvfopCurrentDate => "date",
+                SqlValueFunctionOp::SvfopCurrentTime | SqlValueFunctionOp::SvfopCurrentTimeN => {
+                    "timetz"
+                }
+                SqlValueFunctionOp::SvfopCurrentTimestamp
+                | SqlValueFunctionOp::SvfopCurrentTimestampN => "timestamptz",
+                SqlValueFunctionOp::SvfopLocaltime | SqlValueFunctionOp::SvfopLocaltimeN => "time",
+                SqlValueFunctionOp::SvfopLocaltimestamp
+                | SqlValueFunctionOp::SvfopLocaltimestampN => "timestamp",
+                SqlValueFunctionOp::SqlvalueFunctionOpUndefined => return None,
+                _ => "name",
+            }),
+            _ => None,
+        }
+    }
+
+    fn infer_boxed(&self, node: Option<&pgls_query::protobuf::Node>) -> Option<Option<ExprType>> {
+        match node {
+            Some(n) => self.infer(n.node.as_ref()?).map(Some),
+            None => Some(None),
+        }
+    }
+
+    fn builtin(&self, name: &str) -> Option<ExprType> {
+        self.schema_cache
+            .find_type(name, Some("pg_catalog"))
+            .map(|t| ExprType::Known(t.id))
+    }
+
+    fn array_of(&self, element: ExprType) -> Option<ExprType> {
+        let element = match element {
+            ExprType::Known(id) => self.schema_cache.find_type_by_id(id)?,
+            ExprType::Unknown => self.schema_cache.find_type("text", Some("pg_catalog"))?,
+        };
+        self.schema_cache
+            .find_type(&format!("_{}", element.name), Some(&element.schema))
+            .map(|t| ExprType::Known(t.id))
+    }
+
+    fn resolve_type_name(&self, type_name: &TypeName) -> Option<ExprType> {
+        let name = type_name.names.last().map(get_string_from_node)?;
+        let schema = (type_name.names.len() > 1).then(|| get_string_from_node(&type_name.names[0]));
+
+        let resolved = self
+            .schema_cache
+            .find_type(&name, schema.as_deref())
+            .map(|t| ExprType::Known(t.id))?;
+
+        if type_name.array_bounds.is_empty() {
+            Some(resolved)
+        } else {
+            self.array_of(resolved)
+        }
+    }
+
+    fn resolve_column_ref(&self, fields: &[pgls_query::protobuf::Node]) -> Option<ExprType> {
+        let parts = fields
+            .iter()
+            .map(|f| match f.node.as_ref()? {
+                NodeEnum::String(s) => Some(s.sval.as_str()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let (schema, relation, column) = match parts.as_slice() {
+            [column] => (None, None, *column),
+            [relation, column] => (None, Some(*relation), *column),
+            [schema, relation, column] => (Some(*schema), Some(*relation), *column),
+            _ => return None,
+        };
+
+        let mut type_ids = self
+            .relations
+            .iter()
+            .filter(|r| relation.is_none_or(|rel| r.is_referenced_by(schema, rel)))
+            .flat_map(|r| {
+                self.schema_cache
+                    .find_cols(column, Some(&r.name), r.schema.as_deref())
+            })
+            .map(|c| c.type_id);
+
+        let type_id = type_ids.next()?;
+        // an ambiguous column reference has no type
+        if type_ids.next().is_some() {
+            return None;
+        }
+        Some(ExprType::Known(type_id))
+    }
+
+    fn resolve_operator_call(
+        &self,
+        schema: Option<&str>,
+        name: &str,
+        left: Option<ExprType>,
+        right: Option<ExprType>,
+    ) -> Option<ExprType> {
+        let args: Vec<ExprType> = [left, right].into_iter().flatten().collect();
+        let operator = resolve_operator(self.schema_cache, schema, name, left, right)?;
+        let params: Vec<i64> = operator
+            .left_type_id
+            .into_iter()
+            .chain(operator.right_type_id)
+            .collect();
+        self.resolve_result_type(operator.result_type_id, &params, &args)
+    }
+
+    fn resolve_function_call(
+        &self,
+        schema: Option<&str>,
+        name: &str,
+        args: &[ExprType],
+    ) -> Option<ExprType> {
+        let function = resolve_function(self.schema_cache, schema, name, args)?;
+        let params: Vec<i64> = input_params(function).map(|a| a.type_id).collect();
+        self.resolve_result_type(function.return_type_id?, &params, args)
+    }
+
+    /// Polymorphic result types take the type of the argument passed for the matching parameter.
+    fn resolve_result_type(
+        &self,
+        result_type_id: i64,
+        params: &[i64],
+        args: &[ExprType],
+    ) -> Option<ExprType> {
+        if !is_polymorphic(self.schema_cache, result_type_id) {
+            return Some(ExprType::Known(result_type_id));
+        }
+
+        params
+            .iter()
+            .zip(args)
+            .find(|(param, _)| **param == result_type_id)
+            .and_then(|(_, arg)| match arg {
+                ExprType::Known(_) => Some(*arg),
+                ExprType::Unknown => None,
+            })
+    }
+
+    /// Resolves the common type of `CASE`, `COALESCE`, `GREATEST`, `ARRAY[]` and friends.
+    fn common_type(&self, nodes: &[pgls_query::protobuf::Node]) -> Option<ExprType> {
+        let types = nodes
+            .iter()
+            .map(|n| self.infer(n.node.as_ref()?))
+            .collect::<Option<Vec<_>>>()?;
+
+        let known: Vec<i64> = types.iter().filter_map(|t| t.type_id()).collect();
+        if known.is_empty() {
+            return self.builtin("text");
+        }
+
+        known
+            .iter()
+            .find(|candidate| {
+                known
+                    .iter()
+                    .all(|t| is_implicitly_coercible(self.schema_cache, *t, **candidate))
+            })
+            .map(|id| ExprType::Known(*id))
+    }
+}
+
+fn collect_relations(node: &NodeEnum, relations: &mut Vec<ScopeRelation>) {
+    match node {
+        NodeEnum::RangeVar(r) => relations.push(ScopeRelation {
+            schema: (!r.schemaname.is_empty()).then(|| r.schemaname.clone()),
+            name: r.relname.clone(),
+            alias: r.alias.as_ref().map(|a| a.aliasname.clone()),
+        }),
+        NodeEnum::JoinExpr(j) => {
+            for arg in [j.larg.as_deref(), j.rarg.as_deref()].into_iter().flatten() {
+                if let Some(node) = arg.node.as_ref() {
+                    collect_relations(node, relations);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_polymorphic(schema_cache: &SchemaCache, type_id: i64) -> bool {
+    schema_cache
+        .find_type_by_id(type_id)
+        .is_some_and(|t| t.schema == "pg_catalog" && POLYMORPHIC_TYPES.contains(&t.name.as_str()))
+}
+
+/// Returns whether Postgres converts a value of `source_type_id` into `target_type_id` without an explicit cast.
+pub fn is_implicitly_coercible(
+    schema_cache: &SchemaCache,
+    source_type_id: i64,
+    target_type_id: i64,
+) -> bool {
+    source_type_id == target_type_id
+        || is_polymorphic(schema_cache, target_type_id)
+        || schema_cache
+            .find_cast(source_type_id, target_type_id)
+            .is_some_and(|c| c.context == CastContext::Implicit)
+}
+
+/// Resolves the operator Postgres would pick for the given operand types.
+/// `left` is `None` for prefix operators.
+pub fn resolve_operator<'a>(
+    schema_cache: &'a SchemaCache,
+    schema: Option<&str>,
+    name: &str,
+    left: Option<ExprType>,
+    right: Option<ExprType>,
+) -> Option<&'a Operator> {
+    let kind = match (left, right) {
+        (Some(_), Some(_)) => OperatorKind::Infix,
+        (None, Some(_)) => OperatorKind::Prefix,
+        (Some(_), None) => OperatorKind::Postfix,
+        (None, None) => return None,
+    };
+
+    let args: Vec<ExprType> = [left, right].into_iter().flatten().collect();
+
+    let candidates = schema_cache
+        .find_operators(name, schema)
+        .into_iter()
+        .filter(|o| o.kind == kind)
+        .map(|o| {
+            let params = o.left_type_id.into_iter().chain(o.right_type_id).collect();
+            (o, params)
+        })
+        .collect();
+
+    select_candidate(schema_cache, candidates, &args)
+}
+
+/// Resolves the function overload Postgres would pick for the given argument types.
+pub fn resolve_function<'a>(
+    schema_cache: &'a SchemaCache,
+    schema: Option<&str>,
+    name: &str,
+    args: &[ExprType],
+) -> Option<&'a Function> {
+    let candidates = schema_cache
+        .find_functions(name, schema)
+        .into_iter()
+        .filter_map(|f| {
+            let params: Vec<i64> = input_params(f).map(|a| a.type_id).collect();
+            let defaults = input_params(f)
+                .filter(|a| a.has_default.unwrap_or(false))
+                .count();
+            let required = params.len().saturating_sub(defaults);
+
+            // parameters with defaults may be omitted
+            (required <= args.len() && args.len() <= params.len())
+                .then(|| (f, params.into_iter().take(args.len()).collect()))
+        })
+        .collect();
+
+    select_candidate(schema_cache, candidates, args)
+}
+
+fn input_params(function: &Function) -> impl Iterator<Item = &FunctionArg> + '_ {
+    function
+        .args
+        .args
+        .iter()
+        .filter(|a| matches!(a.mode.as_str(), "in" | "inout" | "variadic"))
+}
+
+/// A simplified version of the overload resolution Postgres uses for operators and functions.
+fn select_candidate<'a, T>(
+    schema_cache: &SchemaCache,
+    candidates: Vec<(&'a T, Vec<i64>)>,
+    args: &[ExprType],
+) -> Option<&'a T> {
+    // if one side is an untyped literal, it is assumed to have the type of the other side
+    let assumed: Vec<ExprType> = match args {
+        [ExprType::Unknown, ExprType::Known(id)] => vec![ExprType::Known(*id); 2],
+        [ExprType::Known(id), ExprType::Unknown] => vec![ExprType::Known(*id); 2],
+        _ => args.to_vec(),
+    };
+
+    let exact_matches = |params: &[i64], args: &[ExprType]| {
+        params
+            .iter()
+            .zip(args)
+            .filter(|(p, a)| a.type_id() == Some(**p))
+            .count()
+    };
+
+    if let Some((c, _)) = candidates
+        .iter()
+        .find(|(_, params)| exact_matches(params, &assumed) == args.len())
+    {
+        return Some(*c);
+    }
+
+    let mut viable: Vec<(&'a T, Vec<i64>)> = candidates
+        .into_iter()
+        .filter(|(_, params)| {
+            params.len() == args.len()
+                && params.iter().zip(args).all(|(p, a)| match a {
+                    ExprType::Known(id) => is_implicitly_coercible(schema_cache, *id, *p),
+                    ExprType::Unknown => true,
+                })
+        })
+        .collect();
+
+    let text = schema_cache
+        .find_type("text", Some("pg_catalog"))
+        .map(|t| t.id);
+    retain_best(&mut viable, |params| exact_matches(params, args));
+
+    // untyped literals prefer the string category
+    retain_best(&mut viable, |params| {
+        params
+            .iter()
+            .zip(args)
+            .filter(|(p, a)| **a == ExprType::Unknown && Some(**p) == text)
+            .count()
+    });
+
+    retain_best(&mut viable, |params| {
+        params
+            .iter()
+            .filter(|p| !is_polymorphic(schema_cache, **p))
+            .count()
+    });
+
+    match viable.as_slice() {
+        [(c, _)] => Some(*c),
+        _ => None,
+    }
+}
+
+/// Keeps the candidates that rank highest, unless the choice is already made.
+fn retain_best<T>(candidates: &mut Vec<(T, Vec<i64>)>, rank: impl Fn(&[i64]) -> usize) {
+    if candidates.len() <= 1 {
+        return;
+    }
+    let best = candidates.iter().map(|(_, p)| rank(p)).max().unwrap_or(0);
+    candidates.retain(|(_, p)| rank(p) == best);
+}
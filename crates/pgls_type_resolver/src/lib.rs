@@ -1,5 +1,10 @@
+mod expressions;
 mod functions;
 mod types;
 mod util;
 
+pub use expressions::{
+    ExprType, ExprTypeResolver, ScopeRelation, infer_expr_type, is_implicitly_coercible,
+    resolve_function, resolve_operator,
+};
 pub use functions::resolve_func_call;
@@ -0,0 +1,169 @@
+use pgls_schema_cache::SchemaCache;
+use pgls_type_resolver::{ExprType, ExprTypeResolver};
+use sqlx::{Executor, PgPool};
+
+async fn infer(test_db: &PgPool, setup: Option<&str>, query: &str) -> Option<String> {
+    if let Some(setup) = setup {
+        test_db
+            .execute(setup)
+            .await
+            .expect("Failed to setup test database");
+    }
+
+    let schema_cache = SchemaCache::load(test_db)
+        .await
+        .expect("Failed to load Schema Cache");
+
+    let root = pgls_query::parse(query)
+        .unwrap()
+        .into_root()
+        .expect("Failed to parse query");
+
+    let pgls_query::NodeEnum::SelectStmt(select) = root else {
+        panic!("Expected a select statement");
+    };
+
+    let pgls_query::NodeEnum::ResTarget(target) = select.target_list[0].node.as_ref().unwrap()
+    else {
+        panic!("Expected a target");
+    };
+
+    let resolver = ExprTypeResolver::new(&schema_cache).with_from_clause(&select.from_clause);
+
+    match resolver.infer(target.val.as_ref().unwrap().node.as_ref().unwrap())? {
+        ExprType::Known(id) => schema_cache.find_type_by_id(id).map(|t| t.name.clone()),
+        ExprType::Unknown => Some("unknown".into()),
+    }
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn infers_literals_and_casts(test_db: PgPool) {
+    assert_eq!(
+        infer(&test_db, None, "select 1").await.as_deref(),
+        Some("int4")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select 1.5").await.as_deref(),
+        Some("numeric")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select 'a'").await.as_deref(),
+        Some("unknown")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select '{}'::jsonb").await.as_deref(),
+        Some("jsonb")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select '{1}'::int[]")
+            .await
+            .as_deref(),
+        Some("_int4")
+    );
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn infers_operators(test_db: PgPool) {
+    assert_eq!(
+        infer(&test_db, None, "select 'a' || 'b'").await.as_deref(),
+        Some("text")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select '{}'::jsonb -> 'k'")
+            .await
+            .as_deref(),
+        Some("jsonb")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select '{}'::jsonb ->> 'k'")
+            .await
+            .as_deref(),
+        Some("text")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select now() + interval '1 day'")
+            .await
+            .as_deref(),
+        Some("timestamptz")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select 1 = 2").await.as_deref(),
+        Some("bool")
+    );
+    assert_eq!(
+        infer(
+            &test_db,
+            Some("create table public.counters (id int8);"),
+            "select -id from public.counters"
+        )
+        .await
+        .as_deref(),
+        Some("int8")
+    );
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn applies_implicit_casts(test_db: PgPool) {
+    assert_eq!(
+        infer(&test_db, None, "select 1 + 2::int8").await.as_deref(),
+        Some("int8")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select coalesce(null, 1, 2::int8)")
+            .await
+            .as_deref(),
+        Some("int8")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select case when true then 1 else 2.5 end")
+            .await
+            .as_deref(),
+        Some("numeric")
+    );
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn infers_columns_and_functions(test_db: PgPool) {
+    let setup = r#"
+        create table public.events (
+            id serial primary key,
+            created_at timestamp not null,
+            payload jsonb
+        );
+    "#;
+
+    assert_eq!(
+        infer(
+            &test_db,
+            Some(setup),
+            "select e.created_at + interval '1 day' from public.events e"
+        )
+        .await
+        .as_deref(),
+        Some("timestamp")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select payload -> 'k' from events")
+            .await
+            .as_deref(),
+        Some("jsonb")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select count(*) from events")
+            .await
+            .as_deref(),
+        Some("int8")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select lower(id::text) from events")
+            .await
+            .as_deref(),
+        Some("text")
+    );
+    assert_eq!(
+        infer(&test_db, None, "select (select max(id) from events)")
+            .await
+            .as_deref(),
+        Some("int4")
+    );
+}
@@ -0,0 +1,226 @@
+use std::borrow::Cow;
+
+use pgls_query::NodeEnum;
+use pgls_schema_cache::SchemaCache;
+use sqlx::postgres::PgDatabaseError;
+use sqlx::{Acquire, Executor, PgPool, Postgres, Transaction};
+
+use crate::TypecheckDiagnostic;
+use crate::diagnostics::create_type_error;
+use crate::search_path_query;
+use crate::typed_identifier::TypedReplacement;
+
+/// Keeps statements on large or busy tables from blocking others while they are checked.
+const LOCK_TIMEOUT: &str = "1s";
+const STATEMENT_TIMEOUT: &str = "10s";
+
+#[derive(Debug)]
+pub struct DdlStatement<'a> {
+    pub sql: &'a str,
+    pub ast: &'a NodeEnum,
+    pub tree: &'a tree_sitter::Tree,
+}
+
+#[derive(Debug)]
+pub struct DdlCheckParams<'a> {
+    pub conn: &'a PgPool,
+    /// The statements of a file, in order. Statements that are neither DDL nor queries are
+    /// skipped.
+    pub statements: &'a [DdlStatement<'a>],
+    pub schema_cache: &'a SchemaCache,
+    /// Set of glob patterns that will be matched against the schemas in the database.
+    /// Each matching schema will be added to the search_path for the typecheck.
+    pub search_path_patterns: Vec<String>,
+}
+
+/// Executes the DDL statements one after another inside a transaction that is rolled back, and
+/// prepares the queries in between, so that each statement sees the effects of the ones before
+/// it.
+///
+/// Returns the errors together with the index of the statement that caused them.
+pub async fn check_ddl(
+    params: DdlCheckParams<'_>,
+) -> Result<Vec<(usize, TypecheckDiagnostic)>, sqlx::Error> {
+    if !params
+        .statements
+        .iter()
+        .any(|s| is_ddl(s.ast) || is_query(s.ast))
+    {
+        return Ok(vec![]);
+    }
+
+    let mut conn = params.conn.acquire().await?;
+
+    // the statements might leave behind cached plans for objects that were never committed
+    conn.close_on_drop();
+
+    let mut tx: Transaction<'_, Postgres> = conn.begin().await?;
+
+    sqlx::query(&format!("SET LOCAL lock_timeout = '{LOCK_TIMEOUT}'"))
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(&format!(
+        "SET LOCAL statement_timeout = '{STATEMENT_TIMEOUT}'"
+    ))
+    .execute(&mut *tx)
+    .await?;
+
+    if let Some(query) = search_path_query(params.schema_cache, params.search_path_patterns) {
+        sqlx::query(&query).execute(&mut *tx).await?;
+    }
+
+    let mut diagnostics = vec![];
+
+    for (idx, statement) in params.statements.iter().enumerate() {
+        let is_query = is_query(statement.ast);
+        if !is_ddl(statement.ast) && !is_query {
+            continue;
+        }
+
+        // a failed statement aborts the transaction, so every statement gets a savepoint that
+        // we can roll back to and continue with the next one
+        sqlx::query("SAVEPOINT ddl_check").execute(&mut *tx).await?;
+
+        // queries are only prepared, so that they don't change any data
+        let result = if is_query {
+            (&mut *tx).prepare(statement.sql).await.map(|_| ())
+        } else {
+            let sql = match statement.ast {
+                // the query of `create table as` would run for real, so the relation is only
+                // created from its result columns
+                NodeEnum::CreateTableAsStmt(stmt)
+                    if stmt.into.as_ref().is_some_and(|into| !into.skip_data) =>
+                {
+                    Cow::Owned(without_data(statement.sql))
+                }
+                _ => Cow::Borrowed(statement.sql),
+            };
+
+            sqlx::query(&sql).execute(&mut *tx).await.map(|_| ())
+        };
+
+        match result {
+            Ok(_) => {
+                sqlx::query("RELEASE SAVEPOINT ddl_check")
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            Err(sqlx::Error::Database(err)) => {
+                let pg_err = err.downcast_ref::<PgDatabaseError>();
+                if is_environmental(pg_err.code()) {
+                    tracing::debug!(
+                        "Skipping DDL check of statement {idx}: {} ({})",
+                        pg_err.message(),
+                        pg_err.code()
+                    );
+                } else {
+                    diagnostics.push((
+                        idx,
+                        create_type_error(
+                            pg_err,
+                            statement.tree,
+                            TypedReplacement::new(statement.sql, vec![]),
+                        ),
+                    ));
+                }
+
+                sqlx::query("ROLLBACK TO SAVEPOINT ddl_check")
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    tx.rollback().await?;
+
+    Ok(diagnostics)
+}
+
+/// Rewrites a `create table as` or `create materialized view` statement to end with
+/// `with no data`, so that its query is analyzed but never executed.
+///
+/// Only the end of the statement changes, so error positions still point into the original text.
+fn without_data(sql: &str) -> String {
+    let sql = sql.trim_end();
+    let sql = sql.strip_suffix(';').unwrap_or(sql).trim_end();
+
+    let lowercase = sql.to_ascii_lowercase();
+    if let Some(idx) = lowercase.rfind("with")
+        && lowercase[idx + "with".len()..]
+            .split_whitespace()
+            .eq(["data"])
+    {
+        return format!("{}with no data", &sql[..idx]);
+    }
+
+    // on a new line, in case the statement ends with a line comment
+    format!("{sql}\nwith no data")
+}
+
+/// Whether an error is caused by the state of the database rather than by the statement,
+/// e.g. a timeout while waiting for a lock held by another session.
+fn is_environmental(code: &str) -> bool {
+    matches!(
+        code,
+        // lock_not_available, query_canceled (statement_timeout)
+        "55P03" | "57014"
+            // serialization_failure, deadlock_detected
+            | "40001" | "40P01"
+    ) || ["53", "57", "58", "XX"]
+        .iter()
+        // insufficient resources, operator intervention, system and internal errors
+        .any(|class| code.starts_with(class))
+}
+
+/// Whether the statement changes the schema and can be executed inside a transaction.
+///
+/// Covers the creation, alteration, renaming and removal of relations, types, functions,
+/// policies and the like, as well as comments and grants. Statements that can't run inside a
+/// transaction block, e.g. `create index concurrently`, are left out.
+fn is_ddl(ast: &NodeEnum) -> bool {
+    match ast {
+        // cannot run inside a transaction block
+        NodeEnum::IndexStmt(stmt) => !stmt.concurrent,
+        NodeEnum::DropStmt(stmt) => !stmt.concurrent,
+        NodeEnum::CreateStmt(_)
+        | NodeEnum::CreateTableAsStmt(_)
+        | NodeEnum::AlterTableStmt(_)
+        | NodeEnum::ViewStmt(_)
+        | NodeEnum::CreatePolicyStmt(_)
+        | NodeEnum::AlterPolicyStmt(_)
+        | NodeEnum::CreateFunctionStmt(_)
+        | NodeEnum::AlterFunctionStmt(_)
+        | NodeEnum::CreateTrigStmt(_)
+        | NodeEnum::CreateSchemaStmt(_)
+        | NodeEnum::CreateSeqStmt(_)
+        | NodeEnum::AlterSeqStmt(_)
+        | NodeEnum::CreateEnumStmt(_)
+        | NodeEnum::AlterEnumStmt(_)
+        | NodeEnum::CompositeTypeStmt(_)
+        | NodeEnum::CreateDomainStmt(_)
+        | NodeEnum::AlterDomainStmt(_)
+        | NodeEnum::DefineStmt(_)
+        | NodeEnum::CreateExtensionStmt(_)
+        | NodeEnum::CreateForeignTableStmt(_)
+        | NodeEnum::RenameStmt(_)
+        | NodeEnum::AlterObjectSchemaStmt(_)
+        | NodeEnum::AlterOwnerStmt(_)
+        | NodeEnum::CommentStmt(_)
+        | NodeEnum::GrantStmt(_)
+        | NodeEnum::RuleStmt(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether the statement is a query that the regular typecheck would prepare.
+pub(crate) fn is_query(ast: &NodeEnum) -> bool {
+    matches!(
+        ast,
+        NodeEnum::SelectStmt(_)
+            | NodeEnum::InsertStmt(_)
+            | NodeEnum::UpdateStmt(_)
+            | NodeEnum::DeleteStmt(_)
+            | NodeEnum::CommonTableExpr(_)
+    )
+}
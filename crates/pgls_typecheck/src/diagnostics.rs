@@ -3,11 +3,16 @@ use std::io;
 use once_cell::sync::Lazy;
 use pgls_console::markup;
 use pgls_diagnostics::{Advices, Diagnostic, LogCategory, MessageAndDescription, Severity, Visit};
-use pgls_text_size::{TextRange, TextSize};
+use pgls_text_size::TextRange;
+#[cfg(feature = "db")]
+use pgls_text_size::TextSize;
 use regex::Regex;
+#[cfg(feature = "db")]
 use sqlx::postgres::{PgDatabaseError, PgSeverity};
 
-use crate::typed_identifier::{IdentifierReplacement, TypedReplacement};
+use crate::typed_identifier::IdentifierReplacement;
+#[cfg(feature = "db")]
+use crate::typed_identifier::TypedReplacement;
 
 /// A specialized diagnostic for the typechecker.
 ///
@@ -26,6 +31,36 @@ pub struct TypecheckDiagnostic {
     severity: Severity,
 }
 
+impl TypecheckDiagnostic {
+    /// Creates an error for a name that does not resolve, with the error code Postgres would report.
+    pub(crate) fn unresolved_name(
+        code: &str,
+        message: String,
+        span: Option<TextRange>,
+        hint: Option<String>,
+    ) -> Self {
+        Self {
+            span,
+            message: message.into(),
+            severity: Severity::Error,
+            advices: TypecheckAdvices {
+                code: code.to_string(),
+                schema: None,
+                table: None,
+                column: None,
+                data_type: None,
+                constraint: None,
+                detail: None,
+                where_: None,
+                hint,
+                line: None,
+                file: None,
+                routine: None,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct TypecheckAdvices {
     code: String,
@@ -149,6 +184,7 @@ pub fn rewrite_error_message(
         .replace(&format!("'{unquoted_default}'"), &replacement.type_name)
 }
 
+#[cfg(feature = "db")]
 pub(crate) fn create_type_error(
     pg_err: &PgDatabaseError,
     ts: &tree_sitter::Tree,
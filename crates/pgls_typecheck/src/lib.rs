@@ -1,17 +1,29 @@
+#[cfg(feature = "db")]
+mod ddl;
 pub mod diagnostics;
+mod name_resolution;
 pub mod typed_identifier;
 
+#[cfg(feature = "db")]
+pub use ddl::{DdlCheckParams, DdlStatement, check_ddl};
 pub use diagnostics::TypecheckDiagnostic;
+#[cfg(feature = "db")]
 use diagnostics::create_type_error;
 use globset::Glob;
 use itertools::Itertools;
+pub use name_resolution::{NameResolutionParams, NameResolver};
 use pgls_schema_cache::SchemaCache;
+#[cfg(feature = "db")]
 use sqlx::postgres::PgDatabaseError;
+#[cfg(feature = "db")]
 pub use sqlx::postgres::PgSeverity;
+#[cfg(feature = "db")]
 use sqlx::{Executor, PgPool};
+#[cfg(feature = "db")]
 use typed_identifier::apply_identifiers;
 pub use typed_identifier::{IdentifierReplacement, IdentifierType, TypedIdentifier};
 
+#[cfg(feature = "db")]
 #[derive(Debug)]
 pub struct TypecheckParams<'a> {
     pub conn: &'a PgPool,
@@ -25,18 +37,12 @@ pub struct TypecheckParams<'a> {
     pub search_path_patterns: Vec<String>,
 }
 
+#[cfg(feature = "db")]
 pub async fn check_sql(
     params: TypecheckParams<'_>,
 ) -> Result<Option<TypecheckDiagnostic>, sqlx::Error> {
     // Check if the AST is not a supported statement type
-    if !matches!(
-        params.ast,
-        pgls_query::NodeEnum::SelectStmt(_)
-            | pgls_query::NodeEnum::InsertStmt(_)
-            | pgls_query::NodeEnum::UpdateStmt(_)
-            | pgls_query::NodeEnum::DeleteStmt(_)
-            | pgls_query::NodeEnum::CommonTableExpr(_)
-    ) {
+    if !ddl::is_query(params.ast) {
         return Ok(None);
     }
 
@@ -55,17 +61,8 @@ pub async fn check_sql(
         params.sql,
     );
 
-    let mut search_path_schemas =
-        get_schemas_in_search_path(params.schema_cache, params.search_path_patterns);
-
-    if !search_path_schemas.is_empty() {
-        // Always include public if we have any schemas in search path
-        if !search_path_schemas.contains(&"public") {
-            search_path_schemas.push("public");
-        }
-
-        let search_path_query = format!("SET search_path TO {};", search_path_schemas.join(", "));
-        conn.execute(&*search_path_query).await?;
+    if let Some(query) = search_path_query(params.schema_cache, params.search_path_patterns) {
+        conn.execute(&*query).await?;
     }
 
     let res = conn
@@ -86,6 +83,26 @@ pub async fn check_sql(
     }
 }
 
+/// Builds the `SET search_path` statement for the schemas matching the patterns, if any match.
+#[cfg(feature = "db")]
+fn search_path_query(schema_cache: &SchemaCache, glob_patterns: Vec<String>) -> Option<String> {
+    let mut search_path_schemas = get_schemas_in_search_path(schema_cache, glob_patterns);
+
+    if search_path_schemas.is_empty() {
+        return None;
+    }
+
+    // Always include public if we have any schemas in search path
+    if !search_path_schemas.contains(&"public") {
+        search_path_schemas.push("public");
+    }
+
+    Some(format!(
+        "SET search_path TO {};",
+        search_path_schemas.join(", ")
+    ))
+}
+
 fn get_schemas_in_search_path(schema_cache: &SchemaCache, glob_patterns: Vec<String>) -> Vec<&str> {
     // iterate over glob_patterns on the outside to keep the order
     glob_patterns
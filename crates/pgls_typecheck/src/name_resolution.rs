@@ -0,0 +1,1983 @@
+use std::collections::{HashMap, HashSet};
+
+use pgls_query::protobuf::{
+    self, AlterTableType, CoercionForm, ConstrType, Node, ObjectType, SetOperation, SubLinkType,
+};
+use pgls_query::{NodeEnum, NodeRef};
+use pgls_schema_cache::SchemaCache;
+use pgls_text_size::{TextRange, TextSize};
+
+use crate::TypecheckDiagnostic;
+use crate::get_schemas_in_search_path;
+use crate::typed_identifier::TypedIdentifier;
+
+const UNDEFINED_TABLE: &str = "42P01";
+const UNDEFINED_COLUMN: &str = "42703";
+const UNDEFINED_FUNCTION: &str = "42883";
+const AMBIGUOUS_COLUMN: &str = "42702";
+
+/// Columns every table has, but which the schema cache does not list.
+const SYSTEM_COLUMNS: [&str; 6] = ["ctid", "xmin", "xmax", "cmin", "cmax", "tableoid"];
+
+/// The columns of a relation, or `None` if they are not known, e.g. for set-returning functions.
+type Columns = Option<Vec<String>>;
+
+#[derive(Debug)]
+pub struct NameResolutionParams<'a> {
+    /// The statement the AST was parsed from. Locations in the AST are offsets into it.
+    pub sql: &'a str,
+    pub ast: &'a NodeEnum,
+    /// The parameters of the SQL function the statement belongs to, which can be referenced by name.
+    pub identifiers: &'a [TypedIdentifier],
+}
+
+/// Resolves the relations, columns and functions referenced by statements against the schema
+/// cache, without a database connection.
+///
+/// Statements are expected in the order they appear in a file, so that relations and functions
+/// created by earlier statements resolve in later ones.
+pub struct NameResolver<'a> {
+    schema_cache: &'a SchemaCache,
+
+    /// All relations of the schema cache by schema and name, with their columns.
+    relations: HashMap<(&'a str, &'a str), Vec<&'a str>>,
+
+    functions: HashSet<(&'a str, &'a str)>,
+
+    search_path: Vec<String>,
+
+    /// Relations created or altered by previous statements.
+    created_relations: HashMap<(String, String), Columns>,
+
+    created_functions: HashSet<String>,
+
+    /// Set once a statement creates an extension, since we can't know which objects it adds.
+    created_extension: bool,
+}
+
+impl<'a> NameResolver<'a> {
+    /// `search_path_patterns` are glob patterns that are matched against the schemas in the
+    /// schema cache, as in the `typecheck.searchPath` setting.
+    pub fn new(schema_cache: &'a SchemaCache, search_path_patterns: &[String]) -> Self {
+        let mut relations: HashMap<(&str, &str), Vec<&str>> = HashMap::new();
+
+        for table in &schema_cache.tables {
+            relations.entry((&table.schema, &table.name)).or_default();
+        }
+
+        for sequence in &schema_cache.sequences {
+            relations
+                .entry((&sequence.schema, &sequence.name))
+                .or_default();
+        }
+
+        for column in &schema_cache.columns {
+            relations
+                .entry((&column.schema_name, &column.table_name))
+                .or_default()
+                .push(&column.name);
+        }
+
+        let functions = schema_cache
+            .functions
+            .iter()
+            .map(|f| (f.schema.as_str(), f.name.as_str()))
+            .collect();
+
+        let mut search_path: Vec<String> =
+            get_schemas_in_search_path(schema_cache, search_path_patterns.to_vec())
+                .into_iter()
+                .map(String::from)
+                .collect();
+
+        if search_path.is_empty() {
+            search_path.push("public".to_string());
+        }
+
+        Self {
+            schema_cache,
+            relations,
+            functions,
+            search_path,
+            created_relations: HashMap::new(),
+            created_functions: HashSet::new(),
+            created_extension: false,
+        }
+    }
+
+    /// Checks the names referenced by the statement, and remembers the objects it creates for
+    /// the statements that follow.
+    pub fn check(&mut self, params: NameResolutionParams<'_>) -> Vec<TypecheckDiagnostic> {
+        let mut checker = Checker {
+            resolver: self,
+            sql: params.sql,
+            identifiers: params.identifiers,
+            scopes: vec![],
+            ctes: vec![],
+            output_names: vec![],
+            diagnostics: vec![],
+        };
+
+        let columns = checker.statement(params.ast);
+        let diagnostics = checker.diagnostics;
+
+        self.record(params.ast, columns);
+
+        diagnostics
+    }
+
+    /// Remembers the objects a statement creates without checking it, e.g. for the statements
+    /// of the migrations that are applied before the checked file.
+    pub fn record_statement(&mut self, sql: &str, ast: &NodeEnum) {
+        self.check(NameResolutionParams {
+            sql,
+            ast,
+            identifiers: &[],
+        });
+    }
+
+    /// The schemas unqualified names are looked up in, in order.
+    fn lookup_path(&self) -> impl Iterator<Item = &str> {
+        ["pg_temp", "pg_catalog"]
+            .into_iter()
+            .chain(self.search_path.iter().map(String::as_str))
+    }
+
+    /// Returns the schema of the relation and its columns, if it exists.
+    fn lookup_relation(&self, schema: Option<&str>, name: &str) -> Option<(String, Columns)> {
+        let schemas: Vec<&str> = match schema {
+            Some(schema) => vec![schema],
+            None => self.lookup_path().collect(),
+        };
+
+        schemas.into_iter().find_map(|schema| {
+            if let Some(columns) = self
+                .created_relations
+                .get(&(schema.to_string(), name.to_string()))
+            {
+                return Some((schema.to_string(), columns.clone()));
+            }
+
+            self.relations.get(&(schema, name)).map(|columns| {
+                let columns =
+                    (!columns.is_empty()).then(|| columns.iter().map(|c| c.to_string()).collect());
+                (schema.to_string(), columns)
+            })
+        })
+    }
+
+    fn function_exists(&self, schema: Option<&str>, name: &str) -> bool {
+        if self.created_functions.contains(name) {
+            return true;
+        }
+
+        match schema {
+            Some(schema) => self.functions.contains(&(schema, name)),
+            None => self
+                .lookup_path()
+                .any(|schema| self.functions.contains(&(schema, name))),
+        }
+    }
+
+    /// The names of the relations in the given schemas.
+    fn relation_names(&self, schemas: &[&str]) -> Vec<String> {
+        self.relations
+            .keys()
+            .filter(|(schema, _)| schemas.contains(schema))
+            .map(|(_, name)| name.to_string())
+            .chain(
+                self.created_relations
+                    .keys()
+                    .filter(|(schema, _)| schemas.contains(&schema.as_str()))
+                    .map(|(_, name)| name.clone()),
+            )
+            .collect()
+    }
+
+    /// The schema a relation is created in if none is given.
+    fn creation_schema(&self, relation: &protobuf::RangeVar) -> String {
+        if !relation.schemaname.is_empty() {
+            relation.schemaname.clone()
+        } else if relation.relpersistence == "t" {
+            "pg_temp".to_string()
+        } else {
+            self.search_path[0].clone()
+        }
+    }
+
+    fn record(&mut self, ast: &NodeEnum, columns: Columns) {
+        match ast {
+            NodeEnum::CreateStmt(stmt) => self.record_table(stmt),
+            NodeEnum::CreateForeignTableStmt(stmt) => {
+                if let Some(stmt) = &stmt.base_stmt {
+                    self.record_table(stmt);
+                }
+            }
+            NodeEnum::CreateTableAsStmt(stmt) => {
+                if let Some(into) = &stmt.into
+                    && let Some(relation) = &into.rel
+                {
+                    let columns = rename(columns, &strings(&into.col_names));
+                    self.created_relations.insert(
+                        (self.creation_schema(relation), relation.relname.clone()),
+                        columns,
+                    );
+                }
+            }
+            NodeEnum::ViewStmt(stmt) => {
+                if let Some(relation) = &stmt.view {
+                    let columns = rename(columns, &strings(&stmt.aliases));
+                    self.created_relations.insert(
+                        (self.creation_schema(relation), relation.relname.clone()),
+                        columns,
+                    );
+                }
+            }
+            NodeEnum::CreateSeqStmt(stmt) => {
+                if let Some(relation) = &stmt.sequence {
+                    self.created_relations.insert(
+                        (self.creation_schema(relation), relation.relname.clone()),
+                        None,
+                    );
+                }
+            }
+            NodeEnum::AlterTableStmt(stmt) => {
+                let Some(relation) = &stmt.relation else {
+                    return;
+                };
+
+                let mut dropped = vec![];
+                let mut added = vec![];
+
+                for cmd in &stmt.cmds {
+                    let Some(NodeEnum::AlterTableCmd(cmd)) = cmd.node.as_ref() else {
+                        continue;
+                    };
+
+                    match (
+                        cmd.subtype(),
+                        cmd.def.as_deref().and_then(|d| d.node.as_ref()),
+                    ) {
+                        (AlterTableType::AtAddColumn, Some(NodeEnum::ColumnDef(def))) => {
+                            added.push(def.colname.clone());
+                        }
+                        (AlterTableType::AtDropColumn, _) => dropped.push(cmd.name.clone()),
+                        _ => {}
+                    }
+                }
+
+                self.update_columns(relation, &dropped, added);
+            }
+            NodeEnum::RenameStmt(stmt) => {
+                let Some(relation) = &stmt.relation else {
+                    return;
+                };
+
+                match stmt.rename_type() {
+                    ObjectType::ObjectColumn => {
+                        self.update_columns(
+                            relation,
+                            std::slice::from_ref(&stmt.subname),
+                            vec![stmt.newname.clone()],
+                        );
+                    }
+                    ObjectType::ObjectTable
+                    | ObjectType::ObjectView
+                    | ObjectType::ObjectMatview
+                    | ObjectType::ObjectForeignTable
+                    | ObjectType::ObjectSequence => {
+                        let schema = (!relation.schemaname.is_empty())
+                            .then_some(relation.schemaname.as_str());
+                        if let Some((schema, columns)) =
+                            self.lookup_relation(schema, &relation.relname)
+                        {
+                            self.created_relations
+                                .insert((schema, stmt.newname.clone()), columns);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            NodeEnum::CreateFunctionStmt(stmt) => {
+                if let Some(name) = strings(&stmt.funcname).pop() {
+                    self.created_functions.insert(name);
+                }
+            }
+            NodeEnum::CreateExtensionStmt(_) => self.created_extension = true,
+            NodeEnum::VariableSetStmt(stmt)
+                if stmt.name == "search_path"
+                    && stmt.kind() == protobuf::VariableSetKind::VarSetValue =>
+            {
+                let schemas: Vec<String> = stmt
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg.node.as_ref() {
+                        Some(NodeEnum::AConst(protobuf::AConst {
+                            val: Some(protobuf::a_const::Val::Sval(s)),
+                            ..
+                        })) => Some(s.sval.clone()),
+                        _ => None,
+                    })
+                    .collect();
+
+                if !schemas.is_empty() {
+                    self.search_path = schemas;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn record_table(&mut self, stmt: &protobuf::CreateStmt) {
+        let Some(relation) = &stmt.relation else {
+            return;
+        };
+
+        let key = (self.creation_schema(relation), relation.relname.clone());
+
+        // `if not exists` keeps the existing table
+        if stmt.if_not_exists && self.lookup_relation(Some(&key.0), &key.1).is_some() {
+            return;
+        }
+
+        self.created_relations.insert(key, created_columns(stmt));
+    }
+
+    fn update_columns(
+        &mut self,
+        relation: &protobuf::RangeVar,
+        dropped: &[String],
+        added: Vec<String>,
+    ) {
+        if dropped.is_empty() && added.is_empty() {
+            return;
+        }
+
+        let schema = (!relation.schemaname.is_empty()).then_some(relation.schemaname.as_str());
+        let Some((schema, columns)) = self.lookup_relation(schema, &relation.relname) else {
+            return;
+        };
+
+        let columns = columns.map(|mut columns| {
+            columns.retain(|c| !dropped.contains(c));
+            columns.extend(added);
+            columns
+        });
+
+        self.created_relations
+            .insert((schema, relation.relname.clone()), columns);
+    }
+}
+
+/// A relation that columns can be referenced from.
+#[derive(Debug, Clone)]
+struct Relation {
+    /// The name it is referenced by: its alias, or the name of the relation.
+    name: String,
+
+    /// Set if it can also be referenced by `schema.name`.
+    schema: Option<String>,
+
+    columns: Columns,
+}
+
+/// The relations of one level of a query, e.g. of its `FROM` clause.
+#[derive(Debug, Default)]
+struct Scope {
+    relations: Vec<Relation>,
+
+    /// Columns merged by `JOIN ... USING` or `NATURAL JOIN`, which are not ambiguous.
+    merged_columns: Vec<String>,
+
+    /// Set if it contains relations we don't understand, so any name may resolve.
+    incomplete: bool,
+}
+
+impl Scope {
+    fn with(relation: Relation) -> Self {
+        Self {
+            relations: vec![relation],
+            ..Default::default()
+        }
+    }
+}
+
+struct Checker<'r, 'a> {
+    resolver: &'r NameResolver<'a>,
+    sql: &'r str,
+    identifiers: &'r [TypedIdentifier],
+
+    /// The scopes of the enclosing queries, innermost last.
+    scopes: Vec<Scope>,
+
+    /// The common table expressions of the enclosing `WITH` clauses, innermost last.
+    ctes: Vec<Vec<(String, Columns)>>,
+
+    /// The output columns that `ORDER BY` and `GROUP BY` can reference.
+    output_names: Vec<String>,
+
+    diagnostics: Vec<TypecheckDiagnostic>,
+}
+
+impl Checker<'_, '_> {
+    /// Checks the statement, and returns its output columns.
+    fn statement(&mut self, node: &NodeEnum) -> Columns {
+        match node {
+            NodeEnum::SelectStmt(stmt) => self.select(stmt),
+            NodeEnum::InsertStmt(stmt) => self.insert(stmt),
+            NodeEnum::UpdateStmt(stmt) => self.update(stmt),
+            NodeEnum::DeleteStmt(stmt) => self.delete(stmt),
+            NodeEnum::MergeStmt(stmt) => self.merge(stmt),
+            NodeEnum::ExplainStmt(stmt) => {
+                self.nested_statement(stmt.query.as_deref());
+                None
+            }
+            NodeEnum::CreateTableAsStmt(stmt) => self.nested_statement(stmt.query.as_deref()),
+            NodeEnum::ViewStmt(stmt) => self.nested_statement(stmt.query.as_deref()),
+            NodeEnum::CopyStmt(stmt) => {
+                if let Some(relation) = &stmt.relation
+                    && let Some(relation) = self.existing_relation(relation)
+                {
+                    for column in strings(&stmt.attlist) {
+                        self.existing_column(&relation, &column, stmt.relation.as_ref());
+                    }
+                }
+                self.nested_statement(stmt.query.as_deref());
+                None
+            }
+            NodeEnum::CreateStmt(stmt) => {
+                self.create_table(stmt);
+                None
+            }
+            NodeEnum::AlterTableStmt(stmt) => {
+                self.alter_table(stmt);
+                None
+            }
+            NodeEnum::IndexStmt(stmt) => {
+                self.index(stmt);
+                None
+            }
+            NodeEnum::CreatePolicyStmt(stmt) => {
+                self.policy(
+                    stmt.table.as_ref(),
+                    [stmt.qual.as_deref(), stmt.with_check.as_deref()],
+                );
+                None
+            }
+            NodeEnum::AlterPolicyStmt(stmt) => {
+                self.policy(
+                    stmt.table.as_ref(),
+                    [stmt.qual.as_deref(), stmt.with_check.as_deref()],
+                );
+                None
+            }
+            NodeEnum::RenameStmt(stmt) => {
+                self.rename(stmt);
+                None
+            }
+            NodeEnum::CreateTrigStmt(stmt) => {
+                self.trigger(stmt);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn nested_statement(&mut self, node: Option<&Node>) -> Columns {
+        node.and_then(|n| n.node.as_ref())
+            .and_then(|n| self.statement(n))
+    }
+
+    fn select(&mut self, stmt: &protobuf::SelectStmt) -> Columns {
+        let has_ctes = self.with_clause(stmt.with_clause.as_ref());
+
+        let columns = if matches!(
+            stmt.op(),
+            SetOperation::SetopUnion | SetOperation::SetopIntersect | SetOperation::SetopExcept
+        ) {
+            let columns = stmt.larg.as_deref().and_then(|s| self.select(s));
+            if let Some(rarg) = stmt.rarg.as_deref() {
+                self.select(rarg);
+            }
+
+            // `ORDER BY` of a set operation can only reference its output columns
+            self.scopes.push(Scope::with(Relation {
+                name: String::new(),
+                schema: None,
+                columns: columns.clone(),
+            }));
+            self.exprs(&stmt.sort_clause);
+            self.optional_expr(stmt.limit_offset.as_deref());
+            self.optional_expr(stmt.limit_count.as_deref());
+            self.scopes.pop();
+
+            columns
+        } else if !stmt.values_lists.is_empty() {
+            self.exprs(&stmt.values_lists);
+
+            match stmt.values_lists[0].node.as_ref() {
+                Some(NodeEnum::List(list)) => Some(
+                    (1..=list.items.len())
+                        .map(|i| format!("column{i}"))
+                        .collect(),
+                ),
+                _ => None,
+            }
+        } else {
+            self.scopes.push(Scope::default());
+
+            for item in &stmt.from_clause {
+                self.range_item(item);
+            }
+
+            self.exprs(&stmt.target_list);
+            let columns = self.output_columns(&stmt.target_list);
+
+            self.optional_expr(stmt.where_clause.as_deref());
+            self.optional_expr(stmt.having_clause.as_deref());
+            self.exprs(&stmt.window_clause);
+            self.optional_expr(stmt.limit_offset.as_deref());
+            self.optional_expr(stmt.limit_count.as_deref());
+
+            self.output_names = output_names(&stmt.target_list);
+            self.exprs(&stmt.group_clause);
+            self.exprs(&stmt.sort_clause);
+            self.exprs(&stmt.distinct_clause);
+            self.output_names.clear();
+
+            self.scopes.pop();
+
+            columns
+        };
+
+        if has_ctes {
+            self.ctes.pop();
+        }
+
+        columns
+    }
+
+    /// Registers the common table expressions, and returns whether there were any.
+    fn with_clause(&mut self, with: Option<&protobuf::WithClause>) -> bool {
+        let Some(with) = with else {
+            return false;
+        };
+
+        self.ctes.push(vec![]);
+
+        for cte in &with.ctes {
+            let Some(NodeEnum::CommonTableExpr(cte)) = cte.node.as_ref() else {
+                continue;
+            };
+
+            let aliases = strings(&cte.aliascolnames);
+
+            if with.recursive {
+                self.define_cte(&cte.ctename, None);
+            }
+
+            let columns = self.nested_statement(cte.ctequery.as_deref());
+            self.define_cte(&cte.ctename, rename(columns, &aliases));
+        }
+
+        true
+    }
+
+    fn define_cte(&mut self, name: &str, columns: Columns) {
+        let ctes = self
+            .ctes
+            .last_mut()
+            .expect("a WITH clause is being checked");
+        ctes.retain(|(n, _)| n != name);
+        ctes.push((name.to_string(), columns));
+    }
+
+    fn cte(&self, name: &str) -> Option<Columns> {
+        self.ctes
+            .iter()
+            .rev()
+            .flatten()
+            .find(|(n, _)| n == name)
+            .map(|(_, columns)| columns.clone())
+    }
+
+    fn insert(&mut self, stmt: &protobuf::InsertStmt) -> Columns {
+        let has_ctes = self.with_clause(stmt.with_clause.as_ref());
+
+        let target = stmt.relation.as_ref().map(|r| self.range_var(r));
+
+        if let Some(target) = &target {
+            for col in &stmt.cols {
+                if let Some(NodeEnum::ResTarget(col)) = col.node.as_ref() {
+                    self.target_column(target, col);
+                }
+            }
+        }
+
+        // the inserted rows can't reference the target
+        self.nested_statement(stmt.select_stmt.as_deref());
+
+        let columns = target.map(|target| {
+            self.scopes.push(Scope::with(target.clone()));
+
+            if let Some(conflict) = &stmt.on_conflict_clause {
+                if let Some(infer) = &conflict.infer {
+                    for elem in &infer.index_elems {
+                        if let Some(NodeEnum::IndexElem(elem)) = elem.node.as_ref() {
+                            self.index_elem(&target, elem, stmt.relation.as_ref());
+                        }
+                    }
+                    self.optional_expr(infer.where_clause.as_deref());
+                }
+
+                // `excluded` is only visible in the `DO UPDATE` clause
+                self.scope().relations.push(Relation {
+                    name: "excluded".to_string(),
+                    schema: None,
+                    columns: target.columns.clone(),
+                });
+
+                self.assignments(&target, &conflict.target_list);
+                self.optional_expr(conflict.where_clause.as_deref());
+
+                self.scope().relations.pop();
+            }
+
+            let columns = self.returning(&stmt.returning_list);
+            self.scopes.pop();
+            columns
+        });
+
+        if has_ctes {
+            self.ctes.pop();
+        }
+
+        columns.flatten()
+    }
+
+    fn update(&mut self, stmt: &protobuf::UpdateStmt) -> Columns {
+        let has_ctes = self.with_clause(stmt.with_clause.as_ref());
+
+        let target = stmt.relation.as_ref().map(|r| self.range_var(r));
+
+        self.scopes.push(Scope::default());
+        if let Some(target) = &target {
+            self.scope().relations.push(target.clone());
+        }
+        for item in &stmt.from_clause {
+            self.range_item(item);
+        }
+
+        match &target {
+            Some(target) => self.assignments(target, &stmt.target_list),
+            None => self.exprs(&stmt.target_list),
+        }
+        self.optional_expr(stmt.where_clause.as_deref());
+        let columns = self.returning(&stmt.returning_list);
+
+        self.scopes.pop();
+
+        if has_ctes {
+            self.ctes.pop();
+        }
+
+        columns
+    }
+
+    fn delete(&mut self, stmt: &protobuf::DeleteStmt) -> Columns {
+        let has_ctes = self.with_clause(stmt.with_clause.as_ref());
+
+        self.scopes.push(Scope::default());
+        if let Some(target) = &stmt.relation {
+            let target = self.range_var(target);
+            self.scope().relations.push(target);
+        }
+        for item in &stmt.using_clause {
+            self.range_item(item);
+        }
+
+        self.optional_expr(stmt.where_clause.as_deref());
+        let columns = self.returning(&stmt.returning_list);
+
+        self.scopes.pop();
+
+        if has_ctes {
+            self.ctes.pop();
+        }
+
+        columns
+    }
+
+    fn merge(&mut self, stmt: &protobuf::MergeStmt) -> Columns {
+        let has_ctes = self.with_clause(stmt.with_clause.as_ref());
+
+        let target = stmt.relation.as_ref().map(|r| self.range_var(r));
+
+        self.scopes.push(Scope::default());
+        if let Some(target) = &target {
+            self.scope().relations.push(target.clone());
+        }
+        if let Some(source) = stmt.source_relation.as_deref() {
+            self.range_item(source);
+        }
+
+        self.optional_expr(stmt.join_condition.as_deref());
+
+        for clause in &stmt.merge_when_clauses {
+            let Some(NodeEnum::MergeWhenClause(clause)) = clause.node.as_ref() else {
+                continue;
+            };
+
+            self.optional_expr(clause.condition.as_deref());
+            match &target {
+                Some(target) => self.assignments(target, &clause.target_list),
+                None => self.exprs(&clause.target_list),
+            }
+            self.exprs(&clause.values);
+        }
+
+        let columns = self.returning(&stmt.returning_list);
+
+        self.scopes.pop();
+
+        if has_ctes {
+            self.ctes.pop();
+        }
+
+        columns
+    }
+
+    /// Checks `SET column = value` lists and the columns of `INSERT`.
+    fn assignments(&mut self, target: &Relation, targets: &[Node]) {
+        for node in targets {
+            if let Some(NodeEnum::ResTarget(res_target)) = node.node.as_ref() {
+                self.target_column(target, res_target);
+            }
+            self.expr(node);
+        }
+    }
+
+    fn returning(&mut self, returning_list: &[Node]) -> Columns {
+        if returning_list.is_empty() {
+            return None;
+        }
+
+        self.exprs(returning_list);
+        self.output_columns(returning_list)
+    }
+
+    fn create_table(&mut self, stmt: &protobuf::CreateStmt) {
+        let Some(relation) = &stmt.relation else {
+            return;
+        };
+
+        for parent in &stmt.inh_relations {
+            if let Some(NodeEnum::RangeVar(parent)) = parent.node.as_ref() {
+                self.existing_relation(parent);
+            }
+        }
+
+        let table = Relation {
+            name: relation.relname.clone(),
+            schema: None,
+            columns: created_columns(stmt),
+        };
+
+        for elt in stmt.table_elts.iter().chain(&stmt.constraints) {
+            match elt.node.as_ref() {
+                Some(NodeEnum::ColumnDef(def)) => {
+                    for constraint in &def.constraints {
+                        if let Some(NodeEnum::Constraint(constraint)) = constraint.node.as_ref() {
+                            self.constraint(&table, constraint, relation);
+                        }
+                    }
+                }
+                Some(NodeEnum::Constraint(constraint)) => {
+                    self.constraint(&table, constraint, relation);
+                }
+                Some(NodeEnum::TableLikeClause(like)) => {
+                    if let Some(like) = &like.relation {
+                        self.existing_relation(like);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn alter_table(&mut self, stmt: &protobuf::AlterTableStmt) {
+        if !matches!(
+            stmt.objtype(),
+            ObjectType::ObjectTable
+                | ObjectType::ObjectView
+                | ObjectType::ObjectMatview
+                | ObjectType::ObjectForeignTable
+        ) {
+            return;
+        }
+
+        let Some(var) = &stmt.relation else {
+            return;
+        };
+
+        let relation = if stmt.missing_ok {
+            let schema = (!var.schemaname.is_empty()).then_some(var.schemaname.as_str());
+            self.resolver
+                .lookup_relation(schema, &var.relname)
+                .map(|(_, columns)| Relation {
+                    name: var.relname.clone(),
+                    schema: None,
+                    columns,
+                })
+        } else {
+            self.existing_relation(var)
+        };
+
+        let Some(mut relation) = relation else {
+            return;
+        };
+
+        for cmd in &stmt.cmds {
+            let Some(NodeEnum::AlterTableCmd(cmd)) = cmd.node.as_ref() else {
+                continue;
+            };
+
+            let def = cmd.def.as_deref().and_then(|d| d.node.as_ref());
+
+            match cmd.subtype() {
+                AlterTableType::AtAddColumn => {
+                    if let Some(NodeEnum::ColumnDef(def)) = def {
+                        if let Some(columns) = relation.columns.as_mut() {
+                            columns.push(def.colname.clone());
+                        }
+
+                        for constraint in &def.constraints {
+                            if let Some(NodeEnum::Constraint(constraint)) = constraint.node.as_ref()
+                            {
+                                self.constraint(&relation, constraint, var);
+                            }
+                        }
+                    }
+                }
+                AlterTableType::AtDropColumn => {
+                    if !cmd.missing_ok {
+                        self.existing_column(&relation, &cmd.name, Some(var));
+                    }
+                }
+                AlterTableType::AtAlterColumnType => {
+                    self.existing_column(&relation, &cmd.name, Some(var));
+
+                    // the `USING` expression
+                    if let Some(NodeEnum::ColumnDef(def)) = def {
+                        self.scoped_expr(&relation, def.raw_default.as_deref());
+                    }
+                }
+                AlterTableType::AtColumnDefault
+                | AlterTableType::AtDropNotNull
+                | AlterTableType::AtSetNotNull
+                | AlterTableType::AtSetExpression
+                | AlterTableType::AtDropExpression
+                | AlterTableType::AtSetStatistics
+                | AlterTableType::AtSetOptions
+                | AlterTableType::AtResetOptions
+                | AlterTableType::AtSetStorage
+                | AlterTableType::AtSetCompression
+                | AlterTableType::AtAddIdentity
+                | AlterTableType::AtSetIdentity
+                | AlterTableType::AtDropIdentity => {
+                    self.existing_column(&relation, &cmd.name, Some(var));
+                }
+                AlterTableType::AtAddConstraint => {
+                    if let Some(NodeEnum::Constraint(constraint)) = def {
+                        self.constraint(&relation, constraint, var);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Checks the columns and referenced tables of a constraint on `table`.
+    fn constraint(
+        &mut self,
+        table: &Relation,
+        constraint: &protobuf::Constraint,
+        table_var: &protobuf::RangeVar,
+    ) {
+        match constraint.contype() {
+            ConstrType::ConstrForeign => {
+                for column in strings(&constraint.fk_attrs) {
+                    self.existing_column(table, &column, Some(table_var));
+                }
+
+                let Some(pktable) = &constraint.pktable else {
+                    return;
+                };
+
+                // a table can reference itself while it is created
+                let referenced = if pktable.relname == table.name
+                    && (pktable.schemaname.is_empty() || pktable.schemaname == table_var.schemaname)
+                {
+                    Some(table.clone())
+                } else {
+                    self.existing_relation(pktable)
+                };
+
+                if let Some(referenced) = referenced {
+                    for column in strings(&constraint.pk_attrs) {
+                        self.existing_column(&referenced, &column, Some(pktable));
+                    }
+                }
+            }
+            ConstrType::ConstrPrimary | ConstrType::ConstrUnique => {
+                for column in strings(&constraint.keys)
+                    .into_iter()
+                    .chain(strings(&constraint.including))
+                {
+                    self.existing_column(table, &column, Some(table_var));
+                }
+            }
+            ConstrType::ConstrCheck => {
+                self.scoped_expr(table, constraint.raw_expr.as_deref());
+            }
+            _ => {}
+        }
+    }
+
+    fn index(&mut self, stmt: &protobuf::IndexStmt) {
+        let Some(var) = &stmt.relation else {
+            return;
+        };
+
+        let Some(relation) = self.existing_relation(var) else {
+            return;
+        };
+
+        self.scopes.push(Scope::with(relation.clone()));
+
+        for param in stmt.index_params.iter().chain(&stmt.index_including_params) {
+            if let Some(NodeEnum::IndexElem(elem)) = param.node.as_ref() {
+                self.index_elem(&relation, elem, Some(var));
+            }
+        }
+
+        self.optional_expr(stmt.where_clause.as_deref());
+
+        self.scopes.pop();
+    }
+
+    fn index_elem(
+        &mut self,
+        relation: &Relation,
+        elem: &protobuf::IndexElem,
+        var: Option<&protobuf::RangeVar>,
+    ) {
+        if !elem.name.is_empty() {
+            self.existing_column(relation, &elem.name, var);
+        }
+        self.optional_expr(elem.expr.as_deref());
+    }
+
+    fn policy(&mut self, table: Option<&protobuf::RangeVar>, exprs: [Option<&Node>; 2]) {
+        let Some(relation) = table.and_then(|t| self.existing_relation(t)) else {
+            return;
+        };
+
+        self.scopes.push(Scope::with(relation));
+        for expr in exprs {
+            self.optional_expr(expr);
+        }
+        self.scopes.pop();
+    }
+
+    fn rename(&mut self, stmt: &protobuf::RenameStmt) {
+        let is_column = match stmt.rename_type() {
+            ObjectType::ObjectColumn => true,
+            ObjectType::ObjectTable
+            | ObjectType::ObjectView
+            | ObjectType::ObjectMatview
+            | ObjectType::ObjectForeignTable => false,
+            _ => return,
+        };
+
+        let Some(var) = &stmt.relation else {
+            return;
+        };
+
+        if stmt.missing_ok {
+            return;
+        }
+
+        if let Some(relation) = self.existing_relation(var)
+            && is_column
+        {
+            self.existing_column(&relation, &stmt.subname, Some(var));
+        }
+    }
+
+    fn trigger(&mut self, stmt: &protobuf::CreateTrigStmt) {
+        let Some(var) = &stmt.relation else {
+            return;
+        };
+
+        let relation = self.existing_relation(var);
+
+        let names = strings(&stmt.funcname);
+        if let Some((schema, name)) = split_qualified(&names)
+            && !self.skip_function_checks()
+            && !self.resolver.function_exists(schema, name)
+        {
+            let span = find_identifier(self.sql, name, var.location);
+            self.unknown_function(schema, name, span);
+        }
+
+        let Some(relation) = relation else {
+            return;
+        };
+
+        for column in strings(&stmt.columns) {
+            self.existing_column(&relation, &column, Some(var));
+        }
+
+        self.scopes.push(Scope {
+            relations: ["new", "old"]
+                .into_iter()
+                .map(|name| Relation {
+                    name: name.to_string(),
+                    schema: None,
+                    columns: relation.columns.clone(),
+                })
+                .collect(),
+            ..Default::default()
+        });
+        self.optional_expr(stmt.when_clause.as_deref());
+        self.scopes.pop();
+    }
+
+    fn scope(&mut self) -> &mut Scope {
+        self.scopes.last_mut().expect("a query is being checked")
+    }
+
+    fn range_item(&mut self, node: &Node) {
+        match node.node.as_ref() {
+            Some(NodeEnum::RangeVar(var)) => {
+                let relation = self.range_var(var);
+                self.scope().relations.push(relation);
+            }
+            Some(NodeEnum::JoinExpr(join)) => self.join(join),
+            Some(NodeEnum::RangeSubselect(subselect)) => {
+                let columns = match subselect.subquery.as_deref().and_then(|n| n.node.as_ref()) {
+                    Some(NodeEnum::SelectStmt(stmt)) => self.select(stmt),
+                    _ => None,
+                };
+
+                let relation = aliased(subselect.alias.as_ref(), String::new(), columns);
+                self.scope().relations.push(relation);
+            }
+            Some(NodeEnum::RangeFunction(function)) => {
+                self.exprs(&function.functions);
+
+                let name = function
+                    .functions
+                    .iter()
+                    .filter_map(|f| f.node.as_ref())
+                    .flat_map(|f| f.iter())
+                    .find_map(|n| match n {
+                        NodeRef::FuncCall(f) => strings(&f.funcname).pop(),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+
+                let columns = (!function.coldeflist.is_empty()).then(|| {
+                    function
+                        .coldeflist
+                        .iter()
+                        .filter_map(|def| match def.node.as_ref() {
+                            Some(NodeEnum::ColumnDef(def)) => Some(def.colname.clone()),
+                            _ => None,
+                        })
+                        .collect()
+                });
+
+                let relation = aliased(function.alias.as_ref(), name, columns);
+                self.scope().relations.push(relation);
+            }
+            Some(NodeEnum::RangeTableSample(sample)) => {
+                if let Some(relation) = sample.relation.as_deref() {
+                    self.range_item(relation);
+                }
+                self.exprs(&sample.args);
+                self.optional_expr(sample.repeatable.as_deref());
+            }
+            _ => self.scope().incomplete = true,
+        }
+    }
+
+    fn join(&mut self, join: &protobuf::JoinExpr) {
+        let start = self.scope().relations.len();
+        if let Some(larg) = join.larg.as_deref() {
+            self.range_item(larg);
+        }
+        let middle = self.scope().relations.len();
+        if let Some(rarg) = join.rarg.as_deref() {
+            self.range_item(rarg);
+        }
+
+        let relations = &self
+            .scopes
+            .last()
+            .expect("a query is being checked")
+            .relations;
+        let left = columns_of(&relations[start..middle]);
+        let right = columns_of(&relations[middle..]);
+
+        let using = strings(&join.using_clause);
+        for column in &using {
+            for (side, columns) in [("left", &left), ("right", &right)] {
+                if columns.as_ref().is_some_and(|c| !c.contains(column)) {
+                    let span = find_identifier(self.sql, column, -1);
+                    self.diagnostics.push(TypecheckDiagnostic::unresolved_name(
+                        UNDEFINED_COLUMN,
+                        format!(
+                            "column \"{column}\" specified in USING clause does not exist in {side} table"
+                        ),
+                        span,
+                        did_you_mean(column, columns.iter().flatten().map(String::as_str)),
+                    ));
+                }
+            }
+        }
+
+        let natural = match (&left, &right) {
+            (Some(left), Some(right)) if join.is_natural => {
+                left.iter().filter(|c| right.contains(c)).cloned().collect()
+            }
+            _ => vec![],
+        };
+
+        let scope = self.scope();
+        scope.merged_columns.extend(using.iter().cloned());
+        scope.merged_columns.extend(natural);
+
+        self.optional_expr(join.quals.as_deref());
+
+        if let Some(alias) = &join.alias {
+            let relations = self.scope().relations.split_off(start);
+            let relation = aliased(Some(alias), String::new(), columns_of(&relations));
+            self.scope().relations.push(relation);
+        }
+
+        if let Some(alias) = &join.join_using_alias {
+            self.scope().relations.push(Relation {
+                name: alias.aliasname.clone(),
+                schema: None,
+                columns: Some(using),
+            });
+        }
+    }
+
+    /// Resolves a relation in a `FROM` clause or the target of a statement.
+    fn range_var(&mut self, var: &protobuf::RangeVar) -> Relation {
+        let aliases = var
+            .alias
+            .as_ref()
+            .map(|a| strings(&a.colnames))
+            .unwrap_or_default();
+
+        let name = var
+            .alias
+            .as_ref()
+            .map_or_else(|| var.relname.clone(), |a| a.aliasname.clone());
+
+        if var.schemaname.is_empty()
+            && let Some(columns) = self.cte(&var.relname)
+        {
+            return Relation {
+                name,
+                schema: None,
+                columns: rename(columns, &aliases),
+            };
+        }
+
+        if !var.catalogname.is_empty() {
+            return Relation {
+                name,
+                schema: None,
+                columns: None,
+            };
+        }
+
+        let schema = (!var.schemaname.is_empty()).then_some(var.schemaname.as_str());
+        match self.resolver.lookup_relation(schema, &var.relname) {
+            Some((schema, columns)) => Relation {
+                schema: var.alias.is_none().then_some(schema),
+                name,
+                columns: rename(columns, &aliases),
+            },
+            None => {
+                self.unknown_relation(var);
+                Relation {
+                    name,
+                    schema: None,
+                    columns: None,
+                }
+            }
+        }
+    }
+
+    /// Resolves a relation that is referenced by a DDL statement.
+    fn existing_relation(&mut self, var: &protobuf::RangeVar) -> Option<Relation> {
+        if !var.catalogname.is_empty() {
+            return None;
+        }
+
+        let schema = (!var.schemaname.is_empty()).then_some(var.schemaname.as_str());
+        match self.resolver.lookup_relation(schema, &var.relname) {
+            Some((schema, columns)) => Some(Relation {
+                name: var.relname.clone(),
+                schema: Some(schema),
+                columns,
+            }),
+            None => {
+                self.unknown_relation(var);
+                None
+            }
+        }
+    }
+
+    /// Checks that a column referenced by name, e.g. by `ALTER TABLE`, exists in the relation.
+    fn existing_column(
+        &mut self,
+        relation: &Relation,
+        column: &str,
+        var: Option<&protobuf::RangeVar>,
+    ) {
+        let Some(columns) = &relation.columns else {
+            return;
+        };
+
+        if column.is_empty()
+            || columns.iter().any(|c| c == column)
+            || SYSTEM_COLUMNS.contains(&column)
+        {
+            return;
+        }
+
+        let span = find_identifier(self.sql, column, var.map_or(-1, |v| v.location));
+        self.diagnostics.push(TypecheckDiagnostic::unresolved_name(
+            UNDEFINED_COLUMN,
+            format!(
+                "column \"{column}\" of relation \"{}\" does not exist",
+                relation.name
+            ),
+            span,
+            did_you_mean(column, columns.iter().map(String::as_str)),
+        ));
+    }
+
+    /// Checks the column a `SET` or `INSERT` target assigns to.
+    fn target_column(&mut self, relation: &Relation, target: &protobuf::ResTarget) {
+        let Some(columns) = &relation.columns else {
+            return;
+        };
+
+        if target.name.is_empty() || columns.contains(&target.name) {
+            return;
+        }
+
+        let span = name_parts(self.sql, target.location).into_iter().next();
+        self.diagnostics.push(TypecheckDiagnostic::unresolved_name(
+            UNDEFINED_COLUMN,
+            format!(
+                "column \"{}\" of relation \"{}\" does not exist",
+                target.name, relation.name
+            ),
+            span,
+            did_you_mean(&target.name, columns.iter().map(String::as_str)),
+        ));
+    }
+
+    fn unknown_relation(&mut self, var: &protobuf::RangeVar) {
+        if self.skip_relation_checks() {
+            return;
+        }
+
+        let qualified_name = if var.schemaname.is_empty() {
+            var.relname.clone()
+        } else {
+            format!("{}.{}", var.schemaname, var.relname)
+        };
+
+        let hint = if var.schemaname.is_empty() {
+            let schemas: Vec<&str> = self.resolver.lookup_path().collect();
+
+            // the relation might exist in a schema that is not on the search path
+            self.resolver
+                .relations
+                .keys()
+                .find(|(schema, name)| *name == var.relname && !schemas.contains(schema))
+                .map(|(schema, name)| format!("Did you mean \"{schema}.{name}\"?"))
+                .or_else(|| {
+                    let mut candidates = self.resolver.relation_names(&schemas);
+                    candidates.extend(self.ctes.iter().flatten().map(|(name, _)| name.clone()));
+                    did_you_mean(&var.relname, candidates.iter().map(String::as_str))
+                })
+        } else {
+            let candidates = self.resolver.relation_names(&[var.schemaname.as_str()]);
+            did_you_mean(&var.relname, candidates.iter().map(String::as_str))
+        };
+
+        self.diagnostics.push(TypecheckDiagnostic::unresolved_name(
+            UNDEFINED_TABLE,
+            format!("relation \"{qualified_name}\" does not exist"),
+            span_of(&name_parts(self.sql, var.location)),
+            hint,
+        ));
+    }
+
+    /// A database without any relations on the search path hasn't had the migrations applied
+    /// yet, so its schema cache can't tell whether a relation exists.
+    fn skip_relation_checks(&self) -> bool {
+        self.resolver.created_extension
+            || !self.resolver.relations.keys().any(|(schema, _)| {
+                self.resolver
+                    .search_path
+                    .iter()
+                    .any(|search_path| search_path == schema)
+            })
+    }
+
+    fn skip_function_checks(&self) -> bool {
+        self.resolver.created_extension || self.resolver.schema_cache.functions.is_empty()
+    }
+
+    fn unknown_function(&mut self, schema: Option<&str>, name: &str, span: Option<TextRange>) {
+        let qualified_name = match schema {
+            Some(schema) => format!("{schema}.{name}"),
+            None => name.to_string(),
+        };
+
+        let schemas: Vec<&str> = match schema {
+            Some(schema) => vec![schema],
+            None => self.resolver.lookup_path().collect(),
+        };
+
+        let candidates = self
+            .resolver
+            .functions
+            .iter()
+            .filter(|(schema, _)| schemas.contains(schema))
+            .map(|(_, name)| *name);
+
+        self.diagnostics.push(TypecheckDiagnostic::unresolved_name(
+            UNDEFINED_FUNCTION,
+            format!("function {qualified_name} does not exist"),
+            span,
+            did_you_mean(name, candidates),
+        ));
+    }
+
+    fn exprs(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.expr(node);
+        }
+    }
+
+    fn optional_expr(&mut self, node: Option<&Node>) {
+        if let Some(node) = node {
+            self.expr(node);
+        }
+    }
+
+    /// Checks an expression against the relations of the current scope.
+    fn scoped_expr(&mut self, relation: &Relation, node: Option<&Node>) {
+        self.scopes.push(Scope::with(relation.clone()));
+        self.optional_expr(node);
+        self.scopes.pop();
+    }
+
+    /// Checks the column references and function calls of an expression. Subqueries are checked
+    /// with a scope of their own.
+    fn expr(&mut self, node: &Node) {
+        let Some(node) = node.node.as_ref() else {
+            return;
+        };
+
+        // the addresses of the nodes within subqueries
+        let mut nested: HashSet<usize> = HashSet::new();
+        let mut subqueries = vec![];
+
+        for n in node.iter() {
+            match n {
+                NodeRef::SubLink(link) if !nested.contains(&address(link)) => {
+                    let Some(subselect) = link.subselect.as_deref().and_then(|s| s.node.as_ref())
+                    else {
+                        continue;
+                    };
+
+                    for inner in subselect.iter() {
+                        match inner {
+                            NodeRef::ColumnRef(n) => nested.insert(address(n)),
+                            NodeRef::FuncCall(n) => nested.insert(address(n)),
+                            NodeRef::SubLink(n) => nested.insert(address(n)),
+                            _ => false,
+                        };
+                    }
+
+                    subqueries.push(subselect);
+                }
+                NodeRef::ColumnRef(column) if !nested.contains(&address(column)) => {
+                    self.column_ref(column);
+                }
+                NodeRef::FuncCall(call) if !nested.contains(&address(call)) => {
+                    self.func_call(call);
+                }
+                _ => {}
+            }
+        }
+
+        for subquery in subqueries {
+            if let NodeEnum::SelectStmt(stmt) = subquery {
+                self.select(stmt);
+            }
+        }
+    }
+
+    fn column_ref(&mut self, column: &protobuf::ColumnRef) {
+        let fields: Vec<Option<&str>> = column
+            .fields
+            .iter()
+            .map(|f| match f.node.as_ref() {
+                Some(NodeEnum::String(s)) => Some(s.sval.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        // parameters of SQL functions, by `name` or `function_name.name`
+        if self.identifiers.iter().any(|i| match fields.as_slice() {
+            [Some(name)] => i.name.as_deref() == Some(*name),
+            [Some(path), Some(name)] => i.path == *path && i.name.as_deref() == Some(*name),
+            _ => false,
+        }) {
+            return;
+        }
+
+        let parts = name_parts(self.sql, column.location);
+
+        match fields.as_slice() {
+            [Some(name)] => self.unqualified_column(name, parts.first().copied()),
+            [Some(relation), column] => {
+                self.qualified_column(None, relation, *column, &parts);
+            }
+            [Some(schema), Some(relation), column] => {
+                self.qualified_column(
+                    Some(schema),
+                    relation,
+                    *column,
+                    &parts[1.min(parts.len())..],
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn unqualified_column(&mut self, name: &str, span: Option<TextRange>) {
+        if self.output_names.iter().any(|n| n == name) {
+            return;
+        }
+
+        for scope in self.scopes.iter().rev() {
+            if scope.merged_columns.iter().any(|c| c == name) {
+                return;
+            }
+
+            let matches: Vec<&Relation> = scope
+                .relations
+                .iter()
+                .filter(|r| {
+                    r.columns
+                        .as_ref()
+                        .is_some_and(|c| c.iter().any(|c| c == name))
+                })
+                .collect();
+
+            if matches.len() > 1 {
+                let candidates = matches
+                    .iter()
+                    .filter(|r| !r.name.is_empty())
+                    .map(|r| format!("\"{}.{name}\"", r.name))
+                    .collect::<Vec<_>>();
+
+                self.diagnostics.push(TypecheckDiagnostic::unresolved_name(
+                    AMBIGUOUS_COLUMN,
+                    format!("column reference \"{name}\" is ambiguous"),
+                    span,
+                    (candidates.len() > 1)
+                        .then(|| format!("It could refer to {}.", candidates.join(" or "))),
+                ));
+                return;
+            }
+
+            if !matches.is_empty()
+                || scope.incomplete
+                || scope
+                    .relations
+                    .iter()
+                    .any(|r| r.columns.is_none() || r.name == name)
+                || (!scope.relations.is_empty() && SYSTEM_COLUMNS.contains(&name))
+            {
+                return;
+            }
+        }
+
+        let candidates: Vec<&str> = self
+            .scopes
+            .iter()
+            .flat_map(|s| &s.relations)
+            .flat_map(|r| r.columns.iter().flatten())
+            .map(String::as_str)
+            .collect();
+
+        self.diagnostics.push(TypecheckDiagnostic::unresolved_name(
+            UNDEFINED_COLUMN,
+            format!("column \"{name}\" does not exist"),
+            span,
+            did_you_mean(name, candidates),
+        ));
+    }
+
+    /// Checks `relation.column` or `relation.*`. `parts` are the spans of the relation and column.
+    fn qualified_column(
+        &mut self,
+        schema: Option<&str>,
+        relation: &str,
+        column: Option<&str>,
+        parts: &[TextRange],
+    ) {
+        for scope in self.scopes.iter().rev() {
+            let found = scope.relations.iter().find(|r| {
+                r.name == relation && (schema.is_none() || r.schema.as_deref() == schema)
+            });
+
+            if let Some(found) = found {
+                if let (Some(column), Some(columns)) = (column, &found.columns)
+                    && !columns.iter().any(|c| c == column)
+                    && !SYSTEM_COLUMNS.contains(&column)
+                {
+                    let hint = did_you_mean(column, columns.iter().map(String::as_str));
+                    self.diagnostics.push(TypecheckDiagnostic::unresolved_name(
+                        UNDEFINED_COLUMN,
+                        format!("column {relation}.{column} does not exist"),
+                        parts.get(1).copied(),
+                        hint,
+                    ));
+                }
+                return;
+            }
+
+            if scope.incomplete {
+                return;
+            }
+        }
+
+        // `a.b` might also select the field `b` of the composite column `a`
+        if schema.is_none()
+            && self.scopes.iter().flat_map(|s| &s.relations).any(|r| {
+                r.columns
+                    .as_ref()
+                    .is_none_or(|columns| columns.iter().any(|c| c == relation))
+            })
+        {
+            return;
+        }
+
+        let candidates: Vec<&str> = self
+            .scopes
+            .iter()
+            .flat_map(|s| &s.relations)
+            .map(|r| r.name.as_str())
+            .collect();
+
+        self.diagnostics.push(TypecheckDiagnostic::unresolved_name(
+            UNDEFINED_TABLE,
+            format!("missing FROM-clause entry for table \"{relation}\""),
+            parts.first().copied(),
+            did_you_mean(relation, candidates),
+        ));
+    }
+
+    fn func_call(&mut self, call: &protobuf::FuncCall) {
+        // e.g. `extract(...)` or `trim(...)`, which are rewritten to built-in functions
+        if call.funcformat() == CoercionForm::CoerceSqlSyntax || self.skip_function_checks() {
+            return;
+        }
+
+        let names = strings(&call.funcname);
+        let Some((schema, name)) = split_qualified(&names) else {
+            return;
+        };
+
+        if self.resolver.function_exists(schema, name) {
+            return;
+        }
+
+        let span = span_of(&name_parts(self.sql, call.location));
+        self.unknown_function(schema, name, span);
+    }
+
+    /// The names of the columns a target list outputs, if they can be determined.
+    fn output_columns(&self, targets: &[Node]) -> Columns {
+        let mut columns = vec![];
+
+        for target in targets {
+            let Some(NodeEnum::ResTarget(target)) = target.node.as_ref() else {
+                return None;
+            };
+
+            if !target.name.is_empty() {
+                columns.push(target.name.clone());
+                continue;
+            }
+
+            match target.val.as_deref().and_then(|v| v.node.as_ref()) {
+                Some(NodeEnum::ColumnRef(column))
+                    if matches!(
+                        column.fields.last().and_then(|f| f.node.as_ref()),
+                        Some(NodeEnum::AStar(_))
+                    ) =>
+                {
+                    let scope = self.scopes.last()?;
+                    let qualifier = match column.fields.len() {
+                        1 => None,
+                        len => match column.fields[len - 2].node.as_ref() {
+                            Some(NodeEnum::String(s)) => Some(s.sval.as_str()),
+                            _ => return None,
+                        },
+                    };
+
+                    if scope.incomplete && qualifier.is_none() {
+                        return None;
+                    }
+
+                    for relation in &scope.relations {
+                        if qualifier.is_none_or(|q| q == relation.name) {
+                            columns.extend(relation.columns.clone()?);
+                        }
+                    }
+                }
+                Some(node) => columns.push(figure_name(node)?),
+                None => return None,
+            }
+        }
+
+        Some(columns)
+    }
+}
+
+/// The name Postgres gives to an output column without an alias, if it can be determined.
+fn figure_name(node: &NodeEnum) -> Option<String> {
+    match node {
+        NodeEnum::ColumnRef(column) => match column.fields.last()?.node.as_ref()? {
+            NodeEnum::String(s) => Some(s.sval.clone()),
+            _ => None,
+        },
+        NodeEnum::FuncCall(call) => strings(&call.funcname).pop(),
+        NodeEnum::TypeCast(cast) => cast
+            .arg
+            .as_deref()
+            .and_then(|a| a.node.as_ref())
+            .and_then(figure_name)
+            .filter(|name| name != "?column?"),
+        NodeEnum::AExpr(expr) if expr.kind() == protobuf::AExprKind::AexprNullif => {
+            Some("nullif".to_string())
+        }
+        NodeEnum::SubLink(link) => match link.sub_link_type() {
+            SubLinkType::ExistsSublink => Some("exists".to_string()),
+            SubLinkType::ArraySublink => Some("array".to_string()),
+            _ => None,
+        },
+        NodeEnum::CaseExpr(_) => Some("case".to_string()),
+        NodeEnum::CoalesceExpr(_) => Some("coalesce".to_string()),
+        NodeEnum::AArrayExpr(_) => Some("array".to_string()),
+        NodeEnum::RowExpr(_) => Some("row".to_string()),
+        NodeEnum::AConst(_)
+        | NodeEnum::AExpr(_)
+        | NodeEnum::BoolExpr(_)
+        | NodeEnum::NullTest(_)
+        | NodeEnum::BooleanTest(_)
+        | NodeEnum::ParamRef(_) => Some("?column?".to_string()),
+        _ => None,
+    }
+}
+
+/// The explicit and derived names of the output columns, for `ORDER BY` and `GROUP BY`.
+fn output_names(targets: &[Node]) -> Vec<String> {
+    targets
+        .iter()
+        .filter_map(|target| match target.node.as_ref() {
+            Some(NodeEnum::ResTarget(target)) if !target.name.is_empty() => {
+                Some(target.name.clone())
+            }
+            Some(NodeEnum::ResTarget(target)) => target
+                .val
+                .as_deref()
+                .and_then(|v| v.node.as_ref())
+                .and_then(figure_name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The columns of a created table, if they are all listed in the statement.
+fn created_columns(stmt: &protobuf::CreateStmt) -> Columns {
+    if !stmt.inh_relations.is_empty() || stmt.of_typename.is_some() || stmt.partbound.is_some() {
+        return None;
+    }
+
+    stmt.table_elts
+        .iter()
+        .filter_map(|elt| match elt.node.as_ref() {
+            Some(NodeEnum::ColumnDef(def)) => Some(Some(def.colname.clone())),
+            Some(NodeEnum::TableLikeClause(_)) => Some(None),
+            _ => None,
+        })
+        .collect()
+}
+
+fn aliased(alias: Option<&protobuf::Alias>, name: String, columns: Columns) -> Relation {
+    match alias {
+        Some(alias) => Relation {
+            name: alias.aliasname.clone(),
+            schema: None,
+            columns: rename(columns, &strings(&alias.colnames)),
+        },
+        None => Relation {
+            name,
+            schema: None,
+            columns,
+        },
+    }
+}
+
+/// Applies column aliases, which rename the first columns.
+fn rename(columns: Columns, aliases: &[String]) -> Columns {
+    if aliases.is_empty() {
+        return columns;
+    }
+
+    let columns = columns?;
+    Some(
+        aliases
+            .iter()
+            .cloned()
+            .chain(columns.into_iter().skip(aliases.len()))
+            .collect(),
+    )
+}
+
+/// All columns of the relations, if they are known.
+fn columns_of(relations: &[Relation]) -> Columns {
+    relations.iter().try_fold(vec![], |mut acc, r| {
+        acc.extend(r.columns.clone()?);
+        Some(acc)
+    })
+}
+
+fn strings(nodes: &[Node]) -> Vec<String> {
+    nodes
+        .iter()
+        .filter_map(|n| match n.node.as_ref() {
+            Some(NodeEnum::String(s)) => Some(s.sval.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn split_qualified(names: &[String]) -> Option<(Option<&str>, &str)> {
+    match names {
+        [name] => Some((None, name)),
+        [schema, name] => Some((Some(schema), name)),
+        _ => None,
+    }
+}
+
+fn address<T>(node: &T) -> usize {
+    node as *const T as usize
+}
+
+/// Returns the candidate closest to `name`, if it is close enough to be a typo.
+fn did_you_mean<'c>(name: &str, candidates: impl IntoIterator<Item = &'c str>) -> Option<String> {
+    let name_chars: Vec<char> = name.to_lowercase().chars().collect();
+    let max_distance = (name_chars.len() / 3).max(1);
+
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| {
+            let chars: Vec<char> = candidate.to_lowercase().chars().collect();
+            (edit_distance(&name_chars, &chars), candidate)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)))
+        .map(|(_, candidate)| format!("Did you mean \"{candidate}\"?"))
+}
+
+/// The optimal string alignment distance: insertions, deletions, substitutions and
+/// transpositions of adjacent characters each count as one edit.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    dist[a.len()][b.len()]
+}
+
+fn text_range(start: usize, end: usize) -> TextRange {
+    TextRange::new(
+        TextSize::new(start.try_into().unwrap()),
+        TextSize::new(end.try_into().unwrap()),
+    )
+}
+
+fn span_of(parts: &[TextRange]) -> Option<TextRange> {
+    Some(parts.first()?.cover(*parts.last()?))
+}
+
+/// The end of the identifier that starts at `pos`, quoted or not.
+fn identifier_end(bytes: &[u8], pos: usize) -> usize {
+    let mut end = pos;
+
+    if bytes.get(end) == Some(&b'"') {
+        end += 1;
+        while end < bytes.len() {
+            match (bytes[end], bytes.get(end + 1)) {
+                (b'"', Some(b'"')) => end += 2,
+                (b'"', _) => return end + 1,
+                _ => end += 1,
+            }
+        }
+        return end;
+    }
+
+    while bytes
+        .get(end)
+        .is_some_and(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'$') || *b >= 0x80)
+    {
+        end += 1;
+    }
+
+    end
+}
+
+/// The ranges of the dot-separated names that start at `location`, e.g. of `public`, `users`
+/// and `id` in `public.users.id`.
+fn name_parts(sql: &str, location: i32) -> Vec<TextRange> {
+    let Ok(mut pos) = usize::try_from(location) else {
+        return vec![];
+    };
+
+    let bytes = sql.as_bytes();
+    let mut parts = vec![];
+
+    loop {
+        let end = if bytes.get(pos) == Some(&b'*') {
+            pos + 1
+        } else {
+            identifier_end(bytes, pos)
+        };
+
+        if end == pos {
+            break;
+        }
+        parts.push(text_range(pos, end));
+
+        let mut next = end;
+        while bytes.get(next).is_some_and(u8::is_ascii_whitespace) {
+            next += 1;
+        }
+        if bytes.get(next) != Some(&b'.') {
+            break;
+        }
+        next += 1;
+        while bytes.get(next).is_some_and(u8::is_ascii_whitespace) {
+            next += 1;
+        }
+        pos = next;
+    }
+
+    parts
+}
+
+/// Finds the first occurrence of the identifier at or after `location`, skipping comments and
+/// string literals.
+fn find_identifier(sql: &str, name: &str, location: i32) -> Option<TextRange> {
+    let bytes = sql.as_bytes();
+    let mut pos = usize::try_from(location).unwrap_or(0);
+
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'-' if bytes.get(pos + 1) == Some(&b'-') => {
+                while pos < bytes.len() && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+            }
+            b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                pos += 2;
+                while pos < bytes.len()
+                    && !(bytes[pos] == b'*' && bytes.get(pos + 1) == Some(&b'/'))
+                {
+                    pos += 1;
+                }
+                pos += 2;
+            }
+            b'\'' => {
+                pos += 1;
+                while pos < bytes.len() {
+                    match (bytes[pos], bytes.get(pos + 1)) {
+                        (b'\'', Some(b'\'')) => pos += 2,
+                        (b'\'', _) => break,
+                        _ => pos += 1,
+                    }
+                }
+                pos += 1;
+            }
+            b'"' => {
+                let end = identifier_end(bytes, pos);
+                let quoted = &sql[pos + 1..end.saturating_sub(1).max(pos + 1)];
+                if quoted.replace("\"\"", "\"") == name {
+                    return Some(text_range(pos, end));
+                }
+                pos = end;
+            }
+            b if b.is_ascii_alphabetic() || b == b'_' || b >= 0x80 => {
+                let end = identifier_end(bytes, pos);
+                if sql[pos..end].to_lowercase() == name {
+                    return Some(text_range(pos, end));
+                }
+                pos = end;
+            }
+            _ => pos += 1,
+        }
+    }
+
+    None
+}
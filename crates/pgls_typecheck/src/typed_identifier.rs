@@ -1,5 +1,7 @@
 use pgls_schema_cache::PostgresType;
-use pgls_text_size::{TextRange, TextRangeReplacement, TextRangeReplacementBuilder, TextSize};
+#[cfg(feature = "db")]
+use pgls_text_size::TextSize;
+use pgls_text_size::{TextRange, TextRangeReplacement, TextRangeReplacementBuilder};
 use pgls_treesitter::queries::{ParameterMatch, TreeSitterQueriesExecutor};
 
 /// It is used to replace parameters within the SQL string.
@@ -32,6 +34,7 @@ pub struct IdentifierReplacement {
 
 /// Contains the text replacement along with metadata about which ranges correspond to which types.
 #[derive(Debug)]
+#[cfg_attr(not(feature = "db"), allow(dead_code))]
 pub struct TypedReplacement {
     text_replacement: TextRangeReplacement,
     identifier_replacements: Vec<IdentifierReplacement>,
@@ -53,6 +56,7 @@ impl TypedReplacement {
     }
 
     /// Finds the original type at the given position in the adjusted text
+    #[cfg(feature = "db")]
     pub(crate) fn find_type_at_position(
         &self,
         original_position: TextSize,
@@ -64,6 +68,7 @@ impl TypedReplacement {
         })
     }
 
+    #[cfg(feature = "db")]
     pub(crate) fn text_replacement(&self) -> &TextRangeReplacement {
         &self.text_replacement
     }
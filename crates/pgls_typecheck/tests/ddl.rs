@@ -0,0 +1,240 @@
+use pgls_console::fmt::{Formatter, HTML};
+use pgls_diagnostics::Diagnostic;
+use pgls_typecheck::{DdlCheckParams, DdlStatement, check_ddl};
+use sqlx::{Connection, Executor, PgConnection, PgPool};
+use std::fmt::Write;
+
+struct TestSetup<'a> {
+    name: &'a str,
+    /// Executed in order, as if they were the statements of one file.
+    statements: &'a [&'a str],
+    setup: Option<&'a str>,
+    test_db: &'a PgPool,
+}
+
+impl TestSetup<'_> {
+    async fn test(self) {
+        if let Some(setup) = self.setup {
+            self.test_db
+                .execute(setup)
+                .await
+                .expect("Failed to setup test database");
+        }
+
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&pgls_treesitter_grammar::LANGUAGE.into())
+            .expect("Error loading sql language");
+
+        let schema_cache = pgls_schema_cache::SchemaCache::load(self.test_db)
+            .await
+            .expect("Failed to load Schema Cache");
+
+        let roots = self
+            .statements
+            .iter()
+            .map(|s| {
+                pgls_query::parse(s)
+                    .unwrap()
+                    .into_root()
+                    .expect("Failed to parse query")
+            })
+            .collect::<Vec<_>>();
+        let trees = self
+            .statements
+            .iter()
+            .map(|s| parser.parse(s, None).unwrap())
+            .collect::<Vec<_>>();
+
+        let statements = self
+            .statements
+            .iter()
+            .zip(&roots)
+            .zip(&trees)
+            .map(|((sql, ast), tree)| DdlStatement { sql, ast, tree })
+            .collect::<Vec<_>>();
+
+        let result = check_ddl(DdlCheckParams {
+            conn: self.test_db,
+            statements: &statements,
+            schema_cache: &schema_cache,
+            search_path_patterns: vec![],
+        })
+        .await;
+
+        let diagnostics = result.expect("Got Typechecking error");
+
+        let mut content = String::new();
+
+        for (idx, statement) in self.statements.iter().enumerate() {
+            writeln!(&mut content, "{statement}").unwrap();
+
+            let Some((_, d)) = diagnostics.iter().find(|(i, _)| *i == idx) else {
+                writeln!(&mut content, "No Diagnostic").unwrap();
+                writeln!(&mut content).unwrap();
+                continue;
+            };
+
+            match d.location().span {
+                Some(span) => writeln!(
+                    &mut content,
+                    "{}~~~{}~~~{}",
+                    &statement[..span.start().into()],
+                    &statement[span],
+                    &statement[span.end().into()..]
+                )
+                .unwrap(),
+                None => writeln!(&mut content, "~~~{statement}~~~").unwrap(),
+            }
+
+            let mut msg_content = vec![];
+            let mut writer = HTML::new(&mut msg_content);
+            let mut formatter = Formatter::new(&mut writer);
+            d.message(&mut formatter).unwrap();
+            writeln!(&mut content, "{}", String::from_utf8(msg_content).unwrap()).unwrap();
+            writeln!(&mut content).unwrap();
+        }
+
+        insta::with_settings!({
+            prepend_module_to_snapshot => false,
+        }, {
+            insta::assert_snapshot!(self.name, content);
+        });
+    }
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn ddl_errors(test_db: PgPool) {
+    TestSetup {
+        name: "ddl_errors",
+        statements: &[
+            "create table products (id serial primary key, price numeric);",
+            "alter table products add column price numeric;",
+            "create view cheap as select id from products where prize < 10;",
+            "create policy own_products on products using (owner = current_user);",
+            "create function product_count() returns bigint language sql as $$ select count(*) from product $$;",
+        ],
+        setup: None,
+        test_db: &test_db,
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn later_statements_see_earlier_ones(test_db: PgPool) {
+    TestSetup {
+        name: "later_statements_see_earlier_ones",
+        statements: &[
+            "create table products (id serial primary key);",
+            "alter table products add column price numeric;",
+            "create index products_price_idx on products (price);",
+            "select price from products;",
+            "create table orders (product_id int references products (id), amount int check (amount > 0));",
+        ],
+        setup: None,
+        test_db: &test_db,
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn queries_see_earlier_ddl(test_db: PgPool) {
+    TestSetup {
+        name: "queries_see_earlier_ddl",
+        statements: &[
+            "create table public.new_t (id int, name text);",
+            "insert into new_t (id, name) values (1, 'a');",
+            "select name from new_t;",
+            "alter table new_t add column nickname text;",
+            "select nickname from new_t;",
+            "select nick from new_t;",
+            "drop index concurrently if exists new_t_name_idx;",
+        ],
+        setup: None,
+        test_db: &test_db,
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn changes_are_rolled_back(test_db: PgPool) {
+    TestSetup {
+        name: "changes_are_rolled_back",
+        statements: &["alter table users add column age int;"],
+        setup: Some("create table users (id serial primary key);"),
+        test_db: &test_db,
+    }
+    .test()
+    .await;
+
+    let columns: Vec<String> = sqlx::query_scalar(
+        "select column_name::text from information_schema.columns where table_name = 'users'",
+    )
+    .fetch_all(&test_db)
+    .await
+    .unwrap();
+
+    assert_eq!(columns, vec!["id".to_string()]);
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn create_table_as_errors(test_db: PgPool) {
+    TestSetup {
+        name: "create_table_as_errors",
+        statements: &[
+            "create table products (id serial primary key, price numeric);",
+            "create table cheap_products as select id from products where prize < 10;",
+        ],
+        setup: None,
+        test_db: &test_db,
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn create_table_as_does_not_run_query(test_db: PgPool) {
+    // the division by zero is only raised if the query runs
+    TestSetup {
+        name: "create_table_as_does_not_run_query",
+        statements: &[
+            "create table ratios as select 1 / (g - g) as ratio from generate_series(1, 3) g;",
+            "select ratio from ratios;",
+        ],
+        setup: None,
+        test_db: &test_db,
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn skips_lock_timeouts(test_db: PgPool) {
+    test_db
+        .execute("create table users (id serial primary key);")
+        .await
+        .unwrap();
+
+    // another session holds a lock on the table until the check is done
+    let mut other = PgConnection::connect_with(&test_db.connect_options())
+        .await
+        .unwrap();
+    other
+        .execute("begin; lock table users in share mode;")
+        .await
+        .unwrap();
+
+    TestSetup {
+        name: "skips_lock_timeouts",
+        statements: &["alter table users add column age int;"],
+        setup: None,
+        test_db: &test_db,
+    }
+    .test()
+    .await;
+
+    other.execute("rollback;").await.unwrap();
+}
@@ -0,0 +1,276 @@
+use pgls_console::fmt::{Display, Formatter, HTML};
+use pgls_diagnostics::{Diagnostic, LogCategory, Visit};
+use pgls_typecheck::{IdentifierType, NameResolutionParams, NameResolver, TypedIdentifier};
+use sqlx::{Executor, PgPool};
+use std::fmt::Write;
+
+struct TestSetup<'a> {
+    name: &'a str,
+    /// Checked in order, as if they were the statements of one file.
+    statements: &'a [&'a str],
+    setup: Option<&'a str>,
+    test_db: &'a PgPool,
+    typed_identifiers: Vec<TypedIdentifier>,
+}
+
+/// Collects the advices of a diagnostic as text.
+#[derive(Default)]
+struct AdviceCollector(Vec<String>);
+
+impl Visit for AdviceCollector {
+    fn record_log(&mut self, _category: LogCategory, text: &dyn Display) -> std::io::Result<()> {
+        let mut content = vec![];
+        let mut writer = HTML::new(&mut content);
+        let mut formatter = Formatter::new(&mut writer);
+        text.fmt(&mut formatter)?;
+        self.0.push(String::from_utf8(content).unwrap());
+        Ok(())
+    }
+}
+
+impl TestSetup<'_> {
+    async fn test(self) {
+        if let Some(setup) = self.setup {
+            self.test_db
+                .execute(setup)
+                .await
+                .expect("Failed to setup test database");
+        }
+
+        let schema_cache = pgls_schema_cache::SchemaCache::load(self.test_db)
+            .await
+            .expect("Failed to load Schema Cache");
+
+        let mut resolver = NameResolver::new(&schema_cache, &[]);
+
+        let mut content = String::new();
+
+        for statement in self.statements {
+            let root = pgls_query::parse(statement)
+                .unwrap()
+                .into_root()
+                .expect("Failed to parse query");
+
+            let diagnostics = resolver.check(NameResolutionParams {
+                sql: statement,
+                ast: &root,
+                identifiers: &self.typed_identifiers,
+            });
+
+            writeln!(&mut content, "{statement}").unwrap();
+
+            if diagnostics.is_empty() {
+                writeln!(&mut content, "No Diagnostic").unwrap();
+            }
+
+            for d in diagnostics {
+                let span = d.location().span.expect("Diagnostic without span");
+                writeln!(
+                    &mut content,
+                    "{}~~~{}~~~{}",
+                    &statement[..span.start().into()],
+                    &statement[span],
+                    &statement[span.end().into()..]
+                )
+                .unwrap();
+
+                let mut msg_content = vec![];
+                let mut writer = HTML::new(&mut msg_content);
+                let mut formatter = Formatter::new(&mut writer);
+                d.message(&mut formatter).unwrap();
+                writeln!(&mut content, "{}", String::from_utf8(msg_content).unwrap()).unwrap();
+
+                let mut advices = AdviceCollector::default();
+                d.advices(&mut advices).unwrap();
+                for advice in advices.0 {
+                    writeln!(&mut content, "{advice}").unwrap();
+                }
+            }
+
+            writeln!(&mut content).unwrap();
+        }
+
+        insta::with_settings!({
+            prepend_module_to_snapshot => false,
+        }, {
+            insta::assert_snapshot!(self.name, content);
+        });
+    }
+}
+
+const SETUP: &str = r#"
+    create table public.users (
+        id serial primary key,
+        name text not null,
+        email text
+    );
+
+    create table public.orders (
+        id serial primary key,
+        user_id int references public.users (id),
+        total numeric
+    );
+
+    create schema billing;
+
+    create table billing.invoices (
+        id serial primary key,
+        order_id int
+    );
+
+    create function public.order_total(order_id int) returns numeric
+    language sql as $$ select 1::numeric $$;
+"#;
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn unknown_names_in_dml(test_db: PgPool) {
+    TestSetup {
+        name: "unknown_names_in_dml",
+        statements: &[
+            "select id, nmae from users;",
+            "select * from userz;",
+            "select * from invoices;",
+            "select u.id, u.mail from users u;",
+            "select x.id from users u;",
+            "insert into users (name, emial) values ('a', 'b');",
+            "update orders set totl = 1 where user_id = 1;",
+            "delete from orders where usr_id = 1;",
+            "select order_totl(id) from orders;",
+        ],
+        setup: Some(SETUP),
+        test_db: &test_db,
+        typed_identifiers: vec![],
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn valid_queries(test_db: PgPool) {
+    TestSetup {
+        name: "valid_queries",
+        statements: &[
+            "select u.id, count(*) as n from users u join orders o on o.user_id = u.id group by u.id order by n;",
+            "select id from users join orders using (id);",
+            "with recent as (select user_id, total from orders) select r.user_id, r.total from recent r;",
+            "select * from (select id, name from users) s where s.name = 'a';",
+            "select id from users where exists (select 1 from orders where orders.user_id = users.id);",
+            "select g from generate_series(1, 10) g;",
+            "select i.order_id from billing.invoices i;",
+            "insert into users (name) values ('a') on conflict (id) do update set name = excluded.name returning id;",
+            "select extract(year from now()), coalesce(email, name) from users;",
+            "select ctid, xmin from users;",
+        ],
+        setup: Some(SETUP),
+        test_db: &test_db,
+        typed_identifiers: vec![],
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn ambiguous_column(test_db: PgPool) {
+    TestSetup {
+        name: "ambiguous_column",
+        statements: &["select id from users join orders on orders.user_id = users.id;"],
+        setup: Some(SETUP),
+        test_db: &test_db,
+        typed_identifiers: vec![],
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn unknown_names_in_ddl(test_db: PgPool) {
+    TestSetup {
+        name: "unknown_names_in_ddl",
+        statements: &[
+            "alter table users alter column emai set not null;",
+            "alter table usr add column age int;",
+            "create index on orders (usr_id);",
+            "create policy own_orders on orders using (user_id = current_setting('app.user')::int and totl > 0);",
+            "alter table orders add constraint fk foreign key (user_id) references users (uid);",
+            "alter table users rename column nme to full_name;",
+        ],
+        setup: Some(SETUP),
+        test_db: &test_db,
+        typed_identifiers: vec![],
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn objects_created_by_previous_statements(test_db: PgPool) {
+    TestSetup {
+        name: "objects_created_by_previous_statements",
+        statements: &[
+            "create table products (id serial primary key, title text);",
+            "alter table products add column price numeric;",
+            "alter table users rename column name to full_name;",
+            "select id, title, price from products;",
+            "select full_name, name from users;",
+            "create view expensive as select id, price as amount from products where price > 100;",
+            "select amount, price from expensive;",
+            "alter table products drop column title;",
+            "select title from products;",
+        ],
+        setup: Some(SETUP),
+        test_db: &test_db,
+        typed_identifiers: vec![],
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn function_parameters(test_db: PgPool) {
+    TestSetup {
+        name: "function_parameters",
+        statements: &["select id from users where id = user_id and name = find_user.user_name;"],
+        setup: Some(SETUP),
+        test_db: &test_db,
+        typed_identifiers: vec![
+            TypedIdentifier {
+                path: "find_user".to_string(),
+                name: Some("user_id".to_string()),
+                type_: IdentifierType {
+                    schema: None,
+                    name: "int4".to_string(),
+                    is_array: false,
+                },
+            },
+            TypedIdentifier {
+                path: "find_user".to_string(),
+                name: Some("user_name".to_string()),
+                type_: IdentifierType {
+                    schema: None,
+                    name: "text".to_string(),
+                    is_array: false,
+                },
+            },
+        ],
+    }
+    .test()
+    .await;
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn database_without_migrations(test_db: PgPool) {
+    TestSetup {
+        name: "database_without_migrations",
+        statements: &[
+            "alter table users drop column deprecated_field;",
+            "create table posts (id int, title text);",
+            "select titel from posts;",
+        ],
+        // the bookkeeping table of sqlx would be the only relation of the database
+        setup: Some("drop table public._sqlx_migrations;"),
+        test_db: &test_db,
+        typed_identifiers: vec![],
+    }
+    .test()
+    .await;
+}
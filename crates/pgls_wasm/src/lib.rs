@@ -64,6 +64,8 @@ pub struct CompletionItem {
     pub kind: String,
     /// Optional detail text
     pub detail: Option<String>,
+    /// Byte ranges of the label that matched the typed text, as `(start, end)`
+    pub highlights: Vec<(u32, u32)>,
 }
 
 /// A single SQL statement extracted from a multi-statement source.
@@ -323,11 +325,21 @@ impl Workspace {
                     pgls_completions::CompletionItemKind::Policy => "policy",
                     pgls_completions::CompletionItemKind::Role => "role",
                     pgls_completions::CompletionItemKind::Keyword => "keyword",
+                    pgls_completions::CompletionItemKind::Type => "type",
+                    pgls_completions::CompletionItemKind::EnumValue => "enumValue",
+                    pgls_completions::CompletionItemKind::Sequence => "sequence",
+                    pgls_completions::CompletionItemKind::JoinCondition => "joinCondition",
+                    pgls_completions::CompletionItemKind::Snippet => "snippet",
                 };
                 CompletionItem {
                     label: c.label,
                     kind: kind.to_string(),
                     detail: Some(c.description),
+                    highlights: c
+                        .highlights
+                        .iter()
+                        .map(|r| (r.start().into(), r.end().into()))
+                        .collect(),
                 }
             })
             .collect();
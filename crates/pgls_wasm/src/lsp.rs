@@ -240,6 +240,11 @@ impl LspHandler {
                     "column" => CompletionItemKind::FIELD,
                     "function" => CompletionItemKind::FUNCTION,
                     "schema" => CompletionItemKind::MODULE,
+                    "type" => CompletionItemKind::TYPE_PARAMETER,
+                    "enumValue" => CompletionItemKind::ENUM_MEMBER,
+                    "sequence" => CompletionItemKind::VARIABLE,
+                    "joinCondition" => CompletionItemKind::REFERENCE,
+                    "snippet" => CompletionItemKind::SNIPPET,
                     _ => CompletionItemKind::TEXT,
                 }),
                 detail: c.detail,
@@ -409,7 +409,7 @@ impl PartialConfigurationExt for PartialConfiguration {
 }
 
 /// Normalizes a path, resolving '..' and '.' segments without requiring the path to exist
-fn normalize_path(path: &Path) -> PathBuf {
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
     let mut components = Vec::new();
     let mut prefix_component = None;
     let mut is_absolute = false;
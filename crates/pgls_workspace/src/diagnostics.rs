@@ -27,6 +27,8 @@ pub enum WorkspaceError {
     CantReadDirectory(CantReadDirectory),
     /// Thrown when we can't read a generic file
     CantReadFile(CantReadFile),
+    /// Thrown when we can't write a generic file
+    CantWriteFile(CantWriteFile),
     /// The file does not exist in the [crate::Workspace]
     NotFound(NotFound),
     /// Error emitted by the underlying transport layer for a remote Workspace
@@ -50,6 +52,10 @@ impl WorkspaceError {
         Self::CantReadFile(CantReadFile { path })
     }
 
+    pub fn cant_write_file(path: String, reason: String) -> Self {
+        Self::CantWriteFile(CantWriteFile { path, reason })
+    }
+
     pub fn not_found() -> Self {
         Self::NotFound(NotFound)
     }
@@ -331,6 +337,20 @@ pub struct CantReadFile {
     path: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Diagnostic)]
+#[diagnostic(
+    category = "internalError/fs",
+    message(
+        message("We couldn't write the following file: "{self.path}": "{self.reason}),
+        description = "We couldn't write the following file: {path}: {reason}"
+    )
+)]
+pub struct CantWriteFile {
+    #[location(resource)]
+    path: String,
+    reason: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Diagnostic)]
 #[diagnostic(
     category = "internalError/fs",
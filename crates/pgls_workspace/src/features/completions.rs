@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use pgls_completions::CompletionItem;
+use pgls_completions::{CompletionItem, CompletionResolveData, ResolvedCompletionItem};
 use pgls_fs::PgLSPath;
 use pgls_text_size::{TextRange, TextSize};
 
@@ -41,6 +41,30 @@ impl IntoIterator for CompletionsResult {
     }
 }
 
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ResolveCompletionParams {
+    /// The `resolve_data` of the completion item to resolve.
+    pub data: CompletionResolveData,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ResolveCompletionResult {
+    pub detail: Option<String>,
+    /// Markdown documentation of the item.
+    pub documentation: Option<String>,
+}
+
+impl From<ResolvedCompletionItem> for ResolveCompletionResult {
+    fn from(item: ResolvedCompletionItem) -> Self {
+        Self {
+            detail: item.detail,
+            documentation: item.documentation,
+        }
+    }
+}
+
 pub(crate) fn get_statement_for_completions(
     doc: &Document,
     position: TextSize,
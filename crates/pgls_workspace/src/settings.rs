@@ -7,31 +7,34 @@ use std::{
     borrow::Cow,
     num::NonZeroU64,
     path::{Path, PathBuf},
-    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
     time::Duration,
 };
 use tracing::trace;
 
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use pgls_configuration::{
-    ConfigurationDiagnostic, LinterConfiguration, PartialConfiguration, TypecheckConfiguration,
+    CompletionsConfiguration, ConfigurationDiagnostic, LinterConfiguration, PartialConfiguration,
+    TypecheckConfiguration,
     database::PartialDatabaseConfiguration,
     diagnostics::InvalidIgnorePattern,
     files::FilesConfiguration,
     format::{FormatConfiguration, IndentStyle, KeywordCase},
-    migrations::{MigrationsConfiguration, PartialMigrationsConfiguration},
+    migrations::{
+        MigrationLayout, MigrationTool, MigrationsConfiguration, PartialMigrationsConfiguration,
+    },
     pglinter::PglinterConfiguration,
     plpgsql_check::PlPgSqlCheckConfiguration,
     splinter::SplinterConfiguration,
 };
-use pgls_fs::PgLSPath;
+use pgls_fs::{FileSystem, PgLSPath};
 #[cfg(feature = "db")]
 use sqlx::postgres::PgConnectOptions;
 
 use crate::{
     WorkspaceError,
     matcher::Matcher,
-    workspace::{ProjectKey, WorkspaceData},
+    workspace::{ProjectKey, WorkspaceData, server::load_migration_layout},
 };
 
 #[derive(Debug, Default)]
@@ -224,6 +227,9 @@ pub struct Settings {
     /// Formatter settings applied to all files in the workspace
     pub formatter: FormatterSettings,
 
+    /// Completion settings for the workspace
+    pub completions: CompletionsSettings,
+
     /// Pglinter (database linter via pglinter extension) settings for the workspace
     pub pglinter: PglinterSettings,
 
@@ -246,6 +252,7 @@ impl Settings {
         working_directory: Option<PathBuf>,
         vcs_path: Option<PathBuf>,
         gitignore_matches: &[String],
+        fs: &dyn FileSystem,
     ) -> Result<(), WorkspaceError> {
         // Filesystem settings
         if let Some(files) = to_file_settings(
@@ -281,6 +288,11 @@ impl Settings {
             )?;
         }
 
+        // completions part
+        if let Some(completions) = configuration.completions {
+            self.completions = to_completions_settings(CompletionsConfiguration::from(completions));
+        }
+
         // pglinter part
         if let Some(pglinter) = configuration.pglinter {
             self.pglinter = to_pglinter_settings(PglinterConfiguration::from(pglinter));
@@ -300,6 +312,7 @@ impl Settings {
         // Migrations settings
         if let Some(migrations) = configuration.migrations {
             self.migrations = to_migration_settings(
+                fs,
                 working_directory.clone(),
                 MigrationsConfiguration::from(migrations),
             );
@@ -382,6 +395,20 @@ fn to_formatter_settings(
     })
 }
 
+fn to_completions_settings(conf: CompletionsConfiguration) -> CompletionsSettings {
+    CompletionsSettings {
+        snippets: conf
+            .snippets
+            .0
+            .into_iter()
+            .map(|snippet| {
+                pgls_completions::Snippet::new(snippet.name, snippet.description, snippet.body)
+            })
+            .collect(),
+        persist_usage: !conf.disable_usage_persistence,
+    }
+}
+
 fn to_pglinter_settings(conf: PglinterConfiguration) -> PglinterSettings {
     PglinterSettings {
         enabled: conf.enabled,
@@ -393,6 +420,7 @@ fn to_typecheck_settings(conf: TypecheckConfiguration) -> TypecheckSettings {
     TypecheckSettings {
         search_path: conf.search_path.into_iter().collect(),
         enabled: conf.enabled,
+        check_ddl: conf.check_ddl,
     }
 }
 
@@ -660,6 +688,25 @@ impl Default for PlPgSqlCheckSettings {
     }
 }
 
+/// Completion settings for the entire workspace
+#[derive(Debug)]
+pub struct CompletionsSettings {
+    /// User-defined snippets from the configuration
+    pub snippets: Vec<pgls_completions::Snippet>,
+
+    /// Whether the usage of database objects is stored on disk
+    pub persist_usage: bool,
+}
+
+impl Default for CompletionsSettings {
+    fn default() -> Self {
+        Self {
+            snippets: vec![],
+            persist_usage: true,
+        }
+    }
+}
+
 /// Type checking settings for the entire workspace
 #[derive(Debug)]
 pub struct TypecheckSettings {
@@ -667,6 +714,8 @@ pub struct TypecheckSettings {
     pub enabled: bool,
     /// Default search path schemas for type checking
     pub search_path: Vec<String>,
+    /// Execute DDL statements in a rolled-back transaction, disabled by default
+    pub check_ddl: bool,
 }
 
 impl Default for TypecheckSettings {
@@ -674,6 +723,7 @@ impl Default for TypecheckSettings {
         Self {
             enabled: true,
             search_path: vec!["public".to_string()],
+            check_ddl: false,
         }
     }
 }
@@ -689,6 +739,8 @@ pub struct DatabaseSettings {
     pub database: String,
     pub conn_timeout_secs: Duration,
     pub allow_statement_executions: bool,
+    pub schema_cache_listen_channel: Option<String>,
+    pub persist_schema_cache: bool,
 }
 
 impl std::fmt::Debug for DatabaseSettings {
@@ -709,6 +761,11 @@ impl std::fmt::Debug for DatabaseSettings {
                 "allow_statement_executions",
                 &self.allow_statement_executions,
             )
+            .field(
+                "schema_cache_listen_channel",
+                &self.schema_cache_listen_channel,
+            )
+            .field("persist_schema_cache", &self.persist_schema_cache)
             .finish()
     }
 }
@@ -725,6 +782,8 @@ impl Default for DatabaseSettings {
             database: "postgres".to_string(),
             conn_timeout_secs: Duration::from_secs(10),
             allow_statement_executions: true,
+            schema_cache_listen_channel: None,
+            persist_schema_cache: true,
         }
     }
 }
@@ -801,6 +860,14 @@ impl From<PartialDatabaseConfiguration> for DatabaseSettings {
                 .unwrap_or(d.conn_timeout_secs),
 
             allow_statement_executions,
+
+            schema_cache_listen_channel: value
+                .schema_cache_listen_channel
+                .filter(|channel| !channel.trim().is_empty()),
+
+            persist_schema_cache: !value
+                .disable_schema_cache_persistence
+                .is_some_and(|disabled| disabled),
         }
     }
 }
@@ -822,28 +889,53 @@ pub struct FilesSettings {
 }
 
 /// Migration settings
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct MigrationSettings {
     pub path: Option<PathBuf>,
     pub after: Option<u64>,
+    pub tool: MigrationTool,
+    /// The layout of the migration files, loaded once for the migrations directory
+    pub layout: Arc<dyn MigrationLayout>,
+}
+
+impl Default for MigrationSettings {
+    fn default() -> Self {
+        Self {
+            path: None,
+            after: None,
+            tool: MigrationTool::default(),
+            layout: MigrationTool::default().layout(None),
+        }
+    }
 }
 
 impl From<PartialMigrationsConfiguration> for MigrationSettings {
     fn from(value: PartialMigrationsConfiguration) -> Self {
+        let path = value.migrations_dir.map(PathBuf::from);
+        let tool = value.tool.unwrap_or_default();
         Self {
-            path: value.migrations_dir.map(PathBuf::from),
+            // without a file system, the plan of Sqitch isn't read
+            layout: tool.layout(None),
+            path,
             after: value.after,
+            tool,
         }
     }
 }
 
 fn to_migration_settings(
+    fs: &dyn FileSystem,
     working_directory: Option<PathBuf>,
     conf: MigrationsConfiguration,
 ) -> Option<MigrationSettings> {
-    working_directory.map(|working_directory| MigrationSettings {
-        path: Some(working_directory.join(conf.migrations_dir)),
-        after: Some(conf.after),
+    working_directory.map(|working_directory| {
+        let path = working_directory.join(conf.migrations_dir);
+        MigrationSettings {
+            layout: load_migration_layout(fs, &path, conf.tool),
+            path: Some(path),
+            after: Some(conf.after),
+            tool: conf.tool,
+        }
     })
 }
 
@@ -938,4 +1030,44 @@ mod tests {
 
         assert!(!config.allow_statement_executions)
     }
+
+    #[test]
+    fn pglinter_follows_the_configuration_default() {
+        use pgls_configuration::PartialConfiguration;
+        use pgls_configuration::pglinter::PartialPglinterConfiguration;
+        use pgls_fs::MemoryFileSystem;
+
+        use super::Settings;
+
+        let with_pglinter = |enabled| PartialConfiguration {
+            pglinter: Some(PartialPglinterConfiguration {
+                enabled,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut settings = Settings::default();
+        settings
+            .merge_with_configuration(
+                with_pglinter(None),
+                None,
+                None,
+                &[],
+                &MemoryFileSystem::default(),
+            )
+            .unwrap();
+        assert!(!settings.pglinter.enabled);
+
+        settings
+            .merge_with_configuration(
+                with_pglinter(Some(true)),
+                None,
+                None,
+                &[],
+                &MemoryFileSystem::default(),
+            )
+            .unwrap();
+        assert!(settings.pglinter.enabled);
+    }
 }
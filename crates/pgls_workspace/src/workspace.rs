@@ -15,7 +15,10 @@ use crate::{
         code_actions::{
             CodeActionsParams, CodeActionsResult, ExecuteStatementParams, ExecuteStatementResult,
         },
-        completions::{CompletionsResult, GetCompletionsParams},
+        completions::{
+            CompletionsResult, GetCompletionsParams, ResolveCompletionParams,
+            ResolveCompletionResult,
+        },
         diagnostics::{
             PullDatabaseDiagnosticsParams, PullDiagnosticsResult, PullFileDiagnosticsParams,
         },
@@ -67,6 +70,18 @@ pub struct UpdateSettingsParams {
     pub workspace_directory: Option<PathBuf>,
 }
 
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FilesChangedParams {
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IndexUsageParams {
+    pub path: PathBuf,
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GetFileContentParams {
@@ -129,6 +144,12 @@ pub trait Workspace: Send + Sync + RefUnwindSafe {
         params: GetCompletionsParams,
     ) -> Result<CompletionsResult, WorkspaceError>;
 
+    /// Computes the documentation of a completion item that was left out of [Workspace::get_completions].
+    fn resolve_completion(
+        &self,
+        params: ResolveCompletionParams,
+    ) -> Result<ResolveCompletionResult, WorkspaceError>;
+
     fn on_hover(&self, params: OnHoverParams) -> Result<OnHoverResult, WorkspaceError>;
 
     /// Register a possible workspace project folder. Returns the key of said project. Use this key when you want to switch to different projects.
@@ -155,6 +176,10 @@ pub trait Workspace: Send + Sync + RefUnwindSafe {
     /// Change the content of an open file
     fn change_file(&self, params: ChangeFileParams) -> Result<(), WorkspaceError>;
 
+    /// Notifies the workspace that files were created, changed or deleted on disk, e.g. as
+    /// reported by a file watcher, so that what it read from them is read again.
+    fn files_changed(&self, params: FilesChangedParams) -> Result<(), WorkspaceError>;
+
     /// Returns information about the server this workspace is connected to or `None` if the workspace isn't connected to a server.
     fn server_info(&self) -> Option<&ServerInfo>;
 
@@ -181,6 +206,20 @@ pub trait Workspace: Send + Sync + RefUnwindSafe {
     ///
     /// The schema will be reloaded lazily on the next operation that requires it.
     fn invalidate_schema_cache(&self, all: bool) -> Result<(), WorkspaceError>;
+
+    /// Returns a counter that is incremented whenever the schema cache is invalidated or
+    /// refreshed, so that clients can tell when the database schema might have changed.
+    fn schema_cache_generation(&self) -> Result<u64, WorkspaceError>;
+
+    /// Writes the usage of database objects collected from the workspace's files to disk, if
+    /// it is persisted. Files are written periodically when they are closed, so this only needs
+    /// to be called before shutting down.
+    fn persist_usage(&self) -> Result<(), WorkspaceError>;
+
+    /// Collects the usage of database objects from the SQL files below a directory, except
+    /// for ignored ones, so that completions can rank objects before their files are opened.
+    /// Open files are collected from their content instead, whenever it changes.
+    fn index_usage(&self, params: IndexUsageParams) -> Result<(), WorkspaceError>;
 }
 
 /// Convenience function for constructing a server instance of [Workspace]
@@ -132,6 +132,10 @@ where
         self.request("pgls/change_file", params)
     }
 
+    fn files_changed(&self, params: super::FilesChangedParams) -> Result<(), WorkspaceError> {
+        self.request("pgls/files_changed", params)
+    }
+
     fn update_settings(&self, params: super::UpdateSettingsParams) -> Result<(), WorkspaceError> {
         self.request("pgls/update_settings", params)
     }
@@ -176,6 +180,13 @@ where
         self.request("pgls/get_completions", params)
     }
 
+    fn resolve_completion(
+        &self,
+        params: crate::features::completions::ResolveCompletionParams,
+    ) -> Result<crate::features::completions::ResolveCompletionResult, WorkspaceError> {
+        self.request("pgls/resolve_completion", params)
+    }
+
     fn on_hover(
         &self,
         params: crate::features::on_hover::OnHoverParams,
@@ -186,4 +197,16 @@ where
     fn invalidate_schema_cache(&self, all: bool) -> Result<(), WorkspaceError> {
         self.request("pgls/invalidate_schema_cache", all)
     }
+
+    fn schema_cache_generation(&self) -> Result<u64, WorkspaceError> {
+        self.request("pgls/schema_cache_generation", ())
+    }
+
+    fn persist_usage(&self) -> Result<(), WorkspaceError> {
+        self.request("pgls/persist_usage", ())
+    }
+
+    fn index_usage(&self, params: super::IndexUsageParams) -> Result<(), WorkspaceError> {
+        self.request("pgls/index_usage", params)
+    }
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     fs,
     panic::RefUnwindSafe,
     path::{Path, PathBuf},
@@ -11,29 +11,36 @@ use analyser::AnalyserVisitorBuilder;
 use async_helper::run_async;
 #[cfg(feature = "db")]
 use connection_manager::ConnectionManager;
-use document::{CursorPositionFilter, DefaultMapper, Document, FormatStatementMapper};
 #[cfg(feature = "db")]
-use document::{ExecuteStatementMapper, TypecheckDiagnosticsMapper};
+use document::ExecuteStatementMapper;
+use document::{
+    CursorPositionFilter, DefaultMapper, Document, FormatStatementMapper,
+    TypecheckDiagnosticsMapper,
+};
 #[cfg(feature = "db")]
 use futures::{StreamExt, TryStreamExt, stream};
-#[cfg(feature = "db")]
 use pg_query::convert_to_positional_params;
 use pgls_analyse::AnalysisFilter;
 use pgls_analyser::{Analyser, AnalyserConfig, AnalyserParams, LinterOptions};
+use pgls_configuration::migrations::MigrationVersion;
 
 use pgls_diagnostics::{
     Diagnostic, DiagnosticExt, Error, Severity, serde::Diagnostic as SDiagnostic,
 };
-use pgls_fs::{ConfigName, PgLSPath};
+use pgls_fs::{ConfigName, FileSystem, OsFileSystem, PgLSPath};
 use pgls_schema_cache::SchemaCache;
 use pgls_text_size::TextRange;
 #[cfg(feature = "db")]
-use pgls_typecheck::{IdentifierType, TypecheckParams, TypedIdentifier};
+use pgls_typecheck::{DdlCheckParams, DdlStatement, TypecheckParams};
+use pgls_typecheck::{NameResolutionParams, NameResolver};
 use pgls_workspace_macros::ignored_path;
 use schema_cache_manager::SchemaCacheManager;
 #[cfg(feature = "db")]
+use schema_cache_manager::affected_object_kinds;
+#[cfg(feature = "db")]
 use sqlx::Executor;
 use tracing::{debug, info};
+use usage_index::UsageIndex;
 
 use crate::{
     WorkspaceError,
@@ -43,7 +50,10 @@ use crate::{
             CodeAction, CodeActionKind, CodeActionsParams, CodeActionsResult, CommandAction,
             CommandActionCategory, ExecuteStatementParams, ExecuteStatementResult,
         },
-        completions::{CompletionsResult, GetCompletionsParams, get_statement_for_completions},
+        completions::{
+            CompletionsResult, GetCompletionsParams, ResolveCompletionParams,
+            ResolveCompletionResult, get_statement_for_completions,
+        },
         diagnostics::{PullDiagnosticsResult, PullFileDiagnosticsParams},
         format::{PullFileFormattingParams, PullFormattingResult, StatementFormatResult},
         on_hover::{OnHoverParams, OnHoverResult},
@@ -53,11 +63,13 @@ use crate::{
 };
 
 use super::{
-    GetFileContentParams, IsPathIgnoredParams, OpenFileParams, ProjectKey,
-    RegisterProjectFolderParams, ServerInfo, UnregisterProjectFolderParams, UpdateSettingsParams,
-    Workspace,
+    FilesChangedParams, GetFileContentParams, IndexUsageParams, IsPathIgnoredParams,
+    OpenFileParams, ProjectKey, RegisterProjectFolderParams, ServerInfo,
+    UnregisterProjectFolderParams, UpdateSettingsParams, Workspace,
 };
 
+use migration::MigrationCache;
+pub use migration::{get_migration, load_migration_layout};
 pub use statement_identifier::StatementId;
 
 mod analyser;
@@ -72,9 +84,12 @@ pub(crate) mod document;
 mod migration;
 mod pg_query;
 mod schema_cache_manager;
+#[cfg(feature = "db")]
+mod schema_cache_store;
 mod sql_function;
 mod statement_identifier;
 mod tree_sitter;
+mod usage_index;
 
 pub struct WorkspaceServer {
     /// global settings object for this workspace
@@ -85,6 +100,15 @@ pub struct WorkspaceServer {
     /// Manages schema cache storage - supports both DB-loaded and JSON-loaded schemas
     schema_cache: SchemaCacheManager,
 
+    /// How often database objects are used across the workspace's files
+    usage: UsageIndex,
+
+    /// The statements of the migrations that come before a checked migration
+    migrations: MigrationCache,
+
+    /// Reads the files that aren't open, e.g. the earlier migrations
+    fs: Box<dyn FileSystem>,
+
     #[cfg(feature = "db")]
     connection: ConnectionManager,
 }
@@ -111,6 +135,9 @@ impl WorkspaceServer {
             settings: RwLock::default(),
             documents: RwLock::new(HashMap::new()),
             schema_cache: SchemaCacheManager::new(),
+            usage: UsageIndex::new(),
+            migrations: MigrationCache::default(),
+            fs: Box::<OsFileSystem>::default(),
             connection: ConnectionManager::new(),
         }
     }
@@ -122,6 +149,9 @@ impl WorkspaceServer {
             settings: RwLock::default(),
             documents: RwLock::new(HashMap::new()),
             schema_cache: SchemaCacheManager::new(),
+            usage: UsageIndex::new(),
+            migrations: MigrationCache::default(),
+            fs: Box::<OsFileSystem>::default(),
         }
     }
 
@@ -195,9 +225,14 @@ impl WorkspaceServer {
             .and_then(|migration_settings| {
                 let ignore_before = migration_settings.after.as_ref()?;
                 let migrations_dir = migration_settings.path.as_ref()?;
-                let migration = migration::get_migration(path, migrations_dir)?;
-
-                Some(&migration.sequence_number <= ignore_before)
+                let migration = migration::get_migration(
+                    self.fs.as_ref(),
+                    path,
+                    migrations_dir,
+                    migration_settings.layout.as_ref(),
+                )?;
+
+                Some(migration.sequence_number <= MigrationVersion::from(*ignore_before))
             })
             .unwrap_or(false)
     }
@@ -247,6 +282,35 @@ impl WorkspaceServer {
                 }
             })
     }
+
+    /// Collects the SQL files below `dir` that are not ignored. Hidden directories such as
+    /// `.git` and symlinks are skipped.
+    fn collect_sql_files(&self, dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                if !entry.file_name().to_string_lossy().starts_with('.') && !self.is_ignored(&path)
+                {
+                    self.collect_sql_files(&path, files);
+                }
+            } else if file_type.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext == "sql" || ext == "pg")
+                && !self.is_ignored(&path)
+            {
+                files.push(path);
+            }
+        }
+    }
 }
 
 impl Workspace for WorkspaceServer {
@@ -301,6 +365,9 @@ impl Workspace for WorkspaceServer {
     fn update_settings(&self, params: UpdateSettingsParams) -> Result<(), WorkspaceError> {
         let mut workspace = self.workspaces_mut();
 
+        #[cfg(feature = "db")]
+        let workspace_directory = params.workspace_directory.clone();
+
         workspace
             .as_mut()
             .get_current_settings_mut()
@@ -309,8 +376,33 @@ impl Workspace for WorkspaceServer {
                 params.workspace_directory,
                 params.vcs_base_path,
                 params.gitignore_matches.as_slice(),
+                self.fs.as_ref(),
             )?;
 
+        // the migrations directory or its layout might have changed
+        self.migrations.clear();
+
+        #[cfg(feature = "db")]
+        if let Some(settings) = workspace.as_mut().get_current_settings() {
+            self.schema_cache
+                .set_listen_channel(settings.db.schema_cache_listen_channel.clone());
+            self.schema_cache.set_store(
+                settings
+                    .db
+                    .persist_schema_cache
+                    .then(schema_cache_store::SchemaCacheStore::in_cache_dir),
+            );
+
+            if let Some(workspace_directory) = workspace_directory.as_deref() {
+                self.usage.set_store(
+                    settings
+                        .completions
+                        .persist_usage
+                        .then(|| usage_index::UsageStore::in_cache_dir(workspace_directory)),
+                );
+            }
+        }
+
         Ok(())
     }
 
@@ -322,6 +414,7 @@ impl Workspace for WorkspaceServer {
         documents
             .entry(params.path.clone())
             .or_insert_with(|| Document::new(params.content, params.version));
+        self.usage.mark_outdated(params.path.as_path());
 
         if let Some(project_key) = self.path_belongs_to_current_workspace(&params.path) {
             self.set_current_project(project_key);
@@ -334,10 +427,12 @@ impl Workspace for WorkspaceServer {
     #[ignored_path(path=&params.path)]
     fn close_file(&self, params: super::CloseFileParams) -> Result<(), WorkspaceError> {
         let mut documents = self.documents.write().unwrap();
-        documents
+        let document = documents
             .remove(&params.path)
             .ok_or_else(WorkspaceError::not_found)?;
 
+        self.usage.close(params.path.as_path(), &document);
+
         Ok(())
     }
 
@@ -360,6 +455,14 @@ impl Workspace for WorkspaceServer {
                 entry.insert(Document::new(params.content, params.version));
             }
         }
+        self.usage.mark_outdated(params.path.as_path());
+        self.migrations.file_changed(params.path.as_path());
+
+        Ok(())
+    }
+
+    fn files_changed(&self, params: FilesChangedParams) -> Result<(), WorkspaceError> {
+        self.migrations.files_changed_on_disk(&params.paths);
 
         Ok(())
     }
@@ -470,7 +573,7 @@ impl Workspace for WorkspaceServer {
 
         let (_id, _range, content, ast) = stmt.unwrap();
 
-        if ast.is_none() {
+        let Some(ast) = ast else {
             return Ok(ExecuteStatementResult {
                 message: "Statement is invalid.".into(),
             });
@@ -479,9 +582,24 @@ impl Workspace for WorkspaceServer {
         let settings = self.workspaces();
         let Some(result) = settings.settings().and_then(|settings| {
             self.connection.with_pool(&settings.db, |pool| {
-                let pool = pool.clone();
-                run_async(async move { pool.execute(sqlx::query(&content)).await })?
-                    .map_err(WorkspaceError::from)
+                let result = {
+                    let pool = pool.clone();
+                    run_async(async move { pool.execute(sqlx::query(&content)).await })?
+                        .map_err(WorkspaceError::from)
+                }?;
+
+                // keep completions and hover in sync with the DDL we just ran. The statement is
+                // already committed, so a failed refresh drops the cache to have it reloaded on
+                // the next request instead of failing the execution.
+                if let Err(err) = self
+                    .schema_cache
+                    .refresh(pool, &affected_object_kinds(&ast))
+                {
+                    tracing::warn!("Failed to refresh the schema cache after execution: {err}");
+                    self.schema_cache.clear_connection(pool);
+                }
+
+                Ok(result)
             })
         }) else {
             return Ok(ExecuteStatementResult {
@@ -537,6 +655,45 @@ impl Workspace for WorkspaceServer {
         Ok(())
     }
 
+    fn schema_cache_generation(&self) -> Result<u64, WorkspaceError> {
+        Ok(self.schema_cache.generation())
+    }
+
+    #[cfg(feature = "db")]
+    fn persist_usage(&self) -> Result<(), WorkspaceError> {
+        self.usage.persist()
+    }
+
+    #[cfg(not(feature = "db"))]
+    fn persist_usage(&self) -> Result<(), WorkspaceError> {
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "info", skip_all, fields(path = params.path.to_str()))]
+    fn index_usage(&self, params: IndexUsageParams) -> Result<(), WorkspaceError> {
+        let mut files = Vec::new();
+        self.collect_sql_files(&params.path, &mut files);
+
+        for path in files {
+            // open files are collected from their content
+            if self
+                .documents
+                .read()
+                .unwrap()
+                .contains_key(&PgLSPath::new(&path))
+            {
+                continue;
+            }
+
+            match fs::read_to_string(&path) {
+                Ok(content) => self.usage.update(&path, &Document::new(content, 0)),
+                Err(err) => debug!("Skipping {} while indexing usage: {err}", path.display()),
+            }
+        }
+
+        Ok(())
+    }
+
     #[ignored_path(path=&params.path)]
     fn pull_file_diagnostics(
         &self,
@@ -567,12 +724,84 @@ impl Workspace for WorkspaceServer {
          */
         let mut diagnostics: Vec<SDiagnostic> = doc.document_diagnostics().to_vec();
 
+        #[cfg(feature = "db")]
+        let schema_cache = self
+            .connection
+            .with_pool(&settings.db, |pool| self.schema_cache.load(pool))
+            .and_then(Result::ok)
+            .or_else(|| self.schema_cache.get());
+
+        #[cfg(not(feature = "db"))]
+        let schema_cache = self.schema_cache.get();
+
+        /*
+         * Resolving names against the schema cache, which also works with offline snapshots.
+         * Statements with unresolved names are not type-checked against the database,
+         * since it would only report the first of them again.
+         */
+        let mut statements_with_unresolved_names = HashSet::new();
+        if settings.typecheck.enabled
+            && let Some(schema_cache) = schema_cache.as_deref()
+        {
+            let mut resolver = NameResolver::new(schema_cache, &settings.typecheck.search_path);
+
+            // The database might not have all migrations applied yet, so the relations created
+            // by the migrations before this one are taken into account
+            if let Some(migration_settings) = settings.migrations.as_ref()
+                && let Some(migrations_dir) = migration_settings.path.as_ref()
+                && let Some(migration) = migration::get_migration(
+                    self.fs.as_ref(),
+                    params.path.as_path(),
+                    migrations_dir,
+                    migration_settings.layout.as_ref(),
+                )
+            {
+                for statements in self.migrations.earlier_statements(
+                    self.fs.as_ref(),
+                    migrations_dir,
+                    migration_settings.layout.as_ref(),
+                    &migration,
+                ) {
+                    for (sql, ast) in statements.iter() {
+                        resolver.record_statement(sql, ast);
+                    }
+                }
+            }
+
+            for (id, range, ast, _, fn_sig) in doc.iter(TypecheckDiagnosticsMapper) {
+                let Some(ast) = ast else {
+                    continue;
+                };
+
+                let identifiers = fn_sig.map(|s| s.typed_identifiers()).unwrap_or_default();
+
+                let name_diagnostics = resolver.check(NameResolutionParams {
+                    sql: convert_to_positional_params(id.content()).as_str(),
+                    ast: &ast,
+                    identifiers: &identifiers,
+                });
+
+                if !name_diagnostics.is_empty() {
+                    statements_with_unresolved_names.insert(id);
+                }
+
+                for diag in name_diagnostics {
+                    let r = diag.location().span.map(|span| span + range.start());
+                    diagnostics.push(SDiagnostic::new(
+                        diag.with_file_path(params.path.as_path().display().to_string())
+                            .with_file_span(r.unwrap_or(range)),
+                    ));
+                }
+            }
+        }
+
         /*
          * Type-checking against database connection (DB mode only)
          */
         #[cfg(feature = "db")]
         {
             let typecheck_enabled = settings.typecheck.enabled;
+            let check_ddl_enabled = settings.typecheck.check_ddl;
             let plpgsql_check_enabled = settings.plpgsql_check.enabled;
             let plpgsql_check_fatal_errors = settings.plpgsql_check.fatal_errors;
             let plpgsql_check_other_warnings = settings.plpgsql_check.other_warnings;
@@ -591,14 +820,22 @@ impl Workspace for WorkspaceServer {
                 let db_diagnostics = self.connection.with_pool(&settings.db, |pool| {
                     let schema_cache = self.schema_cache.load(pool)?;
                     let path_clone = params.path.clone();
-                    let input = doc.iter(TypecheckDiagnosticsMapper).collect::<Vec<_>>();
+                    let input = doc
+                        .iter(TypecheckDiagnosticsMapper)
+                        .map(|(id, range, ast, cst, fn_sig)| {
+                            // with `checkDdl`, top-level statements are checked in the DDL transaction
+                            let skip_typecheck = statements_with_unresolved_names.contains(&id)
+                                || (check_ddl_enabled && id.is_root());
+                            (id, range, ast, cst, fn_sig, skip_typecheck)
+                        })
+                        .collect::<Vec<_>>();
                     let search_path_patterns = settings.typecheck.search_path.clone();
                     let pool = pool.clone();
 
                     // Combined async context for both typecheck and plpgsql_check
                     run_async(async move {
                         stream::iter(input)
-                            .map(|(id, range, ast, cst, fn_sig)| {
+                            .map(|(id, range, ast, cst, fn_sig, skip_typecheck)| {
                                 let pool = pool.clone();
                                 let path = path_clone.clone();
                                 let schema_cache = Arc::clone(&schema_cache);
@@ -609,7 +846,7 @@ impl Workspace for WorkspaceServer {
 
                                     if let Some(ast) = ast {
                                         // Type checking
-                                        if typecheck_enabled {
+                                        if typecheck_enabled && !skip_typecheck {
                                             let typecheck_result =
                                                 pgls_typecheck::check_sql(TypecheckParams {
                                                     conn: &pool,
@@ -620,20 +857,7 @@ impl Workspace for WorkspaceServer {
                                                     schema_cache: schema_cache.as_ref(),
                                                     search_path_patterns,
                                                     identifiers: fn_sig
-                                                        .map(|s| {
-                                                            s.args
-                                                                .iter()
-                                                                .map(|a| TypedIdentifier {
-                                                                    path: s.name.clone(),
-                                                                    name: a.name.clone(),
-                                                                    type_: IdentifierType {
-                                                                        schema: a.type_.schema.clone(),
-                                                                        name: a.type_.name.clone(),
-                                                                        is_array: a.type_.is_array,
-                                                                    },
-                                                                })
-                                                                .collect::<Vec<_>>()
-                                                        })
+                                                        .map(|s| s.typed_identifiers())
                                                         .unwrap_or_default(),
                                                 })
                                                 .await;
@@ -721,6 +945,80 @@ impl Workspace for WorkspaceServer {
                     }
                 }
             }
+
+            /*
+             * Executing the DDL statements of the file in a transaction that is rolled back
+             */
+            if typecheck_enabled && check_ddl_enabled {
+                let ddl_diagnostics = self.connection.with_pool(&settings.db, |pool| {
+                    let schema_cache = self.schema_cache.load(pool)?;
+                    let input = doc
+                        .iter(TypecheckDiagnosticsMapper)
+                        .filter(|(id, ..)| id.is_root())
+                        .map(|(id, range, ast, cst, _)| {
+                            let sql = convert_to_positional_params(id.content());
+                            (id, range, ast, cst, sql)
+                        })
+                        .collect::<Vec<_>>();
+                    let search_path_patterns = settings.typecheck.search_path.clone();
+                    let pool = pool.clone();
+
+                    run_async(async move {
+                        let (ranges, statements): (Vec<_>, Vec<_>) = input
+                            .iter()
+                            .filter_map(|(id, range, ast, cst, sql)| {
+                                let statement = DdlStatement {
+                                    sql: sql.as_str(),
+                                    ast: ast.as_ref()?,
+                                    tree: cst,
+                                };
+                                Some(((id.clone(), *range), statement))
+                            })
+                            .unzip();
+
+                        let results = pgls_typecheck::check_ddl(DdlCheckParams {
+                            conn: &pool,
+                            statements: &statements,
+                            schema_cache: schema_cache.as_ref(),
+                            search_path_patterns,
+                        })
+                        .await?;
+
+                        Ok::<_, sqlx::Error>(
+                            results
+                                .into_iter()
+                                .map(|(idx, diag)| (ranges[idx].clone(), diag))
+                                .collect::<Vec<_>>(),
+                        )
+                    })?
+                    .map_err(WorkspaceError::from)
+                });
+
+                if let Some(result) = ddl_diagnostics {
+                    match result {
+                        Ok(ddl_diagnostics) => {
+                            for ((id, range), diag) in ddl_diagnostics {
+                                // the name resolution already reported the error
+                                if statements_with_unresolved_names.contains(&id) {
+                                    continue;
+                                }
+
+                                let r = diag.location().span.map(|span| span + range.start());
+                                diagnostics.push(SDiagnostic::new(
+                                    diag.with_file_path(
+                                        params.path.as_path().display().to_string(),
+                                    )
+                                    .with_file_span(r.unwrap_or(range)),
+                                ));
+                            }
+                        }
+                        Err(err @ WorkspaceError::DatabaseConnectionError(_)) => {
+                            debug!("Skipping DDL typecheck: {err}");
+                        }
+                        Err(err) => return Err(err),
+                    }
+                }
+            }
         }
 
         /*
@@ -748,19 +1046,34 @@ impl Workspace for WorkspaceServer {
 
         let path = params.path.as_path().display().to_string();
 
-        #[cfg(feature = "db")]
-        let schema_cache = self
-            .connection
-            .with_pool(&settings.db, |pool| self.schema_cache.load(pool))
-            .and_then(Result::ok);
-
-        #[cfg(not(feature = "db"))]
-        let schema_cache = self.schema_cache.get();
+        // Migration files that bundle e.g. an up and a down migration apply their sections on
+        // their own, so the statements of each section are analysed independently
+        let sections = settings
+            .migrations
+            .as_ref()
+            .and_then(|migration_settings| {
+                let migrations_dir = migration_settings.path.as_ref()?;
+                let migration = migration::get_migration(
+                    self.fs.as_ref(),
+                    params.path.as_path(),
+                    migrations_dir,
+                    migration_settings.layout.as_ref(),
+                )?;
+                Some(
+                    migration_settings
+                        .layout
+                        .sections(&migration, doc.get_document_content()),
+                )
+            })
+            .unwrap_or_default();
 
-        let mut analysable_stmts = vec![];
+        let mut analysable_stmts: BTreeMap<Option<usize>, Vec<_>> = BTreeMap::new();
         for (stmt_root, diagnostic) in doc.iter(AnalyserDiagnosticsMapper) {
             if let Some(node) = stmt_root {
-                analysable_stmts.push(node);
+                let section = sections
+                    .iter()
+                    .position(|section| section.range.contains_range(node.range));
+                analysable_stmts.entry(section).or_default().push(node);
             }
             if let Some(diag) = diagnostic {
                 // ignore the syntax error if we already have more specialized diagnostics for the
@@ -785,12 +1098,14 @@ impl Workspace for WorkspaceServer {
         }
 
         diagnostics.extend(
-            analyser
-                .run(AnalyserParams {
-                    stmts: analysable_stmts,
-                    schema_cache: schema_cache.as_deref(),
+            analysable_stmts
+                .into_values()
+                .flat_map(|stmts| {
+                    analyser.run(AnalyserParams {
+                        stmts,
+                        schema_cache: schema_cache.as_deref(),
+                    })
                 })
-                .into_iter()
                 .map(Error::from)
                 .map(|d| {
                     let severity = d
@@ -849,52 +1164,89 @@ impl Workspace for WorkspaceServer {
             return Ok(PullDiagnosticsResult::default());
         };
 
-        if !settings.splinter.enabled {
-            debug!("Splinter is disabled. Skipping database linting.");
+        let splinter_enabled = settings.splinter.enabled;
+        let pglinter_enabled = settings.pglinter.enabled;
+        if !splinter_enabled && !pglinter_enabled {
+            debug!("Splinter and pglinter are disabled. Skipping database linting.");
             return Ok(PullDiagnosticsResult::default());
         }
 
-        let (enabled_rules, disabled_rules) = AnalyserVisitorBuilder::new(settings)
-            .with_splinter_rules(&params.only, &params.skip)
-            .finish();
+        // Group names are only unique within an analyzer, so each one gets its own filter
+        let (splinter_enabled_rules, splinter_disabled_rules) =
+            AnalyserVisitorBuilder::new(settings)
+                .with_splinter_rules(&params.only, &params.skip)
+                .finish();
+        let (pglinter_enabled_rules, pglinter_disabled_rules) =
+            AnalyserVisitorBuilder::new(settings)
+                .with_pglinter_rules(&params.only, &params.skip)
+                .finish();
 
         let categories = params.categories;
         let splinter_config = settings.splinter.to_configuration();
-        let Some(splinter_diagnostics) = self.connection.with_pool(&settings.db, |pool| {
+        let Some(db_diagnostics) = self.connection.with_pool(&settings.db, |pool| {
             let schema_cache = match self.schema_cache.load(pool) {
                 Ok(schema_cache) => Some(schema_cache),
                 Err(err @ WorkspaceError::DatabaseConnectionError(_)) => return Err(err),
                 Err(err) => {
-                    debug!("Unable to load schema cache for splinter: {err}");
+                    debug!("Unable to load schema cache for database linting: {err}");
                     None
                 }
             };
 
             let pool = pool.clone();
-            let splinter_result = run_async(async move {
-                let filter = AnalysisFilter {
-                    categories,
-                    enabled_rules: Some(enabled_rules.as_slice()),
-                    disabled_rules: &disabled_rules,
-                };
-                let splinter_params = pgls_splinter::SplinterParams {
-                    conn: &pool,
-                    schema_cache: schema_cache.as_deref(),
-                    config: Some(&splinter_config),
-                };
-                pgls_splinter::run_splinter(splinter_params, &filter).await
+            let result = run_async(async move {
+                let mut diagnostics: Vec<Error> = vec![];
+
+                if splinter_enabled {
+                    let filter = AnalysisFilter {
+                        categories,
+                        enabled_rules: Some(splinter_enabled_rules.as_slice()),
+                        disabled_rules: &splinter_disabled_rules,
+                    };
+                    let splinter_params = pgls_splinter::SplinterParams {
+                        conn: &pool,
+                        schema_cache: schema_cache.as_deref(),
+                        config: Some(&splinter_config),
+                    };
+                    let splinter_diagnostics =
+                        pgls_splinter::run_splinter(splinter_params, &filter).await?;
+                    diagnostics.extend(splinter_diagnostics.into_iter().map(Error::from));
+                }
+
+                match schema_cache.as_deref() {
+                    Some(schema_cache) if pglinter_enabled => {
+                        let filter = AnalysisFilter {
+                            categories,
+                            enabled_rules: Some(pglinter_enabled_rules.as_slice()),
+                            disabled_rules: &pglinter_disabled_rules,
+                        };
+                        let pglinter_params = pgls_pglinter::PglinterParams {
+                            conn: &pool,
+                            schema_cache,
+                        };
+                        let pglinter_diagnostics =
+                            pgls_pglinter::run_pglinter(pglinter_params, &filter, None).await?;
+                        diagnostics.extend(pglinter_diagnostics.into_iter().map(Error::from));
+                    }
+                    None if pglinter_enabled => {
+                        debug!("No schema cache available. Skipping pglinter checks.");
+                    }
+                    _ => {}
+                }
+
+                Ok::<_, sqlx::Error>(diagnostics)
             })?;
 
-            splinter_result.map_err(WorkspaceError::from)
+            result.map_err(WorkspaceError::from)
         }) else {
-            debug!("No database connection available. Skipping splinter checks.");
+            debug!("No database connection available. Skipping database linting.");
             return Ok(PullDiagnosticsResult::default());
         };
-        let splinter_diagnostics = splinter_diagnostics?;
+        let db_diagnostics = db_diagnostics?;
 
-        let total = splinter_diagnostics.len();
+        let total = db_diagnostics.len();
         let max = params.max_diagnostics as usize;
-        let diagnostics: Vec<SDiagnostic> = splinter_diagnostics
+        let diagnostics: Vec<SDiagnostic> = db_diagnostics
             .into_iter()
             .take(max)
             .map(SDiagnostic::new)
@@ -1094,11 +1446,25 @@ impl Workspace for WorkspaceServer {
             Some((id, range, cst)) => {
                 let position = params.position - range.start();
 
+                let settings = self.workspaces();
+                let snippets = settings
+                    .settings()
+                    .map(|s| s.completions.snippets.as_slice())
+                    .unwrap_or_default();
+
+                let usage = self.usage.get(
+                    documents
+                        .iter()
+                        .map(|(path, document)| (path.as_path(), document)),
+                );
+
                 let items = pgls_completions::complete(pgls_completions::CompletionParams {
                     position,
                     schema: schema_cache.as_ref(),
                     tree: &cst,
                     text: id.content().to_string(),
+                    snippets,
+                    usage: usage.as_deref(),
                 });
 
                 Ok(CompletionsResult::with_offset(items, range.start()))
@@ -1106,6 +1472,38 @@ impl Workspace for WorkspaceServer {
         }
     }
 
+    #[tracing::instrument(level = "debug", skip_all, err)]
+    fn resolve_completion(
+        &self,
+        params: ResolveCompletionParams,
+    ) -> Result<ResolveCompletionResult, WorkspaceError> {
+        #[cfg(feature = "db")]
+        let Some(schema_cache) = self
+            .workspaces()
+            .settings()
+            .and_then(|settings| {
+                self.connection
+                    .with_pool(&settings.db, |pool| self.schema_cache.load(pool))
+            })
+            .transpose()?
+        else {
+            tracing::debug!("No database connection available. Skipping completion resolve.");
+            return Ok(ResolveCompletionResult::default());
+        };
+
+        #[cfg(not(feature = "db"))]
+        let Some(schema_cache) = self.schema_cache.get() else {
+            tracing::debug!("No schema loaded. Skipping completion resolve.");
+            return Ok(ResolveCompletionResult::default());
+        };
+
+        Ok(
+            pgls_completions::resolve_completion_item(&params.data, schema_cache.as_ref())
+                .map(ResolveCompletionResult::from)
+                .unwrap_or_default(),
+        )
+    }
+
     #[ignored_path(path=&params.path)]
     #[tracing::instrument(level = "debug", skip_all, fields(
         path = params.path.as_os_str().to_str(),
@@ -7,12 +7,14 @@ use pgls_analyse::RuleCategories;
 use pgls_configuration::{Merge, StringSet};
 use pgls_configuration::{
     PartialConfiguration, PartialFormatConfiguration, PartialTypecheckConfiguration,
-    database::PartialDatabaseConfiguration, files::PartialFilesConfiguration,
+    database::PartialDatabaseConfiguration,
+    files::PartialFilesConfiguration,
+    migrations::{MigrationTool, PartialMigrationsConfiguration},
 };
 
 #[cfg(not(target_os = "windows"))]
 use pgls_configuration::plpgsql_check::PartialPlPgSqlCheckConfiguration;
-use pgls_diagnostics::Diagnostic;
+use pgls_diagnostics::{Diagnostic, PrintDescription};
 use pgls_fs::PgLSPath;
 use pgls_text_size::TextRange;
 use sqlx::{Executor, PgPool};
@@ -22,8 +24,8 @@ use crate::{
     features::code_actions::ExecuteStatementResult,
     features::format::PullFileFormattingParams,
     workspace::{
-        OpenFileParams, RegisterProjectFolderParams, StatementId, UpdateSettingsParams,
-        server::WorkspaceServer,
+        IndexUsageParams, OpenFileParams, RegisterProjectFolderParams, StatementId,
+        UpdateSettingsParams, server::WorkspaceServer,
     },
 };
 
@@ -146,6 +148,240 @@ fn test_suppresses_multiline_alter_table_drop_column() {
     );
 }
 
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn test_resolves_relations_created_by_earlier_migrations(test_db: PgPool) {
+    test_db
+        .execute("create table existing (id int);")
+        .await
+        .expect("Unable to setup test database");
+
+    let project = tempfile::tempdir().unwrap();
+    let migrations_dir = project.path().join("migrations");
+    std::fs::create_dir(&migrations_dir).unwrap();
+    std::fs::write(
+        migrations_dir.join("1_create_accounts.sql"),
+        "create table accounts (id int);\n",
+    )
+    .unwrap();
+    let file = migrations_dir.join("2_add_email.sql");
+    let content = "alter table accounts add column email text;\ncreate index on accounts (email);\nselect id from missing;\n";
+    std::fs::write(&file, content).unwrap();
+
+    let workspace = WorkspaceServer::new();
+    workspace
+        .register_project_folder(RegisterProjectFolderParams {
+            path: None,
+            set_as_current_workspace: true,
+        })
+        .unwrap();
+
+    let mut configuration = PartialConfiguration::init();
+    configuration.merge_with(PartialConfiguration {
+        db: Some(PartialDatabaseConfiguration {
+            database: Some(
+                test_db
+                    .connect_options()
+                    .get_database()
+                    .unwrap()
+                    .to_string(),
+            ),
+            ..Default::default()
+        }),
+        migrations: Some(PartialMigrationsConfiguration {
+            migrations_dir: Some("migrations".into()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    workspace
+        .update_settings(UpdateSettingsParams {
+            configuration,
+            gitignore_matches: vec![],
+            vcs_base_path: None,
+            workspace_directory: Some(project.path().to_path_buf()),
+        })
+        .unwrap();
+
+    let path = PgLSPath::new(&file);
+    workspace
+        .open_file(OpenFileParams {
+            path: path.clone(),
+            content: content.into(),
+            version: 1,
+        })
+        .expect("Unable to open test file");
+
+    let diagnostics = workspace
+        .pull_file_diagnostics(crate::workspace::PullFileDiagnosticsParams {
+            path: path.clone(),
+            categories: RuleCategories::all(),
+            max_diagnostics: 100,
+            only: vec![],
+            skip: vec![],
+        })
+        .expect("Unable to pull diagnostics")
+        .diagnostics;
+
+    // `accounts` is created by the earlier migration, which the database doesn't have yet
+    let descriptions: Vec<String> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.category().map(|c| c.name()) == Some("typecheck"))
+        .map(|diagnostic| format!("{}", PrintDescription(diagnostic)))
+        .collect();
+    assert_eq!(
+        descriptions,
+        vec!["relation \"missing\" does not exist".to_string()]
+    );
+}
+
+#[test]
+fn test_indexes_usage_of_workspace_files() {
+    let project = tempfile::tempdir().unwrap();
+    std::fs::write(project.path().join("orders.sql"), "select id from orders;").unwrap();
+    std::fs::write(project.path().join("open.sql"), "select id from stale;").unwrap();
+    std::fs::write(project.path().join("notes.txt"), "select id from notes;").unwrap();
+    for (dir, table) in [
+        ("ignored", "ignored_by_config"),
+        ("generated", "ignored_by_vcs"),
+        (".hidden", "hidden"),
+    ] {
+        std::fs::create_dir(project.path().join(dir)).unwrap();
+        std::fs::write(
+            project.path().join(dir).join("file.sql"),
+            format!("select id from {table};"),
+        )
+        .unwrap();
+    }
+
+    let workspace = WorkspaceServer::new();
+    workspace
+        .register_project_folder(RegisterProjectFolderParams {
+            path: Some(project.path().to_path_buf()),
+            set_as_current_workspace: true,
+        })
+        .unwrap();
+
+    let mut configuration = PartialConfiguration::init();
+    configuration.merge_with(PartialConfiguration {
+        files: Some(PartialFilesConfiguration {
+            ignore: Some(StringSet::from_iter(["ignored/**".to_string()])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    workspace
+        .update_settings(UpdateSettingsParams {
+            configuration,
+            gitignore_matches: vec!["generated/".to_string()],
+            vcs_base_path: Some(project.path().to_path_buf()),
+            workspace_directory: Some(project.path().to_path_buf()),
+        })
+        .unwrap();
+
+    workspace
+        .open_file(OpenFileParams {
+            path: PgLSPath::new(project.path().join("open.sql")),
+            content: "select id from customers;".into(),
+            version: 1,
+        })
+        .unwrap();
+
+    workspace
+        .index_usage(IndexUsageParams {
+            path: project.path().to_path_buf(),
+        })
+        .unwrap();
+
+    let documents = workspace.documents.read().unwrap();
+    let usage = workspace
+        .usage
+        .get(
+            documents
+                .iter()
+                .map(|(path, document)| (path.as_path(), document)),
+        )
+        .unwrap();
+
+    let mut expected = pgls_completions::UsageStats::default();
+    for table in ["orders", "customers"] {
+        expected.record_table(None, table);
+        expected.record_column(Some((None, table)), "id");
+    }
+    assert_eq!(*usage, expected);
+}
+
+#[test]
+fn test_analyses_migration_sections_independently() {
+    let project = tempfile::tempdir().unwrap();
+    let migrations_dir = project.path().join("migrations");
+    std::fs::create_dir(&migrations_dir).unwrap();
+    let file = migrations_dir.join("20240101000000_add_email.sql");
+    let content = r#"-- migrate:up
+alter table users add column email text;
+
+-- migrate:down
+alter table users drop column email;
+"#;
+    std::fs::write(&file, content).unwrap();
+
+    let workspace = WorkspaceServer::new();
+    workspace
+        .register_project_folder(RegisterProjectFolderParams {
+            path: None,
+            set_as_current_workspace: true,
+        })
+        .unwrap();
+
+    // `users` does not exist in the test database, which the typecheck would report
+    let mut configuration = PartialConfiguration::init();
+    configuration.merge_with(PartialConfiguration {
+        typecheck: Some(PartialTypecheckConfiguration {
+            enabled: Some(false),
+            ..Default::default()
+        }),
+        migrations: Some(PartialMigrationsConfiguration {
+            migrations_dir: Some("migrations".into()),
+            tool: Some(MigrationTool::Dbmate),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    workspace
+        .update_settings(UpdateSettingsParams {
+            configuration,
+            gitignore_matches: vec![],
+            vcs_base_path: None,
+            workspace_directory: Some(project.path().to_path_buf()),
+        })
+        .unwrap();
+
+    let path = PgLSPath::new(&file);
+    workspace
+        .open_file(OpenFileParams {
+            path: path.clone(),
+            content: content.into(),
+            version: 1,
+        })
+        .expect("Unable to open test file");
+
+    // the up and down sections are applied separately, so they don't alter the table twice
+    let diagnostics = workspace
+        .pull_file_diagnostics(crate::workspace::PullFileDiagnosticsParams {
+            path: path.clone(),
+            categories: RuleCategories::all(),
+            max_diagnostics: 100,
+            only: vec!["safety/multipleAlterTable".parse().unwrap()],
+            skip: vec![],
+        })
+        .expect("Unable to pull diagnostics")
+        .diagnostics;
+
+    assert!(
+        diagnostics.is_empty(),
+        "Expected no diagnostics, got {diagnostics:#?}"
+    );
+}
+
 #[test]
 fn test_unreachable_database_diagnostics_keep_static_results_and_back_off() {
     let mut conf = PartialConfiguration::init();
@@ -592,6 +828,153 @@ async fn test_disable_plpgsql_check(test_db: PgPool) {
     );
 }
 
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn test_typecheck_ddl(test_db: PgPool) {
+    let mut conf = PartialConfiguration::init();
+    conf.merge_with(PartialConfiguration {
+        db: Some(PartialDatabaseConfiguration {
+            database: Some(
+                test_db
+                    .connect_options()
+                    .get_database()
+                    .unwrap()
+                    .to_string(),
+            ),
+            ..Default::default()
+        }),
+        typecheck: Some(PartialTypecheckConfiguration {
+            check_ddl: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let workspace = get_test_workspace(Some(conf)).expect("Unable to create test workspace");
+
+    let path = PgLSPath::new("test.sql");
+
+    test_db
+        .execute("create table users (id serial primary key);")
+        .await
+        .expect("setup sql failed");
+
+    let content =
+        "alter table users add column email text;\nalter table users add column email text;";
+
+    workspace
+        .open_file(OpenFileParams {
+            path: path.clone(),
+            content: content.into(),
+            version: 1,
+        })
+        .expect("Unable to open test file");
+
+    let diagnostics = workspace
+        .pull_file_diagnostics(crate::workspace::PullFileDiagnosticsParams {
+            path: path.clone(),
+            categories: RuleCategories::all(),
+            max_diagnostics: 100,
+            only: vec![],
+            skip: vec![],
+        })
+        .expect("Unable to pull diagnostics")
+        .diagnostics;
+
+    let typecheck_diagnostics = diagnostics
+        .iter()
+        .filter(|d| d.category().is_some_and(|c| c.name() == "typecheck"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        typecheck_diagnostics.len(),
+        1,
+        "Expected one typecheck diagnostic, got {diagnostics:#?}"
+    );
+
+    // the second statement fails because the first one already added the column
+    assert_eq!(
+        typecheck_diagnostics[0].location().span,
+        Some(TextRange::new(41.into(), 81.into()))
+    );
+
+    // the changes were rolled back
+    let columns: Vec<String> = sqlx::query_scalar(
+        "select column_name::text from information_schema.columns where table_name = 'users'",
+    )
+    .fetch_all(&test_db)
+    .await
+    .unwrap();
+    assert_eq!(columns, vec!["id".to_string()]);
+}
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn test_typecheck_ddl_queries_see_earlier_statements(test_db: PgPool) {
+    let mut conf = PartialConfiguration::init();
+    conf.merge_with(PartialConfiguration {
+        db: Some(PartialDatabaseConfiguration {
+            database: Some(
+                test_db
+                    .connect_options()
+                    .get_database()
+                    .unwrap()
+                    .to_string(),
+            ),
+            ..Default::default()
+        }),
+        typecheck: Some(PartialTypecheckConfiguration {
+            check_ddl: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let workspace = get_test_workspace(Some(conf)).expect("Unable to create test workspace");
+
+    let path = PgLSPath::new("test.sql");
+
+    test_db
+        .execute("create table users (id serial primary key);")
+        .await
+        .expect("setup sql failed");
+
+    let content = "create table public.new_t (id int, name text);\ninsert into new_t (id, name) values (1, 'a');\nselect name from new_t;\nalter table users add column nickname text;\nselect nickname from users;";
+
+    workspace
+        .open_file(OpenFileParams {
+            path: path.clone(),
+            content: content.into(),
+            version: 1,
+        })
+        .expect("Unable to open test file");
+
+    let diagnostics = workspace
+        .pull_file_diagnostics(crate::workspace::PullFileDiagnosticsParams {
+            path: path.clone(),
+            categories: RuleCategories::all(),
+            max_diagnostics: 100,
+            only: vec![],
+            skip: vec![],
+        })
+        .expect("Unable to pull diagnostics")
+        .diagnostics;
+
+    assert!(
+        diagnostics
+            .iter()
+            .all(|d| d.category().is_none_or(|c| c.name() != "typecheck")),
+        "Expected no typecheck diagnostics, got {diagnostics:#?}"
+    );
+
+    // the insert was not executed
+    let tables: Vec<String> = sqlx::query_scalar(
+        "select table_name::text from information_schema.tables where table_name = 'new_t'",
+    )
+    .fetch_all(&test_db)
+    .await
+    .unwrap();
+    assert!(tables.is_empty());
+}
+
 #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
 async fn test_disable_typecheck(test_db: PgPool) {
     let mut conf = PartialConfiguration::init();
@@ -908,6 +1291,7 @@ async fn test_search_path_configuration(test_db: PgPool) {
             enabled: Some(true),
             // Adding glob pattern to match the "private" schema
             search_path: Some(StringSet::from_iter(vec!["pr*".to_string()])),
+            ..Default::default()
         }),
         ..Default::default()
     }); // checking with the pattern should yield no diagnostics
@@ -1018,3 +1402,66 @@ select * from auth.users;
         "Expected no syntax diagnostic"
     );
 }
+
+#[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+async fn test_execute_statement_refreshes_schema_cache(test_db: PgPool) {
+    let mut conf = PartialConfiguration::init();
+    conf.merge_with(PartialConfiguration {
+        db: Some(PartialDatabaseConfiguration {
+            database: Some(
+                test_db
+                    .connect_options()
+                    .get_database()
+                    .unwrap()
+                    .to_string(),
+            ),
+            // the test changes the schema, so a cache persisted by a previous run would be stale
+            disable_schema_cache_persistence: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let workspace = get_test_workspace(Some(conf)).expect("Unable to create test workspace");
+
+    let path = PgLSPath::new("test.sql");
+    let create_table = "create table public.freshly_created (id serial primary key);";
+    let content = format!("{create_table}\n\nselect * from public.fr");
+
+    workspace
+        .open_file(OpenFileParams {
+            path: path.clone(),
+            content: content.clone(),
+            version: 1,
+        })
+        .expect("Unable to open test file");
+
+    let completes_new_table = || {
+        workspace
+            .get_completions(crate::workspace::GetCompletionsParams {
+                path: path.clone(),
+                position: pgls_text_size::TextSize::new(content.len() as u32),
+            })
+            .expect("Unable to request completions")
+            .items
+            .iter()
+            .any(|item| item.label == "freshly_created")
+    };
+
+    // loads the schema cache before the table exists
+    assert!(!completes_new_table());
+
+    workspace
+        .execute_statement(crate::workspace::ExecuteStatementParams {
+            path: path.clone(),
+            statement_id: StatementId::Root {
+                content: Arc::from(create_table),
+            },
+        })
+        .expect("Unable to execute statement");
+
+    assert!(
+        completes_new_table(),
+        "Expected the created table to be completed without invalidating the schema cache"
+    );
+}
@@ -1,5 +1,5 @@
 use pgls_analyse::{GroupCategory, RegistryVisitor, RuleCategory, RuleFilter, RuleGroup, RuleMeta};
-use pgls_configuration::RuleSelector;
+use pgls_configuration::{AnalyzerGroup, RuleSelector};
 use rustc_hash::FxHashSet;
 
 use crate::settings::Settings;
@@ -8,6 +8,8 @@ pub(crate) struct AnalyserVisitorBuilder<'a> {
     lint: Option<LintVisitor<'a>>,
     #[cfg(feature = "db")]
     splinter: Option<SplinterVisitor<'a>>,
+    #[cfg(feature = "db")]
+    pglinter: Option<PglinterVisitor<'a>>,
     settings: &'a Settings,
 }
 
@@ -18,6 +20,7 @@ impl<'a> AnalyserVisitorBuilder<'a> {
             settings,
             lint: None,
             splinter: None,
+            pglinter: None,
         }
     }
 
@@ -50,6 +53,17 @@ impl<'a> AnalyserVisitorBuilder<'a> {
         self
     }
 
+    #[cfg(feature = "db")]
+    #[must_use]
+    pub(crate) fn with_pglinter_rules(
+        mut self,
+        only: &'a [RuleSelector],
+        skip: &'a [RuleSelector],
+    ) -> Self {
+        self.pglinter = Some(PglinterVisitor::new(only, skip, self.settings));
+        self
+    }
+
     #[must_use]
     pub(crate) fn finish(self) -> (Vec<RuleFilter<'static>>, Vec<RuleFilter<'static>>) {
         let mut disabled_rules = vec![];
@@ -67,6 +81,13 @@ impl<'a> AnalyserVisitorBuilder<'a> {
             enabled_rules.extend(splinter_enabled_rules);
             disabled_rules.extend(splinter_disabled_rules);
         }
+        #[cfg(feature = "db")]
+        if let Some(mut pglinter) = self.pglinter {
+            pgls_pglinter::registry::visit_registry(&mut pglinter);
+            let (pglinter_enabled_rules, pglinter_disabled_rules) = pglinter.finish();
+            enabled_rules.extend(pglinter_enabled_rules);
+            disabled_rules.extend(pglinter_disabled_rules);
+        }
 
         (enabled_rules, disabled_rules)
     }
@@ -77,22 +98,28 @@ impl<'a> AnalyserVisitorBuilder<'a> {
 struct LintVisitor<'a> {
     pub(crate) enabled_rules: FxHashSet<RuleFilter<'static>>,
     pub(crate) disabled_rules: FxHashSet<RuleFilter<'static>>,
-    only: &'a [RuleSelector],
-    skip: &'a [RuleSelector],
+    /// Whether rules of any analyzer were selected
+    has_only_filter: bool,
+    only: Vec<RuleSelector>,
+    skip: Vec<RuleSelector>,
     settings: &'a Settings,
 }
 
 impl<'a> LintVisitor<'a> {
     pub(crate) fn new(
-        only: &'a [RuleSelector],
-        skip: &'a [RuleSelector],
+        only: &[RuleSelector],
+        skip: &[RuleSelector],
         settings: &'a Settings,
     ) -> Self {
+        // Group names are only unique within an analyzer
+        let is_linter_selector =
+            |selector: &&RuleSelector| matches!(selector.group(), AnalyzerGroup::Linter(_));
         Self {
             enabled_rules: Default::default(),
             disabled_rules: Default::default(),
-            only,
-            skip,
+            has_only_filter: !only.is_empty(),
+            only: only.iter().filter(is_linter_selector).copied().collect(),
+            skip: skip.iter().filter(is_linter_selector).copied().collect(),
             settings,
         }
     }
@@ -103,9 +130,7 @@ impl<'a> LintVisitor<'a> {
         FxHashSet<RuleFilter<'static>>,
         FxHashSet<RuleFilter<'static>>,
     ) {
-        let has_only_filter = !self.only.is_empty();
-
-        if !has_only_filter {
+        if !self.has_only_filter {
             let enabled_rules = self
                 .settings
                 .as_linter_rules()
@@ -130,13 +155,13 @@ impl<'a> LintVisitor<'a> {
         R: RuleMeta + 'static,
     {
         // Do not report unused suppression comment diagnostics if a single rule is run.
-        for selector in self.only {
+        for selector in &self.only {
             let filter = RuleFilter::from(selector);
             if filter.match_rule::<R>() {
                 self.enabled_rules.insert(filter);
             }
         }
-        for selector in self.skip {
+        for selector in &self.skip {
             let filter = RuleFilter::from(selector);
             if filter.match_rule::<R>() {
                 self.disabled_rules.insert(filter);
@@ -153,16 +178,14 @@ impl RegistryVisitor for LintVisitor<'_> {
     }
 
     fn record_group<G: RuleGroup>(&mut self) {
-        for selector in self.only {
-            if RuleFilter::from(selector).match_group::<G>() {
-                G::record_rules(self)
-            }
-        }
-
-        for selector in self.skip {
-            if RuleFilter::from(selector).match_group::<G>() {
-                G::record_rules(self)
-            }
+        let is_selected = self
+            .only
+            .iter()
+            .chain(&self.skip)
+            .any(|selector| RuleFilter::from(selector).match_group::<G>());
+
+        if is_selected {
+            G::record_rules(self)
         }
     }
 
@@ -180,23 +203,29 @@ impl RegistryVisitor for LintVisitor<'_> {
 struct SplinterVisitor<'a> {
     pub(crate) enabled_rules: FxHashSet<RuleFilter<'static>>,
     pub(crate) disabled_rules: FxHashSet<RuleFilter<'static>>,
-    only: &'a [RuleSelector],
-    skip: &'a [RuleSelector],
+    /// Whether rules of any analyzer were selected
+    has_only_filter: bool,
+    only: Vec<RuleSelector>,
+    skip: Vec<RuleSelector>,
     settings: &'a Settings,
 }
 
 #[cfg(feature = "db")]
 impl<'a> SplinterVisitor<'a> {
     pub(crate) fn new(
-        only: &'a [RuleSelector],
-        skip: &'a [RuleSelector],
+        only: &[RuleSelector],
+        skip: &[RuleSelector],
         settings: &'a Settings,
     ) -> Self {
+        // Group names are only unique within an analyzer
+        let is_splinter_selector =
+            |selector: &&RuleSelector| matches!(selector.group(), AnalyzerGroup::Splinter(_));
         Self {
             enabled_rules: Default::default(),
             disabled_rules: Default::default(),
-            only,
-            skip,
+            has_only_filter: !only.is_empty(),
+            only: only.iter().filter(is_splinter_selector).copied().collect(),
+            skip: skip.iter().filter(is_splinter_selector).copied().collect(),
             settings,
         }
     }
@@ -207,9 +236,7 @@ impl<'a> SplinterVisitor<'a> {
         FxHashSet<RuleFilter<'static>>,
         FxHashSet<RuleFilter<'static>>,
     ) {
-        let has_only_filter = !self.only.is_empty();
-
-        if !has_only_filter {
+        if !self.has_only_filter {
             let enabled_rules = self
                 .settings
                 .as_splinter_rules()
@@ -233,13 +260,13 @@ impl<'a> SplinterVisitor<'a> {
     where
         R: RuleMeta + 'static,
     {
-        for selector in self.only {
+        for selector in &self.only {
             let filter = RuleFilter::from(selector);
             if filter.match_rule::<R>() {
                 self.enabled_rules.insert(filter);
             }
         }
-        for selector in self.skip {
+        for selector in &self.skip {
             let filter = RuleFilter::from(selector);
             if filter.match_rule::<R>() {
                 self.disabled_rules.insert(filter);
@@ -257,18 +284,121 @@ impl RegistryVisitor for SplinterVisitor<'_> {
     }
 
     fn record_group<G: RuleGroup>(&mut self) {
-        for selector in self.only {
-            if RuleFilter::from(selector).match_group::<G>() {
-                G::record_rules(self)
-            }
+        let is_selected = self
+            .only
+            .iter()
+            .chain(&self.skip)
+            .any(|selector| RuleFilter::from(selector).match_group::<G>());
+
+        if is_selected {
+            G::record_rules(self)
+        }
+    }
+
+    fn record_rule<R>(&mut self)
+    where
+        R: RuleMeta + 'static,
+    {
+        self.push_rule::<R>()
+    }
+}
+
+/// Type meant to register all the pglinter (database lint) rules
+#[cfg(feature = "db")]
+#[derive(Debug)]
+struct PglinterVisitor<'a> {
+    pub(crate) enabled_rules: FxHashSet<RuleFilter<'static>>,
+    pub(crate) disabled_rules: FxHashSet<RuleFilter<'static>>,
+    /// Whether rules of any analyzer were selected
+    has_only_filter: bool,
+    only: Vec<RuleSelector>,
+    skip: Vec<RuleSelector>,
+    settings: &'a Settings,
+}
+
+#[cfg(feature = "db")]
+impl<'a> PglinterVisitor<'a> {
+    pub(crate) fn new(
+        only: &[RuleSelector],
+        skip: &[RuleSelector],
+        settings: &'a Settings,
+    ) -> Self {
+        // Group names are only unique within an analyzer
+        let is_pglinter_selector =
+            |selector: &&RuleSelector| matches!(selector.group(), AnalyzerGroup::PgLinter(_));
+        Self {
+            enabled_rules: Default::default(),
+            disabled_rules: Default::default(),
+            has_only_filter: !only.is_empty(),
+            only: only.iter().filter(is_pglinter_selector).copied().collect(),
+            skip: skip.iter().filter(is_pglinter_selector).copied().collect(),
+            settings,
         }
+    }
 
-        for selector in self.skip {
-            if RuleFilter::from(selector).match_group::<G>() {
-                G::record_rules(self)
+    fn finish(
+        mut self,
+    ) -> (
+        FxHashSet<RuleFilter<'static>>,
+        FxHashSet<RuleFilter<'static>>,
+    ) {
+        if !self.has_only_filter {
+            let enabled_rules = self
+                .settings
+                .as_pglinter_rules()
+                .map(|rules| rules.as_enabled_rules())
+                .unwrap_or_default();
+
+            self.enabled_rules.extend(enabled_rules);
+
+            let disabled_rules = self
+                .settings
+                .as_pglinter_rules()
+                .map(|rules| rules.as_disabled_rules())
+                .unwrap_or_default();
+            self.disabled_rules.extend(disabled_rules);
+        }
+
+        (self.enabled_rules, self.disabled_rules)
+    }
+
+    fn push_rule<R>(&mut self)
+    where
+        R: RuleMeta + 'static,
+    {
+        for selector in &self.only {
+            let filter = RuleFilter::from(selector);
+            if filter.match_rule::<R>() {
+                self.enabled_rules.insert(filter);
+            }
+        }
+        for selector in &self.skip {
+            let filter = RuleFilter::from(selector);
+            if filter.match_rule::<R>() {
+                self.disabled_rules.insert(filter);
             }
         }
     }
+}
+
+#[cfg(feature = "db")]
+impl RegistryVisitor for PglinterVisitor<'_> {
+    fn record_category<C: GroupCategory>(&mut self) {
+        // We always record because we're visiting the pglinter registry specifically
+        C::record_groups(self)
+    }
+
+    fn record_group<G: RuleGroup>(&mut self) {
+        let is_selected = self
+            .only
+            .iter()
+            .chain(&self.skip)
+            .any(|selector| RuleFilter::from(selector).match_group::<G>());
+
+        if is_selected {
+            G::record_rules(self)
+        }
+    }
 
     fn record_rule<R>(&mut self)
     where
@@ -396,4 +526,48 @@ mod tests {
         assert!(disabled_rules.contains(&RuleFilter::Rule("performance", "authRlsInitplan")));
         assert_eq!(disabled_rules.len(), 2);
     }
+
+    #[cfg(feature = "db")]
+    #[test]
+    fn applies_selectors_to_their_analyzer() {
+        use std::str::FromStr;
+
+        use pgls_configuration::RuleSelector;
+
+        let settings = Settings::default();
+        let only = [RuleSelector::from_str("splinter/performance").unwrap()];
+        let skip = [RuleSelector::from_str("splinter/performance/noPrimaryKey").unwrap()];
+
+        let (enabled_rules, disabled_rules) = AnalyserVisitorBuilder::new(&settings)
+            .with_linter_rules(&only, &skip)
+            .with_splinter_rules(&only, &skip)
+            .finish();
+
+        // Selecting splinter rules disables the linter rules
+        assert_eq!(enabled_rules, vec![RuleFilter::Group("performance")]);
+        assert_eq!(
+            disabled_rules,
+            vec![RuleFilter::Rule("performance", "noPrimaryKey")]
+        );
+    }
+
+    #[cfg(feature = "db")]
+    #[test]
+    fn applies_pglinter_selectors_to_pglinter() {
+        use std::str::FromStr;
+
+        use pgls_configuration::RuleSelector;
+
+        let settings = Settings::default();
+        let only = [
+            RuleSelector::from_str("splinter/performance").unwrap(),
+            RuleSelector::from_str("pglinter/base").unwrap(),
+        ];
+
+        let (enabled_rules, _) = AnalyserVisitorBuilder::new(&settings)
+            .with_pglinter_rules(&only, &[])
+            .finish();
+
+        assert_eq!(enabled_rules, vec![RuleFilter::Group("base")]);
+    }
 }
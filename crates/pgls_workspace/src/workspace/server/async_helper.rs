@@ -1,6 +1,6 @@
 use std::{future::Future, sync::LazyLock};
 
-use tokio::runtime::Runtime;
+use tokio::{runtime::Runtime, task::AbortHandle};
 
 use crate::WorkspaceError;
 
@@ -19,3 +19,12 @@ where
 {
     futures::executor::block_on(async { RUNTIME.spawn(future).await.map_err(|e| e.into()) })
 }
+
+/// Runs a future in the background on the workspace runtime without waiting for it.
+/// The returned handle can be used to cancel it.
+pub fn spawn<F>(future: F) -> AbortHandle
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    RUNTIME.spawn(future).abort_handle()
+}
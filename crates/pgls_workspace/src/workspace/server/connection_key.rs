@@ -1,9 +1,10 @@
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::settings::DatabaseSettings;
 
 /// A unique identifier for database connection settings
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub(crate) struct ConnectionKey {
     pub host: String,
     pub port: u16,
@@ -204,6 +204,15 @@ impl<'a> StatementMapper<'a> for ExecuteStatementMapper {
     }
 }
 
+pub struct AstMapper;
+impl<'a> StatementMapper<'a> for AstMapper {
+    type Output = Arc<Result<pgls_query::NodeEnum, SyntaxDiagnostic>>;
+
+    fn map(&self, parser: &'a Document, id: StatementId, _range: TextRange) -> Self::Output {
+        parser.ast_db.get_or_cache_ast(&id)
+    }
+}
+
 pub struct FormatStatementMapper;
 impl<'a> StatementMapper<'a> for FormatStatementMapper {
     type Output = (
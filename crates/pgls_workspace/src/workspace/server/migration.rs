@@ -1,78 +1,285 @@
-use std::path::Path;
+use crate::configuration::normalize_path;
+use pgls_configuration::migrations::{
+    Migration, MigrationLayout, MigrationTool, SQITCH_PLAN_FILE_NAME,
+};
+use pgls_fs::{FileSystem, OpenOptions};
+use pgls_query::NodeEnum;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 
-#[derive(Debug)]
-pub(crate) struct Migration {
-    pub(crate) sequence_number: u64,
-    #[allow(unused)]
-    pub(crate) name: String,
+/// Returns the layout of the migration files of the given tool. The `sqitch.plan` that orders
+/// the changes of Sqitch is read once here, so that it isn't read again for every file.
+pub fn load_migration_layout(
+    fs: &dyn FileSystem,
+    migrations_dir: &Path,
+    tool: MigrationTool,
+) -> Arc<dyn MigrationLayout> {
+    let sqitch_plan = match tool {
+        MigrationTool::Sqitch => {
+            let mut content = String::new();
+            fs.open_with_options(
+                &migrations_dir.join(SQITCH_PLAN_FILE_NAME),
+                OpenOptions::default().read(true),
+            )
+            .and_then(|mut file| file.read_to_string(&mut content))
+            .ok()
+            .map(|_| content)
+        }
+        _ => None,
+    };
+    tool.layout(sqitch_plan.as_deref())
 }
 
-/// Get the migration associated with a path, if it is a migration file
-pub(crate) fn get_migration(path: &Path, migrations_dir: &Path) -> Option<Migration> {
-    // Check if path is a child of the migration directory
-    let is_child = path
-        .canonicalize()
-        .ok()
-        .and_then(|canonical_child| {
-            migrations_dir
-                .canonicalize()
-                .ok()
-                .map(|canonical_dir| canonical_child.starts_with(&canonical_dir))
-        })
-        .unwrap_or(false);
+/// Get the migration associated with a path, if it is a migration file of the given layout
+pub fn get_migration(
+    fs: &dyn FileSystem,
+    path: &Path,
+    migrations_dir: &Path,
+    layout: &dyn MigrationLayout,
+) -> Option<Migration> {
+    // Check if path is a child of the migration directory. The paths are only normalized, so
+    // that this doesn't depend on the files existing on disk.
+    let working_directory = fs.working_directory().unwrap_or_default();
+    let dir = normalize_path(&working_directory.join(migrations_dir));
+    let child = normalize_path(&working_directory.join(path));
+    let relative_path = child.strip_prefix(&dir).ok()?;
+
+    layout.migration(relative_path)
+}
+
+/// The applied statements of a migration file, with their AST.
+pub(crate) type MigrationStatements = Arc<Vec<(String, NodeEnum)>>;
+
+/// Caches the statements of the migration files by migrations directory, so that the
+/// migrations before the checked one are not listed, read and parsed on every diagnostics pull.
+#[derive(Default)]
+pub(crate) struct MigrationCache {
+    directories: RwLock<HashMap<PathBuf, Vec<CachedMigration>>>,
+}
+
+struct CachedMigration {
+    path: PathBuf,
+    migration: Migration,
+    /// `None` until the file is read, or after it changed.
+    statements: Option<MigrationStatements>,
+}
 
-    if !is_child {
-        return None;
+impl MigrationCache {
+    /// Returns the statements of the migrations that come before `migration`, in the order
+    /// they are applied, so that the objects they create are known when checking it.
+    pub(crate) fn earlier_statements(
+        &self,
+        fs: &dyn FileSystem,
+        migrations_dir: &Path,
+        layout: &dyn MigrationLayout,
+        migration: &Migration,
+    ) -> Vec<MigrationStatements> {
+        let mut directories = self.directories.write().unwrap();
+        let migrations = directories
+            .entry(migrations_dir.to_path_buf())
+            .or_insert_with(|| list_migrations(fs, migrations_dir, layout));
+
+        migrations
+            .iter_mut()
+            .filter(|cached| {
+                !cached.migration.repeatable
+                    && cached.migration.sequence_number < migration.sequence_number
+            })
+            .map(|cached| {
+                cached
+                    .statements
+                    .get_or_insert_with(|| {
+                        read_statements(fs, &cached.path, &cached.migration, layout)
+                    })
+                    .clone()
+            })
+            .collect()
     }
 
-    // we are trying to match patterns used by popular migration tools
+    /// Forgets the statements of a file whose content changed.
+    pub(crate) fn file_changed(&self, path: &Path) {
+        let mut directories = self.directories.write().unwrap();
+        for cached in directories
+            .iter_mut()
+            .filter(|(migrations_dir, _)| path.starts_with(migrations_dir))
+            .flat_map(|(_, migrations)| migrations.iter_mut())
+            .filter(|cached| cached.path == path)
+        {
+            cached.statements = None;
+        }
+    }
 
-    // in the "root" pattern, all files are directly within the migrations directory
-    // and their names follow <timestamp>_<name>.sql.
-    // this is used by supabase
-    let root_migration = path
-        .file_name()
-        .and_then(|os_str| os_str.to_str())
-        .and_then(parse_migration_name);
+    /// Forgets the migrations directories containing a file that was created, changed or
+    /// deleted on disk, so that they are listed again.
+    pub(crate) fn files_changed_on_disk(&self, paths: &[PathBuf]) {
+        self.directories
+            .write()
+            .unwrap()
+            .retain(|migrations_dir, _| !paths.iter().any(|path| path.starts_with(migrations_dir)));
+    }
 
-    if root_migration.is_some() {
-        return root_migration;
+    pub(crate) fn clear(&self) {
+        self.directories.write().unwrap().clear();
     }
+}
 
-    // in the "subdirectory" pattern, each migration is in a subdirectory named <timestamp>_<name>
-    // this is used by prisma and drizzle
-    path.parent()
-        .and_then(|parent| parent.file_name())
-        .and_then(|os_str| os_str.to_str())
-        .and_then(parse_migration_name)
+/// Lists the migration files below `migrations_dir`, in the order they are applied.
+fn list_migrations(
+    fs: &dyn FileSystem,
+    migrations_dir: &Path,
+    layout: &dyn MigrationLayout,
+) -> Vec<CachedMigration> {
+    let mut migrations = vec![];
+    let mut directories = vec![migrations_dir.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        let Ok(entries) = fs.read_dir(&directory) else {
+            continue;
+        };
+        for path in entries {
+            if fs.path_is_dir(&path) {
+                directories.push(path);
+                continue;
+            }
+            let Some(migration) = path
+                .strip_prefix(migrations_dir)
+                .ok()
+                .and_then(|relative_path| layout.migration(relative_path))
+            else {
+                continue;
+            };
+            migrations.push(CachedMigration {
+                path,
+                migration,
+                statements: None,
+            });
+        }
+    }
+    migrations.sort_by(|a, b| {
+        (
+            &a.migration.sequence_number,
+            &a.migration.name,
+            a.migration.direction,
+            &a.path,
+        )
+            .cmp(&(
+                &b.migration.sequence_number,
+                &b.migration.name,
+                b.migration.direction,
+                &b.path,
+            ))
+    });
+    migrations
 }
 
-fn parse_migration_name(name: &str) -> Option<Migration> {
-    let mut parts = name.splitn(2, '_');
-    // remove leading zeros to support numeric
-    let sequence_number: u64 = parts.next()?.trim_start_matches('0').parse().ok()?;
-    let full_name = parts.next()?;
-    let name = full_name
-        .strip_suffix(".sql")
-        .unwrap_or(full_name)
-        .to_string();
-    Some(Migration {
-        sequence_number,
-        name,
-    })
+/// Reads and parses the applied sections of a migration file.
+fn read_statements(
+    fs: &dyn FileSystem,
+    path: &Path,
+    migration: &Migration,
+    layout: &dyn MigrationLayout,
+) -> MigrationStatements {
+    let Ok(content) = fs.read_file_from_path(&path.to_path_buf()) else {
+        return Arc::default();
+    };
+
+    let statements = layout
+        .sections(migration, &content)
+        .into_iter()
+        .filter(|section| section.direction.is_applied())
+        .flat_map(|section| {
+            let section = &content[section.range];
+            pgls_statement_splitter::split(section)
+                .ranges
+                .into_iter()
+                .map(|range| section[range].to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter_map(|sql| {
+            let ast = pgls_query::parse(&sql)
+                .ok()
+                .and_then(|parsed| parsed.into_root())?;
+            Some((sql, ast))
+        })
+        .collect();
+
+    Arc::new(statements)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pgls_configuration::migrations::{MigrationDirection, MigrationVersion};
+    use pgls_fs::{MemoryFileSystem, OsFileSystem};
     use std::fs;
-    use std::path::PathBuf;
     use tempfile::TempDir;
 
     fn setup() -> TempDir {
         TempDir::new().expect("Failed to create temp dir")
     }
 
+    fn migration_of(path: &Path, migrations_dir: &Path, tool: MigrationTool) -> Option<Migration> {
+        let fs = OsFileSystem::default();
+        get_migration(
+            &fs,
+            path,
+            migrations_dir,
+            load_migration_layout(&fs, migrations_dir, tool).as_ref(),
+        )
+    }
+
+    #[test]
+    fn test_migration_cache() {
+        let migrations_dir = PathBuf::from("/project/migrations");
+        let first = migrations_dir.join("0001_create_users.sql");
+        let second = migrations_dir.join("0002_create_posts.sql");
+        let third = migrations_dir.join("0003_create_comments.sql");
+
+        let mut fs = MemoryFileSystem::default();
+        fs.insert(first.clone(), "create table users (id int);");
+        fs.insert(second.clone(), "create table posts (id int);");
+        fs.insert(third.clone(), "create table comments (id int);");
+
+        let layout = MigrationTool::Default.layout(None);
+        let third_migration = layout
+            .migration(Path::new("0003_create_comments.sql"))
+            .unwrap();
+        let cache = MigrationCache::default();
+
+        let earlier_sql = |fs: &MemoryFileSystem| {
+            cache
+                .earlier_statements(fs, &migrations_dir, layout.as_ref(), &third_migration)
+                .iter()
+                .flat_map(|statements| statements.iter().map(|(sql, _)| sql.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            earlier_sql(&fs),
+            vec![
+                "create table users (id int);",
+                "create table posts (id int);"
+            ]
+        );
+
+        // the cached statements are used until the file changes
+        fs.insert(second.clone(), "create table articles (id int);");
+        assert_eq!(earlier_sql(&fs)[1], "create table posts (id int);");
+
+        cache.file_changed(&second);
+        assert_eq!(earlier_sql(&fs)[1], "create table articles (id int);");
+
+        // new files are only picked up once the directory is listed again
+        fs.insert(
+            migrations_dir.join("0002_create_tags.sql"),
+            "create table tags (id int);",
+        );
+        assert_eq!(earlier_sql(&fs).len(), 2);
+
+        cache.files_changed_on_disk(&[migrations_dir.join("0002_create_tags.sql")]);
+        assert_eq!(earlier_sql(&fs).len(), 3);
+    }
+
     #[test]
     fn test_get_migration_root_pattern() {
         let temp_dir = setup();
@@ -80,11 +287,14 @@ mod tests {
         let path = migrations_dir.join("1234567890_create_users.sql");
         fs::write(&path, "").unwrap();
 
-        let migration = get_migration(&path, &migrations_dir);
+        let migration = migration_of(&path, &migrations_dir, MigrationTool::Default);
 
         assert!(migration.is_some());
         let migration = migration.unwrap();
-        assert_eq!(migration.sequence_number, 1234567890);
+        assert_eq!(
+            migration.sequence_number,
+            MigrationVersion::from(1234567890)
+        );
         assert_eq!(migration.name, "create_users");
     }
 
@@ -97,11 +307,14 @@ mod tests {
         let path = subdir.join("up.sql");
         fs::write(&path, "").unwrap();
 
-        let migration = get_migration(&path, &migrations_dir);
+        let migration = migration_of(&path, &migrations_dir, MigrationTool::Default);
 
         assert!(migration.is_some());
         let migration = migration.unwrap();
-        assert_eq!(migration.sequence_number, 1234567890);
+        assert_eq!(
+            migration.sequence_number,
+            MigrationVersion::from(1234567890)
+        );
         assert_eq!(migration.name, "create_users");
     }
 
@@ -112,11 +325,11 @@ mod tests {
         let path = migrations_dir.join("000201_a_migration.sql");
         fs::write(&path, "").unwrap();
 
-        let migration = get_migration(&path, &migrations_dir);
+        let migration = migration_of(&path, &migrations_dir, MigrationTool::Default);
 
         assert!(migration.is_some());
         let migration = migration.unwrap();
-        assert_eq!(migration.sequence_number, 201);
+        assert_eq!(migration.sequence_number, MigrationVersion::from(201));
         assert_eq!(migration.name, "a_migration");
     }
 
@@ -125,7 +338,7 @@ mod tests {
         let migrations_dir = PathBuf::from("/tmp/migrations");
         let path = migrations_dir.join("not_a_migration.sql");
 
-        let migration = get_migration(&path, &migrations_dir);
+        let migration = migration_of(&path, &migrations_dir, MigrationTool::Default);
 
         assert!(migration.is_none());
     }
@@ -135,8 +348,222 @@ mod tests {
         let migrations_dir = PathBuf::from("/tmp/migrations");
         let path = PathBuf::from("/tmp/other/1234567890_create_users.sql");
 
-        let migration = get_migration(&path, &migrations_dir);
+        let migration = migration_of(&path, &migrations_dir, MigrationTool::Default);
 
         assert!(migration.is_none());
     }
+
+    #[test]
+    fn test_get_migration_flyway() {
+        let temp_dir = setup();
+        let migrations_dir = temp_dir.path().to_path_buf();
+        let versioned = migrations_dir.join("V12__create_users.sql");
+        let undo = migrations_dir.join("U12__create_users.sql");
+        let repeatable = migrations_dir.join("R__refresh_views.sql");
+        let other = migrations_dir.join("12_create_users.sql");
+        for path in [&versioned, &undo, &repeatable, &other] {
+            fs::write(path, "").unwrap();
+        }
+
+        let migration = migration_of(&versioned, &migrations_dir, MigrationTool::Flyway).unwrap();
+        assert_eq!(migration.sequence_number, MigrationVersion::from(12));
+        assert_eq!(migration.name, "create_users");
+        assert_eq!(migration.direction, MigrationDirection::Unspecified);
+
+        let migration = migration_of(&undo, &migrations_dir, MigrationTool::Flyway).unwrap();
+        assert_eq!(migration.sequence_number, MigrationVersion::from(12));
+        assert_eq!(migration.direction, MigrationDirection::Down);
+
+        let migration = migration_of(&repeatable, &migrations_dir, MigrationTool::Flyway).unwrap();
+        assert!(migration.repeatable);
+        assert_eq!(migration.name, "refresh_views");
+
+        assert!(migration_of(&other, &migrations_dir, MigrationTool::Flyway).is_none());
+    }
+
+    #[test]
+    fn test_get_migration_flyway_dotted_versions() {
+        let temp_dir = setup();
+        let migrations_dir = temp_dir.path().to_path_buf();
+        let dotted = migrations_dir.join("V1.1__add_email.sql");
+        let underscored = migrations_dir.join("V2024_01_01__add_index.sql");
+        let trailing_zero = migrations_dir.join("V1.1.0__add_name.sql");
+        let invalid = migrations_dir.join("V1.x__add_email.sql");
+        for path in [&dotted, &underscored, &trailing_zero, &invalid] {
+            fs::write(path, "").unwrap();
+        }
+
+        let version = |path: &Path| {
+            migration_of(path, &migrations_dir, MigrationTool::Flyway)
+                .map(|migration| migration.sequence_number)
+        };
+
+        assert_eq!(version(&dotted), Some(MigrationVersion::new(vec![1, 1])));
+        assert_eq!(
+            version(&underscored),
+            Some(MigrationVersion::new(vec![2024, 1, 1]))
+        );
+        assert_eq!(version(&trailing_zero), version(&dotted));
+        assert!(version(&invalid).is_none());
+
+        // versions are compared part by part, not as text
+        assert!(MigrationVersion::new(vec![1, 2]) < MigrationVersion::new(vec![1, 10]));
+        assert!(MigrationVersion::new(vec![1, 10]) < MigrationVersion::from(2));
+        assert!(MigrationVersion::from(2) < MigrationVersion::max());
+    }
+
+    #[test]
+    fn test_get_migration_sqitch() {
+        let temp_dir = setup();
+        let migrations_dir = temp_dir.path().to_path_buf();
+        fs::write(
+            migrations_dir.join("sqitch.plan"),
+            "%syntax-version=1.0.0\n%project=app\n\nusers 2024-01-01T00:00:00Z Jane <jane@example.com> # Add users\nroles [users] 2024-01-02T00:00:00Z Jane <jane@example.com>\n@v1.0 2024-01-03T00:00:00Z Jane <jane@example.com>\nusers [users@v1.0] 2024-01-04T00:00:00Z Jane <jane@example.com>\n",
+        )
+        .unwrap();
+        for dir in ["deploy", "revert", "verify"] {
+            fs::create_dir(migrations_dir.join(dir)).unwrap();
+        }
+        let deploy = migrations_dir.join("deploy/roles.sql");
+        let revert = migrations_dir.join("revert/roles.sql");
+        let verify = migrations_dir.join("verify/roles.sql");
+        let reworked = migrations_dir.join("deploy/users.sql");
+        let first_version = migrations_dir.join("deploy/users@v1.0.sql");
+        let unplanned = migrations_dir.join("deploy/unplanned.sql");
+        for path in [
+            &deploy,
+            &revert,
+            &verify,
+            &reworked,
+            &first_version,
+            &unplanned,
+        ] {
+            fs::write(path, "").unwrap();
+        }
+
+        let migration = migration_of(&deploy, &migrations_dir, MigrationTool::Sqitch).unwrap();
+        assert_eq!(migration.sequence_number, MigrationVersion::from(2));
+        assert_eq!(migration.name, "roles");
+        assert_eq!(migration.direction, MigrationDirection::Up);
+
+        let migration = migration_of(&revert, &migrations_dir, MigrationTool::Sqitch).unwrap();
+        assert_eq!(migration.direction, MigrationDirection::Down);
+
+        let migration = migration_of(&verify, &migrations_dir, MigrationTool::Sqitch).unwrap();
+        assert_eq!(migration.direction, MigrationDirection::Verify);
+
+        let migration = migration_of(&reworked, &migrations_dir, MigrationTool::Sqitch).unwrap();
+        assert_eq!(migration.sequence_number, MigrationVersion::from(3));
+
+        let migration =
+            migration_of(&first_version, &migrations_dir, MigrationTool::Sqitch).unwrap();
+        assert_eq!(migration.sequence_number, MigrationVersion::from(1));
+
+        assert!(migration_of(&unplanned, &migrations_dir, MigrationTool::Sqitch).is_none());
+    }
+
+    #[test]
+    fn test_get_migration_in_memory() {
+        let migrations_dir = PathBuf::from("/project/migrations");
+        let mut fs = MemoryFileSystem::default();
+        fs.insert(
+            migrations_dir.join(SQITCH_PLAN_FILE_NAME),
+            "%syntax-version=1.0.0\n%project=app\n\nusers 2024-01-01T00:00:00Z Jane <jane@example.com>\n",
+        );
+
+        // neither the plan nor the paths are looked up on disk
+        let layout = load_migration_layout(&fs, &migrations_dir, MigrationTool::Sqitch);
+        let migration = get_migration(
+            &fs,
+            Path::new("/project/migrations/../migrations/deploy/users.sql"),
+            &migrations_dir,
+            layout.as_ref(),
+        )
+        .unwrap();
+        assert_eq!(migration.sequence_number, MigrationVersion::from(1));
+        assert_eq!(migration.name, "users");
+    }
+
+    #[test]
+    fn test_get_migration_golang_migrate() {
+        let temp_dir = setup();
+        let migrations_dir = temp_dir.path().to_path_buf();
+        let up = migrations_dir.join("000003_create_users.up.sql");
+        let down = migrations_dir.join("000003_create_users.down.sql");
+        let other = migrations_dir.join("000003_create_users.sql");
+        for path in [&up, &down, &other] {
+            fs::write(path, "").unwrap();
+        }
+
+        let migration = migration_of(&up, &migrations_dir, MigrationTool::GolangMigrate).unwrap();
+        assert_eq!(migration.sequence_number, MigrationVersion::from(3));
+        assert_eq!(migration.name, "create_users");
+        assert_eq!(migration.direction, MigrationDirection::Up);
+
+        let migration = migration_of(&down, &migrations_dir, MigrationTool::GolangMigrate).unwrap();
+        assert_eq!(migration.direction, MigrationDirection::Down);
+
+        assert!(migration_of(&other, &migrations_dir, MigrationTool::GolangMigrate).is_none());
+    }
+
+    #[test]
+    fn test_dbmate_sections() {
+        let temp_dir = setup();
+        let migrations_dir = temp_dir.path().to_path_buf();
+        let path = migrations_dir.join("20240101000000_create_users.sql");
+        let content =
+            "-- migrate:up\ncreate table users (id int);\n\n-- migrate:down\ndrop table users;\n";
+        fs::write(&path, content).unwrap();
+
+        let migration = migration_of(&path, &migrations_dir, MigrationTool::Dbmate).unwrap();
+        let sections = MigrationTool::Dbmate
+            .layout(None)
+            .sections(&migration, content);
+
+        let sections: Vec<_> = sections
+            .iter()
+            .map(|section| (section.direction, &content[section.range]))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                (
+                    MigrationDirection::Up,
+                    "-- migrate:up\ncreate table users (id int);\n\n"
+                ),
+                (
+                    MigrationDirection::Down,
+                    "-- migrate:down\ndrop table users;\n"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_liquibase_sections() {
+        let temp_dir = setup();
+        let migrations_dir = temp_dir.path().to_path_buf();
+        let path = migrations_dir.join("001-create-users.sql");
+        let content = "--liquibase formatted sql\n\n--changeset jane:1\ncreate table users (id int);\n--rollback drop table users;\n\n-- changeset jane:2\nalter table users add column name text;\n";
+        fs::write(&path, content).unwrap();
+
+        let migration = migration_of(&path, &migrations_dir, MigrationTool::Liquibase).unwrap();
+        assert_eq!(migration.sequence_number, MigrationVersion::from(1));
+        assert_eq!(migration.name, "create-users");
+
+        let sections = MigrationTool::Liquibase
+            .layout(None)
+            .sections(&migration, content);
+        let sections: Vec<_> = sections
+            .iter()
+            .map(|section| &content[section.range])
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                "--changeset jane:1\ncreate table users (id int);\n--rollback drop table users;\n\n",
+                "-- changeset jane:2\nalter table users add column name text;\n",
+            ]
+        );
+    }
 }
@@ -1,16 +1,45 @@
-use std::sync::{Arc, RwLock};
+use std::sync::{
+    Arc, RwLock,
+    atomic::{AtomicU64, Ordering},
+};
 
 use pgls_schema_cache::SchemaCache;
 
 use crate::WorkspaceError;
 
 #[cfg(feature = "db")]
-use sqlx::PgPool;
+use pgls_schema_cache::{SchemaCacheObjectKind, SchemaFingerprint};
+#[cfg(feature = "db")]
+use sqlx::{PgPool, postgres::PgListener};
 #[cfg(feature = "db")]
 use std::collections::HashMap;
+#[cfg(feature = "db")]
+use tokio::task::AbortHandle;
+
+#[cfg(feature = "db")]
+use super::{
+    async_helper::{run_async, spawn},
+    connection_key::ConnectionKey,
+    schema_cache_store::SchemaCacheStore,
+};
+
+#[cfg(feature = "db")]
+type DbSchemas = Arc<RwLock<HashMap<ConnectionKey, Arc<SchemaCache>>>>;
+
+/// Running schema change listeners by connection and channel.
+#[cfg(feature = "db")]
+type Listeners = Arc<RwLock<HashMap<(ConnectionKey, String), Listener>>>;
+
+/// Locks serializing the refreshes of a connection's schema cache, so that concurrent
+/// refreshes do not overwrite each other's update.
+#[cfg(feature = "db")]
+type RefreshLocks = Arc<std::sync::Mutex<HashMap<ConnectionKey, Arc<tokio::sync::Mutex<()>>>>>;
 
 #[cfg(feature = "db")]
-use super::{async_helper::run_async, connection_key::ConnectionKey};
+struct Listener {
+    pool: PgPool,
+    task: AbortHandle,
+}
 
 /// Manages schema cache storage and retrieval.
 ///
@@ -21,17 +50,38 @@ use super::{async_helper::run_async, connection_key::ConnectionKey};
 /// - `set()` - Set schema from JSON string
 /// - `get()` - Get the current schema
 /// - `clear()` - Clear the current schema
+/// - `generation()` - Counter of changes to the schema caches
 ///
 /// DB-only API:
-/// - `load()` - Load schema from database connection
+/// - `load()` - Load schema from disk or database connection
+/// - `refresh()` - Reload some kinds of objects for a connection
 /// - `clear_connection()` - Clear schema for specific connection
 pub struct SchemaCacheManager {
     /// Connection-based schema caches (db mode only)
     #[cfg(feature = "db")]
-    db_schemas: RwLock<HashMap<ConnectionKey, Arc<SchemaCache>>>,
+    db_schemas: DbSchemas,
+
+    /// Channel to `LISTEN` on for schema changes (db mode only)
+    #[cfg(feature = "db")]
+    listen_channel: RwLock<Option<String>>,
+
+    /// Running schema change listeners (db mode only)
+    #[cfg(feature = "db")]
+    listeners: Listeners,
+
+    /// Locks serializing schema cache refreshes per connection (db mode only)
+    #[cfg(feature = "db")]
+    refresh_locks: RefreshLocks,
+
+    /// Where to persist schema caches across restarts, if at all (db mode only)
+    #[cfg(feature = "db")]
+    store: RwLock<Option<Arc<SchemaCacheStore>>>,
 
     /// JSON-loaded schema (available in both modes)
     schema: RwLock<Option<Arc<SchemaCache>>>,
+
+    /// Incremented whenever a schema cache is invalidated or replaced
+    generation: Arc<AtomicU64>,
 }
 
 impl Default for SchemaCacheManager {
@@ -44,8 +94,17 @@ impl SchemaCacheManager {
     pub fn new() -> Self {
         Self {
             #[cfg(feature = "db")]
-            db_schemas: RwLock::new(HashMap::new()),
+            db_schemas: Arc::new(RwLock::new(HashMap::new())),
+            #[cfg(feature = "db")]
+            listen_channel: RwLock::new(None),
+            #[cfg(feature = "db")]
+            listeners: Arc::new(RwLock::new(HashMap::new())),
+            #[cfg(feature = "db")]
+            refresh_locks: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            #[cfg(feature = "db")]
+            store: RwLock::new(None),
             schema: RwLock::new(None),
+            generation: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -56,6 +115,7 @@ impl SchemaCacheManager {
         let schema: SchemaCache = serde_json::from_str(json)
             .map_err(|e| WorkspaceError::runtime(&format!("Invalid schema JSON: {e}")))?;
         *self.schema.write().unwrap() = Some(Arc::new(schema));
+        self.generation.fetch_add(1, Ordering::Relaxed);
         Ok(())
     }
 
@@ -67,12 +127,20 @@ impl SchemaCacheManager {
     /// Clear the current schema.
     pub fn clear(&self) {
         *self.schema.write().unwrap() = None;
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a counter that is incremented whenever a schema cache is invalidated or
+    /// replaced. Loading a schema cache that was not cached yet does not change it.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
     }
 
     // ==================== DB-only API ====================
 
     /// Load schema from a database connection.
-    /// Returns cached schema if available, otherwise loads from database.
+    /// Returns cached schema if available. Otherwise, a persisted schema is served right away
+    /// and revalidated in the background, or the schema is loaded from the database.
     #[cfg(feature = "db")]
     pub fn load(&self, pool: &PgPool) -> Result<Arc<SchemaCache>, WorkspaceError> {
         let key: ConnectionKey = pool.into();
@@ -92,29 +160,220 @@ impl SchemaCacheManager {
             return Ok(Arc::clone(cache));
         }
 
+        let store = self.store.read().unwrap().clone();
+
+        // Serve the persisted schema cache, if there is one
+        if let Some(store) = store.as_ref()
+            && let Some((fingerprint, schema_cache)) = store.read(&key)
+        {
+            let schema_cache = Arc::new(schema_cache);
+            schemas.insert(key.clone(), schema_cache.clone());
+            drop(schemas);
+
+            self.start_revalidation(key.clone(), pool, Arc::clone(store), fingerprint);
+            self.start_listener(key, pool);
+
+            return Ok(schema_cache);
+        }
+
         // Load schema cache from database
         let pool_clone = pool.clone();
-        let schema_cache = Arc::new(run_async(
-            async move { SchemaCache::load(&pool_clone).await },
-        )??);
+        let (fingerprint, schema_cache) = run_async(async move {
+            // take the fingerprint first, so changes made while loading are picked up next time
+            let fingerprint = SchemaFingerprint::load(&pool_clone).await?;
+            let schema_cache = SchemaCache::load(&pool_clone).await?;
+            Ok::<_, sqlx::Error>((fingerprint, schema_cache))
+        })??;
+
+        if let Some(store) = store {
+            store.write(&key, fingerprint, &schema_cache);
+        }
+
+        let schema_cache = Arc::new(schema_cache);
+        schemas.insert(key.clone(), schema_cache.clone());
+        drop(schemas);
+
+        self.start_listener(key, pool);
 
-        schemas.insert(key, schema_cache.clone());
         Ok(schema_cache)
     }
 
+    /// Reload the given kinds of objects for a connection.
+    /// Does nothing if the schema for the connection has not been loaded yet.
+    #[cfg(feature = "db")]
+    pub fn refresh(
+        &self,
+        pool: &PgPool,
+        kinds: &[SchemaCacheObjectKind],
+    ) -> Result<(), WorkspaceError> {
+        if kinds.is_empty() {
+            return Ok(());
+        }
+
+        let db_schemas = Arc::clone(&self.db_schemas);
+        let refresh_locks = Arc::clone(&self.refresh_locks);
+        let generation = Arc::clone(&self.generation);
+        let pool = pool.clone();
+        let kinds = kinds.to_vec();
+
+        run_async(async move {
+            refresh_schema_cache(&db_schemas, &refresh_locks, &generation, &pool, &kinds).await
+        })??;
+
+        Ok(())
+    }
+
+    /// Set the channel to `LISTEN` on for schema changes.
+    /// Listeners on another channel are stopped and restarted on the new one.
+    #[cfg(feature = "db")]
+    pub fn set_listen_channel(&self, channel: Option<String>) {
+        let mut listen_channel = self.listen_channel.write().unwrap();
+        if *listen_channel == channel {
+            return;
+        }
+        *listen_channel = channel.clone();
+        drop(listen_channel);
+
+        let stopped: Vec<(ConnectionKey, Listener)> = {
+            let mut listeners = self.listeners.write().unwrap();
+            let outdated: Vec<_> = listeners
+                .keys()
+                .filter(|(_, listening_on)| Some(listening_on) != channel.as_ref())
+                .cloned()
+                .collect();
+            outdated
+                .into_iter()
+                .filter_map(|key| listeners.remove(&key).map(|listener| (key.0, listener)))
+                .collect()
+        };
+
+        for (key, listener) in stopped {
+            listener.task.abort();
+            self.start_listener(key, &listener.pool);
+        }
+    }
+
+    /// Set where to persist schema caches. `None` disables persistence.
+    #[cfg(feature = "db")]
+    pub fn set_store(&self, store: Option<SchemaCacheStore>) {
+        *self.store.write().unwrap() = store.map(Arc::new);
+    }
+
+    #[cfg(feature = "db")]
+    fn start_revalidation(
+        &self,
+        key: ConnectionKey,
+        pool: &PgPool,
+        store: Arc<SchemaCacheStore>,
+        fingerprint: SchemaFingerprint,
+    ) {
+        let db_schemas = Arc::clone(&self.db_schemas);
+        let refresh_locks = Arc::clone(&self.refresh_locks);
+        let generation = Arc::clone(&self.generation);
+        let pool = pool.clone();
+
+        spawn(async move {
+            if let Err(err) = revalidate_schema_cache(
+                &db_schemas,
+                &refresh_locks,
+                &generation,
+                &pool,
+                &key,
+                &store,
+                fingerprint,
+            )
+            .await
+            {
+                tracing::warn!("Failed to revalidate persisted schema cache: {err}");
+            }
+        });
+    }
+
+    #[cfg(feature = "db")]
+    fn start_listener(&self, key: ConnectionKey, pool: &PgPool) {
+        let Some(channel) = self.listen_channel.read().unwrap().clone() else {
+            return;
+        };
+
+        let mut listeners = self.listeners.write().unwrap();
+        let listener_key = (key, channel);
+        if listeners.contains_key(&listener_key) {
+            return;
+        }
+
+        let db_schemas = Arc::clone(&self.db_schemas);
+        let refresh_locks = Arc::clone(&self.refresh_locks);
+        let generation = Arc::clone(&self.generation);
+        let task_listeners = Arc::clone(&self.listeners);
+        let task_key = listener_key.clone();
+        let task_pool = pool.clone();
+
+        let task = spawn(async move {
+            let channel = &task_key.1;
+            if let Err(err) = listen_for_schema_changes(
+                &db_schemas,
+                &refresh_locks,
+                &generation,
+                &task_pool,
+                channel,
+            )
+            .await
+            {
+                tracing::warn!("Stopped listening for schema changes on \"{channel}\": {err}");
+            }
+            task_listeners.write().unwrap().remove(&task_key);
+        });
+
+        listeners.insert(
+            listener_key,
+            Listener {
+                pool: pool.clone(),
+                task,
+            },
+        );
+    }
+
+    /// Stops the schema change listeners of the connections matching `predicate`.
+    #[cfg(feature = "db")]
+    fn stop_listeners(&self, predicate: impl Fn(&ConnectionKey) -> bool) {
+        self.listeners
+            .write()
+            .unwrap()
+            .retain(|(key, _), listener| {
+                if predicate(key) {
+                    listener.task.abort();
+                    false
+                } else {
+                    true
+                }
+            });
+    }
+
     /// Clear the schema cache for a specific connection.
     #[cfg(feature = "db")]
     pub fn clear_connection(&self, pool: &PgPool) {
         let key: ConnectionKey = pool.into();
+        if let Some(store) = self.store.read().unwrap().as_ref() {
+            store.remove(&key);
+        }
+        self.stop_listeners(|listening| *listening == key);
+        self.refresh_locks.lock().unwrap().remove(&key);
         let mut schemas = self.db_schemas.write().unwrap();
         schemas.remove(&key);
+        self.generation.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Clear all connection-based schema caches.
     #[cfg(feature = "db")]
     pub fn clear_all_connections(&self) {
+        self.stop_listeners(|_| true);
+        self.refresh_locks.lock().unwrap().clear();
         let mut schemas = self.db_schemas.write().unwrap();
+        if let Some(store) = self.store.read().unwrap().as_ref() {
+            schemas.keys().for_each(|key| store.remove(key));
+        }
         schemas.clear();
+        self.generation.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Clear everything (both JSON schema and all db connections in db mode).
@@ -124,3 +383,381 @@ impl SchemaCacheManager {
         self.clear_all_connections();
     }
 }
+
+/// Returns the lock serializing the refreshes of the connection's schema cache.
+#[cfg(feature = "db")]
+fn refresh_lock(refresh_locks: &RefreshLocks, key: &ConnectionKey) -> Arc<tokio::sync::Mutex<()>> {
+    refresh_locks
+        .lock()
+        .unwrap()
+        .entry(key.clone())
+        .or_default()
+        .clone()
+}
+
+/// Reloads the given kinds of objects into a copy of the connection's schema cache and swaps it in,
+/// so readers holding the previous cache are not affected.
+#[cfg(feature = "db")]
+async fn refresh_schema_cache(
+    db_schemas: &DbSchemas,
+    refresh_locks: &RefreshLocks,
+    generation: &AtomicU64,
+    pool: &PgPool,
+    kinds: &[SchemaCacheObjectKind],
+) -> Result<(), sqlx::Error> {
+    let key: ConnectionKey = pool.into();
+
+    // copying the cache before another refresh swapped in its update would discard it
+    let lock = refresh_lock(refresh_locks, &key);
+    let _guard = lock.lock().await;
+
+    let Some(current) = db_schemas.read().unwrap().get(&key).cloned() else {
+        return Ok(());
+    };
+
+    let mut refreshed = SchemaCache::clone(&current);
+    refreshed.reload(pool, kinds).await?;
+
+    let mut schemas = db_schemas.write().unwrap();
+    // the cache might have been invalidated in the meantime
+    if let Some(schema_cache) = schemas.get_mut(&key) {
+        *schema_cache = Arc::new(refreshed);
+        generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    Ok(())
+}
+
+/// Reloads the schema cache from the database if the fingerprint of the database
+/// no longer matches the one of the persisted schema cache.
+#[cfg(feature = "db")]
+async fn revalidate_schema_cache(
+    db_schemas: &DbSchemas,
+    refresh_locks: &RefreshLocks,
+    generation: &AtomicU64,
+    pool: &PgPool,
+    key: &ConnectionKey,
+    store: &SchemaCacheStore,
+    persisted: SchemaFingerprint,
+) -> Result<(), sqlx::Error> {
+    let lock = refresh_lock(refresh_locks, key);
+    let _guard = lock.lock().await;
+
+    let fingerprint = SchemaFingerprint::load(pool).await?;
+    if fingerprint == persisted {
+        return Ok(());
+    }
+
+    tracing::debug!("Persisted schema cache is outdated, reloading");
+
+    let schema_cache = SchemaCache::load(pool).await?;
+    store.write(key, fingerprint, &schema_cache);
+
+    let mut schemas = db_schemas.write().unwrap();
+    // the cache might have been invalidated in the meantime
+    if let Some(cached) = schemas.get_mut(key) {
+        *cached = Arc::new(schema_cache);
+        generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "db")]
+async fn listen_for_schema_changes(
+    db_schemas: &DbSchemas,
+    refresh_locks: &RefreshLocks,
+    generation: &AtomicU64,
+    pool: &PgPool,
+    channel: &str,
+) -> Result<(), sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(channel).await?;
+
+    loop {
+        let notification = listener.recv().await?;
+        let kinds = SchemaCacheObjectKind::affected_by_command_tag(notification.payload());
+
+        tracing::debug!(
+            "Received schema change \"{}\", refreshing {:?}",
+            notification.payload(),
+            kinds
+        );
+
+        if let Err(err) =
+            refresh_schema_cache(db_schemas, refresh_locks, generation, pool, &kinds).await
+        {
+            tracing::warn!("Failed to refresh schema cache: {err}");
+        }
+    }
+}
+
+/// Returns the kinds of objects in the schema cache that a statement might change.
+#[cfg(feature = "db")]
+pub(crate) fn affected_object_kinds(stmt: &pgls_query::NodeEnum) -> Vec<SchemaCacheObjectKind> {
+    use pgls_query::NodeEnum;
+    use pgls_query::protobuf::ObjectType;
+
+    let object_type = |object_type: ObjectType| {
+        SchemaCacheObjectKind::affected_by_object_type(object_type.as_str_name())
+    };
+
+    match stmt {
+        NodeEnum::CreateStmt(_)
+        | NodeEnum::CreateForeignTableStmt(_)
+        | NodeEnum::ViewStmt(_)
+        | NodeEnum::RuleStmt(_) => object_type(ObjectType::ObjectTable),
+        NodeEnum::CreateTableAsStmt(n) => object_type(n.objtype()),
+        NodeEnum::AlterTableStmt(n) => object_type(n.objtype()),
+        NodeEnum::DropStmt(n) => object_type(n.remove_type()),
+        NodeEnum::RenameStmt(n) => object_type(n.rename_type()),
+        NodeEnum::AlterObjectSchemaStmt(n) => object_type(n.object_type()),
+        NodeEnum::AlterOwnerStmt(n) => object_type(n.object_type()),
+        NodeEnum::CommentStmt(n) => object_type(n.objtype()),
+        NodeEnum::DefineStmt(n) => object_type(n.kind()),
+        NodeEnum::GrantStmt(_) => SchemaCacheObjectKind::affected_by_command_tag("GRANT"),
+        NodeEnum::IndexStmt(_) => object_type(ObjectType::ObjectIndex),
+        NodeEnum::CreateFunctionStmt(_) | NodeEnum::AlterFunctionStmt(_) => {
+            object_type(ObjectType::ObjectFunction)
+        }
+        NodeEnum::CreatePolicyStmt(_) | NodeEnum::AlterPolicyStmt(_) => {
+            object_type(ObjectType::ObjectPolicy)
+        }
+        NodeEnum::CreateTrigStmt(_) => object_type(ObjectType::ObjectTrigger),
+        NodeEnum::CompositeTypeStmt(_)
+        | NodeEnum::CreateEnumStmt(_)
+        | NodeEnum::AlterEnumStmt(_)
+        | NodeEnum::CreateRangeStmt(_)
+        | NodeEnum::CreateDomainStmt(_)
+        | NodeEnum::AlterDomainStmt(_) => object_type(ObjectType::ObjectType),
+        NodeEnum::CreateSeqStmt(_) | NodeEnum::AlterSeqStmt(_) => {
+            object_type(ObjectType::ObjectSequence)
+        }
+        NodeEnum::CreateRoleStmt(_)
+        | NodeEnum::AlterRoleStmt(_)
+        | NodeEnum::AlterRoleSetStmt(_)
+        | NodeEnum::DropRoleStmt(_)
+        | NodeEnum::GrantRoleStmt(_) => object_type(ObjectType::ObjectRole),
+        NodeEnum::CreateCastStmt(_) => object_type(ObjectType::ObjectCast),
+        NodeEnum::CreateSchemaStmt(_)
+        | NodeEnum::CreateExtensionStmt(_)
+        | NodeEnum::AlterExtensionStmt(_)
+        | NodeEnum::AlterExtensionContentsStmt(_)
+        // a DO block can run arbitrary DDL
+        | NodeEnum::DoStmt(_) => object_type(ObjectType::ObjectSchema),
+        _ => vec![],
+    }
+}
+
+#[cfg(all(test, feature = "db"))]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use sqlx::{Executor, PgPool};
+
+    use super::{SchemaCacheManager, SchemaCacheStore};
+
+    fn manager_with_store(dir: &std::path::Path) -> SchemaCacheManager {
+        let manager = SchemaCacheManager::new();
+        manager.set_store(Some(SchemaCacheStore::new(dir.to_path_buf())));
+        manager
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn serves_persisted_schema_cache_and_revalidates(test_db: PgPool) {
+        let dir = tempfile::tempdir().unwrap();
+
+        test_db
+            .execute("create table public.persisted (id int);")
+            .await
+            .unwrap();
+
+        let first = manager_with_store(dir.path());
+        assert!(
+            first
+                .load(&test_db)
+                .unwrap()
+                .find_tables("persisted", None)
+                .len()
+                == 1
+        );
+
+        // changed behind the back of the persisted cache
+        test_db
+            .execute("create table public.created_later (id int);")
+            .await
+            .unwrap();
+
+        // a fresh manager gets the outdated cache from disk right away ...
+        let second = manager_with_store(dir.path());
+        let served = second.load(&test_db).unwrap();
+        let generation = second.generation();
+        assert!(served.find_tables("persisted", None).len() == 1);
+        assert!(served.find_tables("created_later", None).is_empty());
+
+        // ... and picks up the change once the fingerprint was compared
+        let started = Instant::now();
+        while second
+            .load(&test_db)
+            .unwrap()
+            .find_tables("created_later", None)
+            .is_empty()
+        {
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "Persisted schema cache was not revalidated"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(second.generation() > generation);
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn revalidates_persisted_enum_values(test_db: PgPool) {
+        let dir = tempfile::tempdir().unwrap();
+
+        test_db
+            .execute("create type public.mood as enum ('happy');")
+            .await
+            .unwrap();
+
+        manager_with_store(dir.path()).load(&test_db).unwrap();
+
+        // only touches pg_enum
+        test_db
+            .execute("alter type public.mood add value 'sad';")
+            .await
+            .unwrap();
+
+        let manager = manager_with_store(dir.path());
+        let mood_values = || {
+            manager
+                .load(&test_db)
+                .unwrap()
+                .find_type("mood", Some("public"))
+                .unwrap()
+                .enums
+                .values
+                .clone()
+        };
+        assert_eq!(mood_values(), vec!["happy"]);
+
+        let started = Instant::now();
+        while mood_values() != vec!["happy", "sad"] {
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "Persisted schema cache was not revalidated"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn revalidates_persisted_comments(test_db: PgPool) {
+        let dir = tempfile::tempdir().unwrap();
+
+        test_db
+            .execute("create table public.commented (id int);")
+            .await
+            .unwrap();
+
+        manager_with_store(dir.path()).load(&test_db).unwrap();
+
+        // only touches pg_description
+        test_db
+            .execute("comment on table public.commented is 'described';")
+            .await
+            .unwrap();
+
+        let manager = manager_with_store(dir.path());
+        let comment = || {
+            manager
+                .load(&test_db)
+                .unwrap()
+                .find_tables("commented", None)[0]
+                .comment
+                .clone()
+        };
+        assert_eq!(comment(), None);
+
+        let started = Instant::now();
+        while comment().as_deref() != Some("described") {
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "Persisted schema cache was not revalidated"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn moves_listeners_to_a_changed_channel(test_db: PgPool) {
+        test_db
+            .execute("create table public.listened (id int);")
+            .await
+            .unwrap();
+
+        let manager = SchemaCacheManager::new();
+        manager.set_listen_channel(Some("first_channel".into()));
+        manager.load(&test_db).unwrap();
+
+        let channels = || {
+            manager
+                .listeners
+                .read()
+                .unwrap()
+                .keys()
+                .map(|(_, channel)| channel.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(channels(), vec!["first_channel"]);
+
+        manager.set_listen_channel(Some("second_channel".into()));
+        assert_eq!(channels(), vec!["second_channel"]);
+
+        test_db
+            .execute("create table public.notified (id int);")
+            .await
+            .unwrap();
+
+        // the listener connects in the background, so notify until it picked up the change
+        let started = Instant::now();
+        while manager
+            .load(&test_db)
+            .unwrap()
+            .find_tables("notified", None)
+            .is_empty()
+        {
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "Schema change on the new channel was not picked up"
+            );
+            test_db
+                .execute("select pg_notify('second_channel', 'CREATE TABLE');")
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        manager.clear_connection(&test_db);
+        assert!(channels().is_empty());
+    }
+
+    #[sqlx::test(migrator = "pgls_test_utils::MIGRATIONS")]
+    async fn counts_invalidations_of_the_schema_cache(test_db: PgPool) {
+        test_db
+            .execute("create table public.counted (id int);")
+            .await
+            .unwrap();
+
+        let manager = SchemaCacheManager::new();
+        let initial = manager.generation();
+
+        manager.load(&test_db).unwrap();
+        manager.load(&test_db).unwrap();
+        assert_eq!(manager.generation(), initial);
+
+        manager.clear_connection(&test_db);
+        assert_eq!(manager.generation(), initial + 1);
+    }
+}
@@ -0,0 +1,223 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use pgls_schema_cache::{SchemaCache, SchemaFingerprint};
+use serde::{Deserialize, Serialize};
+
+use super::connection_key::ConnectionKey;
+
+/// A schema cache as it is stored on disk.
+#[derive(Serialize, Deserialize)]
+struct PersistedSchemaCache {
+    /// The version of the language server that wrote the file.
+    /// Files from other versions are ignored, since the layout of the schema cache might differ.
+    version: String,
+    /// The connection the schema cache belongs to, in case the hashes of two connections collide.
+    connection: ConnectionKey,
+    fingerprint: SchemaFingerprint,
+    schema_cache: SchemaCache,
+}
+
+/// Stores schema caches on disk, one file per connection.
+pub(crate) struct SchemaCacheStore {
+    dir: PathBuf,
+}
+
+impl SchemaCacheStore {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Uses the `schema-caches` folder inside the cache directory.
+    pub(crate) fn in_cache_dir() -> Self {
+        Self::new(pgls_fs::ensure_cache_dir().join("schema-caches"))
+    }
+
+    /// Reads the persisted schema cache for a connection.
+    /// Returns `None` if there is none, or if it can't be used.
+    pub(crate) fn read(&self, key: &ConnectionKey) -> Option<(SchemaFingerprint, SchemaCache)> {
+        let path = self.path(key);
+        let content = fs::read(&path).ok()?;
+
+        match serde_json::from_slice::<PersistedSchemaCache>(&content) {
+            Ok(persisted)
+                if persisted.version == pgls_configuration::VERSION
+                    && persisted.connection == *key =>
+            {
+                Some((persisted.fingerprint, persisted.schema_cache))
+            }
+            Ok(_) => None,
+            Err(err) => {
+                tracing::warn!("Ignoring invalid schema cache file {path:?}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Persists the schema cache for a connection. Failures are only logged,
+    /// since the persisted cache is purely an optimization.
+    pub(crate) fn write(
+        &self,
+        key: &ConnectionKey,
+        fingerprint: SchemaFingerprint,
+        schema_cache: &SchemaCache,
+    ) {
+        #[derive(Serialize)]
+        struct PersistedSchemaCacheRef<'a> {
+            version: &'a str,
+            connection: &'a ConnectionKey,
+            fingerprint: SchemaFingerprint,
+            schema_cache: &'a SchemaCache,
+        }
+
+        let persisted = PersistedSchemaCacheRef {
+            version: pgls_configuration::VERSION,
+            connection: key,
+            fingerprint,
+            schema_cache,
+        };
+
+        let path = self.path(key);
+        if let Err(err) = write_atomically(&path, &persisted) {
+            tracing::warn!("Failed to persist schema cache to {path:?}: {err}");
+        }
+    }
+
+    /// Removes the persisted schema cache for a connection, if there is one.
+    pub(crate) fn remove(&self, key: &ConnectionKey) {
+        let path = self.path(key);
+        if let Err(err) = fs::remove_file(&path)
+            && err.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::warn!("Failed to remove persisted schema cache {path:?}: {err}");
+        }
+    }
+
+    fn path(&self, key: &ConnectionKey) -> PathBuf {
+        let key = format!(
+            "{}\0{}\0{}\0{}",
+            key.host, key.port, key.username, key.database
+        );
+
+        self.dir.join(hashed_file_name(&key))
+    }
+}
+
+/// A file name derived from `key`. Unlike the hashers of the standard library, the hash
+/// (FNV-1a) is stable across runs and versions.
+pub(super) fn hashed_file_name(key: &str) -> String {
+    let hash = key.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}.json")
+}
+
+/// Writes to a temporary file first, so that concurrent readers (e.g. the CLI and the
+/// language server) never see a partially written file.
+pub(super) fn write_atomically(path: &Path, value: &impl Serialize) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&tmp_path, serde_json::to_vec(value)?)?;
+    fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp_path);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pgls_schema_cache::{SchemaCache, SchemaFingerprint, Table};
+
+    use super::{ConnectionKey, SchemaCacheStore};
+
+    fn key(database: &str) -> ConnectionKey {
+        ConnectionKey {
+            host: "/var/run/postgresql".into(),
+            port: 5432,
+            username: "postgres".into(),
+            database: database.into(),
+        }
+    }
+
+    #[test]
+    fn roundtrips_schema_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SchemaCacheStore::new(dir.path().to_path_buf());
+
+        assert!(store.read(&key("app")).is_none());
+
+        let schema_cache = SchemaCache {
+            tables: vec![Table {
+                name: "users".into(),
+                schema: "public".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let fingerprint = SchemaFingerprint {
+            catalog_xmin: 42,
+            class_count: 100,
+            proc_count: 200,
+            server_version_num: 170000,
+            ..Default::default()
+        };
+
+        store.write(&key("app"), fingerprint.clone(), &schema_cache);
+
+        let (read_fingerprint, read_cache) = store.read(&key("app")).unwrap();
+        assert_eq!(read_fingerprint, fingerprint);
+        assert_eq!(read_cache.tables.len(), 1);
+        assert_eq!(read_cache.tables[0].name, "users");
+
+        assert!(store.read(&key("other")).is_none());
+
+        store.remove(&key("app"));
+        assert!(store.read(&key("app")).is_none());
+    }
+
+    #[test]
+    fn distinguishes_connections() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SchemaCacheStore::new(dir.path().to_path_buf());
+
+        // these used to map to the same file name
+        let dashed = ConnectionKey {
+            database: "my-app".into(),
+            ..key("app")
+        };
+        let underscored = ConnectionKey {
+            database: "my_app".into(),
+            ..key("app")
+        };
+        assert_ne!(store.path(&dashed), store.path(&underscored));
+
+        let fingerprint = SchemaFingerprint {
+            catalog_xmin: 42,
+            class_count: 100,
+            proc_count: 200,
+            server_version_num: 170000,
+            ..Default::default()
+        };
+        store.write(&dashed, fingerprint, &SchemaCache::default());
+
+        // a file of another connection is never used, even if the names collide
+        std::fs::copy(store.path(&dashed), store.path(&underscored)).unwrap();
+        assert!(store.read(&dashed).is_some());
+        assert!(store.read(&underscored).is_none());
+    }
+
+    #[test]
+    fn ignores_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SchemaCacheStore::new(dir.path().to_path_buf());
+
+        std::fs::write(store.path(&key("app")), "not json").unwrap();
+
+        assert!(store.read(&key("app")).is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use pgls_text_size::TextRange;
+use pgls_typecheck::{IdentifierType, TypedIdentifier};
 
 #[derive(Debug, Clone)]
 pub struct ArgType {
@@ -21,6 +22,24 @@ pub struct SQLFunctionSignature {
     pub args: Vec<SQLFunctionArg>,
 }
 
+impl SQLFunctionSignature {
+    /// The arguments of the function, which can be referenced by name in its body.
+    pub fn typed_identifiers(&self) -> Vec<TypedIdentifier> {
+        self.args
+            .iter()
+            .map(|a| TypedIdentifier {
+                path: self.name.clone(),
+                name: a.name.clone(),
+                type_: IdentifierType {
+                    schema: a.type_.schema.clone(),
+                    name: a.type_.name.clone(),
+                    is_array: a.type_.is_array,
+                },
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SQLFunctionBody {
     pub range: TextRange,
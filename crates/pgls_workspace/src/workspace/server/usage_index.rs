@@ -0,0 +1,452 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use pgls_completions::UsageStats;
+use pgls_query::{
+    NodeEnum, NodeRef,
+    protobuf::{Node, RangeVar},
+};
+
+use super::document::{AstMapper, Document};
+
+#[cfg(feature = "db")]
+use std::{sync::Mutex, time::Instant};
+
+#[cfg(feature = "db")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "db")]
+use super::schema_cache_store::{hashed_file_name, write_atomically};
+
+#[cfg(feature = "db")]
+use crate::WorkspaceError;
+
+/// How long to wait between writes to disk when files are closed.
+/// The CLI opens and closes every file it processes.
+#[cfg(feature = "db")]
+const PERSIST_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Tracks how often database objects are referenced by the statements of each file
+/// in the workspace, to rank the commonly used ones higher in completions.
+/// Files are collected when the workspace is indexed, and again whenever they change while open.
+pub(crate) struct UsageIndex {
+    files: RwLock<HashMap<PathBuf, UsageStats>>,
+
+    /// Files that changed since their usage was collected
+    outdated: RwLock<HashSet<PathBuf>>,
+
+    /// The usage of all files, merged
+    total: RwLock<Option<Arc<UsageStats>>>,
+
+    /// Where to persist the usage across restarts, if at all (db mode only)
+    #[cfg(feature = "db")]
+    store: RwLock<Option<UsageStore>>,
+
+    /// When the usage was last written to disk, and whether it changed since (db mode only)
+    #[cfg(feature = "db")]
+    persisted: Mutex<(Option<Instant>, bool)>,
+}
+
+impl UsageIndex {
+    pub(crate) fn new() -> Self {
+        Self {
+            files: RwLock::new(HashMap::new()),
+            outdated: RwLock::new(HashSet::new()),
+            total: RwLock::new(None),
+            #[cfg(feature = "db")]
+            store: RwLock::new(None),
+            #[cfg(feature = "db")]
+            persisted: Mutex::new((None, false)),
+        }
+    }
+
+    /// Marks the usage of a file as outdated. It is collected again the next time it's needed.
+    pub(crate) fn mark_outdated(&self, path: &Path) {
+        self.outdated.write().unwrap().insert(path.to_path_buf());
+    }
+
+    /// Returns the usage across all files, or `None` if nothing was recorded yet.
+    pub(crate) fn get<'a>(
+        &self,
+        documents: impl IntoIterator<Item = (&'a Path, &'a Document)>,
+    ) -> Option<Arc<UsageStats>> {
+        if !self.outdated.read().unwrap().is_empty() {
+            for (path, document) in documents {
+                if self.outdated.read().unwrap().contains(path) {
+                    self.update(path, document);
+                }
+            }
+        }
+
+        if let Some(total) = self.total.read().unwrap().as_ref() {
+            return (!total.is_empty()).then(|| Arc::clone(total));
+        }
+
+        let mut total = UsageStats::default();
+        for stats in self.files.read().unwrap().values() {
+            total.merge(stats);
+        }
+
+        let total = Arc::new(total);
+        *self.total.write().unwrap() = Some(Arc::clone(&total));
+
+        (!total.is_empty()).then_some(total)
+    }
+
+    /// Collects the usage of a file that is about to be closed. It keeps counting once closed.
+    pub(crate) fn close(&self, path: &Path, document: &Document) {
+        if self.outdated.read().unwrap().contains(path) {
+            self.update(path, document);
+        }
+
+        #[cfg(feature = "db")]
+        {
+            let due = self
+                .persisted
+                .lock()
+                .unwrap()
+                .0
+                .is_none_or(|last| last.elapsed() > PERSIST_INTERVAL);
+            if due && let Err(err) = self.persist() {
+                tracing::warn!("Failed to persist usage: {err}");
+            }
+        }
+    }
+
+    /// Collects the usage of a file, replacing what was collected for it before.
+    pub(crate) fn update(&self, path: &Path, document: &Document) {
+        let mut stats = UsageStats::default();
+        for ast in document.iter(AstMapper) {
+            if let Ok(ast) = ast.as_ref() {
+                collect_usage(ast, &mut stats);
+            }
+        }
+
+        self.files
+            .write()
+            .unwrap()
+            .insert(path.to_path_buf(), stats);
+        self.outdated.write().unwrap().remove(path);
+        *self.total.write().unwrap() = None;
+
+        #[cfg(feature = "db")]
+        {
+            self.persisted.lock().unwrap().1 = true;
+        }
+    }
+
+    /// Set where to persist the usage. Usage persisted for files that are not known yet
+    /// is loaded right away. `None` disables persistence.
+    #[cfg(feature = "db")]
+    pub(crate) fn set_store(&self, store: Option<UsageStore>) {
+        if let Some(store) = store.as_ref() {
+            let mut files = self.files.write().unwrap();
+            for (path, stats) in store.read() {
+                files.entry(path).or_insert(stats);
+            }
+            *self.total.write().unwrap() = None;
+        }
+
+        *self.store.write().unwrap() = store;
+    }
+
+    /// Writes the usage to disk, if it changed since the last write.
+    #[cfg(feature = "db")]
+    pub(crate) fn persist(&self) -> Result<(), WorkspaceError> {
+        let mut persisted = self.persisted.lock().unwrap();
+        if !persisted.1 {
+            return Ok(());
+        }
+
+        if let Some(store) = self.store.read().unwrap().as_ref() {
+            store.write(&self.files.read().unwrap()).map_err(|err| {
+                WorkspaceError::cant_write_file(store.path.display().to_string(), err.to_string())
+            })?;
+            *persisted = (Some(Instant::now()), false);
+        }
+
+        Ok(())
+    }
+}
+
+/// Records the tables, columns and functions a statement references, and which tables it joins.
+fn collect_usage(stmt: &NodeEnum, stats: &mut UsageStats) {
+    let nodes = stmt.nodes();
+
+    let ctes: HashSet<&str> = nodes
+        .iter()
+        .filter_map(|node| match node {
+            NodeRef::CommonTableExpr(cte) => Some(cte.ctename.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut aliases: HashMap<&str, Relation> = HashMap::new();
+    let mut tables: HashSet<Relation> = HashSet::new();
+
+    for node in &nodes {
+        if let NodeRef::RangeVar(relation) = node
+            && is_table(relation, &ctes)
+        {
+            let table = to_relation(relation);
+            stats.record_table(table.0, table.1);
+
+            tables.insert(table);
+            if let Some(alias) = relation.alias.as_ref() {
+                aliases.insert(alias.aliasname.as_str(), table);
+            }
+        }
+    }
+
+    // unqualified columns can only be attributed if there is a single table
+    let single_table = (tables.len() == 1)
+        .then(|| tables.iter().next().copied())
+        .flatten();
+
+    for node in &nodes {
+        match node {
+            NodeRef::ColumnRef(column) => {
+                let names: Option<Vec<&str>> = column
+                    .fields
+                    .iter()
+                    .map(|field| match field.node.as_ref() {
+                        Some(NodeEnum::String(s)) => Some(s.sval.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+
+                match names.as_deref() {
+                    Some([name]) => stats.record_column(single_table, name),
+                    Some([qualifier, name]) => {
+                        let table = aliases
+                            .get(qualifier)
+                            .or_else(|| tables.iter().find(|(_, table)| table == qualifier))
+                            .copied()
+                            .unwrap_or((None, qualifier));
+                        stats.record_column(Some(table), name);
+                    }
+                    Some([schema, table, name]) => {
+                        stats.record_column(Some((Some(schema), table)), name)
+                    }
+                    _ => {}
+                }
+            }
+            NodeRef::FuncCall(call) => {
+                if let Some((schema, name)) = pgls_query_ext::utils::parse_name(&call.funcname) {
+                    stats.record_function(schema.as_deref(), &name);
+                }
+            }
+            NodeRef::JoinExpr(join) => {
+                let right = tables_in(&join.rarg, &ctes);
+                for left in tables_in(&join.larg, &ctes) {
+                    for right in &right {
+                        stats.record_join(left, *right);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A table as `(schema, name)`, with the schema only if it's qualified.
+type Relation<'a> = (Option<&'a str>, &'a str);
+
+fn to_relation(relation: &RangeVar) -> Relation<'_> {
+    let schema = Some(relation.schemaname.as_str()).filter(|s| !s.is_empty());
+    (schema, relation.relname.as_str())
+}
+
+/// Whether a relation refers to a table rather than a CTE.
+fn is_table(relation: &RangeVar, ctes: &HashSet<&str>) -> bool {
+    !relation.schemaname.is_empty() || !ctes.contains(relation.relname.as_str())
+}
+
+/// The tables within one side of a join.
+fn tables_in<'a>(node: &'a Option<Box<Node>>, ctes: &HashSet<&str>) -> Vec<Relation<'a>> {
+    node.as_ref()
+        .and_then(|n| n.node.as_ref())
+        .map(|n| {
+            n.iter()
+                .filter_map(|n| match n {
+                    NodeRef::RangeVar(relation) if is_table(relation, ctes) => {
+                        Some(to_relation(relation))
+                    }
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The usage of the files in a workspace as it is stored on disk.
+#[cfg(feature = "db")]
+#[derive(Serialize, Deserialize)]
+struct PersistedUsage {
+    /// The version of the language server that wrote the file.
+    version: String,
+    /// The workspace the usage belongs to, in case the hashes of two workspaces collide.
+    workspace: PathBuf,
+    files: HashMap<PathBuf, UsageStats>,
+}
+
+/// Stores the usage of a workspace on disk.
+#[cfg(feature = "db")]
+pub(crate) struct UsageStore {
+    path: PathBuf,
+    workspace: PathBuf,
+}
+
+#[cfg(feature = "db")]
+impl UsageStore {
+    pub(crate) fn new(dir: PathBuf, workspace: &Path) -> Self {
+        Self {
+            path: dir.join(hashed_file_name(&workspace.to_string_lossy())),
+            workspace: workspace.to_path_buf(),
+        }
+    }
+
+    /// Uses the `usage` folder inside the cache directory.
+    pub(crate) fn in_cache_dir(workspace: &Path) -> Self {
+        Self::new(pgls_fs::ensure_cache_dir().join("usage"), workspace)
+    }
+
+    /// Reads the persisted usage, skipping files that no longer exist.
+    fn read(&self) -> HashMap<PathBuf, UsageStats> {
+        let Ok(content) = std::fs::read(&self.path) else {
+            return HashMap::new();
+        };
+
+        match serde_json::from_slice::<PersistedUsage>(&content) {
+            Ok(persisted)
+                if persisted.version == pgls_configuration::VERSION
+                    && persisted.workspace == self.workspace =>
+            {
+                persisted
+                    .files
+                    .into_iter()
+                    .filter(|(path, _)| path.exists())
+                    .collect()
+            }
+            Ok(_) => HashMap::new(),
+            Err(err) => {
+                tracing::warn!("Ignoring invalid usage file {:?}: {err}", self.path);
+                HashMap::new()
+            }
+        }
+    }
+
+    fn write(&self, files: &HashMap<PathBuf, UsageStats>) -> std::io::Result<()> {
+        #[derive(Serialize)]
+        struct PersistedUsageRef<'a> {
+            version: &'a str,
+            workspace: &'a Path,
+            files: &'a HashMap<PathBuf, UsageStats>,
+        }
+
+        let persisted = PersistedUsageRef {
+            version: pgls_configuration::VERSION,
+            workspace: &self.workspace,
+            files,
+        };
+
+        write_atomically(&self.path, &persisted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Document, UsageIndex};
+
+    #[test]
+    fn collects_usage_of_open_and_closed_files() {
+        let index = UsageIndex::new();
+
+        let orders = Document::new(
+            r#"
+            select o.id, total from public.orders o join customers c on c.id = o.customer_id;
+            select count(*) from orders where status = 'open';
+            "#
+            .into(),
+            0,
+        );
+        let customers = Document::new("select email from customers;".into(), 0);
+
+        let orders_path = Path::new("orders.sql");
+        let customers_path = Path::new("customers.sql");
+
+        index.mark_outdated(orders_path);
+        index.mark_outdated(customers_path);
+
+        let usage = index
+            .get([(orders_path, &orders), (customers_path, &customers)])
+            .unwrap();
+
+        let expected = {
+            let mut expected = pgls_completions::UsageStats::default();
+            expected.record_table(Some("public"), "orders");
+            expected.record_table(None, "customers");
+            expected.record_table(None, "orders");
+            expected.record_table(None, "customers");
+            expected.record_column(Some((Some("public"), "orders")), "id");
+            expected.record_column(None, "total");
+            expected.record_column(Some((None, "customers")), "id");
+            expected.record_column(Some((Some("public"), "orders")), "customer_id");
+            expected.record_column(Some((None, "orders")), "status");
+            expected.record_column(Some((None, "customers")), "email");
+            expected.record_function(None, "count");
+            expected.record_join((Some("public"), "orders"), (None, "customers"));
+            expected
+        };
+        assert_eq!(*usage, expected);
+
+        // closed files keep counting
+        index.close(customers_path, &customers);
+        let usage = index.get([(orders_path, &orders)]).unwrap();
+        assert_eq!(*usage, expected);
+    }
+
+    #[cfg(feature = "db")]
+    #[test]
+    fn persists_usage_of_workspace() {
+        use super::UsageStore;
+
+        let cache_dir = tempfile::tempdir().unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let file = workspace.path().join("orders.sql");
+        std::fs::write(&file, "select * from orders;").unwrap();
+
+        let index = UsageIndex::new();
+        index.set_store(Some(UsageStore::new(
+            cache_dir.path().to_path_buf(),
+            workspace.path(),
+        )));
+
+        let document = Document::new("select * from orders;".into(), 0);
+        index.mark_outdated(&file);
+        index.close(&file, &document);
+        index.persist().unwrap();
+
+        let restored = UsageIndex::new();
+        restored.set_store(Some(UsageStore::new(
+            cache_dir.path().to_path_buf(),
+            workspace.path(),
+        )));
+
+        let mut expected = pgls_completions::UsageStats::default();
+        expected.record_table(None, "orders");
+        assert_eq!(*restored.get([]).unwrap(), expected);
+
+        // the usage of another workspace in the same file is ignored
+        let other = UsageStore {
+            path: UsageStore::new(cache_dir.path().to_path_buf(), workspace.path()).path,
+            workspace: cache_dir.path().to_path_buf(),
+        };
+        assert!(other.read().is_empty());
+    }
+}
@@ -378,13 +378,14 @@ macro_rules! workspace_method {
 }
 
 /// Returns a list of signature for all the methods in the [Workspace] trait
-pub fn methods() -> [WorkspaceMethod; 9] {
+pub fn methods() -> [WorkspaceMethod; 10] {
     [
         workspace_method!(is_path_ignored),
         workspace_method!(register_project_folder),
         workspace_method!(get_file_content),
         workspace_method!(pull_file_diagnostics),
         workspace_method!(get_completions),
+        workspace_method!(resolve_completion),
         workspace_method!(update_settings),
         workspace_method!(open_file),
         workspace_method!(change_file),
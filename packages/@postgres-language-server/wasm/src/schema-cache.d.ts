@@ -1,9 +1,13 @@
 // Generated file, do not edit by hand, see `xtask/codegen`
 export interface SchemaCache {
+  casts?: Cast[];
+  collations?: Collation[];
   columns?: Column[];
   extensions?: Extension[];
+  foreign_keys?: ForeignKey[];
   functions?: Function[];
   indexes?: Index[];
+  operators?: Operator[];
   policies?: Policy[];
   roles?: Role[];
   schemas?: Schema[];
@@ -13,6 +17,55 @@ export interface SchemaCache {
   types?: PostgresType[];
   version?: Version;
 }
+export interface Cast {
+  context: CastContext;
+  /**
+   * The ID of the function performing the cast. `None` if `method` is not `Function`.
+   */
+  function_id?: number;
+  /**
+   * The Id (`oid`).
+   */
+  id: number;
+  method: CastMethod;
+  /**
+   * Refers to the source type's ID in the `pg_type` table.
+   */
+  source_type_id: number;
+  /**
+   * Refers to the target type's ID in the `pg_type` table.
+   */
+  target_type_id: number;
+}
+export interface Collation {
+  /**
+   * `LC_COLLATE` for this collation. Not set for ICU collations on newer servers.
+   */
+  collate?: string;
+  comment?: string;
+  /**
+   * `LC_CTYPE` for this collation. Not set for ICU collations on newer servers.
+   */
+  ctype?: string;
+  /**
+   * The encoding the collation is usable with. `None` if it works with any encoding.
+   */
+  encoding?: string;
+  /**
+   * The Id (`oid`).
+   */
+  id: number;
+  /**
+   * Non-deterministic collations may consider strings with different bytes equal.
+   */
+  is_deterministic: boolean;
+  name: string;
+  provider: CollationProvider;
+  /**
+   * The name of the schema the collation belongs to.
+   */
+  schema: string;
+}
 export interface Column {
   /**
    * What type of class does this column belong to?
@@ -48,6 +101,36 @@ export interface Extension {
   name: string;
   schema?: string;
 }
+export interface ForeignKey {
+  /**
+   * The referencing columns, in the order of the constraint.
+   */
+  columns: string[];
+  /**
+   * The Id (`oid`) of the constraint.
+   */
+  id: number;
+  /**
+   * The name of the constraint.
+   */
+  name: string;
+  /**
+   * The referenced columns; the n-th one is referenced by the n-th of `columns`.
+   */
+  referenced_columns: string[];
+  referenced_schema: string;
+  referenced_table_id: number;
+  referenced_table_name: string;
+  /**
+   * The schema of the referencing table.
+   */
+  schema: string;
+  table_id: number;
+  /**
+   * The referencing table.
+   */
+  table_name: string;
+}
 export interface Function {
   /**
    * The Rust representation of the function's arguments.
@@ -65,6 +148,10 @@ export interface Function {
    * The body of the function – the `declare [..] begin [..] end [..]` block.` Not set for internal functions.
    */
   body?: string;
+  /**
+   * Comment of the function, if any.
+   */
+  comment?: string;
   /**
    * The full definition of the function. Includes the full `CREATE OR REPLACE...` shenanigans. Not set for internal functions.
    */
@@ -112,11 +199,49 @@ export interface Function {
   security_definer: boolean;
 }
 export interface Index {
+  /**
+   * The `CREATE INDEX` statement, as reconstructed by `pg_get_indexdef`.
+   */
+  definition: string;
   id: number;
+  is_primary: boolean;
+  is_unique: boolean;
   name: string;
   schema: string;
   table_name: string;
 }
+export interface Operator {
+  comment?: string;
+  /**
+   * The ID of the function implementing the operator.
+   */
+  function_id: number;
+  /**
+   * The Id (`oid`).
+   */
+  id: number;
+  kind: OperatorKind;
+  /**
+   * Refers to the left operand's type ID in the `pg_type` table. `None` for prefix operators.
+   */
+  left_type_id?: number;
+  /**
+   * The operator symbol, e.g. `||` or `->>`.
+   */
+  name: string;
+  /**
+   * Refers to the result type's ID in the `pg_type` table.
+   */
+  result_type_id: number;
+  /**
+   * Refers to the right operand's type ID in the `pg_type` table. `None` for postfix operators.
+   */
+  right_type_id?: number;
+  /**
+   * The name of the schema the operator belongs to.
+   */
+  schema: string;
+}
 export interface Policy {
   command: PolicyCommand;
   is_permissive: boolean;
@@ -151,9 +276,26 @@ export interface Schema {
   view_count: number;
 }
 export interface Sequence {
+  /**
+   * Does the sequence wrap around when it reaches its bounds?
+   */
+  cycle: boolean;
+  /**
+   * e.g. `bigint` or `integer`.
+   */
+  data_type: string;
   id: number;
+  increment: number;
+  max_value: number;
+  min_value: number;
   name: string;
+  owned_by_column?: string;
+  /**
+   * The table of the column the sequence is owned by, e.g. via `serial` or `OWNED BY`.
+   */
+  owned_by_table?: string;
   schema: string;
+  start_value: number;
 }
 export interface Table {
   bytes: number;
@@ -195,6 +337,12 @@ export interface Version {
   version?: string;
   version_num?: number;
 }
+/**
+ * `CastContext` describes in which situations Postgres applies a cast on its own.
+ */
+export type CastContext = "Explicit" | "Assignment" | "Implicit";
+export type CastMethod = "Function" | "InOut" | "Binary";
+export type CollationProvider = "Libc" | "Icu" | "Default" | "Builtin";
 export type ColumnClassKind =
   | "OrdinaryTable"
   | "View"
@@ -209,6 +357,7 @@ export interface FunctionArgs {
  */
 export type Behavior = "Immutable" | "Stable" | "Volatile";
 export type ProcKind = "Function" | "Aggregate" | "Window" | "Procedure";
+export type OperatorKind = "Infix" | "Prefix" | "Postfix";
 export type PolicyCommand = "Select" | "Insert" | "Update" | "Delete" | "All";
 export type ReplicaIdentity = "Default" | "Index" | "Full" | "Nothing";
 export type TableKind = "Ordinary" | "View" | "MaterializedView" | "Partitioned";
@@ -34,6 +34,8 @@ export interface CompletionItem {
   documentation?: string;
   /** The text to insert */
   insertText?: string;
+  /** Byte ranges of the label that matched the typed text, as `[start, end]` */
+  highlights: [number, number][];
 }
 
 /**
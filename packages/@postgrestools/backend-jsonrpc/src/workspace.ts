@@ -162,8 +162,16 @@ export type Category =
   | "stdin"
   | "check"
   | "format"
+  | "baseline"
   | "configuration"
   | "database/connection"
+  | "migrations/apply"
+  | "migrations/locks"
+  | "migrations/duplicateSequenceNumber"
+  | "migrations/outOfOrder"
+  | "migrations/usedBeforeCreation"
+  | "migrations/invalidName"
+  | "migrations/unpairedMigration"
   | "internalError/io"
   | "internalError/runtime"
   | "internalError/fs"
@@ -185,6 +193,7 @@ export type Category =
   | "pglinter/cluster"
   | "pglinter/schema";
 export interface Location {
+  databaseObject?: DatabaseObjectOwned;
   path?: Resource_for_String;
   sourceCode?: string;
   span?: TextRange;
@@ -209,6 +218,23 @@ export type Advice =
   | { backtrace: [MarkupBuf, Backtrace] }
   | { command: string }
   | { group: [MarkupBuf, Advices] };
+/**
+ * Owned version of DatabaseObject for use in diagnostic structs
+ */
+export interface DatabaseObjectOwned {
+  /**
+   * Object name (required)
+   */
+  name: string;
+  /**
+   * Optional object type (e.g., "table", "function", "view")
+   */
+  objectType?: string;
+  /**
+   * Optional schema name
+   */
+  schema?: string;
+}
 /**
  * Represents the resource a diagnostic is associated with.
  */
@@ -291,9 +317,17 @@ export interface CompletionItem {
   completion_text?: CompletionText;
   description: string;
   detail?: string;
+  /**
+   * The ranges of the label that matched the typed text, e.g. to highlight them.
+   */
+  highlights: TextRange[];
   kind: CompletionItemKind;
   label: string;
   preselected: boolean;
+  /**
+   * Set if further documentation can be resolved lazily with `resolve_completion_item`.
+   */
+  resolve_data?: CompletionResolveData;
   /**
    * String used for sorting by LSP clients.
    */
@@ -319,7 +353,29 @@ export type CompletionItemKind =
   | "schema"
   | "policy"
   | "role"
-  | "keyword";
+  | "keyword"
+  | "type"
+  | "enumValue"
+  | "sequence"
+  | "joinCondition"
+  | "snippet";
+/**
+ * Identifies the schema cache item a completion was created for. Sent back by the client to request the item's documentation.
+ */
+export type CompletionResolveData = { table: { id: number } } | { function: { id: number } };
+export interface ResolveCompletionParams {
+  /**
+   * The `resolve_data` of the completion item to resolve.
+   */
+  data: CompletionResolveData;
+}
+export interface ResolveCompletionResult {
+  detail?: string;
+  /**
+   * Markdown documentation of the item.
+   */
+  documentation?: string;
+}
 export interface UpdateSettingsParams {
   configuration: PartialConfiguration;
   gitignore_matches: string[];
@@ -334,6 +390,10 @@ export interface PartialConfiguration {
    * A field for the [JSON schema](https://json-schema.org/) specification
    */
   $schema?: string;
+  /**
+   * The configuration for completions
+   */
+  completions?: PartialCompletionsConfiguration;
   /**
    * The configuration of the database connection
    */
@@ -379,6 +439,19 @@ export interface PartialConfiguration {
    */
   vcs?: PartialVcsConfiguration;
 }
+/**
+ * The configuration for completions.
+ */
+export interface PartialCompletionsConfiguration {
+  /**
+   * Don't store the usage of tables, columns and functions on disk. By default, it is written to the cache directory so that completions are ranked by it right away on the next start.
+   */
+  disableUsagePersistence?: boolean;
+  /**
+   * Custom snippets offered at the start of a statement.
+   */
+  snippets?: Snippets;
+}
 /**
  * The configuration of the database connection.
  */
@@ -396,6 +469,10 @@ export interface PartialDatabaseConfiguration {
    * The name of the database. Can also be set via the `PGDATABASE` environment variable.
    */
   database?: string;
+  /**
+   * Don't store the schema cache on disk. By default, the schema cache is written to the cache directory so that it can be used right away on the next start while it is revalidated against the database.
+   */
+  disableSchemaCachePersistence?: boolean;
   /**
    * The host of the database. Required if you want database-related features. All else falls back to sensible defaults. Can also be set via the `PGHOST` environment variable.
    */
@@ -408,6 +485,10 @@ export interface PartialDatabaseConfiguration {
    * The port of the database. Can also be set via the `PGPORT` environment variable.
    */
   port?: number;
+  /**
+   * The name of a channel to `LISTEN` on for schema changes. Every notification refreshes the schema cache for the object type named by its payload, e.g. `ALTER TABLE` or `CREATE FUNCTION`. An empty payload refreshes everything. Typically fed by an event trigger on `ddl_command_end`.
+   */
+  schemaCacheListenChannel?: string;
   /**
    * The username to connect to the database. Can also be set via the `PGUSER` environment variable.
    */
@@ -506,10 +587,14 @@ export interface PartialMigrationsConfiguration {
    * The directory where the migration files are stored
    */
   migrationsDir?: string;
+  /**
+   * The migration tool, which determines how migration files are named and laid out. Defaults to `<number>_<name>.sql` files or `<number>_<name>` directories.
+   */
+  tool?: MigrationTool;
 }
 export interface PartialPglinterConfiguration {
   /**
-   * if `false`, it disables the feature and the linter won't be executed. `true` by default
+   * if `true`, it enables the feature and the linter will be executed. `false` by default
    */
   enabled?: boolean;
   /**
@@ -588,6 +673,10 @@ export interface PartialSplinterConfiguration {
  * The configuration for type checking.
  */
 export interface PartialTypecheckConfiguration {
+  /**
+   * Executes the DDL statements of a file, such as `CREATE TABLE` or `ALTER TABLE`, one after another. They run inside a transaction that is rolled back, so their errors are reported before the migration is applied. Disabled by default.
+   */
+  checkDdl?: boolean;
   /**
    * if `false`, it disables the feature and the typechecker won't be executed. `true` by default
    */
@@ -624,6 +713,10 @@ If we can't find the configuration, it will attempt to use the current working d
    */
   useIgnoreFile?: boolean;
 }
+/**
+ * A list of user-defined snippets.
+ */
+export type Snippets = SnippetConfiguration[];
 /**
  * Keyword casing style for the formatter.
  */
@@ -643,6 +736,16 @@ export interface LinterRules {
   recommended?: boolean;
   safety?: Safety;
 }
+/**
+ * The migration tools whose layout of migration files is supported
+ */
+export type MigrationTool =
+  | "default"
+  | "flyway"
+  | "sqitch"
+  | "golangMigrate"
+  | "dbmate"
+  | "liquibase";
 export interface PglinterRules {
   /**
    * It enables ALL rules. The rules that belong to `nursery` won't be enabled.
@@ -669,6 +772,23 @@ export interface SplinterRules {
   security?: Security;
 }
 export type VcsClientKind = "git";
+/**
+ * A user-defined snippet.
+ */
+export interface SnippetConfiguration {
+  /**
+   * The text to insert. Supports tab stops (`$1`), placeholders (`${1:name}`) and choices (`${1|a,b|}`). Placeholders named `table` or `column` are turned into a choice of the most used tables in the database, or of the columns of those tables.
+   */
+  body: string;
+  /**
+   * Shown next to the snippet in the completion list.
+   */
+  description?: string;
+  /**
+   * The label shown in the completion list.
+   */
+  name: string;
+}
 /**
  * A list of rules that belong to this group
  */
@@ -1187,6 +1307,7 @@ export interface Workspace {
   getFileContent(params: GetFileContentParams): Promise<string>;
   pullFileDiagnostics(params: PullFileDiagnosticsParams): Promise<PullDiagnosticsResult>;
   getCompletions(params: GetCompletionsParams): Promise<CompletionsResult>;
+  resolveCompletion(params: ResolveCompletionParams): Promise<ResolveCompletionResult>;
   updateSettings(params: UpdateSettingsParams): Promise<void>;
   openFile(params: OpenFileParams): Promise<void>;
   changeFile(params: ChangeFileParams): Promise<void>;
@@ -1210,6 +1331,9 @@ export function createWorkspace(transport: Transport): Workspace {
     getCompletions(params) {
       return transport.request("pgls/get_completions", params);
     },
+    resolveCompletion(params) {
+      return transport.request("pgls/resolve_completion", params);
+    },
     updateSettings(params) {
       return transport.request("pgls/update_settings", params);
     },
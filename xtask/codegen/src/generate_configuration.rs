@@ -263,6 +263,44 @@ fn generate_lint_mod_file(tool: &ToolConfig) -> String {
         quote! {}
     };
 
+    // pglinter needs an extension that might not be installed, so it has to be enabled explicitly
+    let enabled_by_default = tool.name != "pglinter";
+
+    // with everything disabled, the default can be derived
+    let (default_derive, default_impl) = if enabled_by_default {
+        (
+            quote! {},
+            quote! {
+                impl Default for #config_struct {
+                    fn default() -> Self {
+                        Self {
+                            enabled: true,
+                            #splinter_ignore_default
+                            rules: Default::default(),
+                            #file_defaults
+                        }
+                    }
+                }
+            },
+        )
+    } else {
+        (quote! { Default, }, quote! {})
+    };
+
+    let enabled_field = if enabled_by_default {
+        quote! {
+            /// if `false`, it disables the feature and the linter won't be executed. `true` by default
+            #[partial(bpaf(hide))]
+            pub enabled: bool,
+        }
+    } else {
+        quote! {
+            /// if `true`, it enables the feature and the linter will be executed. `false` by default
+            #[partial(bpaf(hide))]
+            pub enabled: bool,
+        }
+    };
+
     let content = quote! {
         //! Generated file, do not edit by hand, see `xtask/codegen`
 
@@ -276,14 +314,12 @@ fn generate_lint_mod_file(tool: &ToolConfig) -> String {
         pub use #generated_file_ident::*;
         use serde::{Deserialize, Serialize};
 
-        #[derive(Clone, Debug, Deserialize, Eq, Partial, PartialEq, Serialize)]
+        #[derive(Clone, Debug, #default_derive Deserialize, Eq, Partial, PartialEq, Serialize)]
         #[partial(derive(Bpaf, Clone, Eq, Merge, PartialEq))]
         #[partial(cfg_attr(feature = "schema", derive(schemars::JsonSchema)))]
         #[partial(serde(rename_all = "camelCase", default, deny_unknown_fields))]
         pub struct #config_struct {
-            /// if `false`, it disables the feature and the linter won't be executed. `true` by default
-            #[partial(bpaf(hide))]
-            pub enabled: bool,
+            #enabled_field
 
             #splinter_ignore_field
 
@@ -302,16 +338,7 @@ fn generate_lint_mod_file(tool: &ToolConfig) -> String {
             #splinter_ignore_method
         }
 
-        impl Default for #config_struct {
-            fn default() -> Self {
-                Self {
-                    enabled: true,
-                    #splinter_ignore_default
-                    rules: Default::default(),
-                    #file_defaults
-                }
-            }
-        }
+        #default_impl
 
         impl #partial_config_struct {
             pub const fn is_disabled(&self) -> bool {